@@ -0,0 +1,75 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Inspection of the approval voting state of the parachain's DB, see [`InspectApprovalsCmd`].
+
+use crate::{cli::InspectApprovalsCmd, error::Error};
+use log::info;
+use sc_cli::{CliConfiguration, DatabaseParams, SharedParams};
+use service::DatabaseSource;
+
+impl InspectApprovalsCmd {
+	/// Reports the unapproved blocks of the parachain's DB of the given database source.
+	pub fn run(&self, db_source: &DatabaseSource) -> Result<(), Error> {
+		let blocks = service::inspect_approvals(db_source, self.slot_duration_millis)?;
+		info!("{} unapproved block(s)", blocks.len());
+
+		for block in blocks {
+			info!(
+				"Block #{} ({:?}): session {}, slot {}, current tranche {}",
+				block.block_number,
+				block.block_hash,
+				block.session,
+				u64::from(block.slot),
+				block.tranche_now,
+			);
+
+			for candidate in block.candidates {
+				info!(
+					"  Candidate {:?} of para {} on core {}: {} of {} validators approved",
+					candidate.candidate_hash,
+					u32::from(candidate.para_id),
+					candidate.core_index.0,
+					candidate.approvals.len(),
+					candidate.n_validators,
+				);
+				info!("    Required tranches: {:?}", candidate.required_tranches);
+				for assignment in &candidate.assignments {
+					info!(
+						"    Assignment of validator {} in tranche {} at tick {}, approved: {}",
+						assignment.validator.0,
+						assignment.tranche,
+						assignment.tick,
+						assignment.approved,
+					);
+				}
+				info!("    Status: {:?}", candidate.reason);
+			}
+		}
+
+		Ok(())
+	}
+}
+
+impl CliConfiguration for InspectApprovalsCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
@@ -17,6 +17,7 @@
 //! Polkadot CLI library.
 
 use clap::Parser;
+use std::path::PathBuf;
 
 #[allow(missing_docs)]
 #[derive(Debug, Parser)]
@@ -59,6 +60,20 @@ pub enum Subcommand {
 	/// capabilities of running a validator.
 	HostPerfCheck,
 
+	/// Prepares and executes a PVF against the given candidate, as the validation host of a
+	/// validator would. Useful to reproduce validation results, e.g. of disputes, locally.
+	Pvf(PvfCmd),
+
+	/// Export the parachain's DB to an archive, e.g. to migrate a validator to another machine.
+	ExportParachainsDb(ExportParachainsDbCmd),
+
+	/// Import an archive created by `export-parachains-db` into a new parachain's DB.
+	ImportParachainsDb(ImportParachainsDbCmd),
+
+	/// Report the blocks which are not approved yet according to the approval voting state of the
+	/// parachain's DB, and why their candidates are not approved. Useful when finality stalls.
+	InspectApprovals(InspectApprovalsCmd),
+
 	/// Try some command against runtime state.
 	#[cfg(feature = "try-runtime")]
 	TryRuntime(try_runtime_cli::TryRuntimeCmd),
@@ -86,6 +101,84 @@ pub struct ValidationWorkerCommand {
 	pub node_impl_version: String,
 }
 
+#[allow(missing_docs)]
+#[derive(Debug, Parser)]
+pub struct PvfCmd {
+	/// The path to the validation code, either raw or compressed.
+	#[arg(long)]
+	pub code: PathBuf,
+
+	/// The path to the SCALE-encoded `PersistedValidationData` of the candidate.
+	#[arg(long)]
+	pub pvd: PathBuf,
+
+	/// The path to the SCALE-encoded `PoV` of the candidate.
+	#[arg(long)]
+	pub pov: PathBuf,
+
+	/// The path to the SCALE-encoded `ExecutorParams` of the session. The default executor
+	/// parameters are used if none are given.
+	#[arg(long)]
+	pub executor_params: Option<PathBuf>,
+
+	/// The directory to keep the prepared artifact in, within its `pvf-artifacts` subdirectory. A
+	/// temporary directory is used if none is given. An artifact prepared by an earlier run is
+	/// reused, in which case no preparation stats are available.
+	#[arg(long)]
+	pub cache_path: Option<PathBuf>,
+}
+
+#[allow(missing_docs)]
+#[derive(Debug, Parser)]
+pub struct ExportParachainsDbCmd {
+	/// The path of the archive to create. Fails if the file exists already. The DB is opened in
+	/// read-only mode and must have been upgraded to the current version by the node already.
+	#[arg(long)]
+	pub output: PathBuf,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: sc_cli::SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: sc_cli::DatabaseParams,
+}
+
+#[allow(missing_docs)]
+#[derive(Debug, Parser)]
+pub struct ImportParachainsDbCmd {
+	/// The path of the archive to import. The archive may have been exported from another
+	/// database backend than the one selected by `--database`.
+	#[arg(long)]
+	pub input: PathBuf,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: sc_cli::SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: sc_cli::DatabaseParams,
+}
+
+#[allow(missing_docs)]
+#[derive(Debug, Parser)]
+pub struct InspectApprovalsCmd {
+	/// The slot duration of the chain in milliseconds, used to compute the current tranche of
+	/// the unapproved blocks.
+	#[arg(long, default_value_t = 6000)]
+	pub slot_duration_millis: u64,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: sc_cli::SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: sc_cli::DatabaseParams,
+}
+
 #[allow(missing_docs)]
 #[derive(Debug, Parser)]
 #[group(skip)]
@@ -147,6 +240,60 @@ pub struct RunCmd {
 	/// **Dangerous!** Do not touch unless explicitly adviced to.
 	#[arg(long)]
 	pub overseer_channel_capacity_override: Option<usize>,
+
+	/// Run the PVF workers in a sandbox.
+	///
+	/// Restricts the filesystem access of the workers to the artifact cache and filters the
+	/// system calls they can make. Only supported on Linux.
+	#[arg(long)]
+	pub pvf_sandbox: bool,
+
+	/// Soft limit on the disk space used by the availability store, in MiB.
+	///
+	/// Once exceeded, finalized availability data is pruned early, but never before the dispute
+	/// window has passed. Unlimited by default.
+	#[arg(long, value_name = "MiB")]
+	pub av_store_max_disk_usage: Option<u64>,
+
+	/// How long finalized availability data is kept at least when exceeding
+	/// `--av-store-max-disk-usage`, in seconds.
+	///
+	/// Defaults to the dispute window of the chain. Finalized data is kept for 25 hours anyway, so
+	/// the limit can only reclaim the data finalized between this and then. A period shorter than
+	/// the dispute window reclaims more, but the data might then be missing for late disputes.
+	#[arg(long, value_name = "SECONDS")]
+	pub av_store_min_keep_finalized_for: Option<u64>,
+
+	/// Compress large notifications on the validation and collation protocols.
+	///
+	/// Compression is negotiated with every peer, peers which don't support it keep receiving
+	/// uncompressed notifications.
+	#[arg(long)]
+	pub notification_compression: bool,
+
+	/// Produce compact tranche-0 approval assignments which claim several candidates at once.
+	///
+	/// Compact assignments are only gossiped to peers speaking the second version of the
+	/// validation protocol.
+	#[arg(long)]
+	pub enable_approval_v2_assignments: bool,
+
+	/// Sign up to this many approval votes for candidates of the same relay chain block at once,
+	/// waiting briefly for approval checks to complete. `1` disables coalescing.
+	///
+	/// Coalesced approvals are only gossiped to peers speaking the second version of the
+	/// validation protocol, and approvals are only coalesced once the `ApprovalCoalescing` node
+	/// feature is enabled on chain.
+	#[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+	pub max_approval_coalesce_count: u32,
+
+	/// Gossip approvals, bitfields and statements over a random regular graph in which every
+	/// validator has up to this many neighbors, instead of the 2D grid.
+	///
+	/// Odd degrees are rounded down. All validators need to use the same topology for messages to
+	/// reach everyone quickly.
+	#[arg(long, value_name = "DEGREE")]
+	pub gossip_topology_degree: Option<usize>,
 }
 
 #[allow(missing_docs)]
@@ -352,6 +352,13 @@ where
 			cli.run.overseer_channel_capacity_override,
 			maybe_malus_finality_delay,
 			hwbench,
+			cli.run.pvf_sandbox,
+			cli.run.av_store_max_disk_usage.map(|mib| mib.saturating_mul(1024 * 1024)),
+			cli.run.av_store_min_keep_finalized_for.map(std::time::Duration::from_secs),
+			cli.run.notification_compression,
+			cli.run.enable_approval_v2_assignments,
+			cli.run.max_approval_coalesce_count,
+			cli.run.gossip_topology_degree,
 		)
 		.map(|full| full.task_manager)?;
 
@@ -651,6 +658,36 @@ pub fn run() -> Result<()> {
 
 			host_perf_check()
 		},
+		Some(Subcommand::Pvf(cmd)) => {
+			let mut builder = sc_cli::LoggerBuilder::new("");
+			builder.with_colors(true);
+			builder.init()?;
+
+			#[cfg(target_os = "android")]
+			{
+				return Err(sc_cli::Error::Input(
+					"PVF workers are not supported under this platform".into(),
+				)
+				.into())
+			}
+
+			#[cfg(not(target_os = "android"))]
+			{
+				cmd.run()
+			}
+		},
+		Some(Subcommand::ExportParachainsDb(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| cmd.run(&config.database))
+		},
+		Some(Subcommand::ImportParachainsDb(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| cmd.run(&config.database))
+		},
+		Some(Subcommand::InspectApprovals(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| cmd.run(&config.database))
+		},
 		Some(Subcommand::Key(cmd)) => Ok(cmd.run(&cli)?),
 		#[cfg(feature = "try-runtime")]
 		Some(Subcommand::TryRuntime(cmd)) => {
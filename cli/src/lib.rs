@@ -18,6 +18,8 @@
 
 #![warn(missing_docs)]
 
+#[cfg(feature = "cli")]
+mod approvals;
 #[cfg(feature = "cli")]
 mod cli;
 #[cfg(feature = "cli")]
@@ -26,6 +28,10 @@ mod command;
 mod error;
 #[cfg(all(feature = "hostperfcheck", build_type = "release"))]
 mod host_perf_check;
+#[cfg(feature = "cli")]
+mod parachains_db;
+#[cfg(feature = "cli")]
+mod pvf;
 
 #[cfg(feature = "full-node")]
 pub use service::RuntimeApiCollection;
@@ -0,0 +1,66 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Export and import of the parachain's DB, see [`ExportParachainsDbCmd`] and
+//! [`ImportParachainsDbCmd`].
+
+use crate::{
+	cli::{ExportParachainsDbCmd, ImportParachainsDbCmd},
+	error::Error,
+};
+use log::info;
+use sc_cli::{CliConfiguration, DatabaseParams, SharedParams};
+use service::DatabaseSource;
+
+impl ExportParachainsDbCmd {
+	/// Exports the parachain's DB of the given database source.
+	pub fn run(&self, db_source: &DatabaseSource) -> Result<(), Error> {
+		info!("Exporting the parachain's DB to {:?}...", self.output);
+		let entries = service::export_parachains_db(db_source, &self.output)?;
+		info!("Exported {} entries", entries);
+		Ok(())
+	}
+}
+
+impl CliConfiguration for ExportParachainsDbCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+impl ImportParachainsDbCmd {
+	/// Imports the archive into a new parachain's DB of the given database source.
+	pub fn run(&self, db_source: &DatabaseSource) -> Result<(), Error> {
+		info!("Importing the parachain's DB from {:?}...", self.input);
+		let entries = service::import_parachains_db(db_source, &self.input)?;
+		info!("Imported {} entries", entries);
+		Ok(())
+	}
+}
+
+impl CliConfiguration for ImportParachainsDbCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
@@ -0,0 +1,174 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Offline preparation and execution of a PVF, see [`PvfCmd`].
+
+use crate::{cli::PvfCmd, error::Error};
+use futures::channel::oneshot;
+use log::info;
+use parity_scale_codec::{Decode, Encode};
+use polkadot_node_core_candidate_validation::{
+	DEFAULT_APPROVAL_EXECUTION_TIMEOUT, DEFAULT_LENIENT_PREPARATION_TIMEOUT,
+};
+use polkadot_node_core_pvf::{
+	Config, Metrics, PrepareStats, Priority, PvfPrepData, ValidationError, ValidationHost,
+};
+use polkadot_node_primitives::{PoV, POV_BOMB_LIMIT, VALIDATION_CODE_BOMB_LIMIT};
+use polkadot_parachain::primitives::{BlockData, ValidationParams, ValidationResult};
+use polkadot_primitives::{
+	ExecutorParams, PersistedValidationData, PvfExecTimeoutKind, PvfPrepTimeoutKind,
+};
+use std::{
+	path::Path,
+	time::{Duration, Instant},
+};
+
+/// The subdirectory of the `--cache-path` the artifacts are kept in, named like the one of a
+/// node's database.
+const ARTIFACTS_DIR: &str = "pvf-artifacts";
+
+impl PvfCmd {
+	/// Executes the candidate with a freshly started validation host and reports the outcome.
+	/// Fails if the candidate is invalid or cannot be validated.
+	pub fn run(&self) -> Result<(), Error> {
+		let code = read_file(&self.code, "validation code")?;
+		let code = sp_maybe_compressed_blob::decompress(&code, VALIDATION_CODE_BOMB_LIMIT)
+			.map_err(|e| Error::Other(format!("Failed to decompress the validation code: {}", e)))?
+			.into_owned();
+		let pvd: PersistedValidationData = decode_file(&self.pvd, "persisted validation data")?;
+		let pov: PoV = decode_file(&self.pov, "PoV")?;
+		let executor_params: ExecutorParams = match &self.executor_params {
+			Some(path) => decode_file(path, "executor parameters")?,
+			None => ExecutorParams::default(),
+		};
+
+		let block_data = sp_maybe_compressed_blob::decompress(&pov.block_data.0, POV_BOMB_LIMIT)
+			.map_err(|e| Error::Other(format!("Failed to decompress the PoV: {}", e)))?
+			.into_owned();
+		let params = ValidationParams {
+			parent_head: pvd.parent_head,
+			block_data: BlockData(block_data),
+			relay_parent_number: pvd.relay_parent_number,
+			relay_parent_storage_root: pvd.relay_parent_storage_root,
+		};
+
+		let prep_timeout = executor_params
+			.pvf_prep_timeout(PvfPrepTimeoutKind::Lenient)
+			.unwrap_or(DEFAULT_LENIENT_PREPARATION_TIMEOUT);
+		let exec_timeout = executor_params
+			.pvf_exec_timeout(PvfExecTimeoutKind::Approval)
+			.unwrap_or(DEFAULT_APPROVAL_EXECUTION_TIMEOUT);
+		let pvf = PvfPrepData::from_code(code, executor_params, prep_timeout);
+
+		// The workers are spawned from this very binary, just like for a running node.
+		let program_path = std::env::current_exe()
+			.map_err(|e| Error::Other(format!("Failed to locate the worker program: {}", e)))?;
+		// The validation host removes any file it does not recognize from the cache directory, so
+		// it is only ever given a dedicated subdirectory of a user-provided path.
+		let temp_dir;
+		let cache_path = match &self.cache_path {
+			Some(cache_path) => cache_path.join(ARTIFACTS_DIR),
+			None => {
+				temp_dir = tempfile::tempdir().map_err(|e| {
+					Error::Other(format!("Failed to create a temporary cache directory: {}", e))
+				})?;
+				temp_dir.path().to_path_buf()
+			},
+		};
+
+		let runtime = tokio::runtime::Builder::new_multi_thread()
+			.enable_all()
+			.build()
+			.map_err(|e| Error::Other(format!("Failed to start the async runtime: {}", e)))?;
+		runtime.block_on(async move {
+			let (host, task) = polkadot_node_core_pvf::start(
+				Config::new(cache_path, program_path),
+				Metrics::default(),
+			);
+			let task = tokio::spawn(task);
+
+			let result = execute(host, pvf, exec_timeout, params).await;
+			task.abort();
+			result
+		})
+	}
+}
+
+async fn execute(
+	mut host: ValidationHost,
+	pvf: PvfPrepData,
+	exec_timeout: Duration,
+	params: ValidationParams,
+) -> Result<(), Error> {
+	// Execute the candidate just like a validator does, i.e. the PVF is prepared on demand with
+	// the lenient preparation timeout.
+	info!("Executing {:?} with a timeout of {:?}...", pvf, exec_timeout);
+
+	let (tx, rx) = oneshot::channel();
+	let started_at = Instant::now();
+	host.execute_pvf(pvf.clone(), exec_timeout, params.encode(), Priority::Normal, tx)
+		.await
+		.map_err(Error::Other)?;
+	let execute_result = rx
+		.await
+		.map_err(|_| Error::Other("The validation host hung up during execution".into()))?;
+	info!("Preparation and execution took {:?}", started_at.elapsed());
+
+	// The stats of the preparation done for the execution are kept by the validation host along
+	// with the artifact, so this does not prepare the PVF again.
+	let (tx, rx) = oneshot::channel();
+	host.precheck_pvf(pvf, tx).await.map_err(Error::Other)?;
+	match rx.await {
+		Ok(Ok(PrepareStats { cpu_time_elapsed, memory_stats })) => {
+			info!("Preparation CPU time: {:?}", cpu_time_elapsed);
+			info!("Preparation memory stats: {:?}", memory_stats);
+		},
+		Ok(Err(e)) => info!("The PVF could not be prepared: {:?}", e),
+		Err(_) => info!("No preparation stats are available"),
+	}
+
+	report_outcome(execute_result)
+}
+
+fn report_outcome(result: Result<ValidationResult, ValidationError>) -> Result<(), Error> {
+	match result {
+		Ok(result) => {
+			info!("The candidate is valid");
+			info!("New head data: {:?}", result.head_data);
+			info!("New validation code: {}", result.new_validation_code.is_some());
+			info!("Upward messages: {}", result.upward_messages.len());
+			info!("Horizontal messages: {}", result.horizontal_messages.len());
+			info!("Processed downward messages: {}", result.processed_downward_messages);
+			info!("HRMP watermark: {}", result.hrmp_watermark);
+			Ok(())
+		},
+		Err(ValidationError::InvalidCandidate(e)) =>
+			Err(Error::Other(format!("The candidate is invalid: {:?}", e))),
+		Err(ValidationError::InternalError(e)) =>
+			Err(Error::Other(format!("The candidate could not be validated: {}", e))),
+	}
+}
+
+fn read_file(path: &Path, what: &str) -> Result<Vec<u8>, Error> {
+	std::fs::read(path)
+		.map_err(|e| Error::Other(format!("Failed to read the {} from {:?}: {}", what, path, e)))
+}
+
+fn decode_file<T: Decode>(path: &Path, what: &str) -> Result<T, Error> {
+	let bytes = read_file(path, what)?;
+	T::decode(&mut &bytes[..])
+		.map_err(|e| Error::Other(format!("Failed to decode the {} from {:?}: {}", what, path, e)))
+}
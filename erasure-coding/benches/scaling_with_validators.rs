@@ -15,7 +15,14 @@
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
-use polkadot_primitives::Hash;
+use parity_scale_codec::Encode;
+use polkadot_node_primitives::Proof;
+use polkadot_primitives::{BlakeTwo256, Hash, HashT};
+use sp_core::Blake2Hasher;
+use sp_trie::{
+	trie_types::{TrieDBBuilder, TrieDBMutBuilderV0 as TrieDBMutBuilder},
+	LayoutV0, MemoryDB, Recorder, Trie, TrieMut,
+};
 use std::time::Duration;
 
 fn chunks(n_validators: usize, pov: &Vec<u8>) -> Vec<Vec<u8>> {
@@ -27,6 +34,34 @@ fn erasure_root(n_validators: usize, pov: &Vec<u8>) -> Hash {
 	polkadot_erasure_coding::branches(&chunks).root()
 }
 
+// The branches of all chunks taken with one recorded lookup per chunk, as a baseline for
+// `polkadot_erasure_coding::branches`.
+fn branches_with_lookups(chunks: &[Vec<u8>]) -> Vec<Proof> {
+	let mut trie_storage: MemoryDB<Blake2Hasher> = MemoryDB::default();
+	let mut root = Hash::default();
+	{
+		let mut trie = TrieDBMutBuilder::new(&mut trie_storage, &mut root).build();
+		for (i, chunk) in chunks.iter().enumerate() {
+			(i as u32)
+				.using_encoded(|key| trie.insert(key, BlakeTwo256::hash(chunk).as_ref()))
+				.unwrap();
+		}
+	}
+
+	(0..chunks.len())
+		.map(|i| {
+			let mut recorder = Recorder::<LayoutV0<Blake2Hasher>>::new();
+			{
+				let trie =
+					TrieDBBuilder::new(&trie_storage, &root).with_recorder(&mut recorder).build();
+				(i as u32).using_encoded(|key| trie.get(key)).unwrap().unwrap();
+			}
+			let nodes: Vec<Vec<u8>> = recorder.drain().into_iter().map(|r| r.data).collect();
+			Proof::try_from(nodes).unwrap()
+		})
+		.collect()
+}
+
 fn construct_and_reconstruct_5mb_pov(c: &mut Criterion) {
 	const N_VALIDATORS: [usize; 6] = [200, 500, 1000, 2000, 10_000, 50_000];
 
@@ -75,6 +110,113 @@ fn construct_and_reconstruct_5mb_pov(c: &mut Criterion) {
 	group.finish();
 }
 
+fn branches_and_verification_5mb_pov(c: &mut Criterion) {
+	const N_VALIDATORS: [usize; 6] = [200, 500, 1000, 2000, 10_000, 50_000];
+
+	const KB: usize = 1024;
+	const MB: usize = 1024 * KB;
+
+	let pov = vec![0xfe; 5 * MB];
+
+	let mut group = c.benchmark_group("construct_with_branches");
+	for n_validators in N_VALIDATORS {
+		let expected_root = erasure_root(n_validators, &pov);
+
+		group.throughput(Throughput::Bytes(pov.len() as u64));
+		group.bench_with_input(
+			BenchmarkId::from_parameter(n_validators),
+			&n_validators,
+			|b, &n| {
+				b.iter(|| {
+					let encoded =
+						polkadot_erasure_coding::obtain_chunks_with_branches(n, &pov).unwrap();
+					assert_eq!(encoded.root, expected_root);
+				});
+			},
+		);
+	}
+	group.finish();
+
+	let mut group = c.benchmark_group("branches_single_traversal");
+	for n_validators in N_VALIDATORS {
+		let all_chunks = chunks(n_validators, &pov);
+
+		group.throughput(Throughput::Elements(n_validators as u64));
+		group.bench_with_input(
+			BenchmarkId::from_parameter(n_validators),
+			&n_validators,
+			|b, &n| {
+				b.iter(|| {
+					let proofs: Vec<_> = polkadot_erasure_coding::branches(&all_chunks)
+						.map(|(proof, _)| proof)
+						.collect();
+					assert_eq!(proofs.len(), n);
+				});
+			},
+		);
+	}
+	group.finish();
+
+	let mut group = c.benchmark_group("branches_per_chunk_lookup");
+	for n_validators in N_VALIDATORS {
+		let all_chunks = chunks(n_validators, &pov);
+
+		group.throughput(Throughput::Elements(n_validators as u64));
+		group.bench_with_input(
+			BenchmarkId::from_parameter(n_validators),
+			&n_validators,
+			|b, &n| {
+				b.iter(|| {
+					let proofs = branches_with_lookups(&all_chunks);
+					assert_eq!(proofs.len(), n);
+				});
+			},
+		);
+	}
+	group.finish();
+
+	let mut group = c.benchmark_group("verify_individually");
+	for n_validators in N_VALIDATORS {
+		let encoded =
+			polkadot_erasure_coding::obtain_chunks_with_branches(n_validators, &pov).unwrap();
+
+		group.throughput(Throughput::Elements(n_validators as u64));
+		group.bench_with_input(BenchmarkId::from_parameter(n_validators), &n_validators, |b, _| {
+			b.iter(|| {
+				for (i, (chunk, proof)) in encoded.chunks.iter().zip(&encoded.proofs).enumerate() {
+					let hash =
+						polkadot_erasure_coding::branch_hash(&encoded.root, proof, i).unwrap();
+					assert_eq!(hash, BlakeTwo256::hash(chunk));
+				}
+			});
+		});
+	}
+	group.finish();
+
+	let mut group = c.benchmark_group("verify_batch");
+	for n_validators in N_VALIDATORS {
+		let encoded =
+			polkadot_erasure_coding::obtain_chunks_with_branches(n_validators, &pov).unwrap();
+
+		group.throughput(Throughput::Elements(n_validators as u64));
+		group.bench_with_input(BenchmarkId::from_parameter(n_validators), &n_validators, |b, _| {
+			b.iter(|| {
+				let results = polkadot_erasure_coding::verify_chunks(
+					&encoded.root,
+					encoded
+						.chunks
+						.iter()
+						.zip(&encoded.proofs)
+						.enumerate()
+						.map(|(i, (chunk, proof))| (i, &chunk[..], proof)),
+				);
+				assert!(results.iter().all(|r| r.is_ok()));
+			});
+		});
+	}
+	group.finish();
+}
+
 fn criterion_config() -> Criterion {
 	Criterion::default()
 		.sample_size(15)
@@ -85,6 +227,6 @@ fn criterion_config() -> Criterion {
 criterion_group!(
 	name = re_construct;
 	config = criterion_config();
-	targets = construct_and_reconstruct_5mb_pov,
+	targets = construct_and_reconstruct_5mb_pov, branches_and_verification_5mb_pov,
 );
 criterion_main!(re_construct);
@@ -0,0 +1,78 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use honggfuzz::fuzz;
+use polkadot_erasure_coding::*;
+use polkadot_primitives::PersistedValidationData;
+use primitives::{AvailableData, BlockData, PoV, Proof};
+use std::sync::Arc;
+
+fn main() {
+	loop {
+		fuzz!(|data: (Vec<u8>, usize, Vec<Vec<u8>>)| {
+			let (block_data, corrupt_index, proof_nodes) = data;
+			let pov_block = PoV { block_data: BlockData(block_data) };
+
+			let available_data = AvailableData {
+				pov: Arc::new(pov_block),
+				validation_data: PersistedValidationData::default(),
+			};
+			let ChunksWithBranches { root, chunks, proofs } =
+				obtain_chunks_with_branches_v1(10, &available_data).unwrap();
+
+			assert_eq!(chunks.len(), 10);
+			assert_eq!(proofs.len(), 10);
+
+			// all chunks should verify against their own branches.
+			let results = verify_chunks(
+				&root,
+				chunks.iter().zip(proofs.iter()).enumerate().map(|(i, (c, p))| (i, &c[..], p)),
+			);
+			assert!(results.iter().all(|r| r.is_ok()));
+
+			// a corrupted chunk must be rejected, without affecting the other ones.
+			let corrupt_index = corrupt_index % 10;
+			let mut corrupted = chunks.clone();
+			corrupted[corrupt_index][0] ^= 1;
+			let results = verify_chunks(
+				&root,
+				corrupted
+					.iter()
+					.zip(proofs.iter())
+					.enumerate()
+					.map(|(i, (c, p))| (i, &c[..], p)),
+			);
+			for (i, result) in results.into_iter().enumerate() {
+				assert_eq!(result.is_ok(), i != corrupt_index);
+			}
+
+			// an arbitrary branch is only accepted if it proves the right chunk.
+			if let Ok(proof) = Proof::try_from(proof_nodes) {
+				let results = verify_chunks(
+					&root,
+					vec![(0, &chunks[0][..], &proofs[0]), (1, &chunks[1][..], &proof)],
+				);
+				assert!(results[0].is_ok());
+				// same outcome as checking the branch on its own.
+				assert_eq!(
+					results[1].is_ok(),
+					branch_hash(&root, &proof, 1).ok() == branch_hash(&root, &proofs[1], 1).ok(),
+				);
+				println!("{:?}", results);
+			}
+		});
+	}
+}
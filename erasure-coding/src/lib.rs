@@ -30,9 +30,11 @@ use polkadot_primitives::{BlakeTwo256, Hash as H256, HashT};
 use sp_core::Blake2Hasher;
 use sp_trie::{
 	trie_types::{TrieDBBuilder, TrieDBMutBuilderV0 as TrieDBMutBuilder},
-	LayoutV0, MemoryDB, Trie, TrieMut, EMPTY_PREFIX,
+	LayoutV0, MemoryDB, Recorder, Trie, TrieMut, EMPTY_PREFIX,
 };
+use std::collections::HashSet;
 use thiserror::Error;
+use trie_db::{node::Node, TrieDBNodeIterator};
 
 use novelpoly::{CodeParams, WrappedShard};
 
@@ -75,6 +77,9 @@ pub enum Error {
 	/// Branch out of bounds.
 	#[error("Branch is out of bounds")]
 	BranchOutOfBounds,
+	/// The chunk's hash does not match the one its branch proves to be in the erasure root.
+	#[error("Chunk does not match the erasure root")]
+	ChunkHashMismatch,
 	/// Unknown error
 	#[error("An unknown error has appeared when reconstructing erasure code chunks")]
 	UnknownReconstruction,
@@ -96,6 +101,15 @@ pub const fn recovery_threshold(n_validators: usize) -> Result<usize, Error> {
 	Ok(needed + 1)
 }
 
+/// Obtain the number of systematic chunks, which hold the original data and are enough to recover
+/// it without decoding, see [`reconstruct_from_systematic`].
+///
+/// This is the [`recovery_threshold`] rounded down to a power of two, as required by the code.
+pub fn systematic_recovery_threshold(n_validators: usize) -> Result<usize, Error> {
+	let threshold = recovery_threshold(n_validators)?;
+	Ok(1 << (usize::BITS - 1 - threshold.leading_zeros()))
+}
+
 fn code_params(n_validators: usize) -> Result<CodeParams, Error> {
 	// we need to be able to reconstruct from 1/3 - eps
 
@@ -139,6 +153,51 @@ pub fn obtain_chunks<T: Encode>(n_validators: usize, data: &T) -> Result<Vec<Vec
 	Ok(shards.into_iter().map(|w: WrappedShard| w.into_inner()).collect())
 }
 
+/// Erasure-coded chunks together with the erasure root and the merkle branch of every chunk.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunksWithBranches {
+	/// The erasure root all chunks are committed to.
+	pub root: H256,
+	/// The chunks, one for each validator, ordered by chunk index.
+	pub chunks: Vec<Vec<u8>>,
+	/// The merkle branch of each chunk, in the same order as `chunks`.
+	pub proofs: Vec<Proof>,
+}
+
+/// Obtain erasure-coded chunks for v1 `AvailableData` along with the erasure root and all
+/// merkle branches.
+///
+/// Works only up to 65536 validators, and `n_validators` must be non-zero.
+pub fn obtain_chunks_with_branches_v1(
+	n_validators: usize,
+	data: &AvailableData,
+) -> Result<ChunksWithBranches, Error> {
+	obtain_chunks_with_branches(n_validators, data)
+}
+
+/// Obtain erasure-coded chunks along with the erasure root and all merkle branches.
+///
+/// This is a shorthand for calling [`obtain_chunks`] and [`branches`], and collecting the root and
+/// the proofs of all chunks. The trie is built once and all branches are taken from it in a single
+/// traversal.
+///
+/// Works only up to 65536 validators, and `n_validators` must be non-zero.
+pub fn obtain_chunks_with_branches<T: Encode>(
+	n_validators: usize,
+	data: &T,
+) -> Result<ChunksWithBranches, Error> {
+	let chunks = obtain_chunks(n_validators, data)?;
+	let branches = branches(&chunks);
+	let root = branches.root();
+	let proofs: Vec<Proof> = branches.map(|(proof, _)| proof).collect();
+
+	if proofs.len() != chunks.len() {
+		return Err(Error::TooManyValidators)
+	}
+
+	Ok(ChunksWithBranches { root, chunks, proofs })
+}
+
 /// Reconstruct the v1 available data from a set of chunks.
 ///
 /// Provide an iterator containing chunk data and the corresponding index.
@@ -185,6 +244,13 @@ where
 		received_shards[chunk_idx] = Some(WrappedShard::new(chunk_data.to_vec()));
 	}
 
+	reconstruct_from_shards(params, received_shards)
+}
+
+fn reconstruct_from_shards<T: Decode>(
+	params: CodeParams,
+	received_shards: Vec<Option<WrappedShard>>,
+) -> Result<T, Error> {
 	let res = params.make_encoder().reconstruct(received_shards);
 
 	let payload_bytes = match res {
@@ -204,12 +270,153 @@ where
 	Decode::decode(&mut &payload_bytes[..]).or_else(|_e| Err(Error::BadPayload))
 }
 
+/// Incremental reconstruction of erasure-coded data from chunks added one at a time.
+///
+/// Unlike [`reconstruct`], which needs all chunks up front, chunks are verified against the
+/// erasure root as they arrive, so that the caller knows exactly when enough valid chunks have
+/// been collected. The chunks are handed over to the decoder without copying them again.
+pub struct Reconstructor {
+	params: CodeParams,
+	erasure_root: H256,
+	threshold: usize,
+	shard_len: Option<usize>,
+	received_shards: Vec<Option<WrappedShard>>,
+	received_count: usize,
+}
+
+impl Reconstructor {
+	/// Create a reconstructor for data erasure-coded for `n_validators`, with the given root.
+	///
+	/// Works only up to 65536 validators, and `n_validators` must be non-zero.
+	pub fn new(n_validators: usize, erasure_root: H256) -> Result<Self, Error> {
+		Ok(Reconstructor {
+			params: code_params(n_validators)?,
+			erasure_root,
+			threshold: recovery_threshold(n_validators)?,
+			shard_len: None,
+			received_shards: vec![None; n_validators],
+			received_count: 0,
+		})
+	}
+
+	/// Add the chunk with the given index, after checking it against the erasure root with the
+	/// given Merkle branch.
+	///
+	/// Returns whether enough chunks have been added to reconstruct the data. Chunks added after
+	/// that point, as well as chunks which have been added already, are ignored without being
+	/// checked.
+	pub fn add_chunk(
+		&mut self,
+		index: usize,
+		chunk: Vec<u8>,
+		proof: &Proof,
+	) -> Result<bool, Error> {
+		let n_validators = self.received_shards.len();
+		if index >= n_validators {
+			return Err(Error::ChunkIndexOutOfBounds { chunk_index: index, n_validators })
+		}
+
+		if self.can_reconstruct() || self.received_shards[index].is_some() {
+			return Ok(self.can_reconstruct())
+		}
+
+		if chunk.len() % 2 != 0 {
+			return Err(Error::UnevenLength)
+		}
+		if self.shard_len.map_or(false, |shard_len| shard_len != chunk.len()) || chunk.is_empty() {
+			return Err(Error::NonUniformChunks)
+		}
+
+		if branch_hash(&self.erasure_root, proof, index)? != BlakeTwo256::hash(&chunk) {
+			return Err(Error::ChunkHashMismatch)
+		}
+
+		// Only valid chunks determine the expected length of the others.
+		self.shard_len = Some(chunk.len());
+		self.received_shards[index] = Some(WrappedShard::new(chunk));
+		self.received_count += 1;
+
+		Ok(self.can_reconstruct())
+	}
+
+	/// Whether enough chunks have been added to reconstruct the data.
+	pub fn can_reconstruct(&self) -> bool {
+		self.received_count >= self.threshold
+	}
+
+	/// The number of valid chunks added so far.
+	pub fn received_count(&self) -> usize {
+		self.received_count
+	}
+
+	/// Reconstruct the v1 available data from the chunks added.
+	pub fn reconstruct_v1(self) -> Result<AvailableData, Error> {
+		self.reconstruct()
+	}
+
+	/// Reconstruct decodable data from the chunks added.
+	pub fn reconstruct<T: Decode>(self) -> Result<T, Error> {
+		if !self.can_reconstruct() {
+			return Err(Error::NotEnoughChunks)
+		}
+
+		reconstruct_from_shards(self.params, self.received_shards)
+	}
+}
+
+/// Reconstruct the v1 available data from the systematic chunks.
+///
+/// See [`reconstruct_from_systematic`] for details.
+pub fn reconstruct_from_systematic_v1(
+	n_validators: usize,
+	chunks: Vec<Vec<u8>>,
+) -> Result<AvailableData, Error> {
+	reconstruct_from_systematic(n_validators, chunks)
+}
+
+/// Reconstruct decodable data from the systematic chunks, i.e. the chunks of the first
+/// [`systematic_recovery_threshold`] validators.
+///
+/// The systematic chunks hold the original data, so it is recovered by concatenating them, which
+/// is much cheaper than decoding. Provide the chunk data ordered by chunk index, starting at index
+/// 0. Any additional chunks are ignored.
+///
+/// Works only up to 65536 validators, and `n_validators` must be non-zero.
+pub fn reconstruct_from_systematic<T: Decode>(
+	n_validators: usize,
+	chunks: Vec<Vec<u8>>,
+) -> Result<T, Error> {
+	let threshold = systematic_recovery_threshold(n_validators)?;
+	let chunks = chunks.get(..threshold).ok_or(Error::NotEnoughChunks)?;
+
+	let shard_len = chunks[0].len();
+	if shard_len % 2 != 0 {
+		return Err(Error::UnevenLength)
+	}
+	if shard_len == 0 || chunks.iter().any(|chunk| chunk.len() != shard_len) {
+		return Err(Error::NonUniformChunks)
+	}
+
+	// The encoder splits the data into pieces of two bytes, which are dealt out to the systematic
+	// chunks in turn.
+	let mut payload_bytes = Vec::with_capacity(shard_len * threshold);
+	for i in (0..shard_len).step_by(2) {
+		for chunk in chunks {
+			payload_bytes.extend_from_slice(&chunk[i..i + 2]);
+		}
+	}
+
+	Decode::decode(&mut &payload_bytes[..]).or_else(|_e| Err(Error::BadPayload))
+}
+
 /// An iterator that yields merkle branches and chunk data for all chunks to
 /// be sent to other validators.
 pub struct Branches<'a, I> {
 	trie_storage: MemoryDB<Blake2Hasher>,
 	root: H256,
 	chunks: &'a [I],
+	// The branches of all chunks, built in a single traversal of the trie when first needed.
+	branches: Option<std::vec::IntoIter<Option<Proof>>>,
 	current_pos: usize,
 }
 
@@ -224,29 +431,67 @@ impl<'a, I: AsRef<[u8]>> Iterator for Branches<'a, I> {
 	type Item = (Proof, &'a [u8]);
 
 	fn next(&mut self) -> Option<Self::Item> {
-		use sp_trie::Recorder;
-
-		let mut recorder = Recorder::<LayoutV0<Blake2Hasher>>::new();
-		let res = {
-			let trie = TrieDBBuilder::new(&self.trie_storage, &self.root)
-				.with_recorder(&mut recorder)
-				.build();
-
-			(self.current_pos as u32).using_encoded(|s| trie.get(s))
-		};
-
-		match res.expect("all nodes in trie present; qed") {
-			Some(_) => {
-				let nodes: Vec<Vec<u8>> = recorder.drain().into_iter().map(|r| r.data).collect();
-				let chunk = self.chunks.get(self.current_pos).expect(
-					"there is a one-to-one mapping of chunks to valid merkle branches; qed",
-				);
-				self.current_pos += 1;
-				Proof::try_from(nodes).ok().map(|proof| (proof, chunk.as_ref()))
-			},
-			None => None,
+		let (trie_storage, root, n_chunks) = (&self.trie_storage, &self.root, self.chunks.len());
+		let branch = self
+			.branches
+			.get_or_insert_with(|| all_branches(trie_storage, root, n_chunks).into_iter())
+			.next()?;
+
+		let chunk = self
+			.chunks
+			.get(self.current_pos)
+			.expect("there is a one-to-one mapping of chunks to valid merkle branches; qed");
+		self.current_pos += 1;
+		branch.map(|proof| (proof, chunk.as_ref()))
+	}
+}
+
+/// Collect the merkle branches of all `n_chunks` chunks in a single depth-first traversal of the
+/// trie, instead of a recorded lookup per chunk.
+///
+/// A branch is `None` if it doesn't fit in a [`Proof`].
+fn all_branches(
+	trie_storage: &MemoryDB<Blake2Hasher>,
+	root: &H256,
+	n_chunks: usize,
+) -> Vec<Option<Proof>> {
+	let trie = TrieDBBuilder::new(trie_storage, root).build();
+	let mut branches: Vec<Option<Proof>> = (0..n_chunks).map(|_| None).collect();
+
+	// The nodes from the root to the current one, with the length of their key prefix. Inline
+	// nodes are part of the encoding of their parent, so they aren't part of a branch themselves.
+	let mut path: Vec<(usize, Option<Vec<u8>>)> = Vec::new();
+	let nodes = TrieDBNodeIterator::new(&trie).expect("all nodes in trie present; qed");
+	for item in nodes {
+		let (prefix, hash, node) = item.expect("all nodes in trie present; qed");
+
+		// Nodes are visited depth first, so the ancestors of the current node are the ones on the
+		// path with a shorter key prefix.
+		while path.last().map_or(false, |(prefix_len, _)| *prefix_len >= prefix.len()) {
+			path.pop();
+		}
+		path.push((prefix.len(), hash.map(|_| node.data().to_vec())));
+
+		// All keys are encoded `u32`s of the same length, so only leaves hold values.
+		if let Node::Leaf(partial, _) = node.node() {
+			let nibbles: Vec<u8> = (0..prefix.len())
+				.map(|i| prefix.at(i))
+				.chain((0..partial.len()).map(|i| partial.at(i)))
+				.collect();
+			let key: Vec<u8> = nibbles
+				.chunks(2)
+				.map(|pair| pair[0] << 4 | pair.get(1).copied().unwrap_or_default())
+				.collect();
+			let index = u32::decode(&mut &key[..]).expect("keys are encoded chunk indices; qed");
+
+			if let Some(branch) = branches.get_mut(index as usize) {
+				let nodes = path.iter().filter_map(|(_, data)| data.clone()).collect::<Vec<_>>();
+				*branch = Proof::try_from(nodes).ok();
+			}
 		}
 	}
+
+	branches
 }
 
 /// Construct a trie from chunks of an erasure-coded value. This returns the root hash and an
@@ -270,7 +515,7 @@ where
 		}
 	}
 
-	Branches { trie_storage, root, chunks, current_pos: 0 }
+	Branches { trie_storage, root, chunks, branches: None, current_pos: 0 }
 }
 
 /// Verify a merkle branch, yielding the chunk hash meant to be present at that
@@ -294,6 +539,59 @@ pub fn branch_hash(root: &H256, branch_nodes: &Proof, index: usize) -> Result<H2
 	}
 }
 
+/// Verify a batch of chunks against the same erasure root.
+///
+/// Takes `(index, chunk, proof)` triples and returns one result per chunk, in the same order.
+/// A chunk is valid if its proof is a valid merkle branch for its index and the proven hash is
+/// the hash of the chunk; the outcome is the same as checking each chunk with [`branch_hash`].
+///
+/// All proofs are loaded into a single trie, so nodes shared between branches are only hashed
+/// once. Lookups are recorded to make sure every chunk is proven by its own branch and not by
+/// nodes supplied along with other chunks.
+pub fn verify_chunks<'a, I>(root: &H256, chunks: I) -> Vec<Result<(), Error>>
+where
+	I: IntoIterator<Item = (usize, &'a [u8], &'a Proof)>,
+{
+	let chunks: Vec<_> = chunks.into_iter().collect();
+
+	let mut trie_storage: MemoryDB<Blake2Hasher> = MemoryDB::default();
+	let mut inserted_nodes = HashSet::new();
+	for node in chunks.iter().flat_map(|(_, _, proof)| proof.iter()) {
+		if inserted_nodes.insert(node) {
+			(&mut trie_storage as &mut sp_trie::HashDB<_>).insert(EMPTY_PREFIX, node);
+		}
+	}
+
+	chunks
+		.into_iter()
+		.map(|(index, chunk, proof)| {
+			let mut recorder = Recorder::<LayoutV0<Blake2Hasher>>::new();
+			let res = {
+				let trie =
+					TrieDBBuilder::new(&trie_storage, root).with_recorder(&mut recorder).build();
+
+				(index as u32).using_encoded(|key| {
+					trie.get_with(key, |raw_hash: &[u8]| H256::decode(&mut &raw_hash[..]))
+				})
+			};
+
+			// every node visited by the lookup must come from the chunk's own branch.
+			let branch_nodes: HashSet<&[u8]> = proof.iter().collect();
+			if recorder.drain().iter().any(|record| !branch_nodes.contains(&record.data[..])) {
+				return Err(Error::InvalidBranchProof)
+			}
+
+			match res {
+				Ok(Some(Ok(hash))) if hash == BlakeTwo256::hash(chunk) => Ok(()),
+				Ok(Some(Ok(_))) => Err(Error::ChunkHashMismatch),
+				Ok(Some(Err(_))) => Err(Error::InvalidBranchProof), // hash failed to decode
+				Ok(None) => Err(Error::BranchOutOfBounds),
+				Err(_) => Err(Error::InvalidBranchProof),
+			}
+		})
+		.collect()
+}
+
 // input for `codec` which draws data from the data shards
 struct ShardInput<'a, I> {
 	remaining_len: usize,
@@ -379,6 +677,155 @@ mod tests {
 		assert_eq!(reconstructed, available_data);
 	}
 
+	#[test]
+	fn systematic_round_trip_works() {
+		let pov = PoV { block_data: BlockData((0..255).collect()) };
+		let available_data = AvailableData { pov: pov.into(), validation_data: Default::default() };
+
+		for n_validators in [2, 4, 5, 10, 100, 1000] {
+			let chunks = obtain_chunks(n_validators, &available_data).unwrap();
+			let threshold = systematic_recovery_threshold(n_validators).unwrap();
+			assert!(threshold <= recovery_threshold(n_validators).unwrap());
+
+			let reconstructed =
+				reconstruct_from_systematic_v1(n_validators, chunks[..threshold].to_vec()).unwrap();
+			assert_eq!(reconstructed, available_data);
+
+			assert_eq!(
+				reconstruct_from_systematic_v1(n_validators, chunks[..threshold - 1].to_vec()),
+				Err(Error::NotEnoughChunks),
+			);
+		}
+	}
+
+	#[test]
+	fn reconstructor_works() {
+		let pov = PoV { block_data: BlockData((0..255).collect()) };
+		let available_data = AvailableData { pov: pov.into(), validation_data: Default::default() };
+		let chunks = obtain_chunks(10, &available_data).unwrap();
+		let branches = branches(chunks.as_ref());
+		let root = branches.root();
+		let proofs: Vec<_> = branches.map(|(proof, _)| proof).collect();
+
+		let mut reconstructor = Reconstructor::new(10, root).unwrap();
+
+		// A chunk not matching its proof is rejected.
+		assert_eq!(
+			reconstructor.add_chunk(1, chunks[2].clone(), &proofs[1]),
+			Err(Error::ChunkHashMismatch),
+		);
+		assert_eq!(
+			reconstructor.add_chunk(10, chunks[1].clone(), &proofs[1]),
+			Err(Error::ChunkIndexOutOfBounds { chunk_index: 10, n_validators: 10 }),
+		);
+
+		// Any 4 chunks should work, duplicates are not counted.
+		assert_eq!(reconstructor.add_chunk(1, chunks[1].clone(), &proofs[1]), Ok(false));
+		assert_eq!(reconstructor.add_chunk(1, chunks[1].clone(), &proofs[1]), Ok(false));
+		assert_eq!(reconstructor.add_chunk(4, chunks[4].clone(), &proofs[4]), Ok(false));
+		assert_eq!(reconstructor.add_chunk(6, chunks[6].clone(), &proofs[6]), Ok(false));
+		assert_eq!(reconstructor.received_count(), 3);
+		assert!(!reconstructor.can_reconstruct());
+
+		assert_eq!(reconstructor.add_chunk(9, chunks[9].clone(), &proofs[9]), Ok(true));
+		assert!(reconstructor.can_reconstruct());
+
+		assert_eq!(reconstructor.reconstruct_v1().unwrap(), available_data);
+	}
+
+	#[test]
+	fn reconstructor_needs_enough_chunks() {
+		let pov = PoV { block_data: BlockData((0..255).collect()) };
+		let available_data = AvailableData { pov: pov.into(), validation_data: Default::default() };
+		let chunks = obtain_chunks(10, &available_data).unwrap();
+		let branches = branches(chunks.as_ref());
+		let root = branches.root();
+		let proofs: Vec<_> = branches.map(|(proof, _)| proof).collect();
+
+		let mut reconstructor = Reconstructor::new(10, root).unwrap();
+		for i in 0..3 {
+			assert_eq!(reconstructor.add_chunk(i, chunks[i].clone(), &proofs[i]), Ok(false));
+		}
+
+		assert_eq!(reconstructor.reconstruct_v1(), Err(Error::NotEnoughChunks));
+	}
+
+	#[test]
+	fn obtain_chunks_with_branches_matches_branches() {
+		let pov = PoV { block_data: BlockData((0..255).collect()) };
+		let available_data = AvailableData { pov: pov.into(), validation_data: Default::default() };
+
+		let encoded = obtain_chunks_with_branches_v1(10, &available_data).unwrap();
+		let chunks = obtain_chunks(10, &available_data).unwrap();
+		let branches = branches(chunks.as_ref());
+
+		assert_eq!(encoded.root, branches.root());
+		assert_eq!(encoded.chunks, chunks);
+		assert_eq!(encoded.proofs, branches.map(|(proof, _)| proof).collect::<Vec<_>>());
+	}
+
+	#[test]
+	fn branches_match_recorded_lookups() {
+		let pov = PoV { block_data: BlockData((0..255).collect()) };
+		let available_data = AvailableData { pov: pov.into(), validation_data: Default::default() };
+
+		for n_validators in [1, 2, 10, 17, 300] {
+			let chunks = obtain_chunks(n_validators, &available_data).unwrap();
+			let branches = branches(chunks.as_ref());
+
+			let recorded: Vec<Proof> = (0..n_validators)
+				.map(|i| {
+					let mut recorder = Recorder::<LayoutV0<Blake2Hasher>>::new();
+					let trie = TrieDBBuilder::new(&branches.trie_storage, &branches.root)
+						.with_recorder(&mut recorder)
+						.build();
+					(i as u32).using_encoded(|key| trie.get(key)).unwrap().unwrap();
+					drop(trie);
+					let nodes: Vec<Vec<u8>> =
+						recorder.drain().into_iter().map(|record| record.data).collect();
+					Proof::try_from(nodes).unwrap()
+				})
+				.collect();
+
+			assert_eq!(branches.map(|(proof, _)| proof).collect::<Vec<_>>(), recorded);
+		}
+	}
+
+	#[test]
+	fn verify_chunks_works() {
+		let pov = PoV { block_data: BlockData((0..255).collect()) };
+		let available_data = AvailableData { pov: pov.into(), validation_data: Default::default() };
+		let ChunksWithBranches { root, chunks, proofs } =
+			obtain_chunks_with_branches_v1(10, &available_data).unwrap();
+
+		let results = verify_chunks(
+			&root,
+			chunks.iter().zip(proofs.iter()).enumerate().map(|(i, (c, p))| (i, &c[..], p)),
+		);
+		assert_eq!(results, vec![Ok(()); 10]);
+
+		let mut bad_chunk = chunks[3].clone();
+		bad_chunk[0] ^= 1;
+
+		let results = verify_chunks(
+			&root,
+			vec![
+				(0, &chunks[0][..], &proofs[0]),
+				(3, &bad_chunk[..], &proofs[3]),
+				// a branch belonging to another chunk.
+				(5, &chunks[5][..], &proofs[6]),
+				(6, &chunks[6][..], &proofs[6]),
+			],
+		);
+		assert_eq!(
+			results,
+			vec![Ok(()), Err(Error::ChunkHashMismatch), Err(Error::InvalidBranchProof), Ok(())],
+		);
+
+		let results = verify_chunks(&H256::repeat_byte(1), vec![(0, &chunks[0][..], &proofs[0])]);
+		assert_eq!(results, vec![Err(Error::InvalidBranchProof)]);
+	}
+
 	#[test]
 	fn reconstruct_does_not_panic_on_low_validator_count() {
 		let reconstructed = reconstruct_v1(1, [].iter().cloned());
@@ -17,7 +17,7 @@
 //! Version 1 of the DB schema.
 
 use parity_scale_codec::{Decode, Encode};
-use polkadot_node_primitives::approval::{AssignmentCert, DelayTranche};
+use polkadot_node_primitives::approval::{AssignmentCertV2, DelayTranche};
 use polkadot_node_subsystem::{SubsystemError, SubsystemResult};
 use polkadot_node_subsystem_util::database::{DBTransaction, Database};
 use polkadot_primitives::{
@@ -27,7 +27,10 @@ use polkadot_primitives::{
 use sp_consensus_slots::Slot;
 
 use bitvec::{order::Lsb0 as BitOrderLsb0, vec::BitVec};
-use std::{collections::BTreeMap, sync::Arc};
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	sync::Arc,
+};
 
 use crate::{
 	backend::{Backend, BackendWriteOp},
@@ -161,7 +164,9 @@ pub struct Config {
 /// Details pertaining to our assignment on a block.
 #[derive(Encode, Decode, Debug, Clone, PartialEq)]
 pub struct OurAssignment {
-	pub cert: AssignmentCert,
+	/// The cert. `AssignmentCertV2` shares the encoding of the `AssignmentCert` kinds stored
+	/// before compact assignments were introduced.
+	pub cert: AssignmentCertV2,
 	pub tranche: DelayTranche,
 	pub validator_index: ValidatorIndex,
 	// Whether the assignment has been triggered already.
@@ -304,6 +309,42 @@ pub fn load_all_blocks(store: &dyn Database, config: &Config) -> SubsystemResult
 	Ok(hashes)
 }
 
+/// Return the keys which entries of the DB may be stored at, found by walking from the stored
+/// block range through the blocks at each height to the block entries and the entries of their
+/// candidates.
+///
+/// Database backends like ParityDB don't keep the keys of the approval voting column, but they
+/// are needed to export it.
+pub fn stored_keys(store: &dyn Database, col_approval_data: u32) -> SubsystemResult<Vec<Vec<u8>>> {
+	fn load<D: Decode>(store: &dyn Database, col: u32, key: &[u8]) -> SubsystemResult<Option<D>> {
+		load_decode(store, col, key).map_err(|e| SubsystemError::with_origin("approval-voting", e))
+	}
+
+	let stored_blocks: StoredBlockRange = match load(store, col_approval_data, STORED_BLOCKS_KEY)? {
+		Some(stored_blocks) => stored_blocks,
+		None => return Ok(Vec::new()),
+	};
+
+	let mut keys = vec![STORED_BLOCKS_KEY.to_vec()];
+	// Candidates may be included under several blocks.
+	let mut candidates = BTreeSet::new();
+	for height in stored_blocks.0..stored_blocks.1 {
+		let height_key = blocks_at_height_key(height);
+		let blocks: Vec<Hash> = load(store, col_approval_data, &height_key)?.unwrap_or_default();
+		keys.push(height_key.to_vec());
+		for block_hash in blocks {
+			let entry_key = block_entry_key(&block_hash);
+			if let Some(block_entry) = load::<BlockEntry>(store, col_approval_data, &entry_key)? {
+				candidates.extend(block_entry.candidates.into_iter().map(|(_, hash)| hash));
+			}
+			keys.push(entry_key.to_vec());
+		}
+	}
+	keys.extend(candidates.iter().map(|hash| candidate_entry_key(hash).to_vec()));
+
+	Ok(keys)
+}
+
 /// Load the stored-blocks key from the state.
 pub fn load_stored_blocks(
 	store: &dyn Database,
@@ -18,7 +18,8 @@
 
 use parity_scale_codec::{Decode, Encode};
 use polkadot_node_primitives::approval::{
-	self as approval_types, AssignmentCert, AssignmentCertKind, DelayTranche, RelayVRFStory,
+	self as approval_types, AssignmentCertKindV2, AssignmentCertV2, CoreBitfield, DelayTranche,
+	RelayVRFStory,
 };
 use polkadot_primitives::{
 	AssignmentId, AssignmentPair, CandidateHash, CoreIndex, GroupIndex, IndexedVec, SessionInfo,
@@ -37,7 +38,7 @@ use super::LOG_TARGET;
 /// Details pertaining to our assignment on a block.
 #[derive(Debug, Clone, Encode, Decode, PartialEq)]
 pub struct OurAssignment {
-	cert: AssignmentCert,
+	cert: AssignmentCertV2,
 	tranche: DelayTranche,
 	validator_index: ValidatorIndex,
 	// Whether the assignment has been triggered already.
@@ -45,7 +46,7 @@ pub struct OurAssignment {
 }
 
 impl OurAssignment {
-	pub(crate) fn cert(&self) -> &AssignmentCert {
+	pub(crate) fn cert(&self) -> &AssignmentCertV2 {
 		&self.cert
 	}
 
@@ -105,6 +106,34 @@ fn relay_vrf_modulo_core(vrf_in_out: &VRFInOut, n_cores: u32) -> CoreIndex {
 	CoreIndex(random_core)
 }
 
+fn relay_vrf_modulo_compact_transcript(relay_vrf_story: RelayVRFStory) -> Transcript {
+	let mut t = Transcript::new(approval_types::RELAY_VRF_MODULO_COMPACT_CONTEXT);
+	t.append_message(b"RC-VRF", &relay_vrf_story.0);
+	t
+}
+
+/// Derive `num_samples` cores from a single VRF output. Duplicates are only kept once.
+fn relay_vrf_modulo_cores(vrf_in_out: &VRFInOut, num_samples: u32, n_cores: u32) -> Vec<CoreIndex> {
+	let seed: [u8; 32] = vrf_in_out.make_bytes(approval_types::CORE_RANDOMNESS_CONTEXT);
+
+	let mut t = Transcript::new(approval_types::CORE_RANDOMNESS_CONTEXT);
+	t.append_message(b"seed", &seed);
+
+	let mut cores = Vec::with_capacity(num_samples as usize);
+	for _ in 0..num_samples {
+		let mut bytes = [0u8; 4];
+		t.challenge_bytes(b"core", &mut bytes);
+
+		// interpret as little-endian u32.
+		let core = CoreIndex(u32::from_le_bytes(bytes) % n_cores);
+		if !cores.contains(&core) {
+			cores.push(core);
+		}
+	}
+
+	cores
+}
+
 fn relay_vrf_delay_transcript(relay_vrf_story: RelayVRFStory, core_index: CoreIndex) -> Transcript {
 	let mut t = Transcript::new(approval_types::RELAY_VRF_DELAY_CONTEXT);
 	t.append_message(b"RC-VRF", &relay_vrf_story.0);
@@ -133,6 +162,12 @@ fn assigned_core_transcript(core_index: CoreIndex) -> Transcript {
 	t
 }
 
+fn assigned_cores_transcript(core_bitfield: &CoreBitfield) -> Transcript {
+	let mut t = Transcript::new(approval_types::ASSIGNED_CORES_CONTEXT);
+	core_bitfield.using_encoded(|s| t.append_message(b"cores", s));
+	t
+}
+
 /// Information about the world assignments are being produced in.
 #[derive(Clone)]
 pub(crate) struct Config {
@@ -171,16 +206,17 @@ pub(crate) trait AssignmentCriteria {
 		relay_vrf_story: RelayVRFStory,
 		config: &Config,
 		leaving_cores: Vec<(CandidateHash, CoreIndex, GroupIndex)>,
+		enable_v2_assignments: bool,
 	) -> HashMap<CoreIndex, OurAssignment>;
 
 	fn check_assignment_cert(
 		&self,
-		claimed_core_index: CoreIndex,
+		claimed_core_indices: CoreBitfield,
 		validator_index: ValidatorIndex,
 		config: &Config,
 		relay_vrf_story: RelayVRFStory,
-		assignment: &AssignmentCert,
-		backing_group: GroupIndex,
+		assignment: &AssignmentCertV2,
+		backing_groups: Vec<GroupIndex>,
 	) -> Result<DelayTranche, InvalidAssignment>;
 }
 
@@ -193,26 +229,27 @@ impl AssignmentCriteria for RealAssignmentCriteria {
 		relay_vrf_story: RelayVRFStory,
 		config: &Config,
 		leaving_cores: Vec<(CandidateHash, CoreIndex, GroupIndex)>,
+		enable_v2_assignments: bool,
 	) -> HashMap<CoreIndex, OurAssignment> {
-		compute_assignments(keystore, relay_vrf_story, config, leaving_cores)
+		compute_assignments(keystore, relay_vrf_story, config, leaving_cores, enable_v2_assignments)
 	}
 
 	fn check_assignment_cert(
 		&self,
-		claimed_core_index: CoreIndex,
+		claimed_core_indices: CoreBitfield,
 		validator_index: ValidatorIndex,
 		config: &Config,
 		relay_vrf_story: RelayVRFStory,
-		assignment: &AssignmentCert,
-		backing_group: GroupIndex,
+		assignment: &AssignmentCertV2,
+		backing_groups: Vec<GroupIndex>,
 	) -> Result<DelayTranche, InvalidAssignment> {
 		check_assignment_cert(
-			claimed_core_index,
+			claimed_core_indices,
 			validator_index,
 			config,
 			relay_vrf_story,
 			assignment,
-			backing_group,
+			backing_groups,
 		)
 	}
 }
@@ -227,11 +264,16 @@ impl AssignmentCriteria for RealAssignmentCriteria {
 /// The idea is that most assignments are never triggered and fall by the wayside.
 ///
 /// This will not assign to anything the local validator was part of the backing group for.
+///
+/// If `enable_v2_assignments` is set, the tranche-0 assignments are produced by a single
+/// `RelayVRFModuloCompact` cert covering all the cores it samples, instead of one
+/// `RelayVRFModulo` cert per sample.
 pub(crate) fn compute_assignments(
 	keystore: &LocalKeystore,
 	relay_vrf_story: RelayVRFStory,
 	config: &Config,
 	leaving_cores: impl IntoIterator<Item = (CandidateHash, CoreIndex, GroupIndex)> + Clone,
+	enable_v2_assignments: bool,
 ) -> HashMap<CoreIndex, OurAssignment> {
 	if config.n_cores == 0 ||
 		config.assignment_keys.is_empty() ||
@@ -289,15 +331,26 @@ pub(crate) fn compute_assignments(
 
 	let mut assignments = HashMap::new();
 
-	// First run `RelayVRFModulo` for each sample.
-	compute_relay_vrf_modulo_assignments(
-		&assignments_key,
-		index,
-		config,
-		relay_vrf_story.clone(),
-		leaving_cores.iter().cloned(),
-		&mut assignments,
-	);
+	// First run `RelayVRFModulo` for each sample, or `RelayVRFModuloCompact` once.
+	if enable_v2_assignments {
+		compute_relay_vrf_modulo_compact_assignments(
+			&assignments_key,
+			index,
+			config,
+			relay_vrf_story.clone(),
+			&leaving_cores,
+			&mut assignments,
+		);
+	} else {
+		compute_relay_vrf_modulo_assignments(
+			&assignments_key,
+			index,
+			config,
+			relay_vrf_story.clone(),
+			leaving_cores.iter().cloned(),
+			&mut assignments,
+		);
+	}
 
 	// Then run `RelayVRFDelay` once for the whole block.
 	compute_relay_vrf_delay_assignments(
@@ -354,8 +407,8 @@ fn compute_relay_vrf_modulo_assignments(
 		if let Some((vrf_in_out, vrf_proof, _)) = maybe_assignment {
 			// Sanity: `core` is always initialized to non-default here, as the closure above
 			// has been executed.
-			let cert = AssignmentCert {
-				kind: AssignmentCertKind::RelayVRFModulo { sample: rvm_sample },
+			let cert = AssignmentCertV2 {
+				kind: AssignmentCertKindV2::RelayVRFModulo { sample: rvm_sample },
 				vrf: approval_types::VrfSignature {
 					output: approval_types::VrfOutput(vrf_in_out.to_output()),
 					proof: approval_types::VrfProof(vrf_proof),
@@ -373,6 +426,71 @@ fn compute_relay_vrf_modulo_assignments(
 	}
 }
 
+fn compute_relay_vrf_modulo_compact_assignments(
+	assignments_key: &schnorrkel::Keypair,
+	validator_index: ValidatorIndex,
+	config: &Config,
+	relay_vrf_story: RelayVRFStory,
+	leaving_cores: &[(CandidateHash, CoreIndex)],
+	assignments: &mut HashMap<CoreIndex, OurAssignment>,
+) {
+	let mut assigned_cores = Vec::new();
+
+	let maybe_assignment = {
+		// Extra scope to ensure borrowing instead of moving assigned cores
+		// into closure.
+		let assigned_cores = &mut assigned_cores;
+		assignments_key.vrf_sign_extra_after_check(
+			relay_vrf_modulo_compact_transcript(relay_vrf_story),
+			|vrf_in_out| {
+				*assigned_cores = relay_vrf_modulo_cores(
+					&vrf_in_out,
+					config.relay_vrf_modulo_samples,
+					config.n_cores,
+				)
+				.into_iter()
+				.filter(|core| leaving_cores.iter().any(|(_, c)| c == core))
+				.collect::<Vec<_>>();
+
+				let core_bitfield = CoreBitfield::try_from(assigned_cores.clone()).ok()?;
+
+				gum::trace!(
+					target: LOG_TARGET,
+					?assigned_cores,
+					?validator_index,
+					tranche = 0,
+					"RelayVRFModuloCompact Assignment."
+				);
+
+				Some(assigned_cores_transcript(&core_bitfield))
+			},
+		)
+	};
+
+	if let Some((vrf_in_out, vrf_proof, _)) = maybe_assignment {
+		let core_bitfield = CoreBitfield::try_from(assigned_cores.clone())
+			.expect("The assignment is only produced for a non-empty set of cores; qed");
+
+		let cert = AssignmentCertV2 {
+			kind: AssignmentCertKindV2::RelayVRFModuloCompact { core_bitfield },
+			vrf: approval_types::VrfSignature {
+				output: approval_types::VrfOutput(vrf_in_out.to_output()),
+				proof: approval_types::VrfProof(vrf_proof),
+			},
+		};
+
+		// All assignments of type RelayVRFModuloCompact have tranche 0.
+		for core in assigned_cores {
+			assignments.entry(core).or_insert(OurAssignment {
+				cert: cert.clone(),
+				tranche: 0,
+				validator_index,
+				triggered: false,
+			});
+		}
+	}
+}
+
 fn compute_relay_vrf_delay_assignments(
 	assignments_key: &schnorrkel::Keypair,
 	validator_index: ValidatorIndex,
@@ -391,8 +509,8 @@ fn compute_relay_vrf_delay_assignments(
 			config.zeroth_delay_tranche_width,
 		);
 
-		let cert = AssignmentCert {
-			kind: AssignmentCertKind::RelayVRFDelay { core_index: core },
+		let cert = AssignmentCertV2 {
+			kind: AssignmentCertKindV2::RelayVRFDelay { core_index: core },
 			vrf: approval_types::VrfSignature {
 				output: approval_types::VrfOutput(vrf_in_out.to_output()),
 				proof: approval_types::VrfProof(vrf_proof),
@@ -452,26 +570,33 @@ pub(crate) enum InvalidAssignmentReason {
 	VRFModuloOutputMismatch,
 	VRFDelayCoreIndexMismatch,
 	VRFDelayOutputMismatch,
+	VRFModuloCompactCoreIndexMismatch,
+	VRFModuloCompactOutputMismatch,
+	InvalidArguments,
 }
 
 /// Checks the crypto of an assignment cert. Failure conditions:
 ///   * Validator index out of bounds
+///   * No claimed core, or not exactly one backing group per claimed core
 ///   * VRF signature check fails
-///   * VRF output doesn't match assigned core
-///   * Core is not covered by extra data in signature
+///   * VRF output doesn't match assigned cores
+///   * Cores are not covered by extra data in signature
 ///   * Core index out of bounds
 ///   * Sample is out of bounds
-///   * Validator is present in backing group.
+///   * Validator is present in a backing group.
+///
+/// `RelayVRFModulo` and `RelayVRFDelay` certs claim exactly one core, `RelayVRFModuloCompact`
+/// certs claim exactly the cores of their bitfield.
 ///
-/// This function does not check whether the core is actually a valid assignment or not. That should be done
-/// outside the scope of this function.
+/// This function does not check whether the cores are actually a valid assignment or not. That
+/// should be done outside the scope of this function.
 pub(crate) fn check_assignment_cert(
-	claimed_core_index: CoreIndex,
+	claimed_core_indices: CoreBitfield,
 	validator_index: ValidatorIndex,
 	config: &Config,
 	relay_vrf_story: RelayVRFStory,
-	assignment: &AssignmentCert,
-	backing_group: GroupIndex,
+	assignment: &AssignmentCertV2,
+	backing_groups: Vec<GroupIndex>,
 ) -> Result<DelayTranche, InvalidAssignment> {
 	use InvalidAssignmentReason as Reason;
 
@@ -483,22 +608,62 @@ pub(crate) fn check_assignment_cert(
 	let public = schnorrkel::PublicKey::from_bytes(validator_public.as_slice())
 		.map_err(|_| InvalidAssignment(Reason::InvalidAssignmentKey))?;
 
-	if claimed_core_index.0 >= config.n_cores {
+	if claimed_core_indices.count_ones() == 0 ||
+		claimed_core_indices.count_ones() != backing_groups.len()
+	{
+		return Err(InvalidAssignment(Reason::InvalidArguments))
+	}
+
+	if claimed_core_indices.iter_ones().any(|core| core.0 >= config.n_cores) {
 		return Err(InvalidAssignment(Reason::CoreIndexOutOfBounds))
 	}
 
-	// Check that the validator was not part of the backing group
+	// Check that the validator was not part of any backing group
 	// and not already assigned.
-	let is_in_backing =
-		is_in_backing_group(&config.validator_groups, validator_index, backing_group);
+	let is_in_backing = backing_groups
+		.iter()
+		.any(|group| is_in_backing_group(&config.validator_groups, validator_index, *group));
 
 	if is_in_backing {
 		return Err(InvalidAssignment(Reason::IsInBackingGroup))
 	}
 
 	let vrf_signature = &assignment.vrf;
-	match assignment.kind {
-		AssignmentCertKind::RelayVRFModulo { sample } => {
+	match &assignment.kind {
+		AssignmentCertKindV2::RelayVRFModuloCompact { core_bitfield } => {
+			if !claimed_core_indices.iter_ones().eq(core_bitfield.iter_ones()) {
+				return Err(InvalidAssignment(Reason::VRFModuloCompactCoreIndexMismatch))
+			}
+
+			let (vrf_in_out, _) = public
+				.vrf_verify_extra(
+					relay_vrf_modulo_compact_transcript(relay_vrf_story),
+					&vrf_signature.output.0,
+					&vrf_signature.proof.0,
+					assigned_cores_transcript(core_bitfield),
+				)
+				.map_err(|_| InvalidAssignment(Reason::VRFModuloCompactOutputMismatch))?;
+
+			// ensure that the `vrf_in_out` actually gives us all the claimed cores.
+			let sampled_cores = relay_vrf_modulo_cores(
+				&vrf_in_out,
+				config.relay_vrf_modulo_samples,
+				config.n_cores,
+			);
+			if claimed_core_indices.iter_ones().all(|core| sampled_cores.contains(&core)) {
+				Ok(0)
+			} else {
+				Err(InvalidAssignment(Reason::VRFModuloCompactCoreIndexMismatch))
+			}
+		},
+		_ if claimed_core_indices.count_ones() != 1 =>
+			Err(InvalidAssignment(Reason::InvalidArguments)),
+		AssignmentCertKindV2::RelayVRFModulo { sample } => {
+			let sample = *sample;
+			let claimed_core_index = claimed_core_indices
+				.first_one()
+				.expect("Exactly one core is claimed, checked above; qed");
+
 			if sample >= config.relay_vrf_modulo_samples {
 				return Err(InvalidAssignment(Reason::SampleOutOfBounds))
 			}
@@ -519,7 +684,12 @@ pub(crate) fn check_assignment_cert(
 				Err(InvalidAssignment(Reason::VRFModuloCoreIndexMismatch))
 			}
 		},
-		AssignmentCertKind::RelayVRFDelay { core_index } => {
+		AssignmentCertKindV2::RelayVRFDelay { core_index } => {
+			let core_index = *core_index;
+			let claimed_core_index = claimed_core_indices
+				.first_one()
+				.expect("Exactly one core is claimed, checked above; qed");
+
 			if core_index != claimed_core_index {
 				return Err(InvalidAssignment(Reason::VRFDelayCoreIndexMismatch))
 			}
@@ -633,6 +803,7 @@ mod tests {
 				n_delay_tranches: 40,
 			},
 			vec![(c_a, CoreIndex(0), GroupIndex(1)), (c_b, CoreIndex(1), GroupIndex(0))],
+			false,
 		);
 
 		// Note that alice is in group 0, which was the backing group for core 1.
@@ -668,12 +839,68 @@ mod tests {
 				n_delay_tranches: 40,
 			},
 			vec![(c_a, CoreIndex(0), GroupIndex(0)), (c_b, CoreIndex(1), GroupIndex(1))],
+			false,
 		);
 
 		assert_eq!(assignments.len(), 1);
 		assert!(assignments.get(&CoreIndex(1)).is_some());
 	}
 
+	#[test]
+	fn compact_assignments_produced_for_non_backing() {
+		let keystore = make_keystore(&[Sr25519Keyring::Alice]);
+
+		let c_a = CandidateHash(Hash::repeat_byte(0));
+		let c_b = CandidateHash(Hash::repeat_byte(1));
+		let c_c = CandidateHash(Hash::repeat_byte(2));
+
+		let relay_vrf_story = RelayVRFStory([42u8; 32]);
+		let assignments = compute_assignments(
+			&keystore,
+			relay_vrf_story,
+			&Config {
+				assignment_keys: assignment_keys(&[
+					Sr25519Keyring::Alice,
+					Sr25519Keyring::Bob,
+					Sr25519Keyring::Charlie,
+				]),
+				validator_groups: IndexedVec::<GroupIndex, Vec<ValidatorIndex>>::from(vec![
+					vec![ValidatorIndex(0)],
+					vec![ValidatorIndex(1), ValidatorIndex(2)],
+				]),
+				n_cores: 3,
+				zeroth_delay_tranche_width: 10,
+				relay_vrf_modulo_samples: 3,
+				n_delay_tranches: 40,
+			},
+			vec![
+				(c_a, CoreIndex(0), GroupIndex(1)),
+				(c_b, CoreIndex(1), GroupIndex(0)),
+				(c_c, CoreIndex(2), GroupIndex(1)),
+			],
+			true,
+		);
+
+		// Alice is in group 0, which was the backing group for core 1.
+		assert!(!assignments.is_empty());
+		assert!(assignments.get(&CoreIndex(1)).is_none());
+
+		for (core, assignment) in &assignments {
+			match &assignment.cert().kind {
+				AssignmentCertKindV2::RelayVRFModuloCompact { core_bitfield } => {
+					assert_eq!(assignment.tranche(), 0);
+					assert!(core_bitfield.bit_at(*core));
+					assert!(!core_bitfield.bit_at(CoreIndex(1)));
+				},
+				AssignmentCertKindV2::RelayVRFDelay { core_index } => {
+					assert_eq!(core_index, core);
+				},
+				AssignmentCertKindV2::RelayVRFModulo { .. } =>
+					panic!("No `RelayVRFModulo` assignments with v2 assignments enabled"),
+			}
+		}
+	}
+
 	#[test]
 	fn succeeds_empty_for_0_cores() {
 		let keystore = make_keystore(&[Sr25519Keyring::Alice]);
@@ -695,15 +922,16 @@ mod tests {
 				n_delay_tranches: 40,
 			},
 			vec![],
+			false,
 		);
 
 		assert!(assignments.is_empty());
 	}
 
 	struct MutatedAssignment {
-		core: CoreIndex,
-		cert: AssignmentCert,
-		group: GroupIndex,
+		cores: CoreBitfield,
+		cert: AssignmentCertV2,
+		groups: Vec<GroupIndex>,
 		own_group: GroupIndex,
 		val_index: ValidatorIndex,
 		config: Config,
@@ -714,6 +942,7 @@ mod tests {
 		n_validators: usize,
 		n_cores: usize,
 		rotation_offset: usize,
+		enable_v2_assignments: bool,
 		f: impl Fn(&mut MutatedAssignment) -> Option<bool>, // None = skip
 	) {
 		let keystore = make_keystore(&[Sr25519Keyring::Alice]);
@@ -746,13 +975,21 @@ mod tests {
 					)
 				})
 				.collect::<Vec<_>>(),
+			enable_v2_assignments,
 		);
 
 		let mut counted = 0;
 		for (core, assignment) in assignments {
+			// Compact certs claim all the cores they cover.
+			let cores = match &assignment.cert.kind {
+				AssignmentCertKindV2::RelayVRFModuloCompact { core_bitfield } =>
+					core_bitfield.clone(),
+				_ => core.into(),
+			};
+
 			let mut mutated = MutatedAssignment {
-				core,
-				group: group_for_core(core.0 as _),
+				groups: cores.iter_ones().map(|core| group_for_core(core.0 as _)).collect(),
+				cores,
 				cert: assignment.cert,
 				own_group: GroupIndex(0),
 				val_index: ValidatorIndex(0),
@@ -767,12 +1004,12 @@ mod tests {
 			counted += 1;
 
 			let is_good = check_assignment_cert(
-				mutated.core,
+				mutated.cores,
 				mutated.val_index,
 				&mutated.config,
 				relay_vrf_story.clone(),
 				&mutated.cert,
-				mutated.group,
+				mutated.groups,
 			)
 			.is_ok();
 
@@ -784,28 +1021,47 @@ mod tests {
 
 	#[test]
 	fn computed_assignments_pass_checks() {
-		check_mutated_assignments(200, 100, 25, |_| Some(true));
+		check_mutated_assignments(200, 100, 25, false, |_| Some(true));
+	}
+
+	#[test]
+	fn computed_compact_assignments_pass_checks() {
+		check_mutated_assignments(200, 100, 25, true, |_| Some(true));
 	}
 
 	#[test]
 	fn check_rejects_claimed_core_out_of_bounds() {
-		check_mutated_assignments(200, 100, 25, |m| {
-			m.core.0 += 100;
+		check_mutated_assignments(200, 100, 25, false, |m| {
+			m.cores = m
+				.cores
+				.iter_ones()
+				.map(|core| CoreIndex(core.0 + 100))
+				.collect::<Vec<_>>()
+				.try_into()
+				.unwrap();
 			Some(false)
 		});
 	}
 
 	#[test]
 	fn check_rejects_in_backing_group() {
-		check_mutated_assignments(200, 100, 25, |m| {
-			m.group = m.own_group;
+		check_mutated_assignments(200, 100, 25, false, |m| {
+			m.groups[0] = m.own_group;
+			Some(false)
+		});
+	}
+
+	#[test]
+	fn check_rejects_mismatched_backing_groups() {
+		check_mutated_assignments(200, 100, 25, true, |m| {
+			m.groups.push(m.groups[0]);
 			Some(false)
 		});
 	}
 
 	#[test]
 	fn check_rejects_nonexistent_key() {
-		check_mutated_assignments(200, 100, 25, |m| {
+		check_mutated_assignments(200, 100, 25, false, |m| {
 			m.val_index.0 += 200;
 			Some(false)
 		});
@@ -813,9 +1069,9 @@ mod tests {
 
 	#[test]
 	fn check_rejects_delay_bad_vrf() {
-		check_mutated_assignments(40, 10, 8, |m| {
+		check_mutated_assignments(40, 10, 8, false, |m| {
 			match m.cert.kind.clone() {
-				AssignmentCertKind::RelayVRFDelay { .. } => {
+				AssignmentCertKindV2::RelayVRFDelay { .. } => {
 					m.cert.vrf = garbage_vrf_signature();
 					Some(false)
 				},
@@ -826,9 +1082,22 @@ mod tests {
 
 	#[test]
 	fn check_rejects_modulo_bad_vrf() {
-		check_mutated_assignments(200, 100, 25, |m| {
+		check_mutated_assignments(200, 100, 25, false, |m| {
 			match m.cert.kind.clone() {
-				AssignmentCertKind::RelayVRFModulo { .. } => {
+				AssignmentCertKindV2::RelayVRFModulo { .. } => {
+					m.cert.vrf = garbage_vrf_signature();
+					Some(false)
+				},
+				_ => None, // skip everything else.
+			}
+		});
+	}
+
+	#[test]
+	fn check_rejects_modulo_compact_bad_vrf() {
+		check_mutated_assignments(200, 100, 25, true, |m| {
+			match m.cert.kind.clone() {
+				AssignmentCertKindV2::RelayVRFModuloCompact { .. } => {
 					m.cert.vrf = garbage_vrf_signature();
 					Some(false)
 				},
@@ -839,9 +1108,9 @@ mod tests {
 
 	#[test]
 	fn check_rejects_modulo_sample_out_of_bounds() {
-		check_mutated_assignments(200, 100, 25, |m| {
+		check_mutated_assignments(200, 100, 25, false, |m| {
 			match m.cert.kind.clone() {
-				AssignmentCertKind::RelayVRFModulo { sample } => {
+				AssignmentCertKindV2::RelayVRFModulo { sample } => {
 					m.config.relay_vrf_modulo_samples = sample;
 					Some(false)
 				},
@@ -852,10 +1121,10 @@ mod tests {
 
 	#[test]
 	fn check_rejects_delay_claimed_core_wrong() {
-		check_mutated_assignments(200, 100, 25, |m| {
+		check_mutated_assignments(200, 100, 25, false, |m| {
 			match m.cert.kind.clone() {
-				AssignmentCertKind::RelayVRFDelay { .. } => {
-					m.core = CoreIndex((m.core.0 + 1) % 100);
+				AssignmentCertKindV2::RelayVRFDelay { .. } => {
+					m.cores = shift_cores(&m.cores, 1, 100);
 					Some(false)
 				},
 				_ => None, // skip everything else.
@@ -865,14 +1134,62 @@ mod tests {
 
 	#[test]
 	fn check_rejects_modulo_core_wrong() {
-		check_mutated_assignments(200, 100, 25, |m| {
+		check_mutated_assignments(200, 100, 25, false, |m| {
 			match m.cert.kind.clone() {
-				AssignmentCertKind::RelayVRFModulo { .. } => {
-					m.core = CoreIndex((m.core.0 + 1) % 100);
+				AssignmentCertKindV2::RelayVRFModulo { .. } => {
+					m.cores = shift_cores(&m.cores, 1, 100);
 					Some(false)
 				},
 				_ => None, // skip everything else.
 			}
 		});
 	}
+
+	#[test]
+	fn check_rejects_modulo_compact_core_wrong() {
+		check_mutated_assignments(200, 100, 25, true, |m| {
+			match m.cert.kind.clone() {
+				AssignmentCertKindV2::RelayVRFModuloCompact { .. } => {
+					m.cores = shift_cores(&m.cores, 1, 100);
+					Some(false)
+				},
+				_ => None, // skip everything else.
+			}
+		});
+	}
+
+	#[test]
+	fn check_rejects_modulo_compact_claiming_uncovered_core() {
+		check_mutated_assignments(200, 100, 25, true, |m| {
+			match m.cert.kind.clone() {
+				AssignmentCertKindV2::RelayVRFModuloCompact { mut core_bitfield } => {
+					// Re-claim the covered cores plus one more, the VRF doesn't cover the new
+					// bitfield.
+					let mut cores = core_bitfield.iter_ones().collect::<Vec<_>>();
+					let extra = (0..100)
+						.map(CoreIndex)
+						.find(|core| !cores.contains(core))
+						.expect("Less than 100 cores are covered; qed");
+					cores.push(extra);
+					core_bitfield = cores.try_into().unwrap();
+
+					m.groups.push(m.groups[0]);
+					m.cores = core_bitfield.clone();
+					m.cert.kind = AssignmentCertKindV2::RelayVRFModuloCompact { core_bitfield };
+					Some(false)
+				},
+				_ => None, // skip everything else.
+			}
+		});
+	}
+
+	// Shift all the claimed cores by `by`, wrapping around at `n_cores`.
+	fn shift_cores(cores: &CoreBitfield, by: u32, n_cores: u32) -> CoreBitfield {
+		cores
+			.iter_ones()
+			.map(|core| CoreIndex((core.0 + by) % n_cores))
+			.collect::<Vec<_>>()
+			.try_into()
+			.unwrap()
+	}
 }
@@ -81,6 +81,7 @@ struct ImportedBlockInfoEnv<'a> {
 	session_window: &'a Option<RollingSessionWindow>,
 	assignment_criteria: &'a (dyn AssignmentCriteria + Send + Sync),
 	keystore: &'a LocalKeystore,
+	enable_v2_assignments: bool,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -243,6 +244,7 @@ async fn imported_block_info<Context>(
 								.iter()
 								.map(|(c_hash, _, core, group)| (*c_hash, *core, *group))
 								.collect(),
+							env.enable_v2_assignments,
 						);
 
 						(assignments, slot, relay_vrf)
@@ -410,6 +412,7 @@ pub(crate) async fn handle_new_head<Context, B: Backend>(
 				session_window: &state.session_window,
 				assignment_criteria: &*state.assignment_criteria,
 				keystore: &state.keystore,
+				enable_v2_assignments: state.enable_v2_assignments,
 			};
 
 			match imported_block_info(ctx, env, block_hash, &block_header).await {
@@ -656,6 +659,9 @@ pub(crate) mod tests {
 			session_window: None,
 			keystore: Arc::new(LocalKeystore::in_memory()),
 			slot_duration_millis: 6_000,
+			enable_v2_assignments: false,
+			max_approval_coalesce_count: 1,
+			approvals_to_sign: HashMap::new(),
 			clock: Box::new(MockClock::default()),
 			assignment_criteria: Box::new(MockAssignmentCriteria),
 			db,
@@ -684,18 +690,19 @@ pub(crate) mod tests {
 				polkadot_primitives::CoreIndex,
 				polkadot_primitives::GroupIndex,
 			)>,
+			_enable_v2_assignments: bool,
 		) -> HashMap<polkadot_primitives::CoreIndex, criteria::OurAssignment> {
 			HashMap::new()
 		}
 
 		fn check_assignment_cert(
 			&self,
-			_claimed_core_index: polkadot_primitives::CoreIndex,
+			_claimed_core_indices: polkadot_node_primitives::approval::CoreBitfield,
 			_validator_index: polkadot_primitives::ValidatorIndex,
 			_config: &criteria::Config,
 			_relay_vrf_story: polkadot_node_primitives::approval::RelayVRFStory,
-			_assignment: &polkadot_node_primitives::approval::AssignmentCert,
-			_backing_group: polkadot_primitives::GroupIndex,
+			_assignment: &polkadot_node_primitives::approval::AssignmentCertV2,
+			_backing_groups: Vec<polkadot_primitives::GroupIndex>,
 		) -> Result<polkadot_node_primitives::approval::DelayTranche, criteria::InvalidAssignment> {
 			Ok(0)
 		}
@@ -785,6 +792,7 @@ pub(crate) mod tests {
 					session_window: &Some(session_window),
 					assignment_criteria: &MockAssignmentCriteria,
 					keystore: &LocalKeystore::in_memory(),
+					enable_v2_assignments: false,
 				};
 
 				let info = imported_block_info(&mut ctx, env, hash, &header).await.unwrap();
@@ -891,6 +899,7 @@ pub(crate) mod tests {
 					session_window: &Some(session_window),
 					assignment_criteria: &MockAssignmentCriteria,
 					keystore: &LocalKeystore::in_memory(),
+					enable_v2_assignments: false,
 				};
 
 				let info = imported_block_info(&mut ctx, env, hash, &header).await;
@@ -990,6 +999,7 @@ pub(crate) mod tests {
 					session_window: &session_window,
 					assignment_criteria: &MockAssignmentCriteria,
 					keystore: &LocalKeystore::in_memory(),
+					enable_v2_assignments: false,
 				};
 
 				let info = imported_block_info(&mut ctx, env, hash, &header).await;
@@ -1086,6 +1096,7 @@ pub(crate) mod tests {
 					session_window: &session_window,
 					assignment_criteria: &MockAssignmentCriteria,
 					keystore: &LocalKeystore::in_memory(),
+					enable_v2_assignments: false,
 				};
 
 				let info = imported_block_info(&mut ctx, env, hash, &header).await.unwrap();
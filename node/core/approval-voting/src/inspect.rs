@@ -0,0 +1,557 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Read-only inspection of the approval voting state persisted in the parachain's DB.
+//!
+//! This reports the blocks which are not approved yet and, for each of their unapproved
+//! candidates, why [`approval_checking::check_approval`] is not satisfied. It is meant to
+//! diagnose a stalled finality without running the subsystem.
+
+use polkadot_node_primitives::approval::DelayTranche;
+use polkadot_node_subsystem::SubsystemResult;
+use polkadot_node_subsystem_util::{
+	database::Database,
+	rolling_session_window::{DatabaseParams, RollingSessionWindow},
+};
+use polkadot_primitives::{
+	BlockNumber, CandidateHash, CoreIndex, Hash, Id as ParaId, SessionIndex, ValidatorIndex,
+};
+use sp_consensus_slots::Slot;
+use std::sync::Arc;
+
+pub use crate::{approval_checking::RequiredTranches, approval_db::v1::stored_keys};
+
+use crate::{
+	approval_checking::{self, Check},
+	approval_db::v1::{Config as DatabaseConfig, DbBackend},
+	backend::Backend,
+	persisted_entries::{ApprovalEntry, BlockEntry, CandidateEntry},
+	time::{slot_number_to_tick, Clock, SystemClock, Tick},
+	APPROVAL_DELAY,
+};
+
+/// Configuration of an inspection of the approval voting DB.
+#[derive(Debug, Clone)]
+pub struct InspectConfig {
+	/// The column of the DB where approval voting data is stored.
+	pub col_approval_data: u32,
+	/// The column of the DB where rolling session info is stored.
+	pub col_session_data: u32,
+	/// The slot duration of the consensus algorithm, in milliseconds.
+	pub slot_duration_millis: u64,
+}
+
+/// A block which is not approved yet.
+#[derive(Debug, Clone)]
+pub struct BlockReport {
+	/// The hash of the block.
+	pub block_hash: Hash,
+	/// The number of the block.
+	pub block_number: BlockNumber,
+	/// The session of the block.
+	pub session: SessionIndex,
+	/// The slot of the block.
+	pub slot: Slot,
+	/// The current delay tranche, relative to the slot of the block.
+	pub tranche_now: DelayTranche,
+	/// The candidates included by the block which are not approved yet.
+	pub candidates: Vec<CandidateReport>,
+}
+
+/// A candidate which is not approved under a block yet.
+#[derive(Debug, Clone)]
+pub struct CandidateReport {
+	/// The hash of the candidate.
+	pub candidate_hash: CandidateHash,
+	/// The core the candidate is leaving.
+	pub core_index: CoreIndex,
+	/// The para of the candidate.
+	pub para_id: ParaId,
+	/// The number of validators in the session of the candidate.
+	pub n_validators: usize,
+	/// The assignments received under the block, by ascending tranche.
+	pub assignments: Vec<AssignmentReport>,
+	/// The validators which approved the candidate, under any block.
+	pub approvals: Vec<ValidatorIndex>,
+	/// The tranches required to approve the candidate, if its approval entry and the session
+	/// info of the block are known.
+	pub required_tranches: Option<RequiredTranches>,
+	/// Why the candidate is not approved under the block.
+	pub reason: UnapprovedReason,
+}
+
+/// An assignment of a validator to check a candidate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssignmentReport {
+	/// The assigned validator.
+	pub validator: ValidatorIndex,
+	/// The delay tranche of the assignment.
+	pub tranche: DelayTranche,
+	/// The tick at which the assignment was received.
+	pub tick: u64,
+	/// Whether the validator approved the candidate.
+	pub approved: bool,
+}
+
+/// Why the approval of a candidate under a block is not satisfied.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnapprovedReason {
+	/// There is no approval entry for the candidate under the block.
+	NoApprovalEntry,
+	/// The session info of the block is not in the DB, so the required tranches are unknown.
+	UnknownSession,
+	/// More assignments are awaited: the tranches up to `considered` don't cover the needed
+	/// approvals and the no-shows yet.
+	AwaitingAssignments {
+		/// The highest considered delay tranche.
+		considered: DelayTranche,
+	},
+	/// The no-shows can't be covered anymore, so more than a third of all the validators
+	/// must approve the candidate.
+	AwaitingOneThirdApprovals {
+		/// The number of validators which approved the candidate.
+		approvals: usize,
+		/// The number of approvals needed.
+		needed: usize,
+	},
+	/// Enough validators are assigned, but too many of them didn't approve yet.
+	AwaitingApprovals {
+		/// The tranche up to which assignments are counted.
+		needed: DelayTranche,
+		/// The assigned validators which didn't approve yet.
+		missing: Vec<ValidatorIndex>,
+		/// The number of missing approvals which are tolerated.
+		tolerated_missing: usize,
+	},
+	/// The needed approvals are there, but the last counted assignment was received less than
+	/// `APPROVAL_DELAY` ticks ago.
+	AssignmentTooRecent {
+		/// The tick at which the last counted assignment was received.
+		last_assignment_tick: u64,
+	},
+	/// The approval is satisfied, the candidate is waiting for approval voting to process it.
+	Satisfied,
+}
+
+/// Report the blocks of the approval voting DB which are not approved yet, by ascending block
+/// number. Nothing is written to the DB.
+pub fn inspect_unapproved_blocks(
+	db: Arc<dyn Database>,
+	config: &InspectConfig,
+) -> SubsystemResult<Vec<BlockReport>> {
+	let session_window = RollingSessionWindow::load_from_db(DatabaseParams {
+		db: db.clone(),
+		db_column: config.col_session_data,
+	});
+	let backend = DbBackend::new(
+		db,
+		DatabaseConfig {
+			col_approval_data: config.col_approval_data,
+			col_session_data: config.col_session_data,
+		},
+	);
+
+	unapproved_blocks(
+		&backend,
+		session_window.as_ref(),
+		config.slot_duration_millis,
+		SystemClock.tick_now(),
+	)
+}
+
+fn unapproved_blocks(
+	backend: &impl Backend,
+	session_window: Option<&RollingSessionWindow>,
+	slot_duration_millis: u64,
+	tick_now: Tick,
+) -> SubsystemResult<Vec<BlockReport>> {
+	let mut reports = Vec::new();
+	for block_hash in backend.load_all_blocks()? {
+		let block_entry = match backend.load_block_entry(&block_hash)? {
+			Some(block_entry) if !block_entry.is_fully_approved() => block_entry,
+			_ => continue,
+		};
+
+		let block_tick = slot_number_to_tick(slot_duration_millis, block_entry.slot());
+		let tranche_now = tick_now.saturating_sub(block_tick) as DelayTranche;
+
+		let mut candidates = Vec::new();
+		for (core_index, candidate_hash) in block_entry.candidates() {
+			if block_entry.is_candidate_approved(candidate_hash) {
+				continue
+			}
+
+			// Candidate entries are only pruned along with all the blocks including them.
+			let candidate_entry = match backend.load_candidate_entry(candidate_hash)? {
+				Some(candidate_entry) => candidate_entry,
+				None => continue,
+			};
+
+			candidates.push(candidate_report(
+				&block_entry,
+				*core_index,
+				&candidate_entry,
+				session_window,
+				slot_duration_millis,
+				tick_now,
+			));
+		}
+
+		reports.push(BlockReport {
+			block_hash,
+			block_number: block_entry.block_number(),
+			session: block_entry.session(),
+			slot: block_entry.slot(),
+			tranche_now,
+			candidates,
+		});
+	}
+
+	Ok(reports)
+}
+
+fn candidate_report(
+	block_entry: &BlockEntry,
+	core_index: CoreIndex,
+	candidate_entry: &CandidateEntry,
+	session_window: Option<&RollingSessionWindow>,
+	slot_duration_millis: u64,
+	tick_now: Tick,
+) -> CandidateReport {
+	let approvals = candidate_entry.approvals();
+	let mut report = CandidateReport {
+		candidate_hash: candidate_entry.candidate_receipt().hash(),
+		core_index,
+		para_id: candidate_entry.candidate_receipt().descriptor.para_id,
+		n_validators: approvals.len(),
+		assignments: Vec::new(),
+		approvals: approvals.iter_ones().map(|i| ValidatorIndex(i as _)).collect(),
+		required_tranches: None,
+		reason: UnapprovedReason::NoApprovalEntry,
+	};
+
+	let approval_entry = match candidate_entry.approval_entry(&block_entry.block_hash()) {
+		Some(approval_entry) => approval_entry,
+		None => return report,
+	};
+
+	report.assignments = approval_entry
+		.tranches()
+		.iter()
+		.flat_map(|tranche_entry| {
+			tranche_entry
+				.assignments()
+				.iter()
+				.map(move |(validator, tick)| AssignmentReport {
+					validator: *validator,
+					tranche: tranche_entry.tranche(),
+					tick: *tick,
+					approved: candidate_entry.has_approved(*validator),
+				})
+		})
+		.collect();
+
+	let session_info = match session_window.and_then(|w| w.session_info(block_entry.session())) {
+		Some(session_info) => session_info,
+		None => {
+			report.reason = UnapprovedReason::UnknownSession;
+			return report
+		},
+	};
+
+	// Mirrors how approval voting determines the approval of a candidate under a block.
+	let block_tick = slot_number_to_tick(slot_duration_millis, block_entry.slot());
+	let no_show_duration = slot_number_to_tick(
+		slot_duration_millis,
+		Slot::from(u64::from(session_info.no_show_slots)),
+	);
+	let required_tranches = approval_checking::tranches_to_approve(
+		approval_entry,
+		approvals,
+		tick_now.saturating_sub(block_tick) as DelayTranche,
+		block_tick,
+		no_show_duration,
+		session_info.needed_approvals as _,
+	);
+
+	report.reason =
+		unapproved_reason(candidate_entry, approval_entry, required_tranches.clone(), tick_now);
+	report.required_tranches = Some(required_tranches);
+	report
+}
+
+fn unapproved_reason(
+	candidate_entry: &CandidateEntry,
+	approval_entry: &ApprovalEntry,
+	required_tranches: RequiredTranches,
+	tick_now: Tick,
+) -> UnapprovedReason {
+	let check = approval_checking::check_approval(
+		candidate_entry,
+		approval_entry,
+		required_tranches.clone(),
+	);
+	if check.is_approved(tick_now.saturating_sub(APPROVAL_DELAY)) {
+		return UnapprovedReason::Satisfied
+	}
+
+	match (check, required_tranches) {
+		(Check::Approved(_, Some(last_assignment_tick)), _) =>
+			UnapprovedReason::AssignmentTooRecent { last_assignment_tick },
+		(_, RequiredTranches::Pending { considered, .. }) =>
+			UnapprovedReason::AwaitingAssignments { considered },
+		(_, RequiredTranches::All) => {
+			let approvals = candidate_entry.approvals();
+			UnapprovedReason::AwaitingOneThirdApprovals {
+				approvals: approvals.count_ones(),
+				needed: approvals.len() / 3 + 1,
+			}
+		},
+		(_, RequiredTranches::Exact { needed, tolerated_missing, .. }) => {
+			let missing = approval_entry
+				.assignments_up_to(needed)
+				.iter_ones()
+				.map(|i| ValidatorIndex(i as _))
+				.filter(|validator| !candidate_entry.has_approved(*validator))
+				.collect();
+
+			UnapprovedReason::AwaitingApprovals { needed, missing, tolerated_missing }
+		},
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		approval_db::v1::{
+			ApprovalEntry as DbApprovalEntry, BlockEntry as DbBlockEntry,
+			CandidateEntry as DbCandidateEntry, StoredBlockRange, TrancheEntry as DbTrancheEntry,
+		},
+		backend::OverlayedBackend,
+	};
+	use ::test_helpers::{dummy_candidate_receipt, dummy_hash};
+	use bitvec::{order::Lsb0 as BitOrderLsb0, vec::BitVec};
+	use polkadot_primitives::{GroupIndex, SessionInfo};
+
+	const DATA_COL: u32 = 0;
+	const SESSION_DATA_COL: u32 = 1;
+	const NUM_COLUMNS: u32 = 2;
+	const SLOT_DURATION_MILLIS: u64 = 6000;
+	const N_VALIDATORS: usize = 10;
+
+	fn make_db() -> DbBackend {
+		let db = kvdb_memorydb::create(NUM_COLUMNS);
+		let db = polkadot_node_subsystem_util::database::kvdb_impl::DbAdapter::new(db, &[]);
+		let db: Arc<dyn Database> = Arc::new(db);
+		DbBackend::new(
+			db,
+			DatabaseConfig { col_approval_data: DATA_COL, col_session_data: SESSION_DATA_COL },
+		)
+	}
+
+	fn session_window() -> RollingSessionWindow {
+		let session_info = SessionInfo {
+			validators: Default::default(),
+			discovery_keys: Vec::new(),
+			assignment_keys: Vec::new(),
+			validator_groups: Default::default(),
+			n_cores: 2,
+			zeroth_delay_tranche_width: 5,
+			relay_vrf_modulo_samples: 3,
+			n_delay_tranches: 40,
+			no_show_slots: 2,
+			needed_approvals: 2,
+			active_validator_indices: Vec::new(),
+			dispute_period: 6,
+			random_seed: [0u8; 32],
+		};
+		RollingSessionWindow::with_session_info(1, vec![session_info])
+	}
+
+	fn bitfield(ones: &[usize]) -> BitVec<u8, BitOrderLsb0> {
+		let mut bitfield = bitvec::bitvec![u8, BitOrderLsb0; 0; N_VALIDATORS];
+		for i in ones {
+			bitfield.set(*i, true);
+		}
+		bitfield
+	}
+
+	// Writes a block including the given candidates, each with its assignments at tranche 0
+	// and its approvals. The first candidate is approved under the block.
+	fn write_block(
+		db: &mut DbBackend,
+		block_hash: Hash,
+		candidates: Vec<(Vec<(ValidatorIndex, Tick)>, Vec<usize>)>,
+	) -> Vec<CandidateHash> {
+		let mut overlay_db = OverlayedBackend::new(&*db);
+		let mut block_candidates = Vec::new();
+		for (i, (assignments, approvals)) in candidates.into_iter().enumerate() {
+			let mut candidate = dummy_candidate_receipt(dummy_hash());
+			candidate.descriptor.para_id = ParaId::from(i as u32);
+			let candidate_hash = candidate.hash();
+			block_candidates.push((CoreIndex(i as _), candidate_hash));
+
+			let assigned: Vec<usize> = assignments.iter().map(|(v, _)| v.0 as _).collect();
+			overlay_db.write_candidate_entry(
+				DbCandidateEntry {
+					candidate,
+					session: 1,
+					block_assignments: vec![(
+						block_hash,
+						DbApprovalEntry {
+							tranches: vec![DbTrancheEntry {
+								tranche: 0,
+								assignments: assignments
+									.into_iter()
+									.map(|(v, tick)| (v, tick.into()))
+									.collect(),
+							}],
+							backing_group: GroupIndex(0),
+							our_assignment: None,
+							our_approval_sig: None,
+							assignments: bitfield(&assigned),
+							approved: false,
+						},
+					)]
+					.into_iter()
+					.collect(),
+					approvals: bitfield(&approvals),
+				}
+				.into(),
+			);
+		}
+
+		let mut approved_bitfield = bitvec::bitvec![u8, BitOrderLsb0; 0; block_candidates.len()];
+		approved_bitfield.set(0, true);
+		let candidate_hashes = block_candidates.iter().map(|(_, hash)| *hash).collect();
+		overlay_db.write_stored_block_range(StoredBlockRange(1, 2));
+		overlay_db.write_blocks_at_height(1, vec![block_hash]);
+		overlay_db.write_block_entry(
+			DbBlockEntry {
+				block_hash,
+				block_number: 1,
+				parent_hash: Hash::repeat_byte(0),
+				session: 1,
+				slot: Slot::from(1),
+				relay_vrf_story: [0u8; 32],
+				candidates: block_candidates,
+				approved_bitfield,
+				children: Vec::new(),
+			}
+			.into(),
+		);
+
+		let write_ops = overlay_db.into_write_ops();
+		db.write(write_ops).unwrap();
+		candidate_hashes
+	}
+
+	#[test]
+	fn reports_why_candidates_are_unapproved() {
+		let mut db = make_db();
+		let block_hash = Hash::repeat_byte(1);
+		let block_tick = slot_number_to_tick(SLOT_DURATION_MILLIS, Slot::from(1));
+
+		let candidate_hashes = write_block(
+			&mut db,
+			block_hash,
+			vec![
+				// approved under the block
+				(
+					vec![(ValidatorIndex(0), block_tick), (ValidatorIndex(1), block_tick)],
+					vec![0, 1],
+				),
+				// one assignment too few
+				(vec![(ValidatorIndex(0), block_tick)], vec![0]),
+				// one approval missing
+				(vec![(ValidatorIndex(0), block_tick), (ValidatorIndex(1), block_tick)], vec![1]),
+				// all approved, but validator 1 was assigned just now
+				(
+					vec![(ValidatorIndex(0), block_tick), (ValidatorIndex(1), block_tick + 4)],
+					vec![0, 1],
+				),
+				// all approved long ago, waiting to be processed
+				(
+					vec![(ValidatorIndex(2), block_tick), (ValidatorIndex(3), block_tick)],
+					vec![2, 3],
+				),
+			],
+		);
+
+		let tick_now = block_tick + 5;
+		let reports =
+			unapproved_blocks(&db, Some(&session_window()), SLOT_DURATION_MILLIS, tick_now)
+				.unwrap();
+
+		assert_eq!(reports.len(), 1);
+		let report = &reports[0];
+		assert_eq!(report.block_hash, block_hash);
+		assert_eq!(report.tranche_now, 5);
+		assert_eq!(
+			report.candidates.iter().map(|c| c.candidate_hash).collect::<Vec<_>>(),
+			candidate_hashes[1..].to_vec(),
+		);
+
+		let reasons: Vec<_> = report.candidates.iter().map(|c| c.reason.clone()).collect();
+		assert_eq!(
+			reasons,
+			vec![
+				UnapprovedReason::AwaitingAssignments { considered: 5 },
+				UnapprovedReason::AwaitingApprovals {
+					needed: 0,
+					missing: vec![ValidatorIndex(0)],
+					tolerated_missing: 0,
+				},
+				UnapprovedReason::AssignmentTooRecent { last_assignment_tick: block_tick + 4 },
+				UnapprovedReason::Satisfied,
+			],
+		);
+
+		assert_eq!(
+			report.candidates[1].assignments,
+			vec![
+				AssignmentReport {
+					validator: ValidatorIndex(0),
+					tranche: 0,
+					tick: block_tick,
+					approved: false,
+				},
+				AssignmentReport {
+					validator: ValidatorIndex(1),
+					tranche: 0,
+					tick: block_tick,
+					approved: true,
+				},
+			],
+		);
+		assert_eq!(report.candidates[1].approvals, vec![ValidatorIndex(1)]);
+	}
+
+	#[test]
+	fn unknown_session_is_reported() {
+		let mut db = make_db();
+		let block_hash = Hash::repeat_byte(1);
+
+		write_block(&mut db, block_hash, vec![(vec![], vec![]), (vec![], vec![])]);
+
+		let reports = unapproved_blocks(&db, None, SLOT_DURATION_MILLIS, 0).unwrap();
+		assert_eq!(reports.len(), 1);
+		assert_eq!(reports[0].candidates.len(), 1);
+		assert_eq!(reports[0].candidates[0].reason, UnapprovedReason::UnknownSession);
+		assert_eq!(reports[0].candidates[0].required_tranches, None);
+	}
+}
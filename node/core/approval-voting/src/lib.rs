@@ -25,7 +25,8 @@ use jaeger::{hash_to_trace_identifier, PerLeafSpan};
 use polkadot_node_jaeger as jaeger;
 use polkadot_node_primitives::{
 	approval::{
-		BlockApprovalMeta, DelayTranche, IndirectAssignmentCert, IndirectSignedApprovalVote,
+		AssignmentCertKindV2, AssignmentCertV2, BlockApprovalMeta, CandidateBitfield, CoreBitfield,
+		DelayTranche, IndirectAssignmentCertV2, IndirectSignedApprovalVoteV2,
 	},
 	ValidationResult,
 };
@@ -36,7 +37,7 @@ use polkadot_node_subsystem::{
 		ApprovalVotingMessage, AssignmentCheckError, AssignmentCheckResult,
 		AvailabilityRecoveryMessage, BlockDescription, CandidateValidationMessage, ChainApiMessage,
 		ChainSelectionMessage, DisputeCoordinatorMessage, HighestApprovedAncestorBlock,
-		RuntimeApiMessage, RuntimeApiRequest,
+		PvfExecPriority, RuntimeApiMessage, RuntimeApiRequest,
 	},
 	overseer, FromOrchestra, OverseerSignal, SpawnedSubsystem, SubsystemError, SubsystemResult,
 	SubsystemSender,
@@ -47,12 +48,13 @@ use polkadot_node_subsystem_util::{
 	rolling_session_window::{
 		DatabaseParams, RollingSessionWindow, SessionWindowUpdate, SessionsUnavailable,
 	},
+	runtime::is_approval_coalescing_enabled,
 	TimeoutExt,
 };
 use polkadot_primitives::{
-	ApprovalVote, BlockNumber, CandidateHash, CandidateIndex, CandidateReceipt, DisputeStatement,
-	GroupIndex, Hash, PvfExecTimeoutKind, SessionIndex, SessionInfo, ValidDisputeStatementKind,
-	ValidatorId, ValidatorIndex, ValidatorPair, ValidatorSignature,
+	ApprovalVoteMultipleCandidates, BlockNumber, CandidateHash, CandidateIndex, CandidateReceipt,
+	DisputeStatement, GroupIndex, Hash, PvfExecTimeoutKind, SessionIndex, SessionInfo,
+	ValidDisputeStatementKind, ValidatorId, ValidatorIndex, ValidatorPair, ValidatorSignature,
 };
 use sc_keystore::LocalKeystore;
 use sp_application_crypto::Pair;
@@ -85,6 +87,7 @@ mod approval_db;
 mod backend;
 mod criteria;
 mod import;
+pub mod inspect;
 mod ops;
 mod persisted_entries;
 mod time;
@@ -110,6 +113,9 @@ const APPROVAL_CACHE_SIZE: NonZeroUsize = match NonZeroUsize::new(1024) {
 
 const TICK_TOO_FAR_IN_FUTURE: Tick = 20; // 10 seconds.
 const APPROVAL_DELAY: Tick = 2;
+// How long a local approval vote may be delayed to coalesce it with the votes for other
+// candidates of the same block.
+const MAX_APPROVAL_COALESCE_WAIT_TICKS: Tick = 2; // 1 second.
 const LOG_TARGET: &str = "parachain::approval-voting";
 
 /// Configuration for the approval voting subsystem
@@ -122,6 +128,15 @@ pub struct Config {
 	/// The slot duration of the consensus algorithm, in milliseconds. Should be evenly
 	/// divisible by 500.
 	pub slot_duration_millis: u64,
+	/// Whether to produce compact `RelayVRFModuloCompact` assignments, which claim several
+	/// candidates with one cert. Peers on the first version of the validation protocol don't
+	/// receive them.
+	pub enable_v2_assignments: bool,
+	/// The maximum number of candidates of a block approved with a single signature. Local
+	/// approvals are delayed for a short while to sign them together. `1` disables coalescing.
+	/// Peers on the first version of the validation protocol don't receive coalesced approvals.
+	/// Approvals are only coalesced on blocks enabling the `ApprovalCoalescing` node feature.
+	pub max_approval_coalesce_count: u32,
 }
 
 // The mode of the approval voting subsystem. It should start in a `Syncing` mode when it first
@@ -146,6 +161,8 @@ pub struct ApprovalVotingSubsystem {
 	keystore: Arc<LocalKeystore>,
 	db_config: DatabaseConfig,
 	slot_duration_millis: u64,
+	enable_v2_assignments: bool,
+	max_approval_coalesce_count: u32,
 	db: Arc<dyn Database>,
 	mode: Mode,
 	metrics: Metrics,
@@ -357,6 +374,8 @@ impl ApprovalVotingSubsystem {
 		ApprovalVotingSubsystem {
 			keystore,
 			slot_duration_millis: config.slot_duration_millis,
+			enable_v2_assignments: config.enable_v2_assignments,
+			max_approval_coalesce_count: config.max_approval_coalesce_count,
 			db,
 			db_config: DatabaseConfig {
 				col_approval_data: config.col_approval_data,
@@ -638,10 +657,38 @@ impl CurrentlyCheckingSet {
 	}
 }
 
+// Local approval votes for candidates of a block, waiting to be signed together.
+struct ApprovalsToSign {
+	validator_index: ValidatorIndex,
+	candidate_hashes: Vec<CandidateHash>,
+	// The tick at which the votes are signed, even if fewer than the maximum number of
+	// candidates got approved by then.
+	sign_at: Tick,
+}
+
+// Returns the block whose pending approval votes are to be signed next, once it's time to sign
+// them. This future never returns if there are no pending approval votes.
+async fn next_approvals_to_sign(
+	approvals_to_sign: &HashMap<Hash, ApprovalsToSign>,
+	clock: &(dyn Clock + Sync),
+) -> Hash {
+	match approvals_to_sign.iter().min_by_key(|(_, approvals)| approvals.sign_at) {
+		None => future::pending().await,
+		Some((block_hash, approvals)) => {
+			clock.wait(approvals.sign_at).await;
+			*block_hash
+		},
+	}
+}
+
 struct State {
 	session_window: Option<RollingSessionWindow>,
 	keystore: Arc<LocalKeystore>,
 	slot_duration_millis: u64,
+	enable_v2_assignments: bool,
+	max_approval_coalesce_count: u32,
+	// Local approval votes waiting to be coalesced, by block.
+	approvals_to_sign: HashMap<Hash, ApprovalsToSign>,
 	clock: Box<dyn Clock + Send + Sync>,
 	assignment_criteria: Box<dyn AssignmentCriteria + Send + Sync>,
 	// Require for `RollingSessionWindow`.
@@ -749,10 +796,10 @@ enum Action {
 	},
 	LaunchApproval {
 		candidate_hash: CandidateHash,
-		indirect_cert: IndirectAssignmentCert,
+		indirect_cert: IndirectAssignmentCertV2,
 		assignment_tranche: DelayTranche,
 		relay_block_hash: Hash,
-		candidate_index: CandidateIndex,
+		claimed_candidate_indices: CandidateBitfield,
 		session: SessionIndex,
 		candidate: CandidateReceipt,
 		backing_group: GroupIndex,
@@ -782,6 +829,9 @@ where
 		session_window: None,
 		keystore: subsystem.keystore,
 		slot_duration_millis: subsystem.slot_duration_millis,
+		enable_v2_assignments: subsystem.enable_v2_assignments,
+		max_approval_coalesce_count: subsystem.max_approval_coalesce_count,
+		approvals_to_sign: HashMap::new(),
 		clock,
 		assignment_criteria,
 		db_config: subsystem.db_config,
@@ -818,6 +868,15 @@ where
 					&subsystem.metrics,
 				)?
 			}
+			block_hash = next_approvals_to_sign(&state.approvals_to_sign, &*state.clock).fuse() => {
+				sign_pending_approvals(
+					&mut ctx,
+					&mut state,
+					&mut overlayed_db,
+					&subsystem.metrics,
+					block_hash,
+				).await?
+			}
 			next_msg = ctx.recv().fuse() => {
 				let mut actions = handle_from_overseer(
 					&mut ctx,
@@ -966,7 +1025,7 @@ async fn handle_actions<Context>(
 				indirect_cert,
 				assignment_tranche,
 				relay_block_hash,
-				candidate_index,
+				claimed_candidate_indices,
 				session,
 				candidate,
 				backing_group,
@@ -992,7 +1051,7 @@ async fn handle_actions<Context>(
 
 				ctx.send_unbounded_message(ApprovalDistributionMessage::DistributeAssignment(
 					indirect_cert,
-					candidate_index,
+					claimed_candidate_indices,
 				));
 
 				match approvals_cache.get(&candidate_hash) {
@@ -1102,6 +1161,12 @@ fn distribution_messages_for_activation(
 			session: block_entry.session(),
 		});
 
+		// A coalesced approval vote is stored for each of the candidates it approves. The
+		// signatures are randomized, so candidates with the same signature were approved by the
+		// same vote.
+		let mut approvals: Vec<(ValidatorIndex, ValidatorSignature, Vec<CandidateIndex>)> =
+			Vec::new();
+
 		for (i, (_, candidate_hash)) in block_entry.candidates().iter().enumerate() {
 			let _candidate_span =
 				distribution_message_span.child("candidate").with_candidate(*candidate_hash);
@@ -1125,32 +1190,32 @@ fn distribution_messages_for_activation(
 						(None, None) | (None, Some(_)) => {}, // second is impossible case.
 						(Some(assignment), None) => {
 							messages.push(ApprovalDistributionMessage::DistributeAssignment(
-								IndirectAssignmentCert {
+								IndirectAssignmentCertV2 {
 									block_hash,
 									validator: assignment.validator_index(),
 									cert: assignment.cert().clone(),
 								},
-								i as _,
+								claimed_candidate_indices(&block_entry, assignment.cert(), i as _),
 							));
 						},
 						(Some(assignment), Some(approval_sig)) => {
 							messages.push(ApprovalDistributionMessage::DistributeAssignment(
-								IndirectAssignmentCert {
+								IndirectAssignmentCertV2 {
 									block_hash,
 									validator: assignment.validator_index(),
 									cert: assignment.cert().clone(),
 								},
-								i as _,
+								claimed_candidate_indices(&block_entry, assignment.cert(), i as _),
 							));
 
-							messages.push(ApprovalDistributionMessage::DistributeApproval(
-								IndirectSignedApprovalVote {
-									block_hash,
-									candidate_index: i as _,
-									validator: assignment.validator_index(),
-									signature: approval_sig,
-								},
-							))
+							match approvals.iter_mut().find(|(_, sig, _)| sig == &approval_sig) {
+								Some((_, _, candidate_indices)) => candidate_indices.push(i as _),
+								None => approvals.push((
+									assignment.validator_index(),
+									approval_sig,
+									vec![i as _],
+								)),
+							}
 						},
 					}
 				},
@@ -1164,6 +1229,19 @@ fn distribution_messages_for_activation(
 				},
 			}
 		}
+
+		for (validator, signature, candidate_indices) in approvals {
+			messages.push(ApprovalDistributionMessage::DistributeApproval(
+				IndirectSignedApprovalVoteV2 {
+					block_hash,
+					candidate_indices: candidate_indices
+						.try_into()
+						.expect("every approval covers at least one candidate; qed"),
+					validator,
+					signature,
+				},
+			));
+		}
 	}
 
 	messages[0] = ApprovalDistributionMessage::NewBlocks(approval_meta);
@@ -1258,9 +1336,9 @@ async fn handle_from_overseer<Context>(
 			vec![Action::Conclude]
 		},
 		FromOrchestra::Communication { msg } => match msg {
-			ApprovalVotingMessage::CheckAndImportAssignment(a, claimed_core, res) => {
+			ApprovalVotingMessage::CheckAndImportAssignment(a, claimed_candidates, res) => {
 				let (check_outcome, actions) =
-					check_and_import_assignment(state, db, a, claimed_core)?;
+					check_and_import_assignment(state, db, a, claimed_candidates)?;
 				let _ = res.send(check_outcome);
 
 				actions
@@ -1319,7 +1397,7 @@ async fn get_approval_signatures_for_candidate<Context>(
 	ctx: &mut Context,
 	db: &OverlayedBackend<'_, impl Backend>,
 	candidate_hash: CandidateHash,
-	tx: oneshot::Sender<HashMap<ValidatorIndex, ValidatorSignature>>,
+	tx: oneshot::Sender<HashMap<ValidatorIndex, (Vec<CandidateHash>, ValidatorSignature)>>,
 ) -> SubsystemResult<()> {
 	let send_votes = |votes| {
 		if let Err(_) = tx.send(votes) {
@@ -1345,6 +1423,8 @@ async fn get_approval_signatures_for_candidate<Context>(
 	let relay_hashes = entry.block_assignments.keys();
 
 	let mut candidate_indices = HashSet::new();
+	// Approvals may cover several candidates of a block, which are referenced by index.
+	let mut candidate_hashes_by_block = HashMap::new();
 	// Retrieve `CoreIndices`/`CandidateIndices` as required by approval-distribution:
 	for hash in relay_hashes {
 		let entry = match db.load_block_entry(hash)? {
@@ -1365,6 +1445,10 @@ async fn get_approval_signatures_for_candidate<Context>(
 				break
 			}
 		}
+		candidate_hashes_by_block.insert(
+			*hash,
+			entry.candidates().iter().map(|(_, c_hash)| *c_hash).collect::<Vec<_>>(),
+		);
 	}
 
 	let mut sender = ctx.sender().clone();
@@ -1388,7 +1472,20 @@ async fn get_approval_signatures_for_candidate<Context>(
 				target: LOG_TARGET,
 				"Request for approval signatures got cancelled by `approval-distribution`."
 			),
-			Some(Ok(votes)) => send_votes(votes),
+			Some(Ok(votes)) => send_votes(
+				votes
+					.into_iter()
+					.filter_map(|(validator_index, (hash, candidate_indices, signature))| {
+						let block_candidates = candidate_hashes_by_block.get(&hash)?;
+						let candidate_hashes = candidate_indices
+							.into_iter()
+							.map(|index| block_candidates.get(index as usize).copied())
+							.collect::<Option<Vec<_>>>()?;
+
+						Some((validator_index, (candidate_hashes, signature)))
+					})
+					.collect(),
+			),
 		}
 	};
 
@@ -1741,8 +1838,8 @@ fn schedule_wakeup_action(
 fn check_and_import_assignment(
 	state: &State,
 	db: &mut OverlayedBackend<'_, impl Backend>,
-	assignment: IndirectAssignmentCert,
-	candidate_index: CandidateIndex,
+	assignment: IndirectAssignmentCertV2,
+	candidate_indices: CandidateBitfield,
 ) -> SubsystemResult<(AssignmentCheckResult, Vec<Action>)> {
 	let tick_now = state.clock.tick_now();
 
@@ -1752,7 +1849,10 @@ fn check_and_import_assignment(
 		.map(|span| span.child("check-and-import-assignment"))
 		.unwrap_or_else(|| jaeger::Span::new(assignment.block_hash, "check-and-import-assignment"))
 		.with_relay_parent(assignment.block_hash)
-		.with_uint_tag("candidate-index", candidate_index as u64)
+		.with_string_fmt_debug_tag(
+			"candidate-indices",
+			candidate_indices.iter_ones().collect::<Vec<_>>(),
+		)
 		.with_stage(jaeger::Stage::ApprovalChecking);
 
 	let block_entry = match db.load_block_entry(&assignment.block_hash)? {
@@ -1777,41 +1877,44 @@ fn check_and_import_assignment(
 			)),
 	};
 
-	let (claimed_core_index, assigned_candidate_hash) =
-		match block_entry.candidate(candidate_index as usize) {
-			Some((c, h)) => (*c, *h),
+	// Load all the claimed candidates, together with their cores and backing groups.
+	let mut claimed_core_indices = Vec::new();
+	let mut backing_groups = Vec::new();
+	let mut assigned_candidates = Vec::new();
+	for candidate_index in candidate_indices.iter_ones() {
+		let (claimed_core_index, assigned_candidate_hash) =
+			match block_entry.candidate(candidate_index as usize) {
+				Some((c, h)) => (*c, *h),
+				None =>
+					return Ok((
+						AssignmentCheckResult::Bad(AssignmentCheckError::InvalidCandidateIndex(
+							candidate_index,
+						)),
+						Vec::new(),
+					)), // no candidate at core.
+			};
+
+		check_and_import_assignment_span
+			.add_string_tag("candidate-hash", format!("{:?}", assigned_candidate_hash));
+		check_and_import_assignment_span.add_string_tag(
+			"traceID",
+			format!("{:?}", jaeger::hash_to_trace_identifier(assigned_candidate_hash.0)),
+		);
+
+		let candidate_entry = match db.load_candidate_entry(&assigned_candidate_hash)? {
+			Some(c) => c,
 			None =>
 				return Ok((
-					AssignmentCheckResult::Bad(AssignmentCheckError::InvalidCandidateIndex(
+					AssignmentCheckResult::Bad(AssignmentCheckError::InvalidCandidate(
 						candidate_index,
+						assigned_candidate_hash,
 					)),
 					Vec::new(),
-				)), // no candidate at core.
-		};
-
-	check_and_import_assignment_span
-		.add_string_tag("candidate-hash", format!("{:?}", assigned_candidate_hash));
-	check_and_import_assignment_span.add_string_tag(
-		"traceID",
-		format!("{:?}", jaeger::hash_to_trace_identifier(assigned_candidate_hash.0)),
-	);
-
-	let mut candidate_entry = match db.load_candidate_entry(&assigned_candidate_hash)? {
-		Some(c) => c,
-		None =>
-			return Ok((
-				AssignmentCheckResult::Bad(AssignmentCheckError::InvalidCandidate(
-					candidate_index,
-					assigned_candidate_hash,
 				)),
-				Vec::new(),
-			)),
-	};
+		};
 
-	let res = {
-		// import the assignment.
-		let approval_entry = match candidate_entry.approval_entry_mut(&assignment.block_hash) {
-			Some(a) => a,
+		let backing_group = match candidate_entry.approval_entry(&assignment.block_hash) {
+			Some(a) => a.backing_group(),
 			None =>
 				return Ok((
 					AssignmentCheckResult::Bad(AssignmentCheckError::Internal(
@@ -1822,46 +1925,69 @@ fn check_and_import_assignment(
 				)),
 		};
 
-		let res = state.assignment_criteria.check_assignment_cert(
-			claimed_core_index,
-			assignment.validator,
-			&criteria::Config::from(session_info),
-			block_entry.relay_vrf_story(),
-			&assignment.cert,
-			approval_entry.backing_group(),
-		);
+		claimed_core_indices.push(claimed_core_index);
+		backing_groups.push(backing_group);
+		assigned_candidates.push((assigned_candidate_hash, candidate_entry));
+	}
 
-		let tranche = match res {
-			Err(crate::criteria::InvalidAssignment(reason)) =>
-				return Ok((
-					AssignmentCheckResult::Bad(AssignmentCheckError::InvalidCert(
-						assignment.validator,
-						format!("{:?}", reason),
-					)),
-					Vec::new(),
+	let claimed_core_indices = match CoreBitfield::try_from(claimed_core_indices) {
+		Ok(c) => c,
+		Err(_) =>
+			return Ok((
+				AssignmentCheckResult::Bad(AssignmentCheckError::NoClaimedCandidates),
+				Vec::new(),
+			)),
+	};
+
+	let res = state.assignment_criteria.check_assignment_cert(
+		claimed_core_indices,
+		assignment.validator,
+		&criteria::Config::from(session_info),
+		block_entry.relay_vrf_story(),
+		&assignment.cert,
+		backing_groups,
+	);
+
+	let tranche = match res {
+		Err(crate::criteria::InvalidAssignment(reason)) =>
+			return Ok((
+				AssignmentCheckResult::Bad(AssignmentCheckError::InvalidCert(
+					assignment.validator,
+					format!("{:?}", reason),
 				)),
-			Ok(tranche) => {
-				let current_tranche =
-					state.clock.tranche_now(state.slot_duration_millis, block_entry.slot());
+				Vec::new(),
+			)),
+		Ok(tranche) => {
+			let current_tranche =
+				state.clock.tranche_now(state.slot_duration_millis, block_entry.slot());
 
-				let too_far_in_future = current_tranche + TICK_TOO_FAR_IN_FUTURE as DelayTranche;
+			let too_far_in_future = current_tranche + TICK_TOO_FAR_IN_FUTURE as DelayTranche;
 
-				if tranche >= too_far_in_future {
-					return Ok((AssignmentCheckResult::TooFarInFuture, Vec::new()))
-				}
+			if tranche >= too_far_in_future {
+				return Ok((AssignmentCheckResult::TooFarInFuture, Vec::new()))
+			}
 
-				tranche
-			},
-		};
+			tranche
+		},
+	};
+
+	check_and_import_assignment_span.add_uint_tag("tranche", tranche as u64);
 
-		check_and_import_assignment_span.add_uint_tag("tranche", tranche as u64);
+	// Import the assignment into all the claimed candidates. It is only a duplicate if all of
+	// them knew about it already.
+	let mut is_duplicate = true;
+	let mut actions = Vec::new();
+	for (assigned_candidate_hash, mut candidate_entry) in assigned_candidates {
+		let approval_entry = candidate_entry
+			.approval_entry_mut(&assignment.block_hash)
+			.expect("Approval entry was loaded above; qed");
 
-		let is_duplicate = approval_entry.is_assigned(assignment.validator);
+		let is_assigned = approval_entry.is_assigned(assignment.validator);
 		approval_entry.import_assignment(tranche, assignment.validator, tick_now);
 
-		if is_duplicate {
-			AssignmentCheckResult::AcceptedDuplicate
-		} else {
+		if !is_assigned {
+			is_duplicate = false;
+
 			gum::trace!(
 				target: LOG_TARGET,
 				validator = assignment.validator.0,
@@ -1869,28 +1995,33 @@ fn check_and_import_assignment(
 				para_id = ?candidate_entry.candidate_receipt().descriptor.para_id,
 				"Imported assignment.",
 			);
-
-			AssignmentCheckResult::Accepted
 		}
-	};
 
-	let mut actions = Vec::new();
+		// We've imported a new assignment, so we need to schedule a wake-up for when that might
+		// no-show.
+		if let Some((approval_entry, status)) =
+			state.approval_status(&block_entry, &candidate_entry)
+		{
+			actions.extend(schedule_wakeup_action(
+				approval_entry,
+				block_entry.block_hash(),
+				block_entry.block_number(),
+				assigned_candidate_hash,
+				status.block_tick,
+				tick_now,
+				status.required_tranches,
+			));
+		}
 
-	// We've imported a new approval, so we need to schedule a wake-up for when that might no-show.
-	if let Some((approval_entry, status)) = state.approval_status(&block_entry, &candidate_entry) {
-		actions.extend(schedule_wakeup_action(
-			approval_entry,
-			block_entry.block_hash(),
-			block_entry.block_number(),
-			assigned_candidate_hash,
-			status.block_tick,
-			tick_now,
-			status.required_tranches,
-		));
+		// We also write the candidate entry as it now contains the new candidate.
+		db.write_candidate_entry(candidate_entry.into());
 	}
 
-	// We also write the candidate entry as it now contains the new candidate.
-	db.write_candidate_entry(candidate_entry.into());
+	let res = if is_duplicate {
+		AssignmentCheckResult::AcceptedDuplicate
+	} else {
+		AssignmentCheckResult::Accepted
+	};
 
 	Ok((res, actions))
 }
@@ -1899,7 +2030,7 @@ fn check_and_import_approval<T>(
 	state: &State,
 	db: &mut OverlayedBackend<'_, impl Backend>,
 	metrics: &Metrics,
-	approval: IndirectSignedApprovalVote,
+	approval: IndirectSignedApprovalVoteV2,
 	with_response: impl FnOnce(ApprovalCheckResult) -> T,
 ) -> SubsystemResult<(Vec<Action>, T)> {
 	macro_rules! respond_early {
@@ -1914,7 +2045,7 @@ fn check_and_import_approval<T>(
 		.get(&approval.block_hash)
 		.map(|span| span.child("check-and-import-approval"))
 		.unwrap_or_else(|| jaeger::Span::new(approval.block_hash, "check-and-import-approval"))
-		.with_uint_tag("candidate-index", approval.candidate_index as u64)
+		.with_string_tag("candidate-indices", format!("{:?}", approval.candidate_indices))
 		.with_relay_parent(approval.block_hash)
 		.with_stage(jaeger::Stage::ApprovalChecking);
 
@@ -1936,18 +2067,26 @@ fn check_and_import_approval<T>(
 		},
 	};
 
-	let approved_candidate_hash = match block_entry.candidate(approval.candidate_index as usize) {
-		Some((_, h)) => *h,
-		None => respond_early!(ApprovalCheckResult::Bad(
-			ApprovalCheckError::InvalidCandidateIndex(approval.candidate_index),
-		)),
-	};
-
-	span.add_string_tag("candidate-hash", format!("{:?}", approved_candidate_hash));
-	span.add_string_tag(
-		"traceID",
-		format!("{:?}", hash_to_trace_identifier(approved_candidate_hash.0)),
-	);
+	// The signature covers the approved candidates ordered by their index in the block.
+	let mut approved_candidates = Vec::with_capacity(approval.candidate_indices.count_ones());
+	for candidate_index in approval.candidate_indices.iter_ones() {
+		match block_entry.candidate(candidate_index as usize) {
+			Some((_, h)) => approved_candidates.push((candidate_index, *h)),
+			None => respond_early!(ApprovalCheckResult::Bad(
+				ApprovalCheckError::InvalidCandidateIndex(candidate_index),
+			)),
+		}
+	}
+	let approved_candidate_hashes: Vec<CandidateHash> =
+		approved_candidates.iter().map(|(_, h)| *h).collect();
+
+	if let [approved_candidate_hash] = approved_candidate_hashes[..] {
+		span.add_string_tag("candidate-hash", format!("{:?}", approved_candidate_hash));
+		span.add_string_tag(
+			"traceID",
+			format!("{:?}", hash_to_trace_identifier(approved_candidate_hash.0)),
+		);
+	}
 
 	let pubkey = match session_info.validators.get(approval.validator) {
 		Some(k) => k,
@@ -1957,9 +2096,16 @@ fn check_and_import_approval<T>(
 	};
 
 	// Signature check:
-	match DisputeStatement::Valid(ValidDisputeStatementKind::ApprovalChecking).check_signature(
+	let statement_kind = if approved_candidate_hashes.len() == 1 {
+		ValidDisputeStatementKind::ApprovalChecking
+	} else {
+		ValidDisputeStatementKind::ApprovalCheckingMultipleCandidates(
+			approved_candidate_hashes.clone(),
+		)
+	};
+	match DisputeStatement::Valid(statement_kind).check_signature(
 		&pubkey,
-		approved_candidate_hash,
+		approved_candidate_hashes[0],
 		block_entry.session(),
 		&approval.signature,
 	) {
@@ -1969,53 +2115,67 @@ fn check_and_import_approval<T>(
 		Ok(()) => {},
 	};
 
-	let candidate_entry = match db.load_candidate_entry(&approved_candidate_hash)? {
-		Some(c) => c,
-		None => {
-			respond_early!(ApprovalCheckResult::Bad(ApprovalCheckError::InvalidCandidate(
-				approval.candidate_index,
-				approved_candidate_hash
-			),))
-		},
-	};
+	let mut candidate_entries = Vec::with_capacity(approved_candidates.len());
+	for (candidate_index, approved_candidate_hash) in approved_candidates {
+		let candidate_entry = match db.load_candidate_entry(&approved_candidate_hash)? {
+			Some(c) => c,
+			None => {
+				respond_early!(ApprovalCheckResult::Bad(ApprovalCheckError::InvalidCandidate(
+					candidate_index,
+					approved_candidate_hash
+				),))
+			},
+		};
 
-	// Don't accept approvals until assignment.
-	match candidate_entry.approval_entry(&approval.block_hash) {
-		None => {
-			respond_early!(ApprovalCheckResult::Bad(ApprovalCheckError::Internal(
-				approval.block_hash,
-				approved_candidate_hash
-			),))
-		},
-		Some(e) if !e.is_assigned(approval.validator) => {
-			respond_early!(ApprovalCheckResult::Bad(ApprovalCheckError::NoAssignment(
-				approval.validator
-			),))
-		},
-		_ => {},
+		// Don't accept approvals until assignment.
+		match candidate_entry.approval_entry(&approval.block_hash) {
+			None => {
+				respond_early!(ApprovalCheckResult::Bad(ApprovalCheckError::Internal(
+					approval.block_hash,
+					approved_candidate_hash
+				),))
+			},
+			Some(e) if !e.is_assigned(approval.validator) => {
+				respond_early!(ApprovalCheckResult::Bad(ApprovalCheckError::NoAssignment(
+					approval.validator
+				),))
+			},
+			_ => {},
+		}
+
+		candidate_entries.push((approved_candidate_hash, candidate_entry));
 	}
 
 	// importing the approval can be heavy as it may trigger acceptance for a series of blocks.
 	let t = with_response(ApprovalCheckResult::Accepted);
 
-	gum::trace!(
-		target: LOG_TARGET,
-		validator_index = approval.validator.0,
-		validator = ?pubkey,
-		candidate_hash = ?approved_candidate_hash,
-		para_id = ?candidate_entry.candidate_receipt().descriptor.para_id,
-		"Importing approval vote",
-	);
+	let mut actions = Vec::new();
+	for (approved_candidate_hash, candidate_entry) in candidate_entries {
+		gum::trace!(
+			target: LOG_TARGET,
+			validator_index = approval.validator.0,
+			validator = ?pubkey,
+			candidate_hash = ?approved_candidate_hash,
+			para_id = ?candidate_entry.candidate_receipt().descriptor.para_id,
+			"Importing approval vote",
+		);
 
-	let actions = advance_approval_state(
-		state,
-		db,
-		&metrics,
-		block_entry,
-		approved_candidate_hash,
-		candidate_entry,
-		ApprovalStateTransition::RemoteApproval(approval.validator),
-	);
+		// The block entry may have been updated by importing the approval for another candidate.
+		let block_entry = match db.load_block_entry(&approval.block_hash)? {
+			Some(b) => b,
+			None => break,
+		};
+
+		actions.extend(advance_approval_state(
+			state,
+			db,
+			&metrics,
+			block_entry,
+			approved_candidate_hash,
+			candidate_entry,
+			ApprovalStateTransition::RemoteApproval(approval.validator),
+		));
+	}
 
 	Ok((actions, t))
 }
@@ -2319,13 +2479,14 @@ fn process_wakeup(
 	};
 
 	if let Some((cert, val_index, tranche)) = maybe_cert {
-		let indirect_cert =
-			IndirectAssignmentCert { block_hash: relay_block, validator: val_index, cert };
-
 		let index_in_candidate =
 			block_entry.candidates().iter().position(|(_, h)| &candidate_hash == h);
 
 		if let Some(i) = index_in_candidate {
+			let claimed_candidate_indices = claimed_candidate_indices(&block_entry, &cert, i as _);
+			let indirect_cert =
+				IndirectAssignmentCertV2 { block_hash: relay_block, validator: val_index, cert };
+
 			gum::trace!(
 				target: LOG_TARGET,
 				?candidate_hash,
@@ -2340,7 +2501,7 @@ fn process_wakeup(
 				indirect_cert,
 				assignment_tranche: tranche,
 				relay_block_hash: relay_block,
-				candidate_index: i as _,
+				claimed_candidate_indices,
 				session: block_entry.session(),
 				candidate: candidate_receipt,
 				backing_group,
@@ -2366,6 +2527,27 @@ fn process_wakeup(
 	Ok(actions)
 }
 
+// The candidates of the block claimed by our assignment cert. Compact certs claim the candidates
+// of all the cores they cover, the other certs only the candidate at `candidate_index`.
+fn claimed_candidate_indices(
+	block_entry: &BlockEntry,
+	cert: &AssignmentCertV2,
+	candidate_index: CandidateIndex,
+) -> CandidateBitfield {
+	match &cert.kind {
+		AssignmentCertKindV2::RelayVRFModuloCompact { core_bitfield } => block_entry
+			.candidates()
+			.iter()
+			.enumerate()
+			.filter(|(_, (core, _))| core_bitfield.bit_at(*core))
+			.map(|(i, _)| i as CandidateIndex)
+			.collect::<Vec<_>>()
+			.try_into()
+			.unwrap_or_else(|_| candidate_index.into()),
+		_ => candidate_index.into(),
+	}
+}
+
 // Launch approval work, returning an `AbortHandle` which corresponds to the background task
 // spawned. When the background work is no longer needed, the `AbortHandle` should be dropped
 // to cancel the background work and any requests it has spawned.
@@ -2513,6 +2695,7 @@ async fn launch_approval<Context>(
 				candidate.clone(),
 				available_data.pov,
 				PvfExecTimeoutKind::Approval,
+				PvfExecPriority::Normal,
 				val_tx,
 			))
 			.await;
@@ -2566,6 +2749,9 @@ async fn launch_approval<Context>(
 
 // Issue and import a local approval vote. Should only be invoked after approval checks
 // have been done.
+//
+// If approvals are coalesced, the vote is delayed until enough candidates of the block are
+// approved or the first of them waited long enough.
 #[overseer::contextbounds(ApprovalVoting, prefix = self::overseer)]
 async fn issue_approval<Context>(
 	ctx: &mut Context,
@@ -2612,44 +2798,93 @@ async fn issue_approval<Context>(
 	};
 	issue_approval_span.add_int_tag("candidate_index", candidate_index as i64);
 
-	let session_info = match state.session_info(block_entry.session()) {
-		Some(s) => s,
-		None => {
-			gum::warn!(
-				target: LOG_TARGET,
-				"Missing session info for live block {} in session {}",
-				block_hash,
-				block_entry.session(),
-			);
+	// Coalesced approvals can only be submitted on chain in disputes once enabled.
+	if state.max_approval_coalesce_count <= 1 ||
+		!is_approval_coalescing_enabled(ctx.sender(), block_hash).await
+	{
+		return sign_approvals(
+			ctx,
+			state,
+			db,
+			metrics,
+			block_hash,
+			validator_index,
+			vec![candidate_hash],
+		)
+		.await
+	}
 
-			metrics.on_approval_error();
-			return Ok(Vec::new())
-		},
-	};
+	let sign_at = state.clock.tick_now() + MAX_APPROVAL_COALESCE_WAIT_TICKS;
+	let approvals_to_sign = state.approvals_to_sign.entry(block_hash).or_insert_with(|| {
+		ApprovalsToSign { validator_index, candidate_hashes: Vec::new(), sign_at }
+	});
+	if !approvals_to_sign.candidate_hashes.contains(&candidate_hash) {
+		approvals_to_sign.candidate_hashes.push(candidate_hash);
+	}
 
-	let candidate_hash = match block_entry.candidate(candidate_index as usize) {
-		Some((_, h)) => *h,
-		None => {
-			gum::warn!(
-				target: LOG_TARGET,
-				"Received malformed request to approve out-of-bounds candidate index {} included at block {:?}",
-				candidate_index,
-				block_hash,
-			);
+	gum::trace!(
+		target: LOG_TARGET,
+		?candidate_hash,
+		?block_hash,
+		pending = approvals_to_sign.candidate_hashes.len(),
+		"Delaying approval vote to coalesce it with other candidates of the block",
+	);
 
-			metrics.on_approval_error();
+	if approvals_to_sign.candidate_hashes.len() < state.max_approval_coalesce_count as usize {
+		return Ok(Vec::new())
+	}
+
+	sign_pending_approvals(ctx, state, db, metrics, block_hash).await
+}
+
+// Issue and import the local approval vote for the candidates of the block waiting to be
+// signed, if any.
+#[overseer::contextbounds(ApprovalVoting, prefix = self::overseer)]
+async fn sign_pending_approvals<Context>(
+	ctx: &mut Context,
+	state: &mut State,
+	db: &mut OverlayedBackend<'_, impl Backend>,
+	metrics: &Metrics,
+	block_hash: Hash,
+) -> SubsystemResult<Vec<Action>> {
+	match state.approvals_to_sign.remove(&block_hash) {
+		None => Ok(Vec::new()),
+		Some(ApprovalsToSign { validator_index, candidate_hashes, .. }) =>
+			sign_approvals(ctx, state, db, metrics, block_hash, validator_index, candidate_hashes)
+				.await,
+	}
+}
+
+// Issue a single approval vote for the given candidates of a block, import it for each of them
+// and dispatch it to approval distribution.
+#[overseer::contextbounds(ApprovalVoting, prefix = self::overseer)]
+async fn sign_approvals<Context>(
+	ctx: &mut Context,
+	state: &State,
+	db: &mut OverlayedBackend<'_, impl Backend>,
+	metrics: &Metrics,
+	block_hash: Hash,
+	validator_index: ValidatorIndex,
+	candidate_hashes: Vec<CandidateHash>,
+) -> SubsystemResult<Vec<Action>> {
+	let block_entry = match db.load_block_entry(&block_hash)? {
+		Some(b) => b,
+		None => {
+			// not a cause for alarm - just lost a race with pruning, most likely.
+			metrics.on_approval_stale();
 			return Ok(Vec::new())
 		},
 	};
 
-	let candidate_entry = match db.load_candidate_entry(&candidate_hash)? {
-		Some(c) => c,
+	let session = block_entry.session();
+	let session_info = match state.session_info(session) {
+		Some(s) => s,
 		None => {
 			gum::warn!(
 				target: LOG_TARGET,
-				"Missing entry for candidate index {} included at block {:?}",
-				candidate_index,
+				"Missing session info for live block {} in session {}",
 				block_hash,
+				session,
 			);
 
 			metrics.on_approval_error();
@@ -2664,7 +2899,7 @@ async fn issue_approval<Context>(
 				target: LOG_TARGET,
 				"Validator index {} out of bounds in session {}",
 				validator_index.0,
-				block_entry.session(),
+				session,
 			);
 
 			metrics.on_approval_error();
@@ -2672,8 +2907,59 @@ async fn issue_approval<Context>(
 		},
 	};
 
-	let session = block_entry.session();
-	let sig = match sign_approval(&state.keystore, &validator_pubkey, candidate_hash, session) {
+	let mut candidates = Vec::with_capacity(candidate_hashes.len());
+	for candidate_hash in candidate_hashes {
+		let candidate_index =
+			match block_entry.candidates().iter().position(|e| e.1 == candidate_hash) {
+				None => {
+					gum::warn!(
+						target: LOG_TARGET,
+						"Candidate hash {} is not present in the block entry's candidates for relay block {}",
+						candidate_hash,
+						block_entry.parent_hash(),
+					);
+
+					metrics.on_approval_error();
+					continue
+				},
+				Some(idx) => idx as CandidateIndex,
+			};
+
+		let candidate_entry = match db.load_candidate_entry(&candidate_hash)? {
+			Some(c) => c,
+			None => {
+				gum::warn!(
+					target: LOG_TARGET,
+					"Missing entry for candidate index {} included at block {:?}",
+					candidate_index,
+					block_hash,
+				);
+
+				metrics.on_approval_error();
+				continue
+			},
+		};
+
+		candidates.push((candidate_index, candidate_hash, candidate_entry));
+	}
+
+	// The signature covers the candidates ordered by their index in the block.
+	candidates.sort_by_key(|(candidate_index, _, _)| *candidate_index);
+
+	let candidate_indices = match CandidateBitfield::try_from(
+		candidates
+			.iter()
+			.map(|(candidate_index, _, _)| *candidate_index)
+			.collect::<Vec<_>>(),
+	) {
+		Ok(candidate_indices) => candidate_indices,
+		// None of the candidates is left to approve.
+		Err(_) => return Ok(Vec::new()),
+	};
+	let candidate_hashes: Vec<CandidateHash> =
+		candidates.iter().map(|(_, candidate_hash, _)| *candidate_hash).collect();
+
+	let sig = match sign_approval(&state.keystore, &validator_pubkey, &candidate_hashes, session) {
 		Some(sig) => sig,
 		None => {
 			gum::warn!(
@@ -2690,29 +2976,38 @@ async fn issue_approval<Context>(
 
 	gum::trace!(
 		target: LOG_TARGET,
-		?candidate_hash,
+		?candidate_hashes,
 		?block_hash,
 		validator_index = validator_index.0,
 		"Issuing approval vote",
 	);
 
-	let actions = advance_approval_state(
-		state,
-		db,
-		metrics,
-		block_entry,
-		candidate_hash,
-		candidate_entry,
-		ApprovalStateTransition::LocalApproval(validator_index as _, sig.clone()),
-	);
+	let mut actions = Vec::new();
+	for (_, candidate_hash, candidate_entry) in candidates {
+		// The block entry may have been updated by importing the approval for another candidate.
+		let block_entry = match db.load_block_entry(&block_hash)? {
+			Some(b) => b,
+			None => break,
+		};
 
-	metrics.on_approval_produced();
+		actions.extend(advance_approval_state(
+			state,
+			db,
+			metrics,
+			block_entry,
+			candidate_hash,
+			candidate_entry,
+			ApprovalStateTransition::LocalApproval(validator_index as _, sig.clone()),
+		));
+
+		metrics.on_approval_produced();
+	}
 
 	// dispatch to approval distribution.
 	ctx.send_unbounded_message(ApprovalDistributionMessage::DistributeApproval(
-		IndirectSignedApprovalVote {
+		IndirectSignedApprovalVoteV2 {
 			block_hash,
-			candidate_index: candidate_index as _,
+			candidate_indices,
 			validator: validator_index,
 			signature: sig,
 		},
@@ -2721,16 +3016,17 @@ async fn issue_approval<Context>(
 	Ok(actions)
 }
 
-// Sign an approval vote. Fails if the key isn't present in the store.
+// Sign an approval vote for the given candidates, ordered by their index in the block. Fails if
+// the key isn't present in the store.
 fn sign_approval(
 	keystore: &LocalKeystore,
 	public: &ValidatorId,
-	candidate_hash: CandidateHash,
+	candidate_hashes: &[CandidateHash],
 	session_index: SessionIndex,
 ) -> Option<ValidatorSignature> {
 	let key = keystore.key_pair::<ValidatorPair>(public).ok().flatten()?;
 
-	let payload = ApprovalVote(candidate_hash).signing_payload(session_index);
+	let payload = ApprovalVoteMultipleCandidates(candidate_hashes).signing_payload(session_index);
 
 	Some(key.sign(&payload[..]))
 }
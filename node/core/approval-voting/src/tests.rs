@@ -19,8 +19,8 @@ use crate::tests::test_constants::TEST_CONFIG;
 use super::*;
 use polkadot_node_primitives::{
 	approval::{
-		AssignmentCert, AssignmentCertKind, DelayTranche, VrfOutput, VrfProof, VrfSignature,
-		RELAY_VRF_MODULO_CONTEXT,
+		AssignmentCertKindV2, AssignmentCertV2, CoreBitfield, DelayTranche,
+		IndirectSignedApprovalVote, VrfOutput, VrfProof, VrfSignature, RELAY_VRF_MODULO_CONTEXT,
 	},
 	AvailableData, BlockData, PoV,
 };
@@ -34,8 +34,8 @@ use polkadot_node_subsystem_test_helpers as test_helpers;
 use polkadot_node_subsystem_util::TimeoutExt;
 use polkadot_overseer::HeadSupportsParachains;
 use polkadot_primitives::{
-	CandidateCommitments, CandidateEvent, CoreIndex, GroupIndex, Header, Id as ParaId, IndexedVec,
-	ValidationCode, ValidatorSignature,
+	ApprovalVote, CandidateCommitments, CandidateEvent, CoreIndex, GroupIndex, Header,
+	Id as ParaId, IndexedVec, ValidationCode, ValidatorSignature,
 };
 use std::time::Duration;
 
@@ -242,18 +242,19 @@ where
 			polkadot_primitives::CoreIndex,
 			polkadot_primitives::GroupIndex,
 		)>,
+		_enable_v2_assignments: bool,
 	) -> HashMap<polkadot_primitives::CoreIndex, criteria::OurAssignment> {
 		self.0()
 	}
 
 	fn check_assignment_cert(
 		&self,
-		_claimed_core_index: polkadot_primitives::CoreIndex,
+		_claimed_core_indices: CoreBitfield,
 		validator_index: ValidatorIndex,
 		_config: &criteria::Config,
 		_relay_vrf_story: polkadot_node_primitives::approval::RelayVRFStory,
-		_assignment: &polkadot_node_primitives::approval::AssignmentCert,
-		_backing_group: polkadot_primitives::GroupIndex,
+		_assignment: &AssignmentCertV2,
+		_backing_groups: Vec<polkadot_primitives::GroupIndex>,
 	) -> Result<polkadot_node_primitives::approval::DelayTranche, criteria::InvalidAssignment> {
 		self.1(validator_index)
 	}
@@ -384,7 +385,7 @@ impl Backend for TestStore {
 	}
 }
 
-fn garbage_assignment_cert(kind: AssignmentCertKind) -> AssignmentCert {
+fn garbage_assignment_cert(kind: AssignmentCertKindV2) -> AssignmentCertV2 {
 	let ctx = schnorrkel::signing_context(RELAY_VRF_MODULO_CONTEXT);
 	let msg = b"test-garbage";
 	let mut prng = rand_core::OsRng;
@@ -392,7 +393,7 @@ fn garbage_assignment_cert(kind: AssignmentCertKind) -> AssignmentCert {
 	let (inout, proof, _) = keypair.vrf_sign(ctx.bytes(msg));
 	let out = inout.to_output();
 
-	AssignmentCert { kind, vrf: VrfSignature { output: VrfOutput(out), proof: VrfProof(proof) } }
+	AssignmentCertV2 { kind, vrf: VrfSignature { output: VrfOutput(out), proof: VrfProof(proof) } }
 }
 
 fn sign_approval(
@@ -494,6 +495,8 @@ fn test_harness<T: Future<Output = VirtualOverseer>>(
 				col_approval_data: test_constants::TEST_CONFIG.col_approval_data,
 				slot_duration_millis: SLOT_DURATION_MILLIS,
 				col_session_data: TEST_CONFIG.col_session_data,
+				enable_v2_assignments: false,
+				max_approval_coalesce_count: 1,
 			},
 			Arc::new(db),
 			Arc::new(keystore),
@@ -593,7 +596,8 @@ async fn check_and_import_approval(
 		overseer,
 		FromOrchestra::Communication {
 			msg: ApprovalVotingMessage::CheckAndImportApproval(
-				IndirectSignedApprovalVote { block_hash, candidate_index, validator, signature },
+				IndirectSignedApprovalVote { block_hash, candidate_index, validator, signature }
+					.into(),
 				tx,
 			),
 		},
@@ -621,12 +625,14 @@ async fn check_and_import_assignment(
 		overseer,
 		FromOrchestra::Communication {
 			msg: ApprovalVotingMessage::CheckAndImportAssignment(
-				IndirectAssignmentCert {
+				IndirectAssignmentCertV2 {
 					block_hash,
 					validator,
-					cert: garbage_assignment_cert(AssignmentCertKind::RelayVRFModulo { sample: 0 }),
+					cert: garbage_assignment_cert(AssignmentCertKindV2::RelayVRFModulo {
+						sample: 0,
+					}),
 				},
-				candidate_index,
+				candidate_index.into(),
 				tx,
 			),
 		},
@@ -1107,14 +1113,14 @@ fn blank_subsystem_act_on_bad_block() {
 			&mut virtual_overseer,
 			FromOrchestra::Communication {
 				msg: ApprovalVotingMessage::CheckAndImportAssignment(
-					IndirectAssignmentCert {
+					IndirectAssignmentCertV2 {
 						block_hash: bad_block_hash,
 						validator: 0u32.into(),
-						cert: garbage_assignment_cert(AssignmentCertKind::RelayVRFModulo {
+						cert: garbage_assignment_cert(AssignmentCertKindV2::RelayVRFModulo {
 							sample: 0,
 						}),
 					},
-					0u32,
+					0u32.into(),
 					tx,
 				),
 			},
@@ -1409,6 +1415,89 @@ fn subsystem_accepts_duplicate_assignment() {
 	});
 }
 
+#[test]
+fn subsystem_imports_compact_assignment_for_all_claimed_candidates() {
+	test_harness(HarnessConfig::default(), |test_harness| async move {
+		let TestHarness { mut virtual_overseer, sync_oracle_handle: _sync_oracle_handle, .. } =
+			test_harness;
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::ChainApi(ChainApiMessage::FinalizedBlockNumber(rx)) => {
+				rx.send(Ok(0)).unwrap();
+			}
+		);
+
+		let block_hash = Hash::repeat_byte(0x01);
+		let validator = ValidatorIndex(0);
+
+		let candidate_receipt1 = {
+			let mut receipt = dummy_candidate_receipt(block_hash);
+			receipt.descriptor.para_id = ParaId::from(1_u32);
+			receipt
+		};
+		let candidate_receipt2 = {
+			let mut receipt = dummy_candidate_receipt(block_hash);
+			receipt.descriptor.para_id = ParaId::from(2_u32);
+			receipt
+		};
+
+		ChainBuilder::new()
+			.add_block(
+				block_hash,
+				ChainBuilder::GENESIS_HASH,
+				1,
+				BlockConfig {
+					slot: Slot::from(1),
+					candidates: Some(vec![
+						(candidate_receipt1, CoreIndex(0), GroupIndex(1)),
+						(candidate_receipt2, CoreIndex(1), GroupIndex(1)),
+					]),
+					session_info: None,
+				},
+			)
+			.build(&mut virtual_overseer)
+			.await;
+
+		let (tx, rx) = oneshot::channel();
+		overseer_send(
+			&mut virtual_overseer,
+			FromOrchestra::Communication {
+				msg: ApprovalVotingMessage::CheckAndImportAssignment(
+					IndirectAssignmentCertV2 {
+						block_hash,
+						validator,
+						cert: garbage_assignment_cert(
+							AssignmentCertKindV2::RelayVRFModuloCompact {
+								core_bitfield: vec![CoreIndex(0), CoreIndex(1)].try_into().unwrap(),
+							},
+						),
+					},
+					vec![0u32, 1].try_into().unwrap(),
+					tx,
+				),
+			},
+		)
+		.await;
+
+		assert_eq!(rx.await, Ok(AssignmentCheckResult::Accepted));
+
+		// The assignment was imported for both candidates.
+		for candidate_index in [0, 1] {
+			let rx = check_and_import_assignment(
+				&mut virtual_overseer,
+				block_hash,
+				candidate_index,
+				validator,
+			)
+			.await;
+
+			assert_eq!(rx.await, Ok(AssignmentCheckResult::AcceptedDuplicate));
+		}
+
+		virtual_overseer
+	});
+}
+
 #[test]
 fn subsystem_rejects_assignment_with_unknown_candidate() {
 	test_harness(HarnessConfig::default(), |test_harness| async move {
@@ -1520,6 +1609,125 @@ fn subsystem_accepts_and_imports_approval_after_assignment() {
 	});
 }
 
+#[test]
+fn subsystem_imports_approval_for_multiple_candidates() {
+	test_harness(HarnessConfig::default(), |test_harness| async move {
+		let TestHarness { mut virtual_overseer, sync_oracle_handle: _sync_oracle_handle, .. } =
+			test_harness;
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::ChainApi(ChainApiMessage::FinalizedBlockNumber(rx)) => {
+				rx.send(Ok(0)).unwrap();
+			}
+		);
+
+		let block_hash = Hash::repeat_byte(0x01);
+		let validator = ValidatorIndex(0);
+		let session_index = 1;
+
+		let candidate_receipt1 = {
+			let mut receipt = dummy_candidate_receipt(block_hash);
+			receipt.descriptor.para_id = ParaId::from(1_u32);
+			receipt
+		};
+		let candidate_receipt2 = {
+			let mut receipt = dummy_candidate_receipt(block_hash);
+			receipt.descriptor.para_id = ParaId::from(2_u32);
+			receipt
+		};
+		let candidate_hashes = vec![candidate_receipt1.hash(), candidate_receipt2.hash()];
+
+		ChainBuilder::new()
+			.add_block(
+				block_hash,
+				ChainBuilder::GENESIS_HASH,
+				1,
+				BlockConfig {
+					slot: Slot::from(1),
+					candidates: Some(vec![
+						(candidate_receipt1, CoreIndex(0), GroupIndex(1)),
+						(candidate_receipt2, CoreIndex(1), GroupIndex(1)),
+					]),
+					session_info: None,
+				},
+			)
+			.build(&mut virtual_overseer)
+			.await;
+
+		for candidate_index in [0, 1] {
+			let rx = check_and_import_assignment(
+				&mut virtual_overseer,
+				block_hash,
+				candidate_index,
+				validator,
+			)
+			.await;
+
+			assert_eq!(rx.await, Ok(AssignmentCheckResult::Accepted));
+		}
+
+		// A signature over a single candidate doesn't approve both.
+		let (tx, rx) = oneshot::channel();
+		overseer_send(
+			&mut virtual_overseer,
+			FromOrchestra::Communication {
+				msg: ApprovalVotingMessage::CheckAndImportApproval(
+					IndirectSignedApprovalVoteV2 {
+						block_hash,
+						candidate_indices: vec![0u32, 1].try_into().unwrap(),
+						validator,
+						signature: sign_approval(
+							Sr25519Keyring::Alice,
+							candidate_hashes[0],
+							session_index,
+						),
+					},
+					tx,
+				),
+			},
+		)
+		.await;
+
+		assert_eq!(
+			rx.await,
+			Ok(ApprovalCheckResult::Bad(ApprovalCheckError::InvalidSignature(validator))),
+		);
+
+		let (tx, rx) = oneshot::channel();
+		overseer_send(
+			&mut virtual_overseer,
+			FromOrchestra::Communication {
+				msg: ApprovalVotingMessage::CheckAndImportApproval(
+					IndirectSignedApprovalVoteV2 {
+						block_hash,
+						candidate_indices: vec![0u32, 1].try_into().unwrap(),
+						validator,
+						signature: Sr25519Keyring::Alice
+							.sign(
+								&ApprovalVoteMultipleCandidates(&candidate_hashes)
+									.signing_payload(session_index),
+							)
+							.into(),
+					},
+					tx,
+				),
+			},
+		)
+		.await;
+
+		// The block is only approved once the approval was imported for both candidates.
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::ChainSelection(ChainSelectionMessage::Approved(b_hash)) => {
+				assert_eq!(b_hash, block_hash);
+			}
+		);
+		assert_eq!(rx.await, Ok(ApprovalCheckResult::Accepted));
+
+		virtual_overseer
+	});
+}
+
 #[test]
 fn subsystem_second_approval_import_only_schedules_wakeups() {
 	test_harness(HarnessConfig::default(), |test_harness| async move {
@@ -1775,14 +1983,14 @@ fn linear_import_act_on_leaf() {
 			&mut virtual_overseer,
 			FromOrchestra::Communication {
 				msg: ApprovalVotingMessage::CheckAndImportAssignment(
-					IndirectAssignmentCert {
+					IndirectAssignmentCertV2 {
 						block_hash: head,
 						validator: 0u32.into(),
-						cert: garbage_assignment_cert(AssignmentCertKind::RelayVRFModulo {
+						cert: garbage_assignment_cert(AssignmentCertKindV2::RelayVRFModulo {
 							sample: 0,
 						}),
 					},
-					0u32,
+					0u32.into(),
 					tx,
 				),
 			},
@@ -1845,14 +2053,14 @@ fn forkful_import_at_same_height_act_on_leaf() {
 				&mut virtual_overseer,
 				FromOrchestra::Communication {
 					msg: ApprovalVotingMessage::CheckAndImportAssignment(
-						IndirectAssignmentCert {
+						IndirectAssignmentCertV2 {
 							block_hash: head,
 							validator: 0u32.into(),
-							cert: garbage_assignment_cert(AssignmentCertKind::RelayVRFModulo {
+							cert: garbage_assignment_cert(AssignmentCertKindV2::RelayVRFModulo {
 								sample: 0,
 							}),
 						},
-						0u32,
+						0u32.into(),
 						tx,
 					),
 				},
@@ -2297,7 +2505,9 @@ fn subsystem_validate_approvals_cache() {
 			let _ = assignments.insert(
 				CoreIndex(0),
 				approval_db::v1::OurAssignment {
-					cert: garbage_assignment_cert(AssignmentCertKind::RelayVRFModulo { sample: 0 }),
+					cert: garbage_assignment_cert(AssignmentCertKindV2::RelayVRFModulo {
+						sample: 0,
+					}),
 					tranche: 0,
 					validator_index: ValidatorIndex(0),
 					triggered: false,
@@ -2405,7 +2615,7 @@ async fn handle_double_assignment_import(
 			_,
 			c_index,
 		)) => {
-			assert_eq!(candidate_index, c_index);
+			assert_eq!(c_index, candidate_index.into());
 		}
 	);
 
@@ -2418,13 +2628,13 @@ async fn handle_double_assignment_import(
 			_,
 			c_index
 		)) => {
-			assert_eq!(candidate_index, c_index);
+			assert_eq!(c_index, candidate_index.into());
 		}
 	);
 
 	assert_matches!(
 		overseer_recv(virtual_overseer).await,
-		AllMessages::CandidateValidation(CandidateValidationMessage::ValidateFromExhaustive(_, _, _, _, timeout, tx)) if timeout == PvfExecTimeoutKind::Approval => {
+		AllMessages::CandidateValidation(CandidateValidationMessage::ValidateFromExhaustive(_, _, _, _, timeout, priority, tx)) if timeout == PvfExecTimeoutKind::Approval && priority == PvfExecPriority::Normal => {
 			tx.send(Ok(ValidationResult::Valid(Default::default(), Default::default())))
 				.unwrap();
 		}
@@ -2515,7 +2725,9 @@ where
 			let _ = assignments.insert(
 				CoreIndex(0),
 				approval_db::v1::OurAssignment {
-					cert: garbage_assignment_cert(AssignmentCertKind::RelayVRFModulo { sample: 0 }),
+					cert: garbage_assignment_cert(AssignmentCertKindV2::RelayVRFModulo {
+						sample: 0,
+					}),
 					tranche: our_assigned_tranche,
 					validator_index: ValidatorIndex(0),
 					triggered: false,
@@ -33,7 +33,9 @@ use polkadot_node_subsystem_util::database::{DBTransaction, Database};
 use sp_consensus::SyncOracle;
 
 use bitvec::{order::Lsb0 as BitOrderLsb0, vec::BitVec};
-use polkadot_node_primitives::{AvailableData, ErasureChunk};
+use polkadot_node_primitives::{
+	AvailableData, ErasureChunk, StoredCandidateInfo, StoredCandidateState,
+};
 use polkadot_node_subsystem::{
 	errors::{ChainApiError, RuntimeApiError},
 	messages::{AvailabilityStoreMessage, ChainApiMessage},
@@ -59,6 +61,7 @@ const CHUNK_PREFIX: &[u8; 5] = b"chunk";
 const META_PREFIX: &[u8; 4] = b"meta";
 const UNFINALIZED_PREFIX: &[u8; 11] = b"unfinalized";
 const PRUNE_BY_TIME_PREFIX: &[u8; 13] = b"prune_by_time";
+const STORED_SIZE_PREFIX: &[u8; 11] = b"stored_size";
 
 // We have some keys we want to map to empty values because existence of the key is enough. We use this because
 // rocksdb doesn't support empty values.
@@ -70,6 +73,14 @@ const KEEP_UNAVAILABLE_FOR: Duration = Duration::from_secs(60 * 60);
 /// Finalized data is kept for 25 hours.
 const KEEP_FINALIZED_FOR: Duration = Duration::from_secs(25 * 60 * 60);
 
+/// Finalized data is kept for at least 24 hours by default, even if the disk usage limit is
+/// exceeded.
+///
+/// This is the dispute window on Polkadot, on Kusama it is 6 hours. We need to keep the data for
+/// participating in disputes, so we go with the longer one unless the dispute window of the chain
+/// is given in `Config::min_keep_finalized_for`.
+const MIN_KEEP_FINALIZED_FOR: Duration = Duration::from_secs(24 * 60 * 60);
+
 /// The pruning interval.
 const PRUNING_INTERVAL: Duration = Duration::from_secs(60 * 5);
 
@@ -151,6 +162,33 @@ struct CandidateMeta {
 	chunks_stored: BitVec<u8, BitOrderLsb0>,
 }
 
+/// Number of bytes used for storing available data and chunks.
+///
+/// Stored per candidate, for knowing how much space pruning it frees up, and kept as a total of
+/// all candidates in memory. Candidates stored by versions not yet tracking their size have no
+/// entry and are accounted with zero bytes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+struct StoredSize {
+	available_data: u64,
+	chunks: u64,
+}
+
+impl StoredSize {
+	fn total(&self) -> u64 {
+		self.available_data.saturating_add(self.chunks)
+	}
+
+	fn add(&mut self, other: StoredSize) {
+		self.available_data = self.available_data.saturating_add(other.available_data);
+		self.chunks = self.chunks.saturating_add(other.chunks);
+	}
+
+	fn sub(&mut self, other: StoredSize) {
+		self.available_data = self.available_data.saturating_sub(other.available_data);
+		self.chunks = self.chunks.saturating_sub(other.chunks);
+	}
+}
+
 fn query_inner<D: Decode>(
 	db: &Arc<dyn Database>,
 	column: u32,
@@ -251,6 +289,66 @@ fn delete_meta(tx: &mut DBTransaction, config: &Config, hash: &CandidateHash) {
 	tx.delete(config.col_meta, &key[..])
 }
 
+fn load_stored_size(
+	db: &Arc<dyn Database>,
+	config: &Config,
+	hash: &CandidateHash,
+) -> Result<Option<StoredSize>, Error> {
+	let key = (STORED_SIZE_PREFIX, hash).encode();
+
+	query_inner(db, config.col_meta, &key)
+}
+
+fn write_stored_size(
+	tx: &mut DBTransaction,
+	config: &Config,
+	hash: &CandidateHash,
+	size: &StoredSize,
+) {
+	let key = (STORED_SIZE_PREFIX, hash).encode();
+
+	tx.put_vec(config.col_meta, &key, size.encode());
+}
+
+fn delete_stored_size(tx: &mut DBTransaction, config: &Config, hash: &CandidateHash) {
+	let key = (STORED_SIZE_PREFIX, hash).encode();
+	tx.delete(config.col_meta, &key[..])
+}
+
+/// Sum up the stored sizes of all candidates.
+fn load_storage_usage(db: &Arc<dyn Database>, config: &Config) -> Result<StoredSize, Error> {
+	let mut usage = StoredSize::default();
+	for r in db.iter_with_prefix(config.col_meta, STORED_SIZE_PREFIX) {
+		let (_k, v) = r?;
+		usage.add(StoredSize::decode(&mut &v[..])?);
+	}
+
+	Ok(usage)
+}
+
+/// Return the keys of all available data and chunks in the store, according to the meta
+/// information of the candidates.
+///
+/// Database backends like ParityDB don't keep the keys of the data column, but they are needed to
+/// export it.
+pub fn stored_data_keys(db: &dyn Database, config: &Config) -> Result<Vec<Vec<u8>>, Error> {
+	let mut keys = Vec::new();
+	for r in db.iter_with_prefix(config.col_meta, META_PREFIX) {
+		let (k, v) = r?;
+		let candidate_hash = CandidateHash::decode(&mut &k[META_PREFIX.len()..])?;
+		let meta = CandidateMeta::decode(&mut &v[..])?;
+
+		if meta.data_available {
+			keys.push((AVAILABLE_PREFIX, &candidate_hash).encode());
+		}
+		for chunk_index in meta.chunks_stored.iter_ones() {
+			keys.push((CHUNK_PREFIX, &candidate_hash, ValidatorIndex(chunk_index as u32)).encode());
+		}
+	}
+
+	Ok(keys)
+}
+
 fn delete_unfinalized_height(tx: &mut DBTransaction, config: &Config, block_number: BlockNumber) {
 	let prefix = (UNFINALIZED_PREFIX, BEBlockNumber(block_number)).encode();
 	tx.delete_prefix(config.col_meta, &prefix);
@@ -334,6 +432,14 @@ fn decode_pruning_key(s: &[u8]) -> Result<(Duration, CandidateHash), CodecError>
 		.map(|(t, ch)| (t.into(), ch))
 }
 
+fn decode_meta_key(s: &[u8]) -> Result<CandidateHash, CodecError> {
+	if !s.starts_with(META_PREFIX) {
+		return Err("missing magic string".into())
+	}
+
+	CandidateHash::decode(&mut &s[META_PREFIX.len()..])
+}
+
 #[derive(Debug, thiserror::Error)]
 #[allow(missing_docs)]
 pub enum Error {
@@ -409,6 +515,10 @@ struct PruningConfig {
 
 	/// How often to perform data pruning.
 	pruning_interval: Duration,
+
+	/// How long finalized data should be kept at least, when pruning early because the disk usage
+	/// limit is exceeded.
+	min_keep_finalized_for: Duration,
 }
 
 impl Default for PruningConfig {
@@ -417,6 +527,7 @@ impl Default for PruningConfig {
 			keep_unavailable_for: KEEP_UNAVAILABLE_FOR,
 			keep_finalized_for: KEEP_FINALIZED_FOR,
 			pruning_interval: PRUNING_INTERVAL,
+			min_keep_finalized_for: MIN_KEEP_FINALIZED_FOR,
 		}
 	}
 }
@@ -428,6 +539,23 @@ pub struct Config {
 	pub col_data: u32,
 	/// The column family for availability store meta information.
 	pub col_meta: u32,
+	/// Soft limit on the number of bytes used for available data and chunks.
+	///
+	/// Exceeding it makes the store prune finalized data early, once it is older than the dispute
+	/// window. Unavailable and unfinalized data is never pruned early, so the limit might still be
+	/// exceeded.
+	///
+	/// Finalized data is kept for 25 hours anyway, so only the data finalized between the end of
+	/// the dispute window and then can be reclaimed: an hour's worth with the 24 hour dispute window
+	/// of Polkadot, but most of it with the 6 hour one of Kusama.
+	pub max_disk_usage: Option<u64>,
+	/// How long finalized data is kept at least when exceeding `max_disk_usage`, which should be
+	/// the dispute window of the chain. Defaults to the dispute window on Polkadot.
+	///
+	/// A shorter period lets the limit reclaim more data, at the cost of not having it at hand for
+	/// disputes raised late in the dispute window, neither to participate in them nor to serve it
+	/// to other validators.
+	pub min_keep_finalized_for: Option<Duration>,
 }
 
 trait Clock: Send + Sync {
@@ -450,6 +578,7 @@ pub struct AvailabilityStoreSubsystem {
 	db: Arc<dyn Database>,
 	known_blocks: KnownUnfinalizedBlocks,
 	finalized_number: Option<BlockNumber>,
+	storage_usage: StoredSize,
 	metrics: Metrics,
 	clock: Box<dyn Clock>,
 	sync_oracle: Box<dyn SyncOracle + Send + Sync>,
@@ -463,10 +592,26 @@ impl AvailabilityStoreSubsystem {
 		sync_oracle: Box<dyn SyncOracle + Send + Sync>,
 		metrics: Metrics,
 	) -> Self {
+		let pruning_config = PruningConfig {
+			min_keep_finalized_for: config.min_keep_finalized_for.unwrap_or(MIN_KEEP_FINALIZED_FOR),
+			..PruningConfig::default()
+		};
+
+		if config.max_disk_usage.is_some() &&
+			pruning_config.min_keep_finalized_for >= pruning_config.keep_finalized_for
+		{
+			gum::warn!(
+				target: LOG_TARGET,
+				min_keep_finalized_for = ?pruning_config.min_keep_finalized_for,
+				keep_finalized_for = ?pruning_config.keep_finalized_for,
+				"Finalized data is never pruned early, the disk usage limit has no effect",
+			);
+		}
+
 		Self::with_pruning_config_and_clock(
 			db,
 			config,
-			PruningConfig::default(),
+			pruning_config,
 			Box::new(SystemClock),
 			sync_oracle,
 			metrics,
@@ -491,10 +636,19 @@ impl AvailabilityStoreSubsystem {
 			known_blocks: KnownUnfinalizedBlocks::default(),
 			sync_oracle,
 			finalized_number: None,
+			storage_usage: StoredSize::default(),
 		}
 	}
 }
 
+impl AvailabilityStoreSubsystem {
+	/// Report the current disk usage to the metrics.
+	fn note_storage_usage(&self) {
+		self.metrics
+			.on_storage_usage(self.storage_usage.available_data, self.storage_usage.chunks);
+	}
+}
+
 /// We keep the hashes and numbers of all unfinalized
 /// processed blocks in memory.
 #[derive(Default, Debug)]
@@ -541,6 +695,14 @@ impl<Context> AvailabilityStoreSubsystem {
 async fn run<Context>(mut subsystem: AvailabilityStoreSubsystem, mut ctx: Context) {
 	let mut next_pruning = Delay::new(subsystem.pruning_config.pruning_interval).fuse();
 
+	match load_storage_usage(&subsystem.db, &subsystem.config) {
+		Ok(usage) => {
+			subsystem.storage_usage = usage;
+			subsystem.metrics.on_storage_usage(usage.available_data, usage.chunks);
+		},
+		Err(e) => e.trace(),
+	}
+
 	loop {
 		let res = run_iteration(&mut ctx, &mut subsystem, &mut next_pruning).await;
 		match res {
@@ -604,7 +766,9 @@ async fn run_iteration<Context>(
 				}
 				FromOrchestra::Communication { msg } => {
 					let _timer = subsystem.metrics.time_process_message();
-					process_message(subsystem, msg)?;
+					let res = process_message(subsystem, msg);
+					subsystem.note_storage_usage();
+					res?;
 				}
 			}
 		}
@@ -614,7 +778,24 @@ async fn run_iteration<Context>(
 			*next_pruning = Delay::new(subsystem.pruning_config.pruning_interval).fuse();
 
 			let _timer = subsystem.metrics.time_pruning();
-			prune_all(&subsystem.db, &subsystem.config, &*subsystem.clock)?;
+			let res = prune_all(
+				&subsystem.db,
+				&subsystem.config,
+				&*subsystem.clock,
+				&mut subsystem.storage_usage,
+			)
+			.and_then(|()| {
+				prune_over_capacity(
+					&subsystem.db,
+					&subsystem.config,
+					&subsystem.pruning_config,
+					&*subsystem.clock,
+					&mut subsystem.storage_usage,
+					&subsystem.metrics,
+				)
+			});
+			subsystem.note_storage_usage();
+			res?;
 		}
 	}
 
@@ -1047,22 +1228,21 @@ fn process_message(
 				.map_or(false, |m| m.data_available);
 			let _ = tx.send(a);
 		},
-		AvailabilityStoreMessage::QueryChunk(candidate, validator_index, tx) => {
+		AvailabilityStoreMessage::QueryChunk(candidate, chunk_index, tx) => {
 			let _timer = subsystem.metrics.time_get_chunk();
-			let _ =
-				tx.send(load_chunk(&subsystem.db, &subsystem.config, &candidate, validator_index)?);
+			let _ = tx.send(load_chunk(&subsystem.db, &subsystem.config, &candidate, chunk_index)?);
 		},
 		AvailabilityStoreMessage::QueryChunkSize(candidate, tx) => {
 			let meta = load_meta(&subsystem.db, &subsystem.config, &candidate)?;
 
-			let validator_index = meta.map_or(None, |meta| meta.chunks_stored.first_one());
+			let chunk_index = meta.map_or(None, |meta| meta.chunks_stored.first_one());
 
-			let maybe_chunk_size = if let Some(validator_index) = validator_index {
+			let maybe_chunk_size = if let Some(chunk_index) = chunk_index {
 				load_chunk(
 					&subsystem.db,
 					&subsystem.config,
 					&candidate,
-					ValidatorIndex(validator_index as u32),
+					ValidatorIndex(chunk_index as u32),
 				)?
 				.map(|erasure_chunk| erasure_chunk.chunk.len())
 			} else {
@@ -1103,17 +1283,54 @@ fn process_message(
 				},
 			}
 		},
-		AvailabilityStoreMessage::QueryChunkAvailability(candidate, validator_index, tx) => {
+		AvailabilityStoreMessage::QueryChunkAvailability(candidate, chunk_index, tx) => {
 			let a = load_meta(&subsystem.db, &subsystem.config, &candidate)?.map_or(false, |m| {
-				*m.chunks_stored.get(validator_index.0 as usize).as_deref().unwrap_or(&false)
+				*m.chunks_stored.get(chunk_index.0 as usize).as_deref().unwrap_or(&false)
 			});
 			let _ = tx.send(a);
 		},
+		AvailabilityStoreMessage::QueryStoredCandidate(candidate, tx) => {
+			let info = match load_meta(&subsystem.db, &subsystem.config, &candidate)? {
+				None => None,
+				Some(meta) => Some(stored_candidate_info(
+					&subsystem.db,
+					&subsystem.config,
+					&subsystem.pruning_config,
+					candidate,
+					meta,
+				)?),
+			};
+
+			let _ = tx.send(info);
+		},
+		AvailabilityStoreMessage::ListStoredCandidates { start_after, limit, tx } => {
+			let candidates =
+				load_metas_after(&subsystem.db, &subsystem.config, start_after, limit)?
+					.into_iter()
+					.map(|(candidate_hash, meta)| {
+						stored_candidate_info(
+							&subsystem.db,
+							&subsystem.config,
+							&subsystem.pruning_config,
+							candidate_hash,
+							meta,
+						)
+					})
+					.collect::<Result<Vec<_>, _>>()?;
+
+			let _ = tx.send(candidates);
+		},
 		AvailabilityStoreMessage::StoreChunk { candidate_hash, chunk, tx } => {
 			subsystem.metrics.on_chunks_received(1);
 			let _timer = subsystem.metrics.time_store_chunk();
 
-			match store_chunk(&subsystem.db, &subsystem.config, candidate_hash, chunk) {
+			match store_chunk(
+				&subsystem.db,
+				&subsystem.config,
+				&mut subsystem.storage_usage,
+				candidate_hash,
+				chunk,
+			) {
 				Ok(true) => {
 					let _ = tx.send(Ok(()));
 				},
@@ -1137,7 +1354,7 @@ fn process_message(
 			let _timer = subsystem.metrics.time_store_available_data();
 
 			let res =
-				store_available_data(&subsystem, candidate_hash, n_validators as _, available_data);
+				store_available_data(subsystem, candidate_hash, n_validators as _, available_data);
 
 			match res {
 				Ok(()) => {
@@ -1154,14 +1371,114 @@ fn process_message(
 	Ok(())
 }
 
+/// Load up to `limit` candidate metas, ordered by candidate hash and starting after `start_after`.
+///
+/// The database can only iterate over the keys with a given prefix, so seeking to the first key
+/// after `start_after` is done by prefixes: the greater keys are those sharing the first `i` bytes
+/// with it, followed by a greater byte, where `i` goes from the last byte to the first one.
+fn load_metas_after(
+	db: &Arc<dyn Database>,
+	config: &Config,
+	start_after: Option<CandidateHash>,
+	limit: usize,
+) -> Result<Vec<(CandidateHash, CandidateMeta)>, Error> {
+	let mut metas = Vec::new();
+	let load = |prefix: &[u8], metas: &mut Vec<(CandidateHash, CandidateMeta)>| {
+		for r in db.iter_with_prefix(config.col_meta, prefix) {
+			if metas.len() >= limit {
+				break
+			}
+
+			let (k, v) = r?;
+			let candidate_hash = match decode_meta_key(&k[..]) {
+				Ok(h) => h,
+				Err(_) => continue, // sanity
+			};
+			metas.push((candidate_hash, CandidateMeta::decode(&mut &v[..])?));
+		}
+		Ok::<_, Error>(())
+	};
+
+	let start_after = match start_after {
+		Some(start_after) => (META_PREFIX, start_after).encode(),
+		None => {
+			load(META_PREFIX, &mut metas)?;
+			return Ok(metas)
+		},
+	};
+
+	for i in (META_PREFIX.len()..start_after.len()).rev() {
+		if metas.len() >= limit {
+			break
+		}
+
+		// Most of the longer prefixes are not shared with any other key.
+		let prefix = &start_after[..i];
+		let is_shared = db
+			.iter_with_prefix(config.col_meta, prefix)
+			.any(|r| r.map_or(true, |(k, _v)| &k[..] != &start_after[..]));
+		if !is_shared {
+			continue
+		}
+
+		for byte in (start_after[i]..=u8::MAX).skip(1) {
+			if metas.len() >= limit {
+				break
+			}
+
+			let mut next_prefix = prefix.to_vec();
+			next_prefix.push(byte);
+			load(&next_prefix, &mut metas)?;
+		}
+	}
+
+	Ok(metas)
+}
+
+fn stored_candidate_info(
+	db: &Arc<dyn Database>,
+	config: &Config,
+	pruning_config: &PruningConfig,
+	candidate_hash: CandidateHash,
+	meta: CandidateMeta,
+) -> Result<StoredCandidateInfo, Error> {
+	// Unavailable and finalized candidates are scheduled for pruning a fixed time after entering
+	// their state, unfinalized ones are not scheduled.
+	let prune_at = match &meta.state {
+		State::Unavailable(since) => Some(since.0 + pruning_config.keep_unavailable_for.as_secs()),
+		State::Unfinalized(..) => None,
+		State::Finalized(since) => Some(since.0 + pruning_config.keep_finalized_for.as_secs()),
+	};
+	let state = match meta.state {
+		State::Unavailable(since) => StoredCandidateState::Unavailable { since: since.0 },
+		State::Unfinalized(since, blocks) => StoredCandidateState::Unfinalized {
+			since: since.0,
+			blocks: blocks.into_iter().map(|(number, hash)| (number.0, hash)).collect(),
+		},
+		State::Finalized(since) => StoredCandidateState::Finalized { since: since.0 },
+	};
+	let stored_bytes = load_stored_size(db, config, &candidate_hash)?.unwrap_or_default().total();
+
+	Ok(StoredCandidateInfo {
+		candidate_hash,
+		state,
+		data_available: meta.data_available,
+		chunks_stored: meta.chunks_stored.iter().by_vals().collect(),
+		stored_bytes,
+		prune_at,
+	})
+}
+
 // Ok(true) on success, Ok(false) on failure, and Err on internal error.
 fn store_chunk(
 	db: &Arc<dyn Database>,
 	config: &Config,
+	storage_usage: &mut StoredSize,
 	candidate_hash: CandidateHash,
 	chunk: ErasureChunk,
 ) -> Result<bool, Error> {
 	let mut tx = DBTransaction::new();
+	let added = StoredSize { available_data: 0, chunks: chunk.encoded_size() as u64 };
 
 	let mut meta = match load_meta(db, config, &candidate_hash)? {
 		Some(m) => m,
@@ -1175,6 +1492,10 @@ fn store_chunk(
 
 			write_chunk(&mut tx, config, &candidate_hash, chunk.index, &chunk);
 			write_meta(&mut tx, config, &candidate_hash, &meta);
+
+			let mut size = load_stored_size(db, config, &candidate_hash)?.unwrap_or_default();
+			size.add(added);
+			write_stored_size(&mut tx, config, &candidate_hash, &size);
 		},
 		None => return Ok(false), // out of bounds.
 	}
@@ -1187,12 +1508,13 @@ fn store_chunk(
 	);
 
 	db.write(tx)?;
+	storage_usage.add(added);
 	Ok(true)
 }
 
 // Ok(true) on success, Ok(false) on failure, and Err on internal error.
 fn store_available_data(
-	subsystem: &AvailabilityStoreSubsystem,
+	subsystem: &mut AvailabilityStoreSubsystem,
 	candidate_hash: CandidateHash,
 	n_validators: usize,
 	available_data: AvailableData,
@@ -1222,18 +1544,24 @@ fn store_available_data(
 		},
 	};
 
-	let chunks = erasure::obtain_chunks_v1(n_validators, &available_data)?;
-	let branches = erasure::branches(chunks.as_ref());
+	let encoded = erasure::obtain_chunks_with_branches_v1(n_validators, &available_data)?;
 
-	let erasure_chunks = chunks.iter().zip(branches.map(|(proof, _)| proof)).enumerate().map(
+	let erasure_chunks = encoded.chunks.into_iter().zip(encoded.proofs).enumerate().map(
 		|(index, (chunk, proof))| ErasureChunk {
-			chunk: chunk.clone(),
+			chunk,
 			proof,
 			index: ValidatorIndex(index as u32),
 		},
 	);
 
+	// All chunks get (re-)written, so any chunks stored before are accounted for here again.
+	let old_size =
+		load_stored_size(&subsystem.db, &subsystem.config, &candidate_hash)?.unwrap_or_default();
+	let mut new_size =
+		StoredSize { available_data: available_data.encoded_size() as u64, chunks: 0 };
+
 	for chunk in erasure_chunks {
+		new_size.chunks = new_size.chunks.saturating_add(chunk.encoded_size() as u64);
 		write_chunk(&mut tx, &subsystem.config, &candidate_hash, chunk.index, &chunk);
 	}
 
@@ -1242,19 +1570,29 @@ fn store_available_data(
 
 	write_meta(&mut tx, &subsystem.config, &candidate_hash, &meta);
 	write_available_data(&mut tx, &subsystem.config, &candidate_hash, &available_data);
+	write_stored_size(&mut tx, &subsystem.config, &candidate_hash, &new_size);
 
 	subsystem.db.write(tx)?;
 
+	subsystem.storage_usage.sub(old_size);
+	subsystem.storage_usage.add(new_size);
+
 	gum::debug!(target: LOG_TARGET, ?candidate_hash, "Stored data and chunks");
 
 	Ok(())
 }
 
-fn prune_all(db: &Arc<dyn Database>, config: &Config, clock: &dyn Clock) -> Result<(), Error> {
+fn prune_all(
+	db: &Arc<dyn Database>,
+	config: &Config,
+	clock: &dyn Clock,
+	storage_usage: &mut StoredSize,
+) -> Result<(), Error> {
 	let now = clock.now()?;
 	let (range_start, range_end) = pruning_range(now);
 
 	let mut tx = DBTransaction::new();
+	let mut pruned = StoredSize::default();
 	let iter = db
 		.iter_with_prefix(config.col_meta, &range_start[..])
 		.take_while(|r| r.as_ref().map_or(true, |(k, _v)| &k[..] < &range_end[..]));
@@ -1268,38 +1606,124 @@ fn prune_all(db: &Arc<dyn Database>, config: &Config, clock: &dyn Clock) -> Resu
 			Err(_) => continue, // sanity
 		};
 
-		delete_meta(&mut tx, config, &candidate_hash);
+		pruned.add(delete_candidate(db, &mut tx, config, &candidate_hash)?);
+	}
 
-		// Clean up all attached data of the candidate.
-		if let Some(meta) = load_meta(db, config, &candidate_hash)? {
-			// delete available data.
-			if meta.data_available {
-				delete_available_data(&mut tx, config, &candidate_hash)
-			}
+	db.write(tx)?;
+	storage_usage.sub(pruned);
+	Ok(())
+}
 
-			// delete chunks.
-			for (i, b) in meta.chunks_stored.iter().enumerate() {
-				if *b {
-					delete_chunk(&mut tx, config, &candidate_hash, ValidatorIndex(i as _));
-				}
-			}
+/// Prune finalized data ahead of time, until the disk usage is below `Config::max_disk_usage`.
+///
+/// Data is pruned in the order it got finalized in, but only once it is older than
+/// `PruningConfig::min_keep_finalized_for`.
+fn prune_over_capacity(
+	db: &Arc<dyn Database>,
+	config: &Config,
+	pruning_config: &PruningConfig,
+	clock: &dyn Clock,
+	storage_usage: &mut StoredSize,
+	metrics: &Metrics,
+) -> Result<(), Error> {
+	let max_disk_usage = match config.max_disk_usage {
+		Some(max) if storage_usage.total() > max => max,
+		_ => return Ok(()),
+	};
 
-			// delete unfinalized block references. Pruning references don't need to be
-			// manually taken care of as we are deleting them as we go in the outer loop.
-			if let State::Unfinalized(_, blocks) = meta.state {
-				for (block_number, block_hash) in blocks {
-					delete_unfinalized_inclusion(
-						&mut tx,
-						config,
-						block_number.0,
-						&block_hash,
-						&candidate_hash,
-					);
-				}
-			}
+	// Finalized data is scheduled for pruning `keep_finalized_for` after finalization, so all data
+	// finalized at least `min_keep_finalized_for` ago is scheduled before this point in time.
+	let now = clock.now()?;
+	let latest_prune_at = (now + pruning_config.keep_finalized_for)
+		.saturating_sub(pruning_config.min_keep_finalized_for);
+	let (range_start, range_end) = pruning_range(latest_prune_at);
+
+	let mut tx = DBTransaction::new();
+	let mut remaining = *storage_usage;
+	let mut pruned_candidates = 0;
+	let iter = db
+		.iter_with_prefix(config.col_meta, &range_start[..])
+		.take_while(|r| r.as_ref().map_or(true, |(k, _v)| &k[..] < &range_end[..]));
+
+	for r in iter {
+		if remaining.total() <= max_disk_usage {
+			break
+		}
+
+		let (k, _v) = r?;
+		let (_, candidate_hash) = match decode_pruning_key(&k[..]) {
+			Ok(m) => m,
+			Err(_) => continue, // sanity
+		};
+
+		// Unavailable data is only scheduled for pruning after a shorter time.
+		match load_meta(db, config, &candidate_hash)? {
+			Some(CandidateMeta { state: State::Finalized(_), .. }) => {},
+			_ => continue,
 		}
+
+		tx.delete(config.col_meta, &k[..]);
+		remaining.sub(delete_candidate(db, &mut tx, config, &candidate_hash)?);
+		pruned_candidates += 1;
 	}
 
 	db.write(tx)?;
+
+	gum::debug!(
+		target: LOG_TARGET,
+		pruned_candidates,
+		disk_usage = remaining.total(),
+		max_disk_usage,
+		"Pruned finalized data early, because of exceeding the disk usage limit",
+	);
+	metrics.on_pruned_over_capacity(pruned_candidates);
+	*storage_usage = remaining;
+
 	Ok(())
 }
+
+/// Delete a candidate with all attached data, apart from its pruning key.
+///
+/// Returns the size of the deleted data.
+fn delete_candidate(
+	db: &Arc<dyn Database>,
+	tx: &mut DBTransaction,
+	config: &Config,
+	candidate_hash: &CandidateHash,
+) -> Result<StoredSize, Error> {
+	delete_meta(tx, config, candidate_hash);
+
+	// Clean up all attached data of the candidate.
+	if let Some(meta) = load_meta(db, config, candidate_hash)? {
+		// delete available data.
+		if meta.data_available {
+			delete_available_data(tx, config, candidate_hash)
+		}
+
+		// delete chunks.
+		for (i, b) in meta.chunks_stored.iter().enumerate() {
+			if *b {
+				delete_chunk(tx, config, candidate_hash, ValidatorIndex(i as _));
+			}
+		}
+
+		// delete unfinalized block references. Pruning references don't need to be
+		// manually taken care of as we are deleting them as we go in the outer loop.
+		if let State::Unfinalized(_, blocks) = meta.state {
+			for (block_number, block_hash) in blocks {
+				delete_unfinalized_inclusion(
+					tx,
+					config,
+					block_number.0,
+					&block_hash,
+					candidate_hash,
+				);
+			}
+		}
+	}
+
+	let size = load_stored_size(db, config, candidate_hash)?.unwrap_or_default();
+	delete_stored_size(tx, config, candidate_hash);
+
+	Ok(size)
+}
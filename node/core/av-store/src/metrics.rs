@@ -26,6 +26,8 @@ pub(crate) struct MetricsInner {
 	store_available_data: prometheus::Histogram,
 	store_chunk: prometheus::Histogram,
 	get_chunk: prometheus::Histogram,
+	stored_bytes: prometheus::GaugeVec<prometheus::U64>,
+	pruned_over_capacity_total: prometheus::Counter<prometheus::U64>,
 }
 
 /// Availability metrics.
@@ -85,6 +87,22 @@ impl Metrics {
 	pub(crate) fn time_get_chunk(&self) -> Option<metrics::prometheus::prometheus::HistogramTimer> {
 		self.0.as_ref().map(|metrics| metrics.get_chunk.start_timer())
 	}
+
+	/// Set the number of bytes used for storing available data and chunks.
+	pub(crate) fn on_storage_usage(&self, available_data: u64, chunks: u64) {
+		if let Some(metrics) = &self.0 {
+			metrics.stored_bytes.with_label_values(&["available_data"]).set(available_data);
+			metrics.stored_bytes.with_label_values(&["chunks"]).set(chunks);
+		}
+	}
+
+	pub(crate) fn on_pruned_over_capacity(&self, count: usize) {
+		if let Some(metrics) = &self.0 {
+			// assume usize fits into u64
+			let by = u64::try_from(count).unwrap_or_default();
+			metrics.pruned_over_capacity_total.inc_by(by);
+		}
+	}
 }
 
 impl metrics::Metrics for Metrics {
@@ -152,6 +170,23 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			stored_bytes: prometheus::register(
+				prometheus::GaugeVec::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_av_store_stored_bytes",
+						"Number of bytes used for storing available data and chunks.",
+					),
+					&["kind"],
+				)?,
+				registry,
+			)?,
+			pruned_over_capacity_total: prometheus::register(
+				prometheus::Counter::new(
+					"polkadot_parachain_av_store_pruned_over_capacity_total",
+					"Number of candidates pruned early, because of exceeding the disk usage limit.",
+				)?,
+				registry,
+			)?,
 		};
 		Ok(Metrics(Some(metrics)))
 	}
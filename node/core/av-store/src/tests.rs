@@ -42,7 +42,12 @@ mod columns {
 	pub const NUM_COLUMNS: u32 = 2;
 }
 
-const TEST_CONFIG: Config = Config { col_data: columns::DATA, col_meta: columns::META };
+const TEST_CONFIG: Config = Config {
+	col_data: columns::DATA,
+	col_meta: columns::META,
+	max_disk_usage: None,
+	min_keep_finalized_for: None,
+};
 
 type VirtualOverseer = test_helpers::TestSubsystemContextHandle<AvailabilityStoreMessage>;
 
@@ -71,6 +76,7 @@ impl Clock for TestClock {
 struct TestState {
 	persisted_validation_data: PersistedValidationData,
 	pruning_config: PruningConfig,
+	config: Config,
 	clock: TestClock,
 }
 
@@ -95,11 +101,12 @@ impl Default for TestState {
 			keep_unavailable_for: Duration::from_secs(1),
 			keep_finalized_for: Duration::from_secs(2),
 			pruning_interval: Duration::from_millis(250),
+			min_keep_finalized_for: Duration::from_secs(1),
 		};
 
 		let clock = TestClock { inner: Arc::new(Mutex::new(Duration::from_secs(0))) };
 
-		Self { persisted_validation_data, pruning_config, clock }
+		Self { persisted_validation_data, pruning_config, config: TEST_CONFIG, clock }
 	}
 }
 
@@ -131,7 +138,7 @@ fn test_harness<T: Future<Output = VirtualOverseer>>(
 
 	let subsystem = AvailabilityStoreSubsystem::with_pruning_config_and_clock(
 		store,
-		TEST_CONFIG,
+		state.config,
 		state.pruning_config.clone(),
 		Box::new(state.clock),
 		Box::new(NoSyncOracle),
@@ -415,6 +422,51 @@ fn query_chunk_checks_meta() {
 	});
 }
 
+#[test]
+fn stored_data_keys_follow_meta() {
+	let store = test_store();
+	let candidate_a = CandidateHash(Hash::repeat_byte(1));
+	let candidate_b = CandidateHash(Hash::repeat_byte(2));
+	let n_validators = 10;
+
+	with_tx(&store, |tx| {
+		super::write_meta(
+			tx,
+			&TEST_CONFIG,
+			&candidate_a,
+			&CandidateMeta {
+				data_available: true,
+				chunks_stored: bitvec::bitvec![u8, BitOrderLsb0; 0; n_validators],
+				state: State::Unavailable(BETimestamp(0)),
+			},
+		);
+		super::write_meta(
+			tx,
+			&TEST_CONFIG,
+			&candidate_b,
+			&CandidateMeta {
+				data_available: false,
+				chunks_stored: {
+					let mut v = bitvec::bitvec![u8, BitOrderLsb0; 0; n_validators];
+					v.set(3, true);
+					v.set(7, true);
+					v
+				},
+				state: State::Unavailable(BETimestamp(0)),
+			},
+		);
+	});
+
+	assert_eq!(
+		super::stored_data_keys(&*store, &TEST_CONFIG).unwrap(),
+		vec![
+			(AVAILABLE_PREFIX, &candidate_a).encode(),
+			(CHUNK_PREFIX, &candidate_b, ValidatorIndex(3)).encode(),
+			(CHUNK_PREFIX, &candidate_b, ValidatorIndex(7)).encode(),
+		],
+	);
+}
+
 #[test]
 fn store_block_works() {
 	let store = test_store();
@@ -739,6 +791,290 @@ fn stored_data_kept_until_finalized() {
 	});
 }
 
+#[test]
+fn storage_usage_is_tracked() {
+	let store = test_store();
+	let test_state = TestState::default();
+
+	test_harness(test_state.clone(), store.clone(), |mut virtual_overseer| async move {
+		let candidate_hash = CandidateHash(Hash::repeat_byte(1));
+		let n_validators = 10;
+
+		let pov = PoV { block_data: BlockData(vec![4, 5, 6]) };
+
+		let available_data = AvailableData {
+			pov: Arc::new(pov),
+			validation_data: test_state.persisted_validation_data.clone(),
+		};
+
+		let (tx, rx) = oneshot::channel();
+		let block_msg = AvailabilityStoreMessage::StoreAvailableData {
+			candidate_hash,
+			n_validators,
+			available_data: available_data.clone(),
+			tx,
+		};
+
+		virtual_overseer.send(FromOrchestra::Communication { msg: block_msg }).await;
+
+		rx.await.unwrap().unwrap();
+
+		let mut chunks_size = 0;
+		for i in 0..n_validators {
+			let chunk = query_chunk(&mut virtual_overseer, candidate_hash, ValidatorIndex(i))
+				.await
+				.unwrap();
+			chunks_size += chunk.encoded_size() as u64;
+		}
+
+		assert_eq!(
+			load_storage_usage(&store, &TEST_CONFIG).unwrap(),
+			StoredSize {
+				available_data: available_data.encoded_size() as u64,
+				chunks: chunks_size,
+			},
+		);
+
+		// Wait until pruning.
+		test_state.clock.inc(test_state.pruning_config.keep_unavailable_for);
+		test_state.wait_for_pruning().await;
+
+		assert!(query_available_data(&mut virtual_overseer, candidate_hash).await.is_none());
+		assert_eq!(load_storage_usage(&store, &TEST_CONFIG).unwrap(), StoredSize::default());
+		virtual_overseer
+	});
+}
+
+#[test]
+fn stored_candidates_can_be_queried_and_listed() {
+	let store = test_store();
+	let test_state = TestState::default();
+
+	test_harness(test_state.clone(), store.clone(), |mut virtual_overseer| async move {
+		let n_validators = 10;
+		let mut candidate_hashes = Vec::new();
+
+		for i in 1..=3 {
+			let candidate_hash = CandidateHash(Hash::repeat_byte(i));
+			let available_data = AvailableData {
+				pov: Arc::new(PoV { block_data: BlockData(vec![i; 3]) }),
+				validation_data: test_state.persisted_validation_data.clone(),
+			};
+
+			let (tx, rx) = oneshot::channel();
+			let block_msg = AvailabilityStoreMessage::StoreAvailableData {
+				candidate_hash,
+				n_validators,
+				available_data,
+				tx,
+			};
+
+			virtual_overseer.send(FromOrchestra::Communication { msg: block_msg }).await;
+			rx.await.unwrap().unwrap();
+
+			candidate_hashes.push(candidate_hash);
+		}
+
+		let info = query_stored_candidate(&mut virtual_overseer, candidate_hashes[0])
+			.await
+			.unwrap();
+		assert_eq!(info.candidate_hash, candidate_hashes[0]);
+		assert_eq!(info.state, StoredCandidateState::Unavailable { since: 0 });
+		assert!(info.data_available);
+		assert_eq!(info.chunks_stored, vec![true; n_validators as usize]);
+		assert!(info.stored_bytes > 0);
+		assert_eq!(info.prune_at, Some(test_state.pruning_config.keep_unavailable_for.as_secs()));
+
+		assert!(query_stored_candidate(&mut virtual_overseer, CandidateHash(Hash::repeat_byte(4)))
+			.await
+			.is_none());
+
+		let listed = list_stored_candidates(&mut virtual_overseer, None, 10).await;
+		assert_eq!(
+			listed.iter().map(|info| info.candidate_hash).collect::<Vec<_>>(),
+			candidate_hashes
+		);
+		assert_eq!(listed[0], info);
+
+		let page = list_stored_candidates(&mut virtual_overseer, None, 2).await;
+		assert_eq!(
+			page.iter().map(|info| info.candidate_hash).collect::<Vec<_>>(),
+			candidate_hashes[..2]
+		);
+
+		let page =
+			list_stored_candidates(&mut virtual_overseer, Some(candidate_hashes[1]), 2).await;
+		assert_eq!(
+			page.iter().map(|info| info.candidate_hash).collect::<Vec<_>>(),
+			candidate_hashes[2..]
+		);
+		virtual_overseer
+	});
+}
+
+#[test]
+fn stored_candidates_are_listed_after_any_hash() {
+	let store = test_store();
+	let test_state = TestState::default();
+
+	test_harness(test_state.clone(), store.clone(), |mut virtual_overseer| async move {
+		let hash = |bytes: &[(usize, u8)]| {
+			let mut hash = Hash::repeat_byte(1);
+			for (i, byte) in bytes {
+				hash.0[*i] = *byte;
+			}
+			CandidateHash(hash)
+		};
+
+		// Ordered, some of the hashes only differ in their last byte.
+		let candidate_hashes = vec![
+			hash(&[(0, 0)]),
+			hash(&[(31, 0)]),
+			hash(&[]),
+			hash(&[(31, 2)]),
+			hash(&[(16, 2)]),
+			hash(&[(0, 2)]),
+		];
+
+		for (i, candidate_hash) in candidate_hashes.iter().enumerate() {
+			let available_data = AvailableData {
+				pov: Arc::new(PoV { block_data: BlockData(vec![i as u8; 3]) }),
+				validation_data: test_state.persisted_validation_data.clone(),
+			};
+
+			let (tx, rx) = oneshot::channel();
+			let block_msg = AvailabilityStoreMessage::StoreAvailableData {
+				candidate_hash: *candidate_hash,
+				n_validators: 10,
+				available_data,
+				tx,
+			};
+
+			virtual_overseer.send(FromOrchestra::Communication { msg: block_msg }).await;
+			rx.await.unwrap().unwrap();
+		}
+
+		let listed_hashes = |listed: Vec<StoredCandidateInfo>| {
+			listed.into_iter().map(|info| info.candidate_hash).collect::<Vec<_>>()
+		};
+
+		for (i, start_after) in candidate_hashes.iter().enumerate() {
+			let listed =
+				list_stored_candidates(&mut virtual_overseer, Some(*start_after), 10).await;
+			assert_eq!(listed_hashes(listed), candidate_hashes[i + 1..]);
+
+			let listed = list_stored_candidates(&mut virtual_overseer, Some(*start_after), 2).await;
+			let end = (i + 3).min(candidate_hashes.len());
+			assert_eq!(listed_hashes(listed), candidate_hashes[i + 1..end]);
+		}
+
+		// Hashes which are not stored.
+		for (start_after, first) in
+			[(hash(&[(30, 0)]), 1), (hash(&[(20, 5)]), 4), (hash(&[(16, 3)]), 5)]
+		{
+			let listed = list_stored_candidates(&mut virtual_overseer, Some(start_after), 10).await;
+			assert_eq!(listed_hashes(listed), candidate_hashes[first..]);
+		}
+
+		let listed =
+			list_stored_candidates(&mut virtual_overseer, Some(CandidateHash(Hash::zero())), 10)
+				.await;
+		assert_eq!(listed_hashes(listed), candidate_hashes);
+
+		let listed = list_stored_candidates(
+			&mut virtual_overseer,
+			Some(CandidateHash(Hash::repeat_byte(u8::MAX))),
+			10,
+		)
+		.await;
+		assert!(listed.is_empty());
+		virtual_overseer
+	});
+}
+
+#[test]
+fn finalized_data_is_pruned_early_when_over_disk_usage_limit() {
+	let store = test_store();
+	let test_state = TestState {
+		config: Config { max_disk_usage: Some(0), ..TEST_CONFIG },
+		..Default::default()
+	};
+
+	test_harness(test_state.clone(), store.clone(), |mut virtual_overseer| async move {
+		let n_validators = 10;
+
+		let pov = PoV { block_data: BlockData(vec![4, 5, 6]) };
+
+		let pov_hash = pov.hash();
+
+		let candidate = TestCandidateBuilder { pov_hash, ..Default::default() }.build();
+
+		let candidate_hash = candidate.hash();
+
+		let available_data = AvailableData {
+			pov: Arc::new(pov),
+			validation_data: test_state.persisted_validation_data.clone(),
+		};
+
+		let parent = Hash::repeat_byte(2);
+		let block_number = 10;
+
+		let (tx, rx) = oneshot::channel();
+		let block_msg = AvailabilityStoreMessage::StoreAvailableData {
+			candidate_hash,
+			n_validators,
+			available_data: available_data.clone(),
+			tx,
+		};
+
+		virtual_overseer.send(FromOrchestra::Communication { msg: block_msg }).await;
+
+		rx.await.unwrap().unwrap();
+
+		let new_leaf = import_leaf(
+			&mut virtual_overseer,
+			parent,
+			block_number,
+			vec![candidate_included(candidate)],
+			(0..n_validators).map(|_| Sr25519Keyring::Alice.public().into()).collect(),
+		)
+		.await;
+
+		// Unfinalized data is never pruned early.
+		test_state.wait_for_pruning().await;
+
+		assert_eq!(
+			query_available_data(&mut virtual_overseer, candidate_hash).await.unwrap(),
+			available_data,
+		);
+
+		overseer_signal(
+			&mut virtual_overseer,
+			OverseerSignal::BlockFinalized(new_leaf, block_number),
+		)
+		.await;
+
+		// Neither is finalized data within the dispute window.
+		test_state.clock.inc(test_state.pruning_config.min_keep_finalized_for / 2);
+		test_state.wait_for_pruning().await;
+
+		assert_eq!(
+			query_available_data(&mut virtual_overseer, candidate_hash).await.unwrap(),
+			available_data,
+		);
+
+		// Past the dispute window the data is pruned, even though `keep_finalized_for` has not
+		// passed yet.
+		test_state.clock.inc(test_state.pruning_config.min_keep_finalized_for / 2);
+		test_state.wait_for_pruning().await;
+
+		assert!(query_available_data(&mut virtual_overseer, candidate_hash).await.is_none());
+		assert!(has_all_chunks(&mut virtual_overseer, candidate_hash, n_validators, false).await);
+		assert_eq!(load_storage_usage(&store, &TEST_CONFIG).unwrap(), StoredSize::default());
+		virtual_overseer
+	});
+}
+
 #[test]
 fn we_dont_miss_anything_if_import_notifications_are_missed() {
 	let store = test_store();
@@ -1075,6 +1411,31 @@ async fn query_chunk(
 	rx.await.unwrap()
 }
 
+async fn query_stored_candidate(
+	virtual_overseer: &mut VirtualOverseer,
+	candidate_hash: CandidateHash,
+) -> Option<StoredCandidateInfo> {
+	let (tx, rx) = oneshot::channel();
+
+	let query = AvailabilityStoreMessage::QueryStoredCandidate(candidate_hash, tx);
+	virtual_overseer.send(FromOrchestra::Communication { msg: query }).await;
+
+	rx.await.unwrap()
+}
+
+async fn list_stored_candidates(
+	virtual_overseer: &mut VirtualOverseer,
+	start_after: Option<CandidateHash>,
+	limit: usize,
+) -> Vec<StoredCandidateInfo> {
+	let (tx, rx) = oneshot::channel();
+
+	let query = AvailabilityStoreMessage::ListStoredCandidates { start_after, limit, tx };
+	virtual_overseer.send(FromOrchestra::Communication { msg: query }).await;
+
+	rx.await.unwrap()
+}
+
 async fn has_all_chunks(
 	virtual_overseer: &mut VirtualOverseer,
 	candidate_hash: CandidateHash,
@@ -38,7 +38,7 @@ use polkadot_node_subsystem::{
 	messages::{
 		AvailabilityDistributionMessage, AvailabilityStoreMessage, CandidateBackingMessage,
 		CandidateValidationMessage, CollatorProtocolMessage, ProvisionableData, ProvisionerMessage,
-		RuntimeApiRequest, StatementDistributionMessage,
+		PvfExecPriority, RuntimeApiRequest, StatementDistributionMessage,
 	},
 	overseer, ActiveLeavesUpdate, FromOrchestra, OverseerSignal, PerLeafSpan, SpawnedSubsystem,
 	Stage, SubsystemError,
@@ -350,6 +350,12 @@ async fn handle_active_leaves_update<Context>(
 	for (idx, core) in cores.into_iter().enumerate() {
 		// Ignore prospective assignments on occupied cores for the time being.
 		if let CoreState::Scheduled(scheduled) = core {
+			// The runtime includes a single candidate per para, on the lowest of its cores, so
+			// a para scheduled on several cores is only backed by the group of that core.
+			if groups.contains_key(&scheduled.para_id) {
+				continue
+			}
+
 			let core_index = CoreIndex(idx as _);
 			let group_index = group_rotation_info.group_for_core(core_index, n_cores);
 			if let Some(g) = validator_groups.get(group_index.0 as usize) {
@@ -651,6 +657,7 @@ async fn request_candidate_validation(
 			candidate_receipt,
 			pov,
 			PvfExecTimeoutKind::Backing,
+			PvfExecPriority::Critical,
 			tx,
 		))
 		.await;
@@ -26,17 +26,22 @@ use futures::{
 	lock::Mutex,
 	FutureExt,
 };
+use polkadot_node_primitives::AvailabilityChunkMapping;
 use polkadot_node_subsystem::{
-	errors::RuntimeApiError,
+	errors::{ChainApiError, RuntimeApiError},
 	jaeger,
 	messages::{
-		AvailabilityStoreMessage, BitfieldDistributionMessage, RuntimeApiMessage, RuntimeApiRequest,
+		AvailabilityStoreMessage, BitfieldDistributionMessage, ChainApiMessage, RuntimeApiMessage,
+		RuntimeApiRequest,
 	},
 	overseer, ActivatedLeaf, FromOrchestra, LeafStatus, OverseerSignal, PerLeafSpan,
 	SpawnedSubsystem, SubsystemError, SubsystemResult, SubsystemSender,
 };
 use polkadot_node_subsystem_util::{self as util, Validator};
-use polkadot_primitives::{AvailabilityBitfield, CoreState, Hash, ValidatorIndex};
+use polkadot_primitives::{
+	vstaging::NodeFeatures, AvailabilityBitfield, CoreState, Hash, SessionIndex, SessionInfo,
+	ValidatorIndex,
+};
 use sp_keystore::{Error as KeystoreError, KeystorePtr};
 use std::{collections::HashMap, iter::FromIterator, time::Duration};
 use wasm_timer::{Delay, Instant};
@@ -71,21 +76,58 @@ pub enum Error {
 	#[error(transparent)]
 	Runtime(#[from] RuntimeApiError),
 
+	#[error(transparent)]
+	ChainApi(#[from] ChainApiError),
+
+	#[error("Session info of session {0} is unavailable")]
+	MissingSessionInfo(SessionIndex),
+
 	#[error("Keystore failed: {0:?}")]
 	Keystore(KeystoreError),
 }
 
 /// If there is a candidate pending availability, query the Availability Store
-/// for whether we have the availability chunk for our validator index.
+/// for whether we have the availability chunk assigned to our validator index.
 async fn get_core_availability(
 	core: &CoreState,
 	validator_idx: ValidatorIndex,
+	session_info: &SessionInfo,
+	node_features: &NodeFeatures,
 	sender: &Mutex<&mut impl SubsystemSender<overseer::BitfieldSigningOutgoingMessages>>,
 	span: &jaeger::Span,
 ) -> Result<bool, Error> {
 	if let CoreState::Occupied(core) = core {
 		let _span = span.child("query-chunk-availability");
 
+		let relay_parent = core.candidate_descriptor.relay_parent;
+		let (tx, rx) = oneshot::channel();
+		sender
+			.lock()
+			.await
+			.send_message(ChainApiMessage::BlockNumber(relay_parent, tx).into())
+			.await;
+		let relay_parent_number = match rx.await?? {
+			Some(number) => number,
+			None => {
+				gum::debug!(
+					target: LOG_TARGET,
+					?relay_parent,
+					?core.candidate_hash,
+					"Relay parent of the candidate is unknown",
+				);
+				return Ok(false)
+			},
+		};
+
+		let chunk_index = AvailabilityChunkMapping::new(
+			node_features,
+			session_info.validators.len(),
+			&session_info.random_seed,
+			relay_parent_number,
+			core.para_id(),
+		)
+		.chunk_index(validator_idx);
+
 		let (tx, rx) = oneshot::channel();
 		sender
 			.lock()
@@ -93,7 +135,7 @@ async fn get_core_availability(
 			.send_message(
 				AvailabilityStoreMessage::QueryChunkAvailability(
 					core.candidate_hash,
-					validator_idx,
+					chunk_index,
 					tx,
 				)
 				.into(),
@@ -107,6 +149,7 @@ async fn get_core_availability(
 			para_id = %core.para_id(),
 			availability = ?res,
 			?core.candidate_hash,
+			?chunk_index,
 			"Candidate availability",
 		);
 
@@ -135,6 +178,54 @@ async fn get_availability_cores(
 	}
 }
 
+/// Get the node features enabled at the given relay parent. None are enabled if the runtime does
+/// not support the API yet.
+async fn get_node_features(
+	relay_parent: Hash,
+	sender: &mut impl SubsystemSender<overseer::BitfieldSigningOutgoingMessages>,
+) -> Result<NodeFeatures, Error> {
+	let (tx, rx) = oneshot::channel();
+	sender
+		.send_message(
+			RuntimeApiMessage::Request(relay_parent, RuntimeApiRequest::NodeFeatures(tx)).into(),
+		)
+		.await;
+	match rx.await {
+		Ok(Ok(out)) => Ok(out),
+		Ok(Err(RuntimeApiError::NotSupported { .. })) => Ok(NodeFeatures::new()),
+		Ok(Err(runtime_err)) => Err(runtime_err.into()),
+		Err(err) => Err(err.into()),
+	}
+}
+
+/// Get the information about the session the candidates pending availability at the given relay
+/// parent belong to.
+async fn get_session_info(
+	relay_parent: Hash,
+	sender: &mut impl SubsystemSender<overseer::BitfieldSigningOutgoingMessages>,
+) -> Result<SessionInfo, Error> {
+	let (tx, rx) = oneshot::channel();
+	sender
+		.send_message(
+			RuntimeApiMessage::Request(relay_parent, RuntimeApiRequest::SessionIndexForChild(tx))
+				.into(),
+		)
+		.await;
+	let session_index = rx.await??;
+
+	let (tx, rx) = oneshot::channel();
+	sender
+		.send_message(
+			RuntimeApiMessage::Request(
+				relay_parent,
+				RuntimeApiRequest::SessionInfo(session_index, tx),
+			)
+			.into(),
+		)
+		.await;
+	rx.await??.ok_or(Error::MissingSessionInfo(session_index))
+}
+
 /// - get the list of core states from the runtime
 /// - for each core, concurrently determine chunk availability (see `get_core_availability`)
 /// - return the bitfield if there were no errors at any point in this process
@@ -151,6 +242,11 @@ async fn construct_availability_bitfield(
 		get_availability_cores(relay_parent, sender).await?
 	};
 
+	// The chunk we are supposed to hold depends on the enabled node features and the randomness
+	// of the session.
+	let node_features = get_node_features(relay_parent, sender).await?;
+	let session_info = get_session_info(relay_parent, sender).await?;
+
 	// Wrap the sender in a Mutex to share it between the futures.
 	//
 	// We use a `Mutex` here to not `clone` the sender inside the future, because
@@ -160,11 +256,9 @@ async fn construct_availability_bitfield(
 
 	// Handle all cores concurrently
 	// `try_join_all` returns all results in the same order as the input futures.
-	let results = future::try_join_all(
-		availability_cores
-			.iter()
-			.map(|core| get_core_availability(core, validator_idx, &sender, span)),
-	)
+	let results = future::try_join_all(availability_cores.iter().map(|core| {
+		get_core_availability(core, validator_idx, &session_info, &node_features, &sender, span)
+	}))
 	.await?;
 
 	let core_bits = FromIterator::from_iter(results.into_iter());
@@ -17,8 +17,11 @@
 use super::*;
 use futures::{executor::block_on, pin_mut, StreamExt};
 use polkadot_node_subsystem::messages::AllMessages;
-use polkadot_primitives::{CandidateHash, OccupiedCore};
-use test_helpers::dummy_candidate_descriptor;
+use polkadot_primitives::{BlockNumber, CandidateHash, IndexedVec, OccupiedCore};
+use test_helpers::{dummy_candidate_descriptor, dummy_validator};
+
+const SESSION_INDEX: SessionIndex = 1;
+const RELAY_PARENT_NUMBER: BlockNumber = 10;
 
 fn occupied_core(para_id: u32, candidate_hash: CandidateHash) -> CoreState {
 	CoreState::Occupied(OccupiedCore {
@@ -33,53 +36,135 @@ fn occupied_core(para_id: u32, candidate_hash: CandidateHash) -> CoreState {
 	})
 }
 
+fn session_info(n_validators: usize) -> SessionInfo {
+	SessionInfo {
+		validators: IndexedVec::from(vec![dummy_validator(); n_validators]),
+		discovery_keys: vec![],
+		validator_groups: IndexedVec::from(vec![]),
+		assignment_keys: vec![],
+		n_cores: 0,
+		zeroth_delay_tranche_width: 0,
+		relay_vrf_modulo_samples: 0,
+		n_delay_tranches: 0,
+		no_show_slots: 0,
+		needed_approvals: 0,
+		active_validator_indices: vec![],
+		dispute_period: 6,
+		random_seed: [42u8; 32],
+	}
+}
+
+/// Construct a bitfield with the availability of the candidates `hash_a` and `hash_b` occupying
+/// the second and third core, answering the availability store with `hash_a` being available if
+/// queried for `expected_chunk_index`.
+async fn construct_bitfield_with_node_features(
+	node_features: NodeFeatures,
+	validator_index: ValidatorIndex,
+	expected_chunk_index: ValidatorIndex,
+) -> AvailabilityBitfield {
+	let relay_parent = Hash::default();
+
+	let (mut sender, mut receiver) = polkadot_node_subsystem_test_helpers::sender_receiver();
+	let future = construct_availability_bitfield(
+		relay_parent,
+		&jaeger::Span::Disabled,
+		validator_index,
+		&mut sender,
+	)
+	.fuse();
+	pin_mut!(future);
+
+	let hash_a = CandidateHash(Hash::repeat_byte(1));
+	let hash_b = CandidateHash(Hash::repeat_byte(2));
+
+	loop {
+		futures::select! {
+			m = receiver.next() => match m.unwrap() {
+				AllMessages::RuntimeApi(
+					RuntimeApiMessage::Request(rp, RuntimeApiRequest::AvailabilityCores(tx)),
+				) => {
+					assert_eq!(relay_parent, rp);
+					tx.send(Ok(vec![CoreState::Free, occupied_core(1, hash_a), occupied_core(2, hash_b)])).unwrap();
+				}
+				AllMessages::RuntimeApi(
+					RuntimeApiMessage::Request(rp, RuntimeApiRequest::NodeFeatures(tx)),
+				) => {
+					assert_eq!(relay_parent, rp);
+					tx.send(Ok(node_features.clone())).unwrap();
+				}
+				AllMessages::RuntimeApi(
+					RuntimeApiMessage::Request(rp, RuntimeApiRequest::SessionIndexForChild(tx)),
+				) => {
+					assert_eq!(relay_parent, rp);
+					tx.send(Ok(SESSION_INDEX)).unwrap();
+				}
+				AllMessages::RuntimeApi(
+					RuntimeApiMessage::Request(rp, RuntimeApiRequest::SessionInfo(session_index, tx)),
+				) => {
+					assert_eq!(relay_parent, rp);
+					assert_eq!(session_index, SESSION_INDEX);
+					tx.send(Ok(Some(session_info(10)))).unwrap();
+				}
+				AllMessages::ChainApi(ChainApiMessage::BlockNumber(hash, tx)) => {
+					assert_eq!(hash, Hash::zero());
+					tx.send(Ok(Some(RELAY_PARENT_NUMBER))).unwrap();
+				}
+				AllMessages::AvailabilityStore(
+					AvailabilityStoreMessage::QueryChunkAvailability(c_hash, chunk_index, tx),
+				) => {
+					assert_eq!(expected_chunk_index, chunk_index);
+
+					tx.send(c_hash == hash_a).unwrap();
+				},
+				o => panic!("Unknown message: {:?}", o),
+			},
+			r = future => match r {
+				Ok(r) => return r,
+				Err(e) => panic!("Failed: {:?}", e),
+			},
+		}
+	}
+}
+
 #[test]
 fn construct_availability_bitfield_works() {
 	block_on(async move {
-		let relay_parent = Hash::default();
 		let validator_index = ValidatorIndex(1u32);
 
-		let (mut sender, mut receiver) = polkadot_node_subsystem_test_helpers::sender_receiver();
-		let future = construct_availability_bitfield(
-			relay_parent,
-			&jaeger::Span::Disabled,
+		let r = construct_bitfield_with_node_features(
+			NodeFeatures::new(),
+			validator_index,
 			validator_index,
-			&mut sender,
 		)
-		.fuse();
-		pin_mut!(future);
-
-		let hash_a = CandidateHash(Hash::repeat_byte(1));
-		let hash_b = CandidateHash(Hash::repeat_byte(2));
-
-		loop {
-			futures::select! {
-				m = receiver.next() => match m.unwrap() {
-					AllMessages::RuntimeApi(
-						RuntimeApiMessage::Request(rp, RuntimeApiRequest::AvailabilityCores(tx)),
-					) => {
-						assert_eq!(relay_parent, rp);
-						tx.send(Ok(vec![CoreState::Free, occupied_core(1, hash_a), occupied_core(2, hash_b)])).unwrap();
-					}
-					AllMessages::AvailabilityStore(
-						AvailabilityStoreMessage::QueryChunkAvailability(c_hash, vidx, tx),
-					) => {
-						assert_eq!(validator_index, vidx);
-
-						tx.send(c_hash == hash_a).unwrap();
-					},
-					o => panic!("Unknown message: {:?}", o),
-				},
-				r = future => match r {
-					Ok(r) => {
-						assert!(!r.0.get(0).unwrap());
-						assert!(r.0.get(1).unwrap());
-						assert!(!r.0.get(2).unwrap());
-						break
-					},
-					Err(e) => panic!("Failed: {:?}", e),
-				},
-			}
-		}
+		.await;
+
+		assert!(!r.0.get(0).unwrap());
+		assert!(r.0.get(1).unwrap());
+		assert!(!r.0.get(2).unwrap());
+	});
+}
+
+#[test]
+fn chunk_availability_is_queried_for_shuffled_chunk_index() {
+	block_on(async move {
+		let validator_index = ValidatorIndex(1u32);
+		let node_features = NodeFeatures::repeat(true, 1);
+		let session_info = session_info(10);
+		let chunk_index = AvailabilityChunkMapping::new(
+			&node_features,
+			session_info.validators.len(),
+			&session_info.random_seed,
+			RELAY_PARENT_NUMBER,
+			dummy_candidate_descriptor(Hash::zero()).para_id,
+		)
+		.chunk_index(validator_index);
+		assert_ne!(chunk_index, validator_index);
+
+		let r = construct_bitfield_with_node_features(node_features, validator_index, chunk_index)
+			.await;
+
+		assert!(!r.0.get(0).unwrap());
+		assert!(r.0.get(1).unwrap());
+		assert!(!r.0.get(2).unwrap());
 	});
 }
@@ -24,7 +24,7 @@
 #![warn(missing_docs)]
 
 use polkadot_node_core_pvf::{
-	InvalidCandidate as WasmInvalidCandidate, PrepareError, PrepareStats, PvfPrepData,
+	InvalidCandidate as WasmInvalidCandidate, PrepareError, PrepareStats, Priority, PvfPrepData,
 	ValidationError, ValidationHost,
 };
 use polkadot_node_primitives::{
@@ -33,8 +33,8 @@ use polkadot_node_primitives::{
 use polkadot_node_subsystem::{
 	errors::RuntimeApiError,
 	messages::{
-		CandidateValidationMessage, PreCheckOutcome, RuntimeApiMessage, RuntimeApiRequest,
-		ValidationFailed,
+		CandidateValidationMessage, PreCheckOutcome, PvfExecPriority, RuntimeApiMessage,
+		RuntimeApiRequest, ValidationFailed,
 	},
 	overseer, FromOrchestra, OverseerSignal, SpawnedSubsystem, SubsystemError, SubsystemResult,
 	SubsystemSender,
@@ -71,10 +71,14 @@ const PVF_EXECUTION_RETRY_DELAY: Duration = Duration::from_millis(200);
 
 // Default PVF timeouts. Must never be changed! Use executor environment parameters in
 // `session_info` pallet to adjust them. See also `PvfTimeoutKind` docs.
-const DEFAULT_PRECHECK_PREPARATION_TIMEOUT: Duration = Duration::from_secs(60);
-const DEFAULT_LENIENT_PREPARATION_TIMEOUT: Duration = Duration::from_secs(360);
-const DEFAULT_BACKING_EXECUTION_TIMEOUT: Duration = Duration::from_secs(2);
-const DEFAULT_APPROVAL_EXECUTION_TIMEOUT: Duration = Duration::from_secs(12);
+/// The default timeout for preparing a PVF when pre-checking it.
+pub const DEFAULT_PRECHECK_PREPARATION_TIMEOUT: Duration = Duration::from_secs(60);
+/// The default timeout for preparing a PVF outside of pre-checking.
+pub const DEFAULT_LENIENT_PREPARATION_TIMEOUT: Duration = Duration::from_secs(360);
+/// The default timeout for executing a PVF when backing.
+pub const DEFAULT_BACKING_EXECUTION_TIMEOUT: Duration = Duration::from_secs(2);
+/// The default timeout for executing a PVF when checking approvals or participating in disputes.
+pub const DEFAULT_APPROVAL_EXECUTION_TIMEOUT: Duration = Duration::from_secs(12);
 
 /// Configuration for the candidate validation subsystem
 #[derive(Clone)]
@@ -84,6 +88,8 @@ pub struct Config {
 	/// The path to the executable which can be used for spawning PVF compilation & validation
 	/// workers.
 	pub program_path: PathBuf,
+	/// Whether the PVF workers should be run in a sandbox.
+	pub pvf_sandbox: bool,
 }
 
 /// The candidate validation subsystem.
@@ -118,6 +124,7 @@ impl<Context> CandidateValidationSubsystem {
 			self.pvf_metrics,
 			self.config.artifacts_cache_path,
 			self.config.program_path,
+			self.config.pvf_sandbox,
 		)
 		.map_err(|e| SubsystemError::with_origin("candidate-validation", e))
 		.boxed();
@@ -132,11 +139,11 @@ async fn run<Context>(
 	pvf_metrics: polkadot_node_core_pvf::Metrics,
 	cache_path: PathBuf,
 	program_path: PathBuf,
+	pvf_sandbox: bool,
 ) -> SubsystemResult<()> {
-	let (validation_host, task) = polkadot_node_core_pvf::start(
-		polkadot_node_core_pvf::Config::new(cache_path, program_path),
-		pvf_metrics,
-	);
+	let mut pvf_config = polkadot_node_core_pvf::Config::new(cache_path, program_path);
+	pvf_config.sandbox = pvf_sandbox;
+	let (validation_host, task) = polkadot_node_core_pvf::start(pvf_config, pvf_metrics);
 	ctx.spawn_blocking("pvf-validation-host", task.boxed())?;
 
 	loop {
@@ -149,6 +156,7 @@ async fn run<Context>(
 					candidate_receipt,
 					pov,
 					timeout,
+					priority,
 					response_sender,
 				) => {
 					let bg = {
@@ -164,6 +172,7 @@ async fn run<Context>(
 								candidate_receipt,
 								pov,
 								timeout,
+								priority,
 								&metrics,
 							)
 							.await;
@@ -181,6 +190,7 @@ async fn run<Context>(
 					candidate_receipt,
 					pov,
 					timeout,
+					priority,
 					response_sender,
 				) => {
 					let bg = {
@@ -198,6 +208,7 @@ async fn run<Context>(
 								candidate_receipt,
 								pov,
 								timeout,
+								priority,
 								&metrics,
 							)
 							.await;
@@ -476,6 +487,7 @@ async fn validate_from_chain_state<Sender>(
 	candidate_receipt: CandidateReceipt,
 	pov: Arc<PoV>,
 	exec_timeout_kind: PvfExecTimeoutKind,
+	priority: PvfExecPriority,
 	metrics: &Metrics,
 ) -> Result<ValidationResult, ValidationFailed>
 where
@@ -496,6 +508,7 @@ where
 		candidate_receipt.clone(),
 		pov,
 		exec_timeout_kind,
+		priority,
 		metrics,
 	)
 	.await;
@@ -532,6 +545,7 @@ async fn validate_candidate_exhaustive<Sender>(
 	candidate_receipt: CandidateReceipt,
 	pov: Arc<PoV>,
 	exec_timeout_kind: PvfExecTimeoutKind,
+	priority: PvfExecPriority,
 	metrics: &Metrics,
 ) -> Result<ValidationResult, ValidationFailed>
 where
@@ -617,6 +631,7 @@ where
 		.validate_candidate_with_retry(
 			raw_validation_code.to_vec(),
 			pvf_exec_timeout(&executor_params, exec_timeout_kind),
+			pvf_exec_priority(priority),
 			params,
 			executor_params,
 		)
@@ -686,6 +701,7 @@ trait ValidationBackend {
 		&mut self,
 		pvf: PvfPrepData,
 		exec_timeout: Duration,
+		priority: Priority,
 		encoded_params: Vec<u8>,
 	) -> Result<WasmValidationResult, ValidationError>;
 
@@ -698,6 +714,7 @@ trait ValidationBackend {
 		&mut self,
 		raw_validation_code: Vec<u8>,
 		exec_timeout: Duration,
+		priority: Priority,
 		params: ValidationParams,
 		executor_params: ExecutorParams,
 	) -> Result<WasmValidationResult, ValidationError> {
@@ -705,8 +722,9 @@ trait ValidationBackend {
 		// Construct the PVF a single time, since it is an expensive operation. Cloning it is cheap.
 		let pvf = PvfPrepData::from_code(raw_validation_code, executor_params, prep_timeout);
 
-		let mut validation_result =
-			self.validate_candidate(pvf.clone(), exec_timeout, params.encode()).await;
+		let mut validation_result = self
+			.validate_candidate(pvf.clone(), exec_timeout, priority, params.encode())
+			.await;
 
 		// Allow limited retries for each kind of error.
 		let mut num_internal_retries_left = 1;
@@ -736,8 +754,9 @@ trait ValidationBackend {
 
 				// Encode the params again when re-trying. We expect the retry case to be relatively
 				// rare, and we want to avoid unconditionally cloning data.
-				validation_result =
-					self.validate_candidate(pvf.clone(), exec_timeout, params.encode()).await;
+				validation_result = self
+					.validate_candidate(pvf.clone(), exec_timeout, priority, params.encode())
+					.await;
 			}
 		}
 
@@ -754,10 +773,9 @@ impl ValidationBackend for ValidationHost {
 		&mut self,
 		pvf: PvfPrepData,
 		exec_timeout: Duration,
+		priority: Priority,
 		encoded_params: Vec<u8>,
 	) -> Result<WasmValidationResult, ValidationError> {
-		let priority = polkadot_node_core_pvf::Priority::Normal;
-
 		let (tx, rx) = oneshot::channel();
 		if let Err(err) = self.execute_pvf(pvf, exec_timeout, encoded_params, priority, tx).await {
 			return Err(ValidationError::InternalError(format!(
@@ -823,6 +841,13 @@ fn pvf_prep_timeout(executor_params: &ExecutorParams, kind: PvfPrepTimeoutKind)
 	}
 }
 
+fn pvf_exec_priority(priority: PvfExecPriority) -> Priority {
+	match priority {
+		PvfExecPriority::Normal => Priority::Normal,
+		PvfExecPriority::Critical => Priority::Critical,
+	}
+}
+
 fn pvf_exec_timeout(executor_params: &ExecutorParams, kind: PvfExecTimeoutKind) -> Duration {
 	if let Some(timeout) = executor_params.pvf_exec_timeout(kind) {
 		return timeout
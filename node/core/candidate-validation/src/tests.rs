@@ -398,6 +398,7 @@ impl ValidationBackend for MockValidateCandidateBackend {
 		&mut self,
 		_pvf: PvfPrepData,
 		_timeout: Duration,
+		_priority: Priority,
 		_encoded_params: Vec<u8>,
 	) -> Result<WasmValidationResult, ValidationError> {
 		// This is expected to panic if called more times than expected, indicating an error in the
@@ -474,6 +475,7 @@ fn candidate_validation_ok_is_ok() {
 			candidate_receipt,
 			Arc::new(pov),
 			PvfExecTimeoutKind::Backing,
+			PvfExecPriority::Critical,
 			&metrics,
 		)
 	})
@@ -533,6 +535,7 @@ fn candidate_validation_bad_return_is_invalid() {
 			candidate_receipt,
 			Arc::new(pov),
 			PvfExecTimeoutKind::Backing,
+			PvfExecPriority::Critical,
 			&metrics,
 		)
 	});
@@ -604,6 +607,7 @@ fn candidate_validation_one_ambiguous_error_is_valid() {
 			candidate_receipt,
 			Arc::new(pov),
 			PvfExecTimeoutKind::Backing,
+			PvfExecPriority::Critical,
 			&metrics,
 		)
 	})
@@ -664,6 +668,7 @@ fn candidate_validation_multiple_ambiguous_errors_is_invalid() {
 			candidate_receipt,
 			Arc::new(pov),
 			PvfExecTimeoutKind::Backing,
+			PvfExecPriority::Critical,
 			&metrics,
 		)
 	})
@@ -721,6 +726,7 @@ fn candidate_validation_retry_internal_errors() {
 			candidate_receipt,
 			Arc::new(pov),
 			PvfExecTimeoutKind::Backing,
+			PvfExecPriority::Critical,
 			&metrics,
 		)
 	});
@@ -772,6 +778,7 @@ fn candidate_validation_timeout_is_internal_error() {
 			candidate_receipt,
 			Arc::new(pov),
 			PvfExecTimeoutKind::Backing,
+			PvfExecPriority::Critical,
 			&metrics,
 		)
 	});
@@ -824,6 +831,7 @@ fn candidate_validation_commitment_hash_mismatch_is_invalid() {
 			candidate_receipt,
 			Arc::new(pov),
 			PvfExecTimeoutKind::Backing,
+			PvfExecPriority::Critical,
 			&metrics,
 		)
 	})
@@ -875,6 +883,7 @@ fn candidate_validation_code_mismatch_is_invalid() {
 		candidate_receipt,
 		Arc::new(pov),
 		PvfExecTimeoutKind::Backing,
+		PvfExecPriority::Critical,
 		&Default::default(),
 	))
 	.unwrap();
@@ -938,6 +947,7 @@ fn compressed_code_works() {
 			candidate_receipt,
 			Arc::new(pov),
 			PvfExecTimeoutKind::Backing,
+			PvfExecPriority::Critical,
 			&metrics,
 		)
 	});
@@ -991,6 +1001,7 @@ fn code_decompression_failure_is_error() {
 		candidate_receipt,
 		Arc::new(pov),
 		PvfExecTimeoutKind::Backing,
+		PvfExecPriority::Critical,
 		&Default::default(),
 	));
 
@@ -1044,6 +1055,7 @@ fn pov_decompression_failure_is_invalid() {
 		candidate_receipt,
 		Arc::new(pov),
 		PvfExecTimeoutKind::Backing,
+		PvfExecPriority::Critical,
 		&Default::default(),
 	));
 
@@ -1066,6 +1078,7 @@ impl ValidationBackend for MockPreCheckBackend {
 		&mut self,
 		_pvf: PvfPrepData,
 		_timeout: Duration,
+		_priority: Priority,
 		_encoded_params: Vec<u8>,
 	) -> Result<WasmValidationResult, ValidationError> {
 		unreachable!()
@@ -1256,6 +1269,7 @@ fn precheck_properly_classifies_outcomes() {
 	inner(Err(PrepareError::Prevalidation("foo".to_owned())), PreCheckOutcome::Invalid);
 	inner(Err(PrepareError::Preparation("bar".to_owned())), PreCheckOutcome::Invalid);
 	inner(Err(PrepareError::Panic("baz".to_owned())), PreCheckOutcome::Invalid);
+	inner(Err(PrepareError::OutOfMemory), PreCheckOutcome::Invalid);
 
 	inner(Err(PrepareError::TimedOut), PreCheckOutcome::Failed);
 	inner(Err(PrepareError::IoErr("fizz".to_owned())), PreCheckOutcome::Failed);
@@ -34,7 +34,7 @@ use polkadot_node_primitives::{
 use polkadot_node_subsystem::overseer;
 use polkadot_node_subsystem_util::runtime::RuntimeInfo;
 use polkadot_primitives::{
-	CandidateReceipt, DisputeStatement, Hash, IndexedVec, SessionIndex, SessionInfo,
+	CandidateHash, CandidateReceipt, DisputeStatement, Hash, IndexedVec, SessionIndex, SessionInfo,
 	ValidDisputeStatementKind, ValidatorId, ValidatorIndex, ValidatorPair, ValidatorSignature,
 };
 use sc_keystore::LocalKeystore;
@@ -118,7 +118,9 @@ impl OwnVoteState {
 		let our_valid_votes = controlled_indices
 			.iter()
 			.filter_map(|i| votes.valid.raw().get_key_value(i))
-			.map(|(index, (kind, sig))| (*index, (DisputeStatement::Valid(*kind), sig.clone())));
+			.map(|(index, (kind, sig))| {
+				(*index, (DisputeStatement::Valid(kind.clone()), sig.clone()))
+			});
 		let our_invalid_votes = controlled_indices
 			.iter()
 			.filter_map(|i| votes.invalid.get_key_value(i))
@@ -141,15 +143,16 @@ impl OwnVoteState {
 	/// vote).
 	fn approval_votes(
 		&self,
-	) -> Option<impl Iterator<Item = (ValidatorIndex, &ValidatorSignature)>> {
+	) -> Option<impl Iterator<Item = (ValidatorIndex, &DisputeStatement, &ValidatorSignature)>> {
 		match self {
-			Self::Voted(votes) => Some(votes.iter().filter_map(|(index, (kind, sig))| {
-				if let DisputeStatement::Valid(ValidDisputeStatementKind::ApprovalChecking) = kind {
-					Some((*index, sig))
-				} else {
-					None
-				}
-			})),
+			Self::Voted(votes) =>
+				Some(votes.iter().filter_map(|(index, (statement, sig))| match statement {
+					DisputeStatement::Valid(ValidDisputeStatementKind::ApprovalChecking) |
+					DisputeStatement::Valid(
+						ValidDisputeStatementKind::ApprovalCheckingMultipleCandidates(_),
+					) => Some((*index, statement, sig)),
+					_ => None,
+				})),
 			Self::CannotVote => None,
 		}
 	}
@@ -289,7 +292,7 @@ impl CandidateVoteState<CandidateVotes> {
 				DisputeStatement::Valid(valid_kind) => {
 					let fresh = votes.valid.insert_vote(
 						val_index,
-						*valid_kind,
+						valid_kind.clone(),
 						statement.into_validator_signature(),
 					);
 					if fresh {
@@ -355,7 +358,7 @@ impl<V> CandidateVoteState<V> {
 	/// Own approval votes if any:
 	pub fn own_approval_votes(
 		&self,
-	) -> Option<impl Iterator<Item = (ValidatorIndex, &ValidatorSignature)>> {
+	) -> Option<impl Iterator<Item = (ValidatorIndex, &DisputeStatement, &ValidatorSignature)>> {
 		self.own_vote.approval_votes()
 	}
 
@@ -481,10 +484,13 @@ impl ImportResult {
 	///
 	/// Both results and `new_state` will be changed as if those approval votes had been in the
 	/// original import.
+	///
+	/// Each vote comes with the candidates covered by its signature, which might be several if
+	/// the approval was coalesced.
 	pub fn import_approval_votes(
 		self,
 		env: &CandidateEnvironment,
-		approval_votes: HashMap<ValidatorIndex, ValidatorSignature>,
+		approval_votes: HashMap<ValidatorIndex, (Vec<CandidateHash>, ValidatorSignature)>,
 		now: Timestamp,
 	) -> Self {
 		let Self {
@@ -498,19 +504,24 @@ impl ImportResult {
 
 		let (mut votes, _) = new_state.into_old_state();
 
-		for (index, sig) in approval_votes.into_iter() {
+		for (index, (candidate_hashes, sig)) in approval_votes.into_iter() {
+			let kind = if candidate_hashes.len() == 1 {
+				ValidDisputeStatementKind::ApprovalChecking
+			} else {
+				ValidDisputeStatementKind::ApprovalCheckingMultipleCandidates(candidate_hashes)
+			};
 			debug_assert!(
 				{
 					let pub_key = &env.session_info().validators.get(index).expect("indices are validated by approval-voting subsystem; qed");
 					let candidate_hash = votes.candidate_receipt.hash();
 					let session_index = env.session_index();
-					DisputeStatement::Valid(ValidDisputeStatementKind::ApprovalChecking)
+					DisputeStatement::Valid(kind.clone())
 						.check_signature(pub_key, candidate_hash, session_index, &sig)
 						.is_ok()
 				},
 				"Signature check for imported approval votes failed! This is a serious bug. Session: {:?}, candidate hash: {:?}, validator index: {:?}", env.session_index(), votes.candidate_receipt.hash(), index
 			);
-			if votes.valid.insert_vote(index, ValidDisputeStatementKind::ApprovalChecking, sig) {
+			if votes.valid.insert_vote(index, kind, sig) {
 				imported_valid_votes += 1;
 				imported_approval_votes += 1;
 			}
@@ -29,8 +29,8 @@ use futures::{
 use sc_keystore::LocalKeystore;
 
 use polkadot_node_primitives::{
-	disputes::ValidCandidateVotes, CandidateVotes, DisputeStatus, SignedDisputeStatement,
-	Timestamp, DISPUTE_WINDOW,
+	disputes::ValidCandidateVotes, CandidateVotes, DisputeStatus, DisputeUpdate,
+	SignedDisputeStatement, Timestamp, DISPUTE_WINDOW,
 };
 use polkadot_node_subsystem::{
 	messages::{
@@ -39,8 +39,11 @@ use polkadot_node_subsystem::{
 	},
 	overseer, ActivatedLeaf, ActiveLeavesUpdate, FromOrchestra, OverseerSignal,
 };
-use polkadot_node_subsystem_util::runtime::RuntimeInfo;
+use polkadot_node_subsystem_util::runtime::{
+	get_unapplied_slashes, key_ownership_proof, submit_report_dispute_lost, RuntimeInfo,
+};
 use polkadot_primitives::{
+	vstaging::slashing::{DisputeProof, DisputesTimeSlot},
 	BlockNumber, CandidateHash, CandidateReceipt, CompactStatement, DisputeStatement,
 	DisputeStatementSet, Hash, ScrapedOnChainVotes, SessionIndex, ValidDisputeStatementKind,
 	ValidatorId, ValidatorIndex,
@@ -109,6 +112,11 @@ pub(crate) struct Initialized {
 	/// To resolve this, we limit the amount of votes imported at once to
 	/// `CHAIN_IMPORT_MAX_BATCH_SIZE` and put the rest here for later processing.
 	chain_import_backlog: VecDeque<ScrapedOnChainVotes>,
+	/// Subscribers to the changes of disputes, see
+	/// [`DisputeCoordinatorMessage::SubscribeDisputeUpdates`].
+	dispute_subscribers: Vec<mpsc::Sender<DisputeUpdate>>,
+	/// Changes of disputes which are sent to the subscribers once they are written to disk.
+	pending_dispute_updates: Vec<DisputeUpdate>,
 	metrics: Metrics,
 }
 
@@ -138,6 +146,8 @@ impl Initialized {
 			participation,
 			participation_receiver,
 			chain_import_backlog: VecDeque::new(),
+			dispute_subscribers: Vec::new(),
+			pending_dispute_updates: Vec::new(),
 			metrics,
 		}
 	}
@@ -181,6 +191,9 @@ impl Initialized {
 	where
 		B: Backend,
 	{
+		// Changes from a failed write must not be notified.
+		self.pending_dispute_updates.clear();
+
 		if let Some(InitialData { participations, votes: on_chain_votes, leaf: first_leaf }) =
 			initial_data.take()
 		{
@@ -203,6 +216,7 @@ impl Initialized {
 				let ops = overlay_db.into_write_ops();
 				backend.write(ops)?;
 			}
+			self.notify_dispute_subscribers();
 
 			// Also provide first leaf to participation for good measure.
 			self.participation
@@ -275,6 +289,7 @@ impl Initialized {
 				let ops = overlay_db.into_write_ops();
 				backend.write(ops)?;
 			}
+			self.notify_dispute_subscribers();
 			// even if the changeset was empty,
 			// otherwise the caller will error.
 			confirm_write()?;
@@ -339,6 +354,11 @@ impl Initialized {
 						session_idx.saturating_sub(DISPUTE_WINDOW.get() - 1),
 					)?;
 					self.spam_slots.prune_old(session_idx.saturating_sub(DISPUTE_WINDOW.get() - 1));
+
+					// Disputes about candidates of past sessions can't be slashed directly by the
+					// runtime, instead they get recorded as unapplied slashes. Report them once
+					// per session, the transaction pool takes care of deduplication.
+					self.process_unapplied_slashes(ctx, new_leaf.hash).await;
 				},
 				Ok(_) => { /* no new session => nothing to cache */ },
 				Err(err) => {
@@ -371,6 +391,132 @@ impl Initialized {
 		Ok(())
 	}
 
+	/// For each unapplied (past-session) slash, report an unsigned extrinsic
+	/// to the runtime.
+	async fn process_unapplied_slashes<Context>(&mut self, ctx: &mut Context, relay_parent: Hash) {
+		let pending = match get_unapplied_slashes(ctx.sender(), relay_parent).await {
+			Ok(pending) => pending,
+			Err(error) => {
+				gum::debug!(
+					target: LOG_TARGET,
+					?error,
+					"Error fetching unapplied slashes",
+				);
+				return
+			},
+		};
+
+		gum::debug!(
+			target: LOG_TARGET,
+			n_unapplied_slashes = pending.len(),
+			"Processing unapplied validator slashes",
+		);
+
+		for (session_index, candidate_hash, pending) in pending {
+			let mut key_ownership_proofs = Vec::new();
+			let mut dispute_proofs = Vec::new();
+
+			// Key ownership proofs have to be generated at a block of the session in which the
+			// offence happened, so we use the blocks including the disputed candidate.
+			let blocks_including = self.scraper.get_blocks_including_candidate(&candidate_hash);
+			for (_height, inclusion_parent) in blocks_including {
+				for (validator_index, validator_id) in pending.keys.iter() {
+					let res =
+						key_ownership_proof(ctx.sender(), inclusion_parent, validator_id.clone())
+							.await;
+
+					match res {
+						Ok(Some(key_ownership_proof)) => {
+							key_ownership_proofs.push(key_ownership_proof);
+							let time_slot = DisputesTimeSlot::new(session_index, candidate_hash);
+							let dispute_proof = DisputeProof {
+								time_slot,
+								kind: pending.kind,
+								validator_index: *validator_index,
+								validator_id: validator_id.clone(),
+							};
+							dispute_proofs.push(dispute_proof);
+						},
+						Ok(None) => {},
+						Err(error) => {
+							gum::debug!(
+								target: LOG_TARGET,
+								?error,
+								?session_index,
+								?candidate_hash,
+								?validator_id,
+								"Could not generate key ownership proof",
+							);
+						},
+					}
+				}
+
+				if !key_ownership_proofs.is_empty() {
+					// If we found a parent that we can use, stop searching.
+					// If one key ownership was resolved successfully, all of them should be.
+					debug_assert_eq!(key_ownership_proofs.len(), pending.keys.len());
+					break
+				}
+			}
+
+			let expected_keys = pending.keys.len();
+			let resolved_keys = key_ownership_proofs.len();
+			if resolved_keys < expected_keys {
+				gum::warn!(
+					target: LOG_TARGET,
+					?session_index,
+					?candidate_hash,
+					"Could not generate key ownership proofs for {} keys",
+					expected_keys - resolved_keys,
+				);
+			}
+			debug_assert_eq!(resolved_keys, dispute_proofs.len());
+
+			for (key_ownership_proof, dispute_proof) in
+				key_ownership_proofs.into_iter().zip(dispute_proofs.into_iter())
+			{
+				let validator_id = dispute_proof.validator_id.clone();
+				let res = submit_report_dispute_lost(
+					ctx.sender(),
+					relay_parent,
+					dispute_proof,
+					key_ownership_proof,
+				)
+				.await;
+
+				match res {
+					Err(error) => {
+						gum::warn!(
+							target: LOG_TARGET,
+							?error,
+							?session_index,
+							?candidate_hash,
+							"Error reporting pending slash",
+						);
+					},
+					Ok(Some(())) => {
+						gum::info!(
+							target: LOG_TARGET,
+							?session_index,
+							?candidate_hash,
+							?validator_id,
+							"Successfully reported pending slash",
+						);
+					},
+					Ok(None) => {
+						gum::debug!(
+							target: LOG_TARGET,
+							?session_index,
+							?candidate_hash,
+							?validator_id,
+							"Pending slash report was not submitted, it might be a duplicate",
+						);
+					},
+				}
+			}
+		}
+	}
+
 	/// Process one batch of our `chain_import_backlog`.
 	///
 	/// `new_votes` will be appended beforehand.
@@ -471,7 +617,7 @@ impl Initialized {
 						};
 					debug_assert!(
 						SignedDisputeStatement::new_checked(
-							DisputeStatement::Valid(valid_statement_kind),
+							DisputeStatement::Valid(valid_statement_kind.clone()),
 							candidate_hash,
 							session,
 							validator_public.clone(),
@@ -705,6 +851,22 @@ impl Initialized {
 				}
 				let _ = tx.send(query_output);
 			},
+			DisputeCoordinatorMessage::QueryParticipation(query, tx) => {
+				gum::trace!(target: LOG_TARGET, "DisputeCoordinatorMessage::QueryParticipation");
+				let _ = tx.send(
+					query
+						.into_iter()
+						.map(|(session_index, candidate_hash)| {
+							let state = self.participation.participation_state(&candidate_hash);
+							(session_index, candidate_hash, state)
+						})
+						.collect(),
+				);
+			},
+			DisputeCoordinatorMessage::SubscribeDisputeUpdates(tx) => {
+				gum::trace!(target: LOG_TARGET, "DisputeCoordinatorMessage::SubscribeDisputeUpdates");
+				self.dispute_subscribers.push(tx);
+			},
 			DisputeCoordinatorMessage::IssueLocalStatement(
 				session,
 				candidate_hash,
@@ -1013,7 +1175,7 @@ impl Initialized {
 		// Also send any already existing approval vote on new disputes:
 		if import_result.is_freshly_disputed() {
 			let our_approval_votes = new_state.own_approval_votes().into_iter().flatten();
-			for (validator_index, sig) in our_approval_votes {
+			for (validator_index, statement, sig) in our_approval_votes {
 				let pub_key = match env.validators().get(validator_index) {
 					None => {
 						gum::error!(
@@ -1027,7 +1189,7 @@ impl Initialized {
 					Some(k) => k,
 				};
 				let statement = SignedDisputeStatement::new_unchecked_from_trusted_source(
-					DisputeStatement::Valid(ValidDisputeStatementKind::ApprovalChecking),
+					statement.clone(),
 					candidate_hash,
 					session,
 					pub_key.clone(),
@@ -1090,6 +1252,17 @@ impl Initialized {
 				);
 				overlay_db.write_recent_disputes(recent_disputes);
 			}
+
+			if import_result.dispute_state_changed() || import_result.votes_changed() {
+				let votes = new_state.votes();
+				self.pending_dispute_updates.push(DisputeUpdate {
+					session,
+					candidate_hash,
+					status: *new_status,
+					valid_votes: votes.valid.raw().len() as u32,
+					invalid_votes: votes.invalid.len() as u32,
+				});
+			}
 		}
 
 		// Notify ChainSelection if a dispute has concluded against a candidate. ChainSelection
@@ -1164,6 +1337,25 @@ impl Initialized {
 		Ok(ImportStatementsResult::ValidImport)
 	}
 
+	/// Sends the pending changes of disputes to the subscribers. Subscribers which went away or fell
+	/// behind are dropped.
+	fn notify_dispute_subscribers(&mut self) {
+		for update in self.pending_dispute_updates.drain(..) {
+			self.dispute_subscribers.retain_mut(|tx| match tx.try_send(update.clone()) {
+				Ok(()) => true,
+				Err(err) => {
+					if err.is_full() {
+						gum::debug!(
+							target: LOG_TARGET,
+							"Dropping a dispute updates subscriber which fell behind",
+						);
+					}
+					false
+				},
+			});
+		}
+	}
+
 	async fn issue_local_statement<Context>(
 		&mut self,
 		ctx: &mut Context,
@@ -44,7 +44,8 @@ use polkadot_node_subsystem_util::{
 	runtime::{Config as RuntimeInfoConfig, RuntimeInfo},
 };
 use polkadot_primitives::{
-	DisputeStatement, ScrapedOnChainVotes, SessionIndex, SessionInfo, ValidatorIndex,
+	DisputeStatement, ScrapedOnChainVotes, SessionIndex, SessionInfo, ValidDisputeStatementKind,
+	ValidatorIndex,
 };
 
 use crate::{
@@ -572,14 +573,22 @@ pub fn make_dispute_message(
 			.map_err(|()| DisputeMessageCreationError::InvalidStoredStatement)?;
 			(our_vote, our_index, other_vote, *validator_index)
 		} else {
+			// Votes of coalesced approvals can't be decoded by older nodes, prefer others.
+			let is_coalesced_approval = |statement_kind: &ValidDisputeStatementKind| {
+				matches!(
+					statement_kind,
+					ValidDisputeStatementKind::ApprovalCheckingMultipleCandidates(_)
+				)
+			};
 			let (validator_index, (statement_kind, validator_signature)) = votes
 				.valid
 				.raw()
 				.iter()
-				.next()
+				.find(|(_, (statement_kind, _))| !is_coalesced_approval(statement_kind))
+				.or_else(|| votes.valid.raw().iter().next())
 				.ok_or(DisputeMessageCreationError::NoOppositeVote)?;
 			let other_vote = SignedDisputeStatement::new_checked(
-				DisputeStatement::Valid(*statement_kind),
+				DisputeStatement::Valid(statement_kind.clone()),
 				*our_vote.candidate_hash(),
 				our_vote.session_index(),
 				validators
@@ -25,9 +25,9 @@ use futures::{
 #[cfg(test)]
 use futures_timer::Delay;
 
-use polkadot_node_primitives::ValidationResult;
+use polkadot_node_primitives::{DisputeParticipation, ValidationResult};
 use polkadot_node_subsystem::{
-	messages::{AvailabilityRecoveryMessage, CandidateValidationMessage},
+	messages::{AvailabilityRecoveryMessage, CandidateValidationMessage, PvfExecPriority},
 	overseer, ActiveLeavesUpdate, RecoveryError,
 };
 use polkadot_node_subsystem_util::runtime::get_validation_code_by_hash;
@@ -178,6 +178,17 @@ impl Participation {
 		self.queue.queue(ctx.sender(), priority, req).await
 	}
 
+	/// Get the state of our participation in the dispute about the given candidate.
+	pub fn participation_state(&self, candidate_hash: &CandidateHash) -> DisputeParticipation {
+		if self.running_participations.contains(candidate_hash) {
+			DisputeParticipation::Running
+		} else if let Some(priority) = self.queue.is_queued(candidate_hash) {
+			DisputeParticipation::Queued { priority }
+		} else {
+			DisputeParticipation::Idle
+		}
+	}
+
 	/// Message from a worker task was received - get the outcome.
 	///
 	/// Call this function to keep participations going and to receive `ParticipationStatement`s.
@@ -367,6 +378,8 @@ async fn participate(
 			req.candidate_receipt().clone(),
 			available_data.pov,
 			PvfExecTimeoutKind::Approval,
+			// Disputes must conclude quickly, so they may use the workers reserved for backing.
+			PvfExecPriority::Critical,
 			validation_tx,
 		))
 		.await;
@@ -198,6 +198,21 @@ impl Queues {
 		None
 	}
 
+	/// Whether a participation request for the given candidate is queued. Returns `Some(true)` if
+	/// it is in the priority queue and `Some(false)` if it is in the best effort queue.
+	pub fn is_queued(&self, candidate_hash: &CandidateHash) -> Option<bool> {
+		let in_queue = |queue: &BTreeMap<CandidateComparator, ParticipationRequest>| {
+			queue.values().any(|req| req.candidate_hash() == candidate_hash)
+		};
+		if in_queue(&self.priority) {
+			Some(true)
+		} else if in_queue(&self.best_effort) {
+			Some(false)
+		} else {
+			None
+		}
+	}
+
 	/// Reprioritizes any participation requests pertaining to the
 	/// passed candidates from best effort to priority.
 	pub async fn prioritize_if_present(
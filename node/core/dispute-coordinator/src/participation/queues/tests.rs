@@ -211,3 +211,41 @@ fn candidate_is_only_dequeued_once() {
 	assert_eq!(queue.dequeue(), Some(req1));
 	assert_matches!(queue.dequeue(), None);
 }
+
+/// Check that queued requests are found in the queue they are in.
+#[test]
+fn is_queued_reports_the_queue() {
+	let mut queue = Queues::new(Metrics::default());
+	let req_best_effort = make_participation_request(Hash::repeat_byte(0x01));
+	let req_prio = make_participation_request(Hash::repeat_byte(0x02));
+	let req_unknown = make_participation_request(Hash::repeat_byte(0x03));
+
+	queue
+		.queue_with_comparator(
+			make_dummy_comparator(&req_best_effort, Some(1)),
+			ParticipationPriority::BestEffort,
+			clone_request(&req_best_effort),
+		)
+		.unwrap();
+	queue
+		.queue_with_comparator(
+			make_dummy_comparator(&req_prio, Some(1)),
+			ParticipationPriority::Priority,
+			clone_request(&req_prio),
+		)
+		.unwrap();
+
+	assert_eq!(queue.is_queued(req_best_effort.candidate_hash()), Some(false));
+	assert_eq!(queue.is_queued(req_prio.candidate_hash()), Some(true));
+	assert_eq!(queue.is_queued(req_unknown.candidate_hash()), None);
+
+	queue
+		.prioritize_with_comparator(make_dummy_comparator(&req_best_effort, Some(1)))
+		.unwrap();
+	assert_eq!(queue.is_queued(req_best_effort.candidate_hash()), Some(true));
+
+	assert!(queue.dequeue().is_some());
+	assert!(queue.dequeue().is_some());
+	assert_eq!(queue.is_queued(req_best_effort.candidate_hash()), None);
+	assert_eq!(queue.is_queued(req_prio.candidate_hash()), None);
+}
@@ -120,8 +120,8 @@ pub async fn participation_full_happy_path(
 	assert_matches!(
 	ctx_handle.recv().await,
 	AllMessages::CandidateValidation(
-		CandidateValidationMessage::ValidateFromExhaustive(_, _, candidate_receipt, _, timeout, tx)
-		) if timeout == PvfExecTimeoutKind::Approval => {
+		CandidateValidationMessage::ValidateFromExhaustive(_, _, candidate_receipt, _, timeout, priority, tx)
+		) if timeout == PvfExecTimeoutKind::Approval && priority == PvfExecPriority::Critical => {
 			if expected_commitments_hash != candidate_receipt.commitments_hash {
 				tx.send(Ok(ValidationResult::Invalid(InvalidCandidate::CommitmentsHashMismatch))).unwrap();
 			} else {
@@ -454,8 +454,8 @@ fn cast_invalid_vote_if_validation_fails_or_is_invalid() {
 		assert_matches!(
 			ctx_handle.recv().await,
 			AllMessages::CandidateValidation(
-				CandidateValidationMessage::ValidateFromExhaustive(_, _, _, _, timeout, tx)
-			) if timeout == PvfExecTimeoutKind::Approval => {
+				CandidateValidationMessage::ValidateFromExhaustive(_, _, _, _, timeout, priority, tx)
+			) if timeout == PvfExecTimeoutKind::Approval && priority == PvfExecPriority::Critical => {
 				tx.send(Ok(ValidationResult::Invalid(InvalidCandidate::Timeout))).unwrap();
 			},
 			"overseer did not receive candidate validation message",
@@ -491,8 +491,8 @@ fn cast_invalid_vote_if_commitments_dont_match() {
 		assert_matches!(
 			ctx_handle.recv().await,
 			AllMessages::CandidateValidation(
-				CandidateValidationMessage::ValidateFromExhaustive(_, _, _, _, timeout, tx)
-			) if timeout == PvfExecTimeoutKind::Approval => {
+				CandidateValidationMessage::ValidateFromExhaustive(_, _, _, _, timeout, priority, tx)
+			) if timeout == PvfExecTimeoutKind::Approval && priority == PvfExecPriority::Critical => {
 				tx.send(Ok(ValidationResult::Invalid(InvalidCandidate::CommitmentsHashMismatch))).unwrap();
 			},
 			"overseer did not receive candidate validation message",
@@ -528,8 +528,8 @@ fn cast_valid_vote_if_validation_passes() {
 		assert_matches!(
 			ctx_handle.recv().await,
 			AllMessages::CandidateValidation(
-				CandidateValidationMessage::ValidateFromExhaustive(_, _, _, _, timeout, tx)
-			) if timeout == PvfExecTimeoutKind::Approval => {
+				CandidateValidationMessage::ValidateFromExhaustive(_, _, _, _, timeout, priority, tx)
+			) if timeout == PvfExecTimeoutKind::Approval && priority == PvfExecPriority::Critical => {
 				tx.send(Ok(ValidationResult::Valid(dummy_candidate_commitments(None), PersistedValidationData::default()))).unwrap();
 			},
 			"overseer did not receive candidate validation message",
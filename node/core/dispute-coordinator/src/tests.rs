@@ -25,14 +25,16 @@ use std::{
 
 use assert_matches::assert_matches;
 use futures::{
-	channel::oneshot,
+	channel::{mpsc, oneshot},
 	future::{self, BoxFuture},
+	StreamExt,
 };
 
 use polkadot_node_subsystem_util::database::Database;
 
 use polkadot_node_primitives::{
-	DisputeMessage, DisputeStatus, SignedDisputeStatement, SignedFullStatement, Statement,
+	DisputeMessage, DisputeStatus, DisputeUpdate, SignedDisputeStatement, SignedFullStatement,
+	Statement,
 };
 use polkadot_node_subsystem::{
 	messages::{
@@ -61,10 +63,14 @@ use polkadot_node_subsystem_test_helpers::{
 	make_buffered_subsystem_context, TestSubsystemContextHandle,
 };
 use polkadot_primitives::{
-	ApprovalVote, BlockNumber, CandidateCommitments, CandidateEvent, CandidateHash,
-	CandidateReceipt, CoreIndex, DisputeStatement, GroupIndex, Hash, HeadData, Header, IndexedVec,
-	MultiDisputeStatementSet, ScrapedOnChainVotes, SessionIndex, SessionInfo, SigningContext,
-	ValidDisputeStatementKind, ValidatorId, ValidatorIndex, ValidatorSignature,
+	vstaging::slashing::{
+		DisputesTimeSlot, OpaqueKeyOwnershipProof, PendingSlashes, SlashingOffenceKind,
+	},
+	ApprovalVote, ApprovalVoteMultipleCandidates, BlockNumber, CandidateCommitments,
+	CandidateEvent, CandidateHash, CandidateReceipt, CoreIndex, DisputeStatement, GroupIndex, Hash,
+	HeadData, Header, IndexedVec, MultiDisputeStatementSet, ScrapedOnChainVotes, SessionIndex,
+	SessionInfo, SigningContext, ValidDisputeStatementKind, ValidatorId, ValidatorIndex,
+	ValidatorSignature,
 };
 
 use crate::{
@@ -327,21 +333,21 @@ impl TestState {
 					assert_eq!(h, block_hash);
 					let _ = tx.send(Ok(session));
 
-					// Queries for session caching - see `handle_startup`
-					if self.known_session.is_none() {
-						for i in 0..=session {
-							assert_matches!(
-								overseer_recv(virtual_overseer).await,
-								AllMessages::RuntimeApi(RuntimeApiMessage::Request(
-									h,
-									RuntimeApiRequest::SessionInfo(session_index, tx),
-								)) => {
-									assert_eq!(h, block_hash);
-									assert_eq!(session_index, i);
-									let _ = tx.send(Ok(Some(self.session_info())));
-								}
-							);
-						}
+					// Queries for session caching - see `handle_startup` and
+					// `process_active_leaves_update`.
+					let first_unknown_session = self.known_session.map_or(0, |known| known + 1);
+					for i in first_unknown_session..=session {
+						assert_matches!(
+							overseer_recv(virtual_overseer).await,
+							AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+								h,
+								RuntimeApiRequest::SessionInfo(session_index, tx),
+							)) => {
+								assert_eq!(h, block_hash);
+								assert_eq!(session_index, i);
+								let _ = tx.send(Ok(Some(self.session_info())));
+							}
+						);
 					}
 
 					self.known_session = Some(session);
@@ -532,6 +538,24 @@ impl TestState {
 		)
 	}
 
+	fn issue_approval_vote_for_candidates_with_index(
+		&self,
+		index: ValidatorIndex,
+		candidate_hashes: &[CandidateHash],
+		session: SessionIndex,
+	) -> ValidatorSignature {
+		let keystore = self.master_keystore.clone() as KeystorePtr;
+		let validator_id = self.validators[index.0 as usize].public();
+
+		let payload = ApprovalVoteMultipleCandidates(candidate_hashes).signing_payload(session);
+		keystore
+			.sr25519_sign(ValidatorId::ID, &validator_id, &payload)
+			.ok()
+			.flatten()
+			.unwrap()
+			.into()
+	}
+
 	fn resume<F>(mut self, test: F) -> Self
 	where
 		F: FnOnce(TestState, VirtualOverseer) -> BoxFuture<'static, TestState>,
@@ -639,7 +663,7 @@ fn make_candidate_included_event(candidate_receipt: CandidateReceipt) -> Candida
 pub async fn handle_approval_vote_request(
 	ctx_handle: &mut VirtualOverseer,
 	expected_hash: &CandidateHash,
-	votes_to_send: HashMap<ValidatorIndex, ValidatorSignature>,
+	votes_to_send: HashMap<ValidatorIndex, (Vec<CandidateHash>, ValidatorSignature)>,
 ) {
 	assert_matches!(
 		ctx_handle.recv().await,
@@ -845,9 +869,12 @@ fn approval_vote_import_works() {
 				.await;
 			gum::trace!("After sending `ImportStatements`");
 
-			let approval_votes = [(ValidatorIndex(4), approval_vote.into_validator_signature())]
-				.into_iter()
-				.collect();
+			let approval_votes = [(
+				ValidatorIndex(4),
+				(vec![candidate_hash1], approval_vote.into_validator_signature()),
+			)]
+			.into_iter()
+			.collect();
 
 			handle_approval_vote_request(&mut virtual_overseer, &candidate_hash1, approval_votes)
 				.await;
@@ -897,6 +924,110 @@ fn approval_vote_import_works() {
 	});
 }
 
+#[test]
+fn approval_vote_for_multiple_candidates_import_works() {
+	test_harness(|mut test_state, mut virtual_overseer| {
+		Box::pin(async move {
+			let session = 1;
+
+			test_state.handle_resume_sync(&mut virtual_overseer, session).await;
+
+			let candidate_receipt1 = make_valid_candidate_receipt();
+			let candidate_hash1 = candidate_receipt1.hash();
+
+			test_state
+				.activate_leaf_at_session(&mut virtual_overseer, session, 1, Vec::new())
+				.await;
+
+			let (valid_vote1, invalid_vote1) = generate_opposing_votes_pair(
+				&test_state,
+				ValidatorIndex(3),
+				ValidatorIndex(1),
+				candidate_hash1,
+				session,
+				VoteType::Backing,
+			)
+			.await;
+
+			let approval_candidates = vec![candidate_hash1, CandidateHash(Hash::repeat_byte(0x42))];
+			let approval_signature = test_state.issue_approval_vote_for_candidates_with_index(
+				ValidatorIndex(4),
+				&approval_candidates,
+				session,
+			);
+
+			gum::trace!("Before sending `ImportStatements`");
+			virtual_overseer
+				.send(FromOrchestra::Communication {
+					msg: DisputeCoordinatorMessage::ImportStatements {
+						candidate_receipt: candidate_receipt1.clone(),
+						session,
+						statements: vec![
+							(valid_vote1, ValidatorIndex(3)),
+							(invalid_vote1, ValidatorIndex(1)),
+						],
+						pending_confirmation: None,
+					},
+				})
+				.await;
+			gum::trace!("After sending `ImportStatements`");
+
+			let approval_votes =
+				[(ValidatorIndex(4), (approval_candidates.clone(), approval_signature))]
+					.into_iter()
+					.collect();
+
+			handle_approval_vote_request(&mut virtual_overseer, &candidate_hash1, approval_votes)
+				.await;
+
+			// Participation won't happen here because the dispute is neither backed, not confirmed
+			// nor the candidate is included. Or in other words - we'll refrain from participation.
+
+			{
+				let (tx, rx) = oneshot::channel();
+				virtual_overseer
+					.send(FromOrchestra::Communication {
+						msg: DisputeCoordinatorMessage::ActiveDisputes(tx),
+					})
+					.await;
+
+				assert_eq!(
+					rx.await.unwrap(),
+					vec![(session, candidate_hash1, DisputeStatus::Active)]
+				);
+
+				let (tx, rx) = oneshot::channel();
+				virtual_overseer
+					.send(FromOrchestra::Communication {
+						msg: DisputeCoordinatorMessage::QueryCandidateVotes(
+							vec![(session, candidate_hash1)],
+							tx,
+						),
+					})
+					.await;
+
+				let (_, _, votes) = rx.await.unwrap().get(0).unwrap().clone();
+				assert_eq!(votes.valid.raw().len(), 2);
+				assert_matches!(
+					votes.valid.raw().get(&ValidatorIndex(4)),
+					Some((ValidDisputeStatementKind::ApprovalCheckingMultipleCandidates(candidates), _)) => {
+						assert_eq!(candidates, &approval_candidates);
+					},
+					"Approval vote is missing!"
+				);
+				assert_eq!(votes.invalid.len(), 1);
+			}
+
+			virtual_overseer.send(FromOrchestra::Signal(OverseerSignal::Conclude)).await;
+
+			// No more messages expected:
+			assert!(virtual_overseer.try_recv().await.is_none());
+
+			test_state
+		})
+	});
+}
+
 #[test]
 fn dispute_gets_confirmed_via_participation() {
 	test_harness(|mut test_state, mut virtual_overseer| {
@@ -3231,6 +3362,91 @@ fn participation_requests_reprioritized_for_newly_included() {
 	});
 }
 
+#[test]
+fn subscribers_are_notified_of_dispute_updates() {
+	test_harness(|mut test_state, mut virtual_overseer| {
+		Box::pin(async move {
+			let session = 1;
+
+			test_state.handle_resume_sync(&mut virtual_overseer, session).await;
+
+			let candidate_receipt = make_valid_candidate_receipt();
+			let candidate_hash = candidate_receipt.hash();
+
+			test_state
+				.activate_leaf_at_session(
+					&mut virtual_overseer,
+					session,
+					1,
+					vec![make_candidate_backed_event(candidate_receipt.clone())],
+				)
+				.await;
+
+			let (updates_tx, mut updates_rx) = mpsc::channel(16);
+			virtual_overseer
+				.send(FromOrchestra::Communication {
+					msg: DisputeCoordinatorMessage::SubscribeDisputeUpdates(updates_tx),
+				})
+				.await;
+
+			let (valid_vote, invalid_vote) = generate_opposing_votes_pair(
+				&test_state,
+				ValidatorIndex(2),
+				ValidatorIndex(1),
+				candidate_hash,
+				session,
+				VoteType::Explicit,
+			)
+			.await;
+
+			virtual_overseer
+				.send(FromOrchestra::Communication {
+					msg: DisputeCoordinatorMessage::ImportStatements {
+						candidate_receipt: candidate_receipt.clone(),
+						session,
+						statements: vec![
+							(valid_vote, ValidatorIndex(2)),
+							(invalid_vote, ValidatorIndex(1)),
+						],
+						pending_confirmation: None,
+					},
+				})
+				.await;
+			handle_approval_vote_request(&mut virtual_overseer, &candidate_hash, HashMap::new())
+				.await;
+
+			assert_eq!(
+				updates_rx.next().timeout(TEST_TIMEOUT).await.unwrap(),
+				Some(DisputeUpdate {
+					session,
+					candidate_hash,
+					status: DisputeStatus::Active,
+					valid_votes: 1,
+					invalid_votes: 1,
+				})
+			);
+
+			// Our own vote from participation is notified as well.
+			participation_with_distribution(
+				&mut virtual_overseer,
+				&candidate_hash,
+				candidate_receipt.commitments_hash,
+			)
+			.await;
+
+			assert_matches!(
+				updates_rx.next().timeout(TEST_TIMEOUT).await.unwrap(),
+				Some(DisputeUpdate { valid_votes: 2, invalid_votes: 1, .. })
+			);
+
+			virtual_overseer.send(FromOrchestra::Signal(OverseerSignal::Conclude)).await;
+			assert!(virtual_overseer.try_recv().await.is_none());
+
+			test_state
+		})
+	});
+}
+
 // When a dispute has concluded against a parachain block candidate we want to notify
 // the chain selection subsystem. Then chain selection can revert the relay parents of
 // the disputed candidate and mark all descendants as non-viable. This direct
@@ -3379,3 +3595,92 @@ fn informs_chain_selection_when_dispute_concluded_against() {
 		})
 	});
 }
+
+#[test]
+fn unapplied_slashes_get_reported_on_new_session() {
+	test_harness(|mut test_state, mut virtual_overseer| {
+		Box::pin(async move {
+			let session = 1;
+
+			test_state.handle_resume_sync(&mut virtual_overseer, session).await;
+
+			let candidate_receipt = make_invalid_candidate_receipt();
+			let candidate_hash = candidate_receipt.hash();
+
+			// Include the candidate, so the subsystem knows a block of the offence session.
+			test_state
+				.activate_leaf_at_session(
+					&mut virtual_overseer,
+					session,
+					1,
+					vec![make_candidate_included_event(candidate_receipt.clone())],
+				)
+				.await;
+			let inclusion_block = test_state.last_block;
+
+			// Moving to a new session triggers processing of unapplied slashes.
+			let new_session = session + 1;
+			test_state
+				.activate_leaf_at_session(&mut virtual_overseer, new_session, 2, Vec::new())
+				.await;
+			let new_leaf = test_state.last_block;
+
+			let validator_index = ValidatorIndex(1);
+			let validator_id = test_state.validator_public[validator_index].clone();
+			let pending = PendingSlashes {
+				keys: vec![(validator_index, validator_id.clone())].into_iter().collect(),
+				kind: SlashingOffenceKind::ForInvalid,
+			};
+
+			assert_matches!(
+				virtual_overseer.recv().await,
+				AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					h,
+					RuntimeApiRequest::UnappliedSlashes(tx),
+				)) => {
+					assert_eq!(h, new_leaf);
+					tx.send(Ok(vec![(session, candidate_hash, pending)])).unwrap();
+				}
+			);
+
+			let key_ownership_proof = OpaqueKeyOwnershipProof::new(vec![1, 2, 3]);
+			assert_matches!(
+				virtual_overseer.recv().await,
+				AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					h,
+					RuntimeApiRequest::KeyOwnershipProof(id, tx),
+				)) => {
+					// The proof must be generated at a block of the offence session.
+					assert_eq!(h, inclusion_block);
+					assert_eq!(id, validator_id);
+					tx.send(Ok(Some(key_ownership_proof.clone()))).unwrap();
+				}
+			);
+
+			assert_matches!(
+				virtual_overseer.recv().await,
+				AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					h,
+					RuntimeApiRequest::SubmitReportDisputeLost(dispute_proof, proof, tx),
+				)) => {
+					assert_eq!(h, new_leaf);
+					assert_eq!(proof, key_ownership_proof);
+					assert_eq!(dispute_proof.time_slot, DisputesTimeSlot::new(session, candidate_hash));
+					assert_eq!(dispute_proof.kind, SlashingOffenceKind::ForInvalid);
+					assert_eq!(dispute_proof.validator_index, validator_index);
+					assert_eq!(dispute_proof.validator_id, validator_id);
+					tx.send(Ok(Some(()))).unwrap();
+				}
+			);
+
+			// Wrap up
+			virtual_overseer.send(FromOrchestra::Signal(OverseerSignal::Conclude)).await;
+			assert_matches!(
+				virtual_overseer.try_recv().await,
+				None => {}
+			);
+
+			test_state
+		})
+	});
+}
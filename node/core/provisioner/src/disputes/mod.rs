@@ -25,12 +25,17 @@ use crate::LOG_TARGET;
 use futures::channel::oneshot;
 use polkadot_node_primitives::CandidateVotes;
 use polkadot_node_subsystem::{messages::DisputeCoordinatorMessage, overseer};
-use polkadot_primitives::{CandidateHash, SessionIndex};
+use polkadot_primitives::{CandidateHash, SessionIndex, ValidDisputeStatementKind};
 
 /// Request the relevant dispute statements for a set of disputes identified by `CandidateHash` and the `SessionIndex`.
+///
+/// Votes of approvals coalesced over several candidates are left out, unless
+/// `approval_coalescing_enabled`: validators not supporting them would fail to decode the whole
+/// inherent.
 async fn request_votes(
 	sender: &mut impl overseer::ProvisionerSenderTrait,
 	disputes_to_query: Vec<(SessionIndex, CandidateHash)>,
+	approval_coalescing_enabled: bool,
 ) -> Vec<(SessionIndex, CandidateHash, CandidateVotes)> {
 	let (tx, rx) = oneshot::channel();
 	// Bounded by block production - `ProvisionerMessage::RequestInherentData`.
@@ -40,7 +45,19 @@ async fn request_votes(
 	));
 
 	match rx.await {
-		Ok(v) => v,
+		Ok(mut v) => {
+			if !approval_coalescing_enabled {
+				for (_, _, votes) in v.iter_mut() {
+					votes.valid.retain(|_, (statement_kind, _)| {
+						!matches!(
+							statement_kind,
+							ValidDisputeStatementKind::ApprovalCheckingMultipleCandidates(_)
+						)
+					});
+				}
+			}
+			v
+		},
 		Err(oneshot::Canceled) => {
 			gum::warn!(target: LOG_TARGET, "Unable to query candidate votes");
 			Vec::new()
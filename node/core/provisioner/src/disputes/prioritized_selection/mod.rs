@@ -26,6 +26,7 @@ use polkadot_node_subsystem::{
 	messages::{DisputeCoordinatorMessage, RuntimeApiMessage, RuntimeApiRequest},
 	overseer, ActivatedLeaf,
 };
+use polkadot_node_subsystem_util::runtime::is_approval_coalescing_enabled;
 use polkadot_primitives::{
 	supermajority_threshold, CandidateHash, DisputeState, DisputeStatement, DisputeStatementSet,
 	Hash, MultiDisputeStatementSet, SessionIndex, ValidDisputeStatementKind, ValidatorIndex,
@@ -171,8 +172,10 @@ where
 		);
 	}
 
+	let approval_coalescing_enabled = is_approval_coalescing_enabled(sender, leaf.hash).await;
+
 	gum::trace!(target: LOG_TARGET, ?leaf, "Vote selection for recent disputes");
-	let result = vote_selection(sender, partitioned, &onchain).await;
+	let result = vote_selection(sender, partitioned, &onchain, approval_coalescing_enabled).await;
 
 	gum::trace!(target: LOG_TARGET, ?leaf, "Convert to multi dispute statement set");
 	make_multi_dispute_statement_set(metrics, result)
@@ -181,10 +184,13 @@ where
 /// Selects dispute votes from `PartitionedDisputes` which should be sent to the runtime. Votes which
 /// are already onchain are filtered out. Result should be sorted by `(SessionIndex, CandidateHash)`
 /// which is enforced by the `BTreeMap`. This is a requirement from the runtime.
+///
+/// Votes of coalesced approvals are only selected if `approval_coalescing_enabled` by the runtime.
 async fn vote_selection<Sender>(
 	sender: &mut Sender,
 	partitioned: PartitionedDisputes,
 	onchain: &HashMap<(SessionIndex, CandidateHash), DisputeState>,
+	approval_coalescing_enabled: bool,
 ) -> BTreeMap<(SessionIndex, CandidateHash), CandidateVotes>
 where
 	Sender: overseer::ProvisionerSenderTrait,
@@ -202,7 +208,7 @@ where
 		// Filter votes which are already onchain
 		request_votes_counter += 1;
 		gum::trace!(target: LOG_TARGET, "requesting onchain votes",);
-		let votes = super::request_votes(sender, batch)
+		let votes = super::request_votes(sender, batch, approval_coalescing_enabled)
 			.await
 			.into_iter()
 			.map(|(session_index, candidate_hash, mut votes)| {
@@ -217,7 +223,7 @@ where
 				votes.valid.retain(|validator_idx, (statement_kind, _)| {
 					is_vote_worth_to_keep(
 						validator_idx,
-						DisputeStatement::Valid(*statement_kind),
+						DisputeStatement::Valid(statement_kind.clone()),
 						&onchain_state,
 					)
 				});
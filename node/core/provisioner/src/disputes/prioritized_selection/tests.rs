@@ -26,6 +26,7 @@ use polkadot_node_subsystem::messages::{
 };
 use polkadot_node_subsystem_test_helpers::TestSubsystemSender;
 use polkadot_primitives::{
+	vstaging::{node_features::FeatureIndex, NodeFeatures},
 	CandidateHash, DisputeState, InvalidDisputeStatementKind, SessionIndex,
 	ValidDisputeStatementKind, ValidatorSignature,
 };
@@ -329,6 +330,12 @@ async fn mock_overseer(
 					.map(|(k, v)| (k.0, k.1, v))
 					.collect::<Vec<_>>()));
 			},
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				_,
+				RuntimeApiRequest::NodeFeatures(sender),
+			)) => {
+				let _ = sender.send(Ok(disputes_db.node_features.clone()));
+			},
 			AllMessages::RuntimeApi(_) => panic!("Unexpected RuntimeApi request"),
 			AllMessages::DisputeCoordinator(DisputeCoordinatorMessage::RecentDisputes(sender)) => {
 				let _ = sender.send(disputes_db.local_disputes.clone());
@@ -364,6 +371,7 @@ struct TestDisputes {
 	pub local_disputes: Vec<(SessionIndex, CandidateHash, DisputeStatus)>,
 	pub votes_db: HashMap<(SessionIndex, CandidateHash), CandidateVotes>,
 	pub onchain_disputes: HashMap<(u32, CandidateHash), DisputeState>,
+	pub node_features: NodeFeatures,
 	validators_count: usize,
 }
 
@@ -373,6 +381,7 @@ impl TestDisputes {
 			local_disputes: Vec::<(SessionIndex, CandidateHash, DisputeStatus)>::new(),
 			votes_db: HashMap::<(SessionIndex, CandidateHash), CandidateVotes>::new(),
 			onchain_disputes: HashMap::<(u32, CandidateHash), DisputeState>::new(),
+			node_features: NodeFeatures::new(),
 			validators_count,
 		}
 	}
@@ -789,3 +798,54 @@ fn unconfirmed_are_handled_correctly() {
 		},
 	);
 }
+
+#[test]
+fn coalesced_approvals_are_only_selected_if_enabled_by_the_runtime() {
+	const VALIDATOR_COUNT: usize = 10;
+
+	let mut enabled_features = NodeFeatures::new();
+	enabled_features.resize(FeatureIndex::ApprovalCoalescing as usize + 1, false);
+	enabled_features.set(FeatureIndex::ApprovalCoalescing as usize, true);
+
+	for (node_features, coalescing_enabled) in
+		[(NodeFeatures::new(), false), (enabled_features, true)]
+	{
+		let mut input = TestDisputes::new(VALIDATOR_COUNT);
+		input.node_features = node_features;
+		let (_, votes_count) = input.add_confirmed_disputes_unknown_onchain(1);
+
+		// The last validator approved the candidate along with another one.
+		let ((_, candidate_hash), votes) = input.votes_db.iter_mut().next().unwrap();
+		votes.valid.insert_vote(
+			ValidatorIndex(VALIDATOR_COUNT as u32 - 1),
+			ValidDisputeStatementKind::ApprovalCheckingMultipleCandidates(vec![
+				*candidate_hash,
+				CandidateHash(Hash::random()),
+			]),
+			test_helpers::dummy_signature(),
+		);
+
+		let metrics = metrics::Metrics::new_dummy();
+		let mut vote_queries: usize = 0;
+		test_harness(
+			|r| mock_overseer(r, &mut input, &mut vote_queries),
+			|mut tx: TestSubsystemSender| async move {
+				let lf = leaf();
+				let result = select_disputes(&mut tx, &metrics, &lf).await;
+
+				assert_eq!(result.len(), 1);
+				let statements = &result[0].statements;
+				assert_eq!(statements.len(), votes_count + coalescing_enabled as usize);
+				assert_eq!(
+					statements.iter().any(|(statement, _, _)| matches!(
+						statement,
+						DisputeStatement::Valid(
+							ValidDisputeStatementKind::ApprovalCheckingMultipleCandidates(_)
+						)
+					)),
+					coalescing_enabled,
+				);
+			},
+		);
+	}
+}
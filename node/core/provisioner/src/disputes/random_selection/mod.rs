@@ -147,8 +147,9 @@ where
 		recent
 	};
 
-	// Load all votes for all disputes from the coordinator.
-	let dispute_candidate_votes = super::request_votes(sender, disputes).await;
+	// Load all votes for all disputes from the coordinator. Runtimes this old don't support
+	// coalesced approvals.
+	let dispute_candidate_votes = super::request_votes(sender, disputes, false).await;
 
 	// Transform all `CandidateVotes` into `MultiDisputeStatementSet`.
 	dispute_candidate_votes
@@ -41,7 +41,7 @@ use polkadot_primitives::{
 	BackedCandidate, BlockNumber, CandidateReceipt, CoreState, Hash, OccupiedCoreAssumption,
 	SignedAvailabilityBitfield, ValidatorIndex,
 };
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 mod disputes;
 mod error;
@@ -543,6 +543,9 @@ async fn select_candidates(
 
 	let mut selected_candidates =
 		Vec::with_capacity(candidates.len().min(availability_cores.len()));
+	// The runtime includes a single candidate per para, even if it is scheduled on several
+	// cores.
+	let mut selected_paras = HashSet::new();
 
 	gum::debug!(
 		target: LOG_TARGET,
@@ -576,6 +579,10 @@ async fn select_candidates(
 			CoreState::Free => continue,
 		};
 
+		if selected_paras.contains(&scheduled_core.para_id) {
+			continue
+		}
+
 		let validation_data = match request_persisted_validation_data(
 			relay_parent,
 			scheduled_core.para_id,
@@ -608,6 +615,7 @@ async fn select_candidates(
 				"Selected candidate receipt",
 			);
 
+			selected_paras.insert(scheduled_core.para_id);
 			selected_candidates.push(candidate_hash);
 		}
 	}
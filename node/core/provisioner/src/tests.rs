@@ -446,6 +446,54 @@ mod select_candidates {
 		)
 	}
 
+	#[test]
+	fn selects_a_single_candidate_per_para() {
+		// Para 1 is scheduled on two cores, but only one of its candidates can be included.
+		let mock_cores = vec![
+			CoreState::Scheduled(scheduled_core(1)),
+			CoreState::Scheduled(scheduled_core(1)),
+			CoreState::Scheduled(scheduled_core(2)),
+		];
+
+		let empty_hash = PersistedValidationData::<Hash, BlockNumber>::default().hash();
+		let candidates: Vec<_> = [(1u32, 0xa), (1, 0xb), (2, 0xc)]
+			.iter()
+			.map(|&(para_id, head)| {
+				let mut descriptor = dummy_candidate_descriptor(dummy_hash());
+				descriptor.para_id = para_id.into();
+				descriptor.para_head = Hash::repeat_byte(head);
+				descriptor.persisted_validation_data_hash = empty_hash;
+				CandidateReceipt {
+					descriptor,
+					commitments_hash: CandidateCommitments::default().hash(),
+				}
+			})
+			.collect();
+
+		test_harness(
+			|r| mock_overseer(r, Vec::new()),
+			|mut tx: TestSubsystemSender| async move {
+				let selected = select_candidate_hashes_from_tracked(
+					&mock_cores,
+					&[],
+					&candidates,
+					Default::default(),
+					&mut tx,
+				)
+				.await
+				.unwrap();
+
+				assert_eq!(
+					selected,
+					vec![
+						(candidates[0].hash(), candidates[0].descriptor.relay_parent),
+						(candidates[2].hash(), candidates[2].descriptor.relay_parent),
+					],
+				);
+			},
+		)
+	}
+
 	#[test]
 	fn selects_max_one_code_upgrade() {
 		let mock_cores = mock_availability_cores();
@@ -18,8 +18,11 @@
 //!
 //!	# Lifecycle of an artifact
 //!
-//! 1. During node start-up, the artifacts cache is cleaned up. This means that all local artifacts
-//!    stored on-disk are cleared, and we start with an empty [`Artifacts`] table.
+//! 1. During node start-up, the artifacts cache is scanned. Artifacts that were produced by the
+//!    same node version and whose contents match their stored checksum are put into the
+//!    [`Artifacts`] table as [`ArtifactState::Prepared`]. Everything else found in the cache
+//!    directory (artifacts of other node versions, corrupted artifacts, leftover temporary files)
+//!    is removed.
 //!
 //! 2. In order to be executed, a PVF should be prepared first. This means that artifacts should
 //!    have an [`ArtifactState::Prepared`] entry for that artifact in the table. If not, the
@@ -35,8 +38,9 @@
 //!
 //! 3. The pool gets an available worker and instructs it to work on the given PVF. The worker
 //!    starts compilation. When the worker finishes successfully, it writes the serialized artifact
-//!    into a temporary file and notifies the host that it's done. The host atomically moves
-//!    (renames) the temporary file to the destination filename of the artifact.
+//!    into a temporary file and notifies the host that it's done. The host writes the checksum of
+//!    the artifact next to its destination and then atomically moves (renames) the temporary file
+//!    to the destination filename of the artifact.
 //!
 //! 4. If the worker concluded successfully or returned an error, then the pool notifies the queue.
 //!    In both cases, the queue reports to the host that the result is ready.
@@ -53,18 +57,39 @@
 //!
 //! 7. There is a separate process for pruning the prepared artifacts whose `last_time_needed` is
 //!    older by a predefined parameter. This process is run very rarely (say, once a day). Once the
-//!    artifact is expired it is removed from disk eagerly atomically.
+//!    artifact is expired it is removed from disk eagerly atomically, together with its checksum.
 
-use crate::{error::PrepareError, host::PrepareResultSender, prepare::PrepareStats};
+use crate::{error::PrepareError, host::PrepareResultSender, prepare::PrepareStats, LOG_TARGET};
 use always_assert::always;
 use polkadot_parachain::primitives::ValidationCodeHash;
 use polkadot_primitives::ExecutorParamsHash;
 use std::{
-	collections::HashMap,
+	collections::{HashMap, HashSet},
+	ffi::OsString,
 	path::{Path, PathBuf},
 	time::{Duration, SystemTime},
 };
 
+/// The version of the node that produced the artifacts. Artifacts compiled by a different node
+/// version are never reused.
+const NODE_VERSION: &str = env!("SUBSTRATE_CLI_IMPL_VERSION");
+
+/// The extension of the file that holds the checksum of an artifact.
+const CHECKSUM_EXTENSION: &str = "checksum";
+
+/// Computes the checksum of the given artifact bytes.
+pub(crate) fn artifact_checksum(bytes: &[u8]) -> [u8; 32] {
+	sp_core::hashing::blake2_256(bytes)
+}
+
+/// Returns the path of the file holding the checksum of the artifact at the given path.
+pub(crate) fn checksum_path(artifact_path: &Path) -> PathBuf {
+	let mut path = OsString::from(artifact_path.as_os_str());
+	path.push(".");
+	path.push(CHECKSUM_EXTENSION);
+	path.into()
+}
+
 /// Contains the bytes for a successfully compiled artifact.
 pub struct CompiledArtifact(Vec<u8>);
 
@@ -97,12 +122,14 @@ impl ArtifactId {
 	}
 
 	/// Tries to recover the artifact id from the given file name.
-	#[cfg(test)]
+	///
+	/// Only the file names of artifacts produced by the current node version are recognized.
 	pub fn from_file_name(file_name: &str) -> Option<Self> {
 		use polkadot_core_primitives::Hash;
 		use std::str::FromStr as _;
 
 		let file_name = file_name.strip_prefix(Self::PREFIX)?;
+		let file_name = file_name.strip_prefix(NODE_VERSION)?.strip_prefix('_')?;
 		let (code_hash_str, executor_params_hash_str) = file_name.split_once('_')?;
 		let code_hash = Hash::from_str(code_hash_str).ok()?.into();
 		let executor_params_hash =
@@ -113,8 +140,13 @@ impl ArtifactId {
 
 	/// Returns the expected path to this artifact given the root of the cache.
 	pub fn path(&self, cache_path: &Path) -> PathBuf {
-		let file_name =
-			format!("{}{:#x}_{:#x}", Self::PREFIX, self.code_hash, self.executor_params_hash);
+		let file_name = format!(
+			"{}{}_{:#x}_{:#x}",
+			Self::PREFIX,
+			NODE_VERSION,
+			self.code_hash,
+			self.executor_params_hash
+		);
 		cache_path.join(file_name)
 	}
 }
@@ -177,17 +209,22 @@ pub struct Artifacts {
 }
 
 impl Artifacts {
-	/// Initialize a blank cache at the given path. This will clear everything present at the
-	/// given path, to be populated over time.
+	/// Initialize the cache at the given path, reusing the artifacts that survived a restart.
 	///
 	/// The recognized artifacts will be filled in the table and unrecognized will be removed.
 	pub async fn new(cache_path: &Path) -> Self {
 		// Make sure that the cache path directory and all its parents are created.
-		// First delete the entire cache. Nodes are long-running so this should populate shortly.
-		let _ = tokio::fs::remove_dir_all(cache_path).await;
 		let _ = tokio::fs::create_dir_all(cache_path).await;
 
-		Self { artifacts: HashMap::new() }
+		let artifacts = scan_cache(cache_path).await;
+		gum::info!(
+			target: LOG_TARGET,
+			"PVF cache: reusing {} prepared artifacts from {}",
+			artifacts.len(),
+			cache_path.display(),
+		);
+
+		Self { artifacts }
 	}
 
 	#[cfg(test)]
@@ -259,9 +296,119 @@ impl Artifacts {
 	}
 }
 
+/// Scans the cache directory, returning the valid artifacts found there as prepared. Anything
+/// else in the directory is removed.
+async fn scan_cache(cache_path: &Path) -> HashMap<ArtifactId, ArtifactState> {
+	let mut artifacts = HashMap::new();
+
+	let mut entries = match tokio::fs::read_dir(cache_path).await {
+		Ok(entries) => entries,
+		Err(err) => {
+			gum::warn!(
+				target: LOG_TARGET,
+				"failed to read the PVF cache at {}: {:?}",
+				cache_path.display(),
+				err,
+			);
+			return artifacts
+		},
+	};
+
+	let mut files = Vec::new();
+	loop {
+		match entries.next_entry().await {
+			Ok(Some(entry)) => {
+				let path = entry.path();
+				match entry.file_type().await {
+					Ok(file_type) if file_type.is_file() => files.push(path),
+					_ => {
+						let _ = tokio::fs::remove_dir_all(&path).await;
+					},
+				}
+			},
+			Ok(None) => break,
+			Err(err) => {
+				gum::warn!(
+					target: LOG_TARGET,
+					"failed to read an entry of the PVF cache at {}: {:?}",
+					cache_path.display(),
+					err,
+				);
+				break
+			},
+		}
+	}
+
+	let (checksums, candidates): (Vec<_>, Vec<_>) = files
+		.into_iter()
+		.partition(|path| path.extension().map_or(false, |ext| ext == CHECKSUM_EXTENSION));
+
+	let now = SystemTime::now();
+	let mut kept_checksums = HashSet::new();
+	for path in candidates {
+		let artifact_id = path
+			.file_name()
+			.and_then(|name| name.to_str())
+			.and_then(ArtifactId::from_file_name);
+		let artifact_id = match artifact_id {
+			Some(artifact_id) => artifact_id,
+			None => {
+				gum::debug!(
+					target: LOG_TARGET,
+					"removing unrecognized file {} from the PVF cache",
+					path.display(),
+				);
+				let _ = tokio::fs::remove_file(&path).await;
+				continue
+			},
+		};
+
+		let checksum_path = checksum_path(&path);
+		if !is_artifact_intact(&path, &checksum_path).await {
+			gum::debug!(
+				target: LOG_TARGET,
+				"removing artifact {} with a missing or mismatching checksum",
+				path.display(),
+			);
+			let _ = tokio::fs::remove_file(&path).await;
+			continue
+		}
+
+		kept_checksums.insert(checksum_path);
+		artifacts.insert(
+			artifact_id,
+			ArtifactState::Prepared {
+				last_time_needed: now,
+				prepare_stats: PrepareStats::default(),
+			},
+		);
+	}
+
+	for path in checksums {
+		if !kept_checksums.contains(&path) {
+			let _ = tokio::fs::remove_file(&path).await;
+		}
+	}
+
+	artifacts
+}
+
+/// Checks that the artifact at the given path matches the checksum stored at `checksum_path`.
+async fn is_artifact_intact(artifact_path: &Path, checksum_path: &Path) -> bool {
+	let (artifact, checksum) =
+		match (tokio::fs::read(artifact_path).await, tokio::fs::read(checksum_path).await) {
+			(Ok(artifact), Ok(checksum)) => (artifact, checksum),
+			_ => return false,
+		};
+
+	checksum == artifact_checksum(&artifact)
+}
+
 #[cfg(test)]
 mod tests {
-	use super::{ArtifactId, Artifacts};
+	use super::{
+		artifact_checksum, checksum_path, ArtifactId, ArtifactState, Artifacts, NODE_VERSION,
+	};
 	use polkadot_primitives::ExecutorParamsHash;
 	use sp_core::H256;
 	use std::{path::Path, str::FromStr};
@@ -271,10 +418,17 @@ mod tests {
 		assert!(ArtifactId::from_file_name("").is_none());
 		assert!(ArtifactId::from_file_name("junk").is_none());
 
+		// Artifacts of other node versions are not recognized.
+		assert!(ArtifactId::from_file_name(
+			"wasmtime_0x0022800000000000000000000000000000000000000000000000000000000000_0x0033900000000000000000000000000000000000000000000000000000000000"
+		)
+		.is_none());
+
 		assert_eq!(
-			ArtifactId::from_file_name(
-				"wasmtime_0x0022800000000000000000000000000000000000000000000000000000000000_0x0033900000000000000000000000000000000000000000000000000000000000"
-			),
+			ArtifactId::from_file_name(&format!(
+				"wasmtime_{}_0x0022800000000000000000000000000000000000000000000000000000000000_0x0033900000000000000000000000000000000000000000000000000000000000",
+				NODE_VERSION,
+			)),
 			Some(ArtifactId::new(
 				hex_literal::hex![
 					"0022800000000000000000000000000000000000000000000000000000000000"
@@ -295,33 +449,66 @@ mod tests {
 				.unwrap();
 
 		assert_eq!(
-			ArtifactId::new(hash.into(), ExecutorParamsHash::from_hash(hash)).path(path).to_str(),
-			Some(
-				"/test/wasmtime_0x1234567890123456789012345678901234567890123456789012345678901234_0x1234567890123456789012345678901234567890123456789012345678901234"
-			),
+			ArtifactId::new(hash.into(), ExecutorParamsHash::from_hash(hash))
+				.path(path)
+				.to_str()
+				.map(ToOwned::to_owned),
+			Some(format!(
+				"/test/wasmtime_{}_0x1234567890123456789012345678901234567890123456789012345678901234_0x1234567890123456789012345678901234567890123456789012345678901234",
+				NODE_VERSION,
+			)),
 		);
 	}
 
 	#[tokio::test]
-	async fn artifacts_removes_cache_on_startup() {
+	async fn artifacts_keep_valid_cache_on_startup() {
 		let fake_cache_path = crate::worker_common::tmpfile("test-cache").await.unwrap();
-		let fake_artifact_path = {
-			let mut p = fake_cache_path.clone();
-			p.push("wasmtime_0x1234567890123456789012345678901234567890123456789012345678901234");
-			p
-		};
-
-		// create a tmp cache with 1 artifact.
-
 		std::fs::create_dir_all(&fake_cache_path).unwrap();
-		std::fs::File::create(fake_artifact_path).unwrap();
-
-		// this should remove it and re-create.
 
-		let p = &fake_cache_path;
-		Artifacts::new(p).await;
+		let artifact_id = |byte: u8| {
+			let hash = H256::repeat_byte(byte);
+			ArtifactId::new(hash.into(), ExecutorParamsHash::from_hash(hash))
+		};
 
-		assert_eq!(std::fs::read_dir(&fake_cache_path).unwrap().count(), 0);
+		// A valid artifact with a matching checksum.
+		let valid_id = artifact_id(1);
+		let valid_path = valid_id.path(&fake_cache_path);
+		std::fs::write(&valid_path, b"valid").unwrap();
+		std::fs::write(checksum_path(&valid_path), artifact_checksum(b"valid")).unwrap();
+
+		// An artifact whose contents do not match its checksum.
+		let corrupted_path = artifact_id(2).path(&fake_cache_path);
+		std::fs::write(&corrupted_path, b"corrupted").unwrap();
+		std::fs::write(checksum_path(&corrupted_path), artifact_checksum(b"valid")).unwrap();
+
+		// An artifact without a checksum.
+		let unchecked_path = artifact_id(3).path(&fake_cache_path);
+		std::fs::write(&unchecked_path, b"unchecked").unwrap();
+
+		// An artifact of another node version and a leftover temporary file.
+		std::fs::write(
+			fake_cache_path.join(
+				"wasmtime_0x1234567890123456789012345678901234567890123456789012345678901234",
+			),
+			b"old",
+		)
+		.unwrap();
+		std::fs::write(fake_cache_path.join("prepare-artifact-junk"), b"junk").unwrap();
+
+		let mut artifacts = Artifacts::new(&fake_cache_path).await;
+
+		assert_eq!(artifacts.artifacts.len(), 1);
+		assert!(matches!(
+			artifacts.artifact_state_mut(&valid_id),
+			Some(ArtifactState::Prepared { .. })
+		));
+
+		let mut remaining = std::fs::read_dir(&fake_cache_path)
+			.unwrap()
+			.map(|entry| entry.unwrap().path())
+			.collect::<Vec<_>>();
+		remaining.sort();
+		assert_eq!(remaining, vec![valid_path.clone(), checksum_path(&valid_path)]);
 
 		std::fs::remove_dir_all(fake_cache_path).unwrap();
 	}
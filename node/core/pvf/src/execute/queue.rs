@@ -21,6 +21,8 @@ use crate::{
 	artifacts::{ArtifactId, ArtifactPathId},
 	host::ResultSender,
 	metrics::Metrics,
+	priority::Priority,
+	security::SandboxConfig,
 	worker_common::{IdleWorker, WorkerHandle},
 	InvalidCandidate, ValidationError, LOG_TARGET,
 };
@@ -57,6 +59,7 @@ pub enum ToQueue {
 /// to the given result sender.
 #[derive(Debug)]
 pub struct PendingExecutionRequest {
+	pub priority: Priority,
 	pub exec_timeout: Duration,
 	pub params: Vec<u8>,
 	pub executor_params: ExecutorParams,
@@ -64,6 +67,7 @@ pub struct PendingExecutionRequest {
 }
 
 struct ExecuteJob {
+	priority: Priority,
 	artifact: ArtifactPathId,
 	exec_timeout: Duration,
 	params: Vec<u8>,
@@ -93,6 +97,14 @@ struct Workers {
 
 	/// The maximum number of workers queue can have at once.
 	capacity: usize,
+
+	/// The maximum number of workers that can be busy with jobs of a priority below critical at
+	/// once. The remaining workers are reserved for critical jobs.
+	normal_capacity: usize,
+
+	/// The number of workers (either running or being spawned) busy with jobs of a priority below
+	/// critical.
+	normal_busy: usize,
 }
 
 impl Workers {
@@ -100,6 +112,10 @@ impl Workers {
 		self.spawn_inflight + self.running.len() < self.capacity
 	}
 
+	fn has_room_for(&self, priority: Priority) -> bool {
+		priority.is_critical() || self.normal_busy < self.normal_capacity
+	}
+
 	fn find_available(&self, executor_params_hash: ExecutorParamsHash) -> Option<Worker> {
 		self.running.iter().find_map(|d| {
 			if d.1.idle.is_some() && d.1.executor_params_hash == executor_params_hash {
@@ -126,7 +142,7 @@ impl Workers {
 
 enum QueueEvent {
 	Spawn(IdleWorker, WorkerHandle, ExecuteJob),
-	StartWork(Worker, Outcome, ArtifactId, ResultSender),
+	StartWork(Worker, Outcome, ArtifactId, ResultSender, Priority),
 }
 
 type Mux = FuturesUnordered<BoxFuture<'static, QueueEvent>>;
@@ -139,6 +155,7 @@ struct Queue {
 
 	program_path: PathBuf,
 	spawn_timeout: Duration,
+	sandbox: Option<SandboxConfig>,
 
 	/// The queue of jobs that are waiting for a worker to pick up.
 	queue: VecDeque<ExecuteJob>,
@@ -151,13 +168,16 @@ impl Queue {
 		metrics: Metrics,
 		program_path: PathBuf,
 		worker_capacity: usize,
+		normal_worker_capacity: usize,
 		spawn_timeout: Duration,
+		sandbox: Option<SandboxConfig>,
 		to_queue_rx: mpsc::Receiver<ToQueue>,
 	) -> Self {
 		Self {
 			metrics,
 			program_path,
 			spawn_timeout,
+			sandbox,
 			to_queue_rx,
 			queue: VecDeque::new(),
 			mux: Mux::new(),
@@ -165,6 +185,8 @@ impl Queue {
 				running: HopSlotMap::with_capacity_and_key(10),
 				spawn_inflight: 0,
 				capacity: worker_capacity,
+				normal_capacity: normal_worker_capacity,
+				normal_busy: 0,
 			},
 		}
 	}
@@ -186,29 +208,48 @@ impl Queue {
 		}
 	}
 
-	/// Tries to assign a job in the queue to a worker. If an idle worker is provided, it does its
-	/// best to find a job with a compatible execution environment unless there are jobs in the
-	/// queue waiting too long. In that case, it kills an existing idle worker and spawns a new
-	/// one. It may spawn an additional worker if that is affordable.
+	/// Tries to assign a job in the queue to a worker. Critical jobs are always served first, and
+	/// jobs of a lower priority are only considered if there is room for them left in their lane.
+	///
+	/// If an idle worker is provided, it does its best to find a job with a compatible execution
+	/// environment unless there are jobs in the queue waiting too long. In that case, it kills an
+	/// existing idle worker and spawns a new one. It may spawn an additional worker if that is
+	/// affordable.
 	/// If all the workers are busy or the queue is empty, it does nothing.
 	/// Should be called every time a new job arrives to the queue or a job finishes.
 	fn try_assign_next_job(&mut self, finished_worker: Option<Worker>) {
-		// New jobs are always pushed to the tail of the queue; the one at its head is always
-		// the eldest one.
-		let eldest = if let Some(eldest) = self.queue.get(0) { eldest } else { return };
+		let priority = if self.queue.iter().any(|job| job.priority.is_critical()) {
+			Priority::Critical
+		} else {
+			Priority::Normal
+		};
+		if !self.workers.has_room_for(priority) {
+			return
+		}
+
+		// New jobs are always pushed to the tail of the queue; the first one of the given priority
+		// is always the eldest one.
+		let eldest_index =
+			if let Some(i) = self.queue.iter().position(|job| job.priority == priority) {
+				i
+			} else {
+				return
+			};
 
 		// By default, we're going to execute the eldest job on any worker slot available, even if
 		// we have to kill and re-spawn a worker
 		let mut worker = None;
-		let mut job_index = 0;
+		let mut job_index = eldest_index;
 
 		// But if we're not pressed for time, we can try to find a better job-worker pair not
 		// requiring the expensive kill-spawn operation
-		if eldest.waiting_since.elapsed() < MAX_KEEP_WAITING {
+		if self.queue[eldest_index].waiting_since.elapsed() < MAX_KEEP_WAITING {
 			if let Some(finished_worker) = finished_worker {
 				if let Some(worker_data) = self.workers.running.get(finished_worker) {
-					for (i, job) in self.queue.iter().enumerate() {
-						if worker_data.executor_params_hash == job.executor_params.hash() {
+					for (i, job) in self.queue.iter().enumerate().skip(eldest_index) {
+						if job.priority == priority &&
+							worker_data.executor_params_hash == job.executor_params.hash()
+						{
 							(worker, job_index) = (Some(finished_worker), i);
 							break
 						}
@@ -239,6 +280,12 @@ impl Queue {
 
 		let job = self.queue.remove(job_index).expect("Job is just checked to be in queue; qed");
 
+		if !job.priority.is_critical() {
+			self.workers.normal_busy += 1;
+		}
+		self.metrics
+			.observe_execution_queued_time(job.priority, job.waiting_since.elapsed());
+
 		if let Some(worker) = worker {
 			assign(self, worker, job);
 		} else {
@@ -264,15 +311,17 @@ async fn purge_dead(metrics: &Metrics, workers: &mut Workers) {
 
 fn handle_to_queue(queue: &mut Queue, to_queue: ToQueue) {
 	let ToQueue::Enqueue { artifact, pending_execution_request } = to_queue;
-	let PendingExecutionRequest { exec_timeout, params, executor_params, result_tx } =
+	let PendingExecutionRequest { priority, exec_timeout, params, executor_params, result_tx } =
 		pending_execution_request;
 	gum::debug!(
 		target: LOG_TARGET,
 		validation_code_hash = ?artifact.id.code_hash,
+		?priority,
 		"enqueueing an artifact for execution",
 	);
 	queue.metrics.execute_enqueued();
 	let job = ExecuteJob {
+		priority,
 		artifact,
 		exec_timeout,
 		params,
@@ -289,8 +338,8 @@ async fn handle_mux(queue: &mut Queue, event: QueueEvent) {
 		QueueEvent::Spawn(idle, handle, job) => {
 			handle_worker_spawned(queue, idle, handle, job);
 		},
-		QueueEvent::StartWork(worker, outcome, artifact_id, result_tx) => {
-			handle_job_finish(queue, worker, outcome, artifact_id, result_tx);
+		QueueEvent::StartWork(worker, outcome, artifact_id, result_tx, priority) => {
+			handle_job_finish(queue, worker, outcome, artifact_id, result_tx, priority);
 		},
 	}
 }
@@ -322,11 +371,20 @@ fn handle_job_finish(
 	outcome: Outcome,
 	artifact_id: ArtifactId,
 	result_tx: ResultSender,
+	priority: Priority,
 ) {
+	if !priority.is_critical() {
+		queue.workers.normal_busy -= 1;
+	}
+
 	let (idle_worker, result, duration) = match outcome {
-		Outcome::Ok { result_descriptor, duration, idle_worker } => {
+		Outcome::Ok { result_descriptor, duration, wall_clock_duration, idle_worker } => {
 			// TODO: propagate the soft timeout
 
+			queue
+				.metrics
+				.observe_execution_wall_clock_overhead(duration, wall_clock_duration);
+
 			(Some(idle_worker), Ok(result_descriptor), Some(duration))
 		},
 		Outcome::InvalidCandidate { err, idle_worker } => (
@@ -396,9 +454,15 @@ fn spawn_extra_worker(queue: &mut Queue, job: ExecuteJob) {
 	queue.metrics.execute_worker().on_begin_spawn();
 	gum::debug!(target: LOG_TARGET, "spawning an extra worker");
 
-	queue
-		.mux
-		.push(spawn_worker_task(queue.program_path.clone(), job, queue.spawn_timeout).boxed());
+	queue.mux.push(
+		spawn_worker_task(
+			queue.program_path.clone(),
+			job,
+			queue.spawn_timeout,
+			queue.sandbox.clone(),
+		)
+		.boxed(),
+	);
 	queue.workers.spawn_inflight += 1;
 }
 
@@ -412,12 +476,18 @@ async fn spawn_worker_task(
 	program_path: PathBuf,
 	job: ExecuteJob,
 	spawn_timeout: Duration,
+	sandbox: Option<SandboxConfig>,
 ) -> QueueEvent {
 	use futures_timer::Delay;
 
 	loop {
-		match super::worker_intf::spawn(&program_path, job.executor_params.clone(), spawn_timeout)
-			.await
+		match super::worker_intf::spawn(
+			&program_path,
+			job.executor_params.clone(),
+			spawn_timeout,
+			sandbox.clone(),
+		)
+		.await
 		{
 			Ok((idle, handle)) => break QueueEvent::Spawn(idle, handle, job),
 			Err(err) => {
@@ -468,7 +538,7 @@ fn assign(queue: &mut Queue, worker: Worker, job: ExecuteJob) {
 				job.params,
 			)
 			.await;
-			QueueEvent::StartWork(worker, outcome, job.artifact.id, job.result_tx)
+			QueueEvent::StartWork(worker, outcome, job.artifact.id, job.result_tx, job.priority)
 		}
 		.boxed(),
 	);
@@ -478,9 +548,20 @@ pub fn start(
 	metrics: Metrics,
 	program_path: PathBuf,
 	worker_capacity: usize,
+	normal_worker_capacity: usize,
 	spawn_timeout: Duration,
+	sandbox: Option<SandboxConfig>,
 ) -> (mpsc::Sender<ToQueue>, impl Future<Output = ()>) {
 	let (to_queue_tx, to_queue_rx) = mpsc::channel(20);
-	let run = Queue::new(metrics, program_path, worker_capacity, spawn_timeout, to_queue_rx).run();
+	let run = Queue::new(
+		metrics,
+		program_path,
+		worker_capacity,
+		normal_worker_capacity,
+		spawn_timeout,
+		sandbox,
+		to_queue_rx,
+	)
+	.run();
 	(to_queue_tx, run)
 }
@@ -18,6 +18,7 @@
 
 use crate::{
 	artifacts::ArtifactPathId,
+	security::{recv_sandbox_status, SandboxConfig, SandboxStatus},
 	worker_common::{
 		framed_recv, framed_send, path_to_bytes, spawn_with_program_path, IdleWorker, SpawnErr,
 		WorkerHandle, JOB_TIMEOUT_WALL_CLOCK_FACTOR,
@@ -34,13 +35,15 @@ use std::{path::Path, time::Duration};
 use tokio::{io, net::UnixStream};
 
 /// Spawns a new worker with the given program path that acts as the worker and the spawn timeout.
-/// Sends a handshake message to the worker as soon as it is spawned.
+/// Sends a handshake message to the worker as soon as it is spawned and waits for the worker to
+/// report its sandbox status.
 ///
 /// The program should be able to handle `<program-path> execute-worker <socket-path>` invocation.
 pub async fn spawn(
 	program_path: &Path,
 	executor_params: ExecutorParams,
 	spawn_timeout: Duration,
+	sandbox: Option<SandboxConfig>,
 ) -> Result<(IdleWorker, WorkerHandle), SpawnErr> {
 	let (mut idle_worker, worker_handle) = spawn_with_program_path(
 		"execute",
@@ -49,17 +52,21 @@ pub async fn spawn(
 		spawn_timeout,
 	)
 	.await?;
-	send_handshake(&mut idle_worker.stream, Handshake { executor_params })
-		.await
-		.map_err(|error| {
-			gum::warn!(
-				target: LOG_TARGET,
-				worker_pid = %idle_worker.pid,
-				?error,
-				"failed to send a handshake to the spawned worker",
-			);
-			SpawnErr::Handshake
-		})?;
+
+	let expected = SandboxStatus { landlock: sandbox.is_some(), seccomp: sandbox.is_some() };
+	let handshake_result = async {
+		send_handshake(&mut idle_worker.stream, Handshake { executor_params, sandbox }).await?;
+		recv_sandbox_status(&mut idle_worker.stream, "execute", idle_worker.pid, expected).await
+	};
+	handshake_result.await.map_err(|error| {
+		gum::warn!(
+			target: LOG_TARGET,
+			worker_pid = %idle_worker.pid,
+			?error,
+			"failed to handshake with the spawned worker",
+		);
+		SpawnErr::Handshake
+	})?;
 	Ok((idle_worker, worker_handle))
 }
 
@@ -67,7 +74,12 @@ pub async fn spawn(
 pub enum Outcome {
 	/// PVF execution completed successfully and the result is returned. The worker is ready for
 	/// another job.
-	Ok { result_descriptor: ValidationResult, duration: Duration, idle_worker: IdleWorker },
+	Ok {
+		result_descriptor: ValidationResult,
+		duration: Duration,
+		wall_clock_duration: Duration,
+		idle_worker: IdleWorker,
+	},
 	/// The candidate validation failed. It may be for example because the wasm execution triggered a trap.
 	/// Errors related to the preparation process are not expected to be encountered by the execution workers.
 	InvalidCandidate { err: String, idle_worker: IdleWorker },
@@ -166,8 +178,12 @@ pub async fn start_work(
 	};
 
 	match response {
-		Response::Ok { result_descriptor, duration } =>
-			Outcome::Ok { result_descriptor, duration, idle_worker: IdleWorker { stream, pid } },
+		Response::Ok { result_descriptor, duration, wall_clock_duration } => Outcome::Ok {
+			result_descriptor,
+			duration,
+			wall_clock_duration,
+			idle_worker: IdleWorker { stream, pid },
+		},
 		Response::InvalidCandidate(err) =>
 			Outcome::InvalidCandidate { err, idle_worker: IdleWorker { stream, pid } },
 		Response::TimedOut => Outcome::HardTimeout,
@@ -207,6 +223,8 @@ async fn recv_response(stream: &mut UnixStream) -> io::Result<Response> {
 pub struct Handshake {
 	/// The executor parameters.
 	pub executor_params: ExecutorParams,
+	/// The sandbox the worker should enter, if any.
+	pub sandbox: Option<SandboxConfig>,
 }
 
 /// The response from an execution job on the worker.
@@ -218,6 +236,9 @@ pub enum Response {
 		result_descriptor: ValidationResult,
 		/// The amount of CPU time taken by the job.
 		duration: Duration,
+		/// The amount of wall clock time taken by the job. The difference to the CPU time shows how
+		/// much the job was slowed down by the load on the machine.
+		wall_clock_duration: Duration,
 	},
 	/// The candidate is invalid.
 	InvalidCandidate(String),
@@ -21,11 +21,13 @@
 //! [`ValidationHost`], that allows communication with that event-loop.
 
 use crate::{
-	artifacts::{ArtifactId, ArtifactPathId, ArtifactState, Artifacts},
+	artifacts::{checksum_path, ArtifactId, ArtifactPathId, ArtifactState, Artifacts},
 	error::PrepareError,
 	execute::{self, PendingExecutionRequest},
 	metrics::Metrics,
-	prepare, PrepareResult, Priority, PvfPrepData, ValidationError, LOG_TARGET,
+	prepare,
+	security::SandboxConfig,
+	PrepareResult, Priority, PvfPrepData, ValidationError, LOG_TARGET,
 };
 use always_assert::never;
 use futures::{
@@ -153,8 +155,13 @@ pub struct Config {
 	pub execute_worker_program_path: PathBuf,
 	/// The time allotted for an execute worker to spawn and report to the host.
 	pub execute_worker_spawn_timeout: Duration,
-	/// The maximum number of execute workers that can run at the same time.
-	pub execute_workers_max_num: usize,
+	/// The maximum number of execute workers that can be busy with tasks with the priority below
+	/// critical at the same time. The rest of the workers are reserved for critical tasks.
+	pub execute_workers_soft_max_num: usize,
+	/// The absolute number of execute workers that can run at the same time.
+	pub execute_workers_hard_max_num: usize,
+	/// Whether the workers should restrict themselves with a sandbox. Only supported on Linux.
+	pub sandbox: bool,
 }
 
 impl Config {
@@ -172,7 +179,9 @@ impl Config {
 			prepare_workers_hard_max_num: 1,
 			execute_worker_program_path: program_path,
 			execute_worker_spawn_timeout: Duration::from_secs(3),
-			execute_workers_max_num: 2,
+			execute_workers_soft_max_num: 2,
+			execute_workers_hard_max_num: 3,
+			sandbox: false,
 		}
 	}
 }
@@ -189,12 +198,14 @@ pub fn start(config: Config, metrics: Metrics) -> (ValidationHost, impl Future<O
 	let (to_host_tx, to_host_rx) = mpsc::channel(10);
 
 	let validation_host = ValidationHost { to_host_tx };
+	let sandbox = config.sandbox.then(|| SandboxConfig::new(&config.cache_path));
 
 	let (to_prepare_pool, from_prepare_pool, run_prepare_pool) = prepare::start_pool(
 		metrics.clone(),
 		config.prepare_worker_program_path.clone(),
 		config.cache_path.clone(),
 		config.prepare_worker_spawn_timeout,
+		sandbox.clone(),
 	);
 
 	let (to_prepare_queue_tx, from_prepare_queue_rx, run_prepare_queue) = prepare::start_queue(
@@ -209,8 +220,10 @@ pub fn start(config: Config, metrics: Metrics) -> (ValidationHost, impl Future<O
 	let (to_execute_queue_tx, run_execute_queue) = execute::start(
 		metrics,
 		config.execute_worker_program_path.to_owned(),
-		config.execute_workers_max_num,
+		config.execute_workers_hard_max_num,
+		config.execute_workers_soft_max_num,
 		config.execute_worker_spawn_timeout,
+		sandbox,
 	);
 
 	let (to_sweeper_tx, to_sweeper_rx) = mpsc::channel(100);
@@ -484,6 +497,7 @@ async fn handle_execute_pvf(
 						execute::ToQueue::Enqueue {
 							artifact: ArtifactPathId::new(artifact_id, cache_path),
 							pending_execution_request: PendingExecutionRequest {
+								priority,
 								exec_timeout,
 								params,
 								executor_params,
@@ -512,6 +526,7 @@ async fn handle_execute_pvf(
 						priority,
 						artifact_id,
 						PendingExecutionRequest {
+							priority,
 							exec_timeout,
 							params,
 							executor_params,
@@ -524,7 +539,13 @@ async fn handle_execute_pvf(
 			ArtifactState::Preparing { .. } => {
 				awaiting_prepare.add(
 					artifact_id,
-					PendingExecutionRequest { exec_timeout, params, executor_params, result_tx },
+					PendingExecutionRequest {
+						priority,
+						exec_timeout,
+						params,
+						executor_params,
+						result_tx,
+					},
 				);
 			},
 			ArtifactState::FailedToProcess { last_time_failed, num_failures, error } => {
@@ -552,6 +573,7 @@ async fn handle_execute_pvf(
 						priority,
 						artifact_id,
 						PendingExecutionRequest {
+							priority,
 							exec_timeout,
 							params,
 							executor_params,
@@ -574,7 +596,7 @@ async fn handle_execute_pvf(
 			pvf,
 			priority,
 			artifact_id,
-			PendingExecutionRequest { exec_timeout, params, executor_params, result_tx },
+			PendingExecutionRequest { priority, exec_timeout, params, executor_params, result_tx },
 		)
 		.await?;
 	}
@@ -696,7 +718,7 @@ async fn handle_prepare_done(
 	// It's finally time to dispatch all the execution requests that were waiting for this artifact
 	// to be prepared.
 	let pending_requests = awaiting_prepare.take(&artifact_id);
-	for PendingExecutionRequest { exec_timeout, params, executor_params, result_tx } in
+	for PendingExecutionRequest { priority, exec_timeout, params, executor_params, result_tx } in
 		pending_requests
 	{
 		if result_tx.is_canceled() {
@@ -716,6 +738,7 @@ async fn handle_prepare_done(
 			execute::ToQueue::Enqueue {
 				artifact: ArtifactPathId::new(artifact_id.clone(), cache_path),
 				pending_execution_request: PendingExecutionRequest {
+					priority,
 					exec_timeout,
 					params,
 					executor_params,
@@ -799,6 +822,7 @@ async fn handle_cleanup_pulse(
 			"pruning artifact",
 		);
 		let artifact_path = artifact_id.path(cache_path);
+		sweeper_tx.send(checksum_path(&artifact_path)).await.map_err(|_| Fatal)?;
 		sweeper_tx.send(artifact_path).await.map_err(|_| Fatal)?;
 	}
 
@@ -1092,6 +1116,7 @@ pub(crate) mod tests {
 		run_until(
 			&mut test.run,
 			async {
+				assert_eq!(to_sweeper_rx.next().await.unwrap(), checksum_path(&artifact_path(2)));
 				assert_eq!(to_sweeper_rx.next().await.unwrap(), artifact_path(2));
 			}
 			.boxed(),
@@ -55,6 +55,12 @@
 //!
 //! Priority can never go down, only up.
 //!
+//! Execution requests with the critical priority are always picked up from the execution queue
+//! before the others. On top of that, the execution workers are split into lanes: requests with the
+//! priority below critical can only occupy a limited number of workers, and the rest of the workers
+//! are reserved for critical requests. This way a flood of non-critical requests cannot starve the
+//! critical ones.
+//!
 //! # Under the hood
 //!
 //! ## The flow
@@ -96,6 +102,7 @@ mod metrics;
 mod prepare;
 mod priority;
 mod pvf;
+mod security;
 mod worker_common;
 
 pub use artifacts::CompiledArtifact;
@@ -103,9 +110,10 @@ pub use error::{InvalidCandidate, PrepareError, PrepareResult, ValidationError};
 pub use execute::{ExecuteHandshake, ExecuteResponse};
 #[cfg(any(target_os = "linux", feature = "jemalloc-allocator"))]
 pub use prepare::MemoryAllocationStats;
-pub use prepare::{MemoryStats, PrepareStats};
+pub use prepare::{MemoryStats, PrepareHandshake, PrepareStats};
 pub use priority::Priority;
 pub use pvf::PvfPrepData;
+pub use security::{send_sandbox_status, SandboxConfig, SandboxStatus};
 
 pub use host::{start, Config, ValidationHost};
 pub use metrics::Metrics;
@@ -16,8 +16,9 @@
 
 //! Prometheus metrics related to the validation host.
 
-use crate::prepare::MemoryStats;
+use crate::{prepare::MemoryStats, Priority};
 use polkadot_node_metrics::metrics::{self, prometheus};
+use std::time::Duration;
 
 /// Validation host metrics.
 #[derive(Default, Clone)]
@@ -74,6 +75,30 @@ impl Metrics {
 		self.0.as_ref().map(|metrics| metrics.execution_time.start_timer())
 	}
 
+	/// Observe the time an execution job of the given priority spent waiting in the queue.
+	pub(crate) fn observe_execution_queued_time(&self, priority: Priority, elapsed: Duration) {
+		if let Some(metrics) = &self.0 {
+			let lane = if priority.is_critical() { "critical" } else { "normal" };
+			metrics
+				.execution_queued_time
+				.with_label_values(&[lane])
+				.observe(elapsed.as_secs_f64());
+		}
+	}
+
+	/// Observe by how much the wall clock time of an execution job exceeded its CPU time.
+	pub(crate) fn observe_execution_wall_clock_overhead(
+		&self,
+		cpu_time: Duration,
+		wall_clock_time: Duration,
+	) {
+		if let Some(metrics) = &self.0 {
+			metrics
+				.execution_wall_clock_overhead
+				.observe(wall_clock_time.saturating_sub(cpu_time).as_secs_f64());
+		}
+	}
+
 	/// Observe memory stats for preparation.
 	#[allow(unused_variables)]
 	pub(crate) fn observe_preparation_memory_metrics(&self, memory_stats: MemoryStats) {
@@ -107,6 +132,8 @@ struct MetricsInner {
 	execute_finished: prometheus::Counter<prometheus::U64>,
 	preparation_time: prometheus::Histogram,
 	execution_time: prometheus::Histogram,
+	execution_queued_time: prometheus::HistogramVec,
+	execution_wall_clock_overhead: prometheus::Histogram,
 	#[cfg(target_os = "linux")]
 	preparation_max_rss: prometheus::Histogram,
 	#[cfg(any(target_os = "linux", feature = "jemalloc-allocator"))]
@@ -231,6 +258,51 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			execution_queued_time: prometheus::register(
+				prometheus::HistogramVec::new(
+					prometheus::HistogramOpts::new(
+						"polkadot_pvf_execution_queued_time",
+						"Time spent by execution jobs waiting in the queue, per priority lane",
+					).buckets(vec![
+						0.01,
+						0.025,
+						0.05,
+						0.1,
+						0.25,
+						0.5,
+						1.0,
+						2.0,
+						4.0,
+						8.0,
+						16.0,
+						32.0,
+					]),
+					&["priority"],
+				)?,
+				registry,
+			)?,
+			execution_wall_clock_overhead: prometheus::register(
+				prometheus::Histogram::with_opts(
+					prometheus::HistogramOpts::new(
+						"polkadot_pvf_execution_wall_clock_overhead",
+						"Difference between the wall clock time and the CPU time of PVF executions in seconds",
+					).buckets(vec![
+						0.001,
+						0.005,
+						0.01,
+						0.025,
+						0.05,
+						0.1,
+						0.25,
+						0.5,
+						1.0,
+						2.0,
+						4.0,
+						8.0,
+					]),
+				)?,
+				registry,
+			)?,
 			#[cfg(target_os = "linux")]
 			preparation_max_rss: prometheus::register(
 				prometheus::Histogram::with_opts(
@@ -316,3 +388,30 @@ impl<'a> WorkerRelatedMetrics<'a> {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use polkadot_node_metrics::metrics::Metrics as _;
+
+	#[test]
+	fn execution_wall_clock_overhead_is_observed() {
+		let registry = prometheus::Registry::new();
+		let metrics = Metrics::try_register(&registry).unwrap();
+
+		metrics.observe_execution_wall_clock_overhead(
+			Duration::from_millis(200),
+			Duration::from_millis(450),
+		);
+		// The wall clock time can't be lower than the CPU time of a single-threaded job, but the
+		// measurements might still be off by a bit.
+		metrics.observe_execution_wall_clock_overhead(
+			Duration::from_millis(200),
+			Duration::from_millis(199),
+		);
+
+		let overhead = &metrics.0.as_ref().unwrap().execution_wall_clock_overhead;
+		assert_eq!(overhead.get_sample_count(), 2);
+		assert!((overhead.get_sample_sum() - 0.25).abs() < 1e-9);
+	}
+}
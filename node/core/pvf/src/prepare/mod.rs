@@ -28,6 +28,7 @@ mod worker_intf;
 
 pub use pool::start as start_pool;
 pub use queue::{start as start_queue, FromQueue, ToQueue};
+pub use worker_intf::Handshake as PrepareHandshake;
 
 use parity_scale_codec::{Decode, Encode};
 
@@ -17,10 +17,12 @@
 //! Host interface to the prepare worker.
 
 use crate::{
+	artifacts::{artifact_checksum, checksum_path},
 	error::{PrepareError, PrepareResult},
 	metrics::Metrics,
 	prepare::PrepareStats,
 	pvf::PvfPrepData,
+	security::{recv_sandbox_status, SandboxConfig, SandboxStatus},
 	worker_common::{
 		framed_recv, framed_send, path_to_bytes, spawn_with_program_path, tmpfile_in, IdleWorker,
 		SpawnErr, WorkerHandle, JOB_TIMEOUT_WALL_CLOCK_FACTOR,
@@ -37,19 +39,38 @@ use std::{
 use tokio::{io, net::UnixStream};
 
 /// Spawns a new worker with the given program path that acts as the worker and the spawn timeout.
+/// Sends a handshake message to the worker as soon as it is spawned and waits for the worker to
+/// report its sandbox status.
 ///
 /// The program should be able to handle `<program-path> prepare-worker <socket-path>` invocation.
 pub async fn spawn(
 	program_path: &Path,
 	spawn_timeout: Duration,
+	sandbox: Option<SandboxConfig>,
 ) -> Result<(IdleWorker, WorkerHandle), SpawnErr> {
-	spawn_with_program_path(
+	let (mut idle_worker, worker_handle) = spawn_with_program_path(
 		"prepare",
 		program_path,
 		&["prepare-worker", "--node-impl-version", env!("SUBSTRATE_CLI_IMPL_VERSION")],
 		spawn_timeout,
 	)
-	.await
+	.await?;
+
+	let expected = SandboxStatus { landlock: sandbox.is_some(), seccomp: false };
+	let handshake_result = async {
+		send_handshake(&mut idle_worker.stream, Handshake { sandbox }).await?;
+		recv_sandbox_status(&mut idle_worker.stream, "prepare", idle_worker.pid, expected).await
+	};
+	handshake_result.await.map_err(|error| {
+		gum::warn!(
+			target: LOG_TARGET,
+			worker_pid = %idle_worker.pid,
+			?error,
+			"failed to handshake with the spawned worker",
+		);
+		SpawnErr::Handshake
+	})?;
+	Ok((idle_worker, worker_handle))
 }
 
 pub enum Outcome {
@@ -67,6 +88,10 @@ pub enum Outcome {
 	///
 	/// The worker is no longer usable and should be killed.
 	TimedOut,
+	/// The job exceeded the memory limit. The compilation may still be running in the background.
+	///
+	/// The worker is no longer usable and should be killed.
+	OutOfMemory,
 	/// An IO error occurred while receiving the result from the worker process.
 	///
 	/// This doesn't return an idle worker instance, thus this worker is no longer usable.
@@ -76,8 +101,8 @@ pub enum Outcome {
 /// Given the idle token of a worker and parameters of work, communicates with the worker and
 /// returns the outcome.
 ///
-/// NOTE: Returning the `TimedOut`, `IoErr` or `Unreachable` outcomes will trigger the child process
-/// being killed.
+/// NOTE: Returning the `TimedOut`, `OutOfMemory`, `IoErr` or `Unreachable` outcomes will trigger the
+/// child process being killed.
 pub async fn start_work(
 	metrics: &Metrics,
 	worker: IdleWorker,
@@ -172,6 +197,8 @@ async fn handle_response(
 		Ok(result) => result,
 		// Timed out on the child. This should already be logged by the child.
 		Err(PrepareError::TimedOut) => return Outcome::TimedOut,
+		// Exceeded the memory limit on the child. This should already be logged by the child.
+		Err(PrepareError::OutOfMemory) => return Outcome::OutOfMemory,
 		Err(_) => return Outcome::Concluded { worker, result },
 	};
 
@@ -196,6 +223,18 @@ async fn handle_response(
 		artifact_path.display(),
 	);
 
+	// Store the checksum of the artifact next to it, so that it can be verified and reused after
+	// a restart. Failing to do so only means that the artifact will be discarded on restart.
+	if let Err(err) = write_checksum(&tmp_file, &artifact_path).await {
+		gum::warn!(
+			target: LOG_TARGET,
+			%worker_pid,
+			"failed to write the checksum of the artifact {}: {:?}",
+			artifact_path.display(),
+			err,
+		);
+	}
+
 	let outcome = match tokio::fs::rename(&tmp_file, &artifact_path).await {
 		Ok(()) => Outcome::Concluded { worker, result },
 		Err(err) => {
@@ -218,6 +257,13 @@ async fn handle_response(
 	outcome
 }
 
+/// Computes the checksum of the artifact located at `tmp_file` and writes it to the checksum file
+/// of the artifact at `artifact_path`.
+async fn write_checksum(tmp_file: &Path, artifact_path: &Path) -> io::Result<()> {
+	let artifact = tokio::fs::read(tmp_file).await?;
+	tokio::fs::write(checksum_path(artifact_path), artifact_checksum(&artifact)).await
+}
+
 /// Create a temporary file for an artifact at the given cache path and execute the given
 /// future/closure passing the file path in.
 ///
@@ -266,6 +312,10 @@ where
 	outcome
 }
 
+async fn send_handshake(stream: &mut UnixStream, handshake: Handshake) -> io::Result<()> {
+	framed_send(stream, &handshake.encode()).await
+}
+
 async fn send_request(
 	stream: &mut UnixStream,
 	pvf: PvfPrepData,
@@ -294,3 +344,11 @@ async fn recv_response(stream: &mut UnixStream, pid: u32) -> io::Result<PrepareR
 	})?;
 	Ok(result)
 }
+
+/// The payload of the one-time handshake that is done when a worker process is created. Carries
+/// data from the host to the worker.
+#[derive(Encode, Decode)]
+pub struct Handshake {
+	/// The sandbox the worker should enter, if any.
+	pub sandbox: Option<SandboxConfig>,
+}
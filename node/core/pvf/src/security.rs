@@ -0,0 +1,98 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Sandboxing of the workers.
+//!
+//! The sandbox is opt-in and only supported on Linux. When it is enabled, the host passes a
+//! [`SandboxConfig`] to every worker in the handshake. The worker then restricts itself before
+//! accepting any jobs and reports back the protections that are actually active, see
+//! [`SandboxStatus`]. The worker restricts itself as follows:
+//!
+//! - Landlock: filesystem access is limited to the artifact cache directory. This applies to both
+//!   the prepare and the execute workers.
+//!
+//! - seccomp: the execute workers filter out the system calls that are never needed to execute a
+//!   PVF, such as networking and spawning new processes.
+
+use crate::{
+	worker_common::{framed_recv, framed_send, path_to_bytes},
+	LOG_TARGET,
+};
+use parity_scale_codec::{Decode, Encode};
+use std::path::Path;
+use tokio::{io, net::UnixStream};
+
+/// The sandbox that the host asks a worker to enter.
+#[derive(Clone, Debug, Encode, Decode)]
+pub struct SandboxConfig {
+	/// The path to the artifact cache, which is the only directory the worker may access. Encoded
+	/// the same way as the other paths sent to the workers.
+	pub cache_path: Vec<u8>,
+}
+
+impl SandboxConfig {
+	/// Creates a sandbox configuration limiting the workers to the given cache path.
+	pub fn new(cache_path: &Path) -> Self {
+		Self { cache_path: path_to_bytes(cache_path).to_vec() }
+	}
+}
+
+/// The protections reported as active by a worker after it entered the sandbox.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Encode, Decode)]
+pub struct SandboxStatus {
+	/// Filesystem access is restricted to the artifact cache with Landlock.
+	pub landlock: bool,
+	/// System calls are filtered with seccomp.
+	pub seccomp: bool,
+}
+
+/// Sends the sandbox status from the worker to the host.
+pub async fn send_sandbox_status(stream: &mut UnixStream, status: SandboxStatus) -> io::Result<()> {
+	framed_send(stream, &status.encode()).await
+}
+
+/// Receives the sandbox status reported by a freshly spawned worker and logs it.
+///
+/// `expected` is the status that the worker is expected to reach with the requested sandbox.
+pub(crate) async fn recv_sandbox_status(
+	stream: &mut UnixStream,
+	debug_id: &'static str,
+	worker_pid: u32,
+	expected: SandboxStatus,
+) -> io::Result<SandboxStatus> {
+	let status_bytes = framed_recv(stream).await?;
+	let status = SandboxStatus::decode(&mut &status_bytes[..]).map_err(|e| {
+		io::Error::new(
+			io::ErrorKind::Other,
+			format!("{} pvf recv_sandbox_status: failed to decode SandboxStatus: {}", debug_id, e),
+		)
+	})?;
+
+	if status == expected {
+		gum::debug!(target: LOG_TARGET, %worker_pid, ?status, "{} worker sandbox status", debug_id);
+	} else {
+		gum::warn!(
+			target: LOG_TARGET,
+			%worker_pid,
+			?status,
+			?expected,
+			"{} worker could not enable all of the requested sandbox protections",
+			debug_id,
+		);
+	}
+
+	Ok(status)
+}
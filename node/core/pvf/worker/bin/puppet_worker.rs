@@ -14,4 +14,9 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
+// Allocate through jemalloc like the node does, so that the memory limit of preparation is enforced.
+#[cfg(target_os = "linux")]
+#[global_allocator]
+static ALLOC: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
+
 polkadot_node_core_pvf_worker::decl_puppet_worker_main!();
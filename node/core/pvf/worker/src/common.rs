@@ -25,7 +25,7 @@ use std::{
 use tokio::{
 	io,
 	net::UnixStream,
-	runtime::{Handle, Runtime},
+	runtime::{Builder, Handle},
 };
 
 /// Some allowed overhead that we account for in the "CPU time monitor" thread's sleeps, on the
@@ -67,12 +67,19 @@ pub fn worker_event_loop<F, Fut>(
 	}
 
 	// Run the main worker loop.
-	let rt = Runtime::new().expect("Creates tokio runtime. If this panics the worker will die and the host will detect that and deal with it.");
+	//
+	// NOTE: The runtime must not spawn any thread before the worker entered its sandbox. Landlock
+	// only restricts the calling thread and the threads it spawns afterwards, so the event loop
+	// runs on this very thread and jobs run on blocking threads which are spawned lazily.
+	let rt = Builder::new_current_thread().enable_all().build().expect("Creates tokio runtime. If this panics the worker will die and the host will detect that and deal with it.");
 	let handle = rt.handle();
 	let err = rt
 		.block_on(async move {
 			let stream = UnixStream::connect(socket_path).await?;
-			let _ = tokio::fs::remove_file(socket_path).await;
+			// NOTE: Not using `tokio::fs` here on purpose. It would spawn a blocking thread before
+			// the worker entered its sandbox, and that thread could be reused for running jobs
+			// later on without any restrictions.
+			let _ = std::fs::remove_file(socket_path);
 
 			let result = event_loop(handle.clone(), stream).await;
 
@@ -17,19 +17,20 @@
 use crate::{
 	common::{bytes_to_path, cpu_time_monitor_loop, worker_event_loop},
 	executor_intf::Executor,
-	LOG_TARGET,
+	sandbox, LOG_TARGET,
 };
 use cpu_time::ProcessTime;
 use futures::{pin_mut, select_biased, FutureExt};
 use parity_scale_codec::{Decode, Encode};
 use polkadot_node_core_pvf::{
-	framed_recv, framed_send, ExecuteHandshake as Handshake, ExecuteResponse as Response,
+	framed_recv, framed_send, send_sandbox_status, ExecuteHandshake as Handshake,
+	ExecuteResponse as Response,
 };
 use polkadot_parachain::primitives::ValidationResult;
 use std::{
 	path::{Path, PathBuf},
 	sync::{mpsc::channel, Arc},
-	time::Duration,
+	time::{Duration, Instant},
 };
 use tokio::{io, net::UnixStream};
 
@@ -80,6 +81,10 @@ pub fn worker_entrypoint(socket_path: &str, node_version: Option<&str>) {
 			io::Error::new(io::ErrorKind::Other, format!("cannot create executor: {}", e))
 		})?);
 
+		// Enter the sandbox before any job thread is spawned, so that all of them inherit it.
+		let sandbox_status = sandbox::enter(handshake.sandbox.as_ref(), true);
+		send_sandbox_status(&mut stream, sandbox_status).await?;
+
 		loop {
 			let (artifact_path, params, execution_timeout) = recv_request(&mut stream).await?;
 			gum::debug!(
@@ -92,6 +97,7 @@ pub fn worker_entrypoint(socket_path: &str, node_version: Option<&str>) {
 			// Used to signal to the cpu time monitor thread that it can finish.
 			let (finished_tx, finished_rx) = channel::<()>();
 			let cpu_time_start = ProcessTime::now();
+			let wall_clock_start = Instant::now();
 
 			// Spawn a new thread that runs the CPU time monitor.
 			let cpu_time_monitor_fut = rt_handle
@@ -102,7 +108,13 @@ pub fn worker_entrypoint(socket_path: &str, node_version: Option<&str>) {
 			let executor_2 = executor.clone();
 			let execute_fut = rt_handle
 				.spawn_blocking(move || {
-					validate_using_artifact(&artifact_path, &params, executor_2, cpu_time_start)
+					validate_using_artifact(
+						&artifact_path,
+						&params,
+						executor_2,
+						cpu_time_start,
+						wall_clock_start,
+					)
 				})
 				.fuse();
 
@@ -145,6 +157,7 @@ fn validate_using_artifact(
 	params: &[u8],
 	executor: Arc<Executor>,
 	cpu_time_start: ProcessTime,
+	wall_clock_start: Instant,
 ) -> Response {
 	// Check here if the file exists, because the error from Substrate is not match-able.
 	// TODO: Re-evaluate after <https://github.com/paritytech/substrate/issues/13860>.
@@ -164,6 +177,7 @@ fn validate_using_artifact(
 	};
 
 	let duration = cpu_time_start.elapsed();
+	let wall_clock_duration = wall_clock_start.elapsed();
 
 	let result_descriptor = match ValidationResult::decode(&mut &descriptor_bytes[..]) {
 		Err(err) =>
@@ -171,5 +185,5 @@ fn validate_using_artifact(
 		Ok(r) => r,
 	};
 
-	Response::Ok { result_descriptor, duration }
+	Response::Ok { result_descriptor, duration, wall_clock_duration }
 }
@@ -19,6 +19,7 @@ mod execute;
 mod executor_intf;
 mod memory_stats;
 mod prepare;
+mod sandbox;
 
 #[doc(hidden)]
 pub mod testing;
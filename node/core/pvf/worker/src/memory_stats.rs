@@ -22,9 +22,9 @@
 //! - `resident` memory stat provided by `tikv-malloc-ctl`.
 //! - `allocated` memory stat also from `tikv-malloc-ctl`.
 //!
-//! Currently we are only logging these for the purposes of gathering data. In the future, we may
-//! use these stats to reject PVFs during pre-checking. See
-//! <https://github.com/paritytech/polkadot/issues/6472#issuecomment-1381941762> for more
+//! These are mostly logged for the purposes of gathering data. The only exception is the
+//! `allocated` stat, which is also used to enforce the `PrecheckingMaxMemory` executor parameter.
+//! See <https://github.com/paritytech/polkadot/issues/6472#issuecomment-1381941762> for more
 //! background.
 
 /// Module for the memory tracker. The memory tracker runs in its own thread, where it polls memory
@@ -124,6 +124,59 @@ pub mod memory_tracker {
 		}
 	}
 
+	/// Runs a thread in the background that enforces the memory limit of a preparation job, if any.
+	///
+	/// The limit applies to the memory allocated since the start of the job. It is polled at a much
+	/// shorter interval than in [`memory_tracker_loop`], so that a PVF cannot get away with a large
+	/// but short-lived allocation.
+	///
+	/// Returns `Some` with the allocated memory if the limit was exceeded. Returns `None` once we
+	/// receive a signal that preparation has completed.
+	pub fn memory_limit_monitor_loop(
+		max_memory: Option<u64>,
+		finished_rx: Receiver<()>,
+	) -> Option<u64> {
+		const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+		let (max_memory, tracker) = match (max_memory, MemoryAllocationTracker::new()) {
+			(Some(max_memory), Ok(tracker)) => (max_memory, tracker),
+			(None, _) => {
+				let _ = finished_rx.recv();
+				return None
+			},
+			(Some(_), Err(err)) => {
+				gum::warn!(
+					target: LOG_TARGET,
+					worker_pid = %std::process::id(),
+					"worker: cannot enforce the memory limit of the preparation: {}",
+					err,
+				);
+				let _ = finished_rx.recv();
+				return None
+			},
+		};
+
+		// If the baseline cannot be read, count all of the allocated memory towards the limit.
+		let baseline = tracker.snapshot().map(|stats| stats.allocated).unwrap_or(0);
+
+		loop {
+			if let Ok(stats) = tracker.snapshot() {
+				let allocated = stats.allocated.saturating_sub(baseline);
+				if allocated > max_memory {
+					return Some(allocated)
+				}
+			}
+
+			match finished_rx.recv_timeout(POLL_INTERVAL) {
+				// Received finish signal.
+				Ok(()) => return None,
+				// Timed out, restart loop.
+				Err(RecvTimeoutError::Timeout) => continue,
+				Err(RecvTimeoutError::Disconnected) => return None,
+			}
+		}
+	}
+
 	/// Helper function to terminate the memory tracker thread and get the stats. Helps isolate all this
 	/// error handling.
 	pub async fn get_memory_tracker_loop_stats(
@@ -165,6 +218,30 @@ pub mod memory_tracker {
 	}
 }
 
+/// Fallback for platforms where the memory tracker is not available.
+#[cfg(not(any(target_os = "linux", feature = "jemalloc-allocator")))]
+pub mod memory_tracker {
+	use crate::LOG_TARGET;
+	use std::sync::mpsc::Receiver;
+
+	/// The memory limit cannot be enforced without jemalloc. Waits for preparation to complete and
+	/// returns `None`.
+	pub fn memory_limit_monitor_loop(
+		max_memory: Option<u64>,
+		finished_rx: Receiver<()>,
+	) -> Option<u64> {
+		if max_memory.is_some() {
+			gum::warn!(
+				target: LOG_TARGET,
+				worker_pid = %std::process::id(),
+				"worker: enforcing the memory limit of the preparation requires jemalloc",
+			);
+		}
+		let _ = finished_rx.recv();
+		None
+	}
+}
+
 /// Module for dealing with the `ru_maxrss` (peak resident memory) stat from `getrusage`.
 ///
 /// NOTE: `getrusage` with the `RUSAGE_THREAD` parameter is only supported on Linux. `RUSAGE_SELF`
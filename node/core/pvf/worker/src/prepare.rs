@@ -20,18 +20,30 @@ use crate::memory_stats::max_rss_stat::{extract_max_rss_stat, get_max_rss_thread
 use crate::memory_stats::memory_tracker::{get_memory_tracker_loop_stats, memory_tracker_loop};
 use crate::{
 	common::{bytes_to_path, cpu_time_monitor_loop, worker_event_loop},
-	prepare, prevalidate, LOG_TARGET,
+	memory_stats::memory_tracker::memory_limit_monitor_loop,
+	prepare, prevalidate, sandbox, LOG_TARGET,
 };
 use cpu_time::ProcessTime;
 use futures::{pin_mut, select_biased, FutureExt};
 use parity_scale_codec::{Decode, Encode};
 use polkadot_node_core_pvf::{
-	framed_recv, framed_send, CompiledArtifact, MemoryStats, PrepareError, PrepareResult,
-	PrepareStats, PvfPrepData,
+	framed_recv, framed_send, send_sandbox_status, CompiledArtifact, MemoryStats, PrepareError,
+	PrepareHandshake as Handshake, PrepareResult, PrepareStats, PvfPrepData,
 };
 use std::{any::Any, panic, path::PathBuf, sync::mpsc::channel};
 use tokio::{io, net::UnixStream};
 
+async fn recv_handshake(stream: &mut UnixStream) -> io::Result<Handshake> {
+	let handshake_enc = framed_recv(stream).await?;
+	let handshake = Handshake::decode(&mut &handshake_enc[..]).map_err(|_| {
+		io::Error::new(
+			io::ErrorKind::Other,
+			"prepare pvf recv_handshake: failed to decode Handshake".to_owned(),
+		)
+	})?;
+	Ok(handshake)
+}
+
 async fn recv_request(stream: &mut UnixStream) -> io::Result<(PvfPrepData, PathBuf)> {
 	let pvf = framed_recv(stream).await?;
 	let pvf = PvfPrepData::decode(&mut &pvf[..]).map_err(|e| {
@@ -61,15 +73,19 @@ async fn send_response(stream: &mut UnixStream, result: PrepareResult) -> io::Re
 ///
 /// # Flow
 ///
-/// This runs the following in a loop:
+/// First, the worker receives the handshake from the host and enters the sandbox, if requested.
+///
+/// Then, this runs the following in a loop:
 ///
 /// 1. Get the code and parameters for preparation from the host.
 ///
 /// 2. Start a memory tracker in a separate thread.
 ///
-/// 3. Start the CPU time monitor loop and the actual preparation in two separate threads.
+/// 3. Start the CPU time monitor loop, the memory limit monitor loop and the actual preparation in
+///    three separate threads.
 ///
-/// 4. Select on the two threads created in step 3. If the CPU timeout was hit, the CPU time monitor
+/// 4. Select on the three threads created in step 3. If the CPU timeout was hit, the CPU time
+///    monitor thread will trigger first. If the memory limit was exceeded, the memory limit monitor
 ///    thread will trigger first.
 ///
 /// 5. Stop the memory tracker and get the stats.
@@ -82,6 +98,11 @@ pub fn worker_entrypoint(socket_path: &str, node_version: Option<&str>) {
 	worker_event_loop("prepare", socket_path, node_version, |rt_handle, mut stream| async move {
 		let worker_pid = std::process::id();
 
+		// Enter the sandbox before any job thread is spawned, so that all of them inherit it.
+		let handshake = recv_handshake(&mut stream).await?;
+		let sandbox_status = sandbox::enter(handshake.sandbox.as_ref(), false);
+		send_sandbox_status(&mut stream, sandbox_status).await?;
+
 		loop {
 			let (pvf, dest) = recv_request(&mut stream).await?;
 			gum::debug!(
@@ -92,6 +113,7 @@ pub fn worker_entrypoint(socket_path: &str, node_version: Option<&str>) {
 
 			let cpu_time_start = ProcessTime::now();
 			let preparation_timeout = pvf.prep_timeout();
+			let max_memory = pvf.executor_params().prechecking_max_memory();
 
 			// Run the memory tracker.
 			#[cfg(any(target_os = "linux", feature = "jemalloc-allocator"))]
@@ -106,6 +128,13 @@ pub fn worker_entrypoint(socket_path: &str, node_version: Option<&str>) {
 					cpu_time_monitor_loop(cpu_time_start, preparation_timeout, cpu_time_monitor_rx)
 				})
 				.fuse();
+			// Spawn a new thread that enforces the memory limit.
+			let (memory_limit_monitor_tx, memory_limit_monitor_rx) = channel::<()>();
+			let memory_limit_monitor_fut = rt_handle
+				.spawn_blocking(move || {
+					memory_limit_monitor_loop(max_memory, memory_limit_monitor_rx)
+				})
+				.fuse();
 			// Spawn another thread for preparation.
 			let prepare_fut = rt_handle
 				.spawn_blocking(move || {
@@ -120,6 +149,7 @@ pub fn worker_entrypoint(socket_path: &str, node_version: Option<&str>) {
 				.fuse();
 
 			pin_mut!(cpu_time_monitor_fut);
+			pin_mut!(memory_limit_monitor_fut);
 			pin_mut!(prepare_fut);
 
 			let result = select_biased! {
@@ -142,9 +172,29 @@ pub fn worker_entrypoint(socket_path: &str, node_version: Option<&str>) {
 						Err(err) => Err(PrepareError::IoErr(err.to_string())),
 					}
 				},
+				// If this future is not selected, the join handle is dropped and the thread will
+				// finish in the background.
+				join_res = memory_limit_monitor_fut => {
+					match join_res {
+						Ok(Some(allocated)) => {
+							// Log if we exceed the limit and the other thread hasn't finished.
+							gum::warn!(
+								target: LOG_TARGET,
+								%worker_pid,
+								"prepare job allocated {} bytes, exceeded memory limit {} bytes",
+								allocated,
+								max_memory.unwrap_or_default(),
+							);
+							Err(PrepareError::OutOfMemory)
+						},
+						Ok(None) => Err(PrepareError::IoErr("error communicating over finished channel".into())),
+						Err(err) => Err(PrepareError::IoErr(err.to_string())),
+					}
+				},
 				prepare_res = prepare_fut => {
 					let cpu_time_elapsed = cpu_time_start.elapsed();
 					let _ = cpu_time_monitor_tx.send(());
+					let _ = memory_limit_monitor_tx.send(());
 
 					match prepare_res.unwrap_or_else(|err| Err(PrepareError::IoErr(err.to_string()))) {
 						Err(err) => {
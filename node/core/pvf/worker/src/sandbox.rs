@@ -0,0 +1,219 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Restricting the worker process with a sandbox.
+//!
+//! Landlock restrictions apply to the calling thread and are inherited by the threads it spawns
+//! afterwards. Hence the sandbox must be entered from the thread driving the worker event loop,
+//! before any other thread is spawned. The seccomp filter is synchronized to all the threads of the
+//! worker.
+
+use crate::LOG_TARGET;
+use polkadot_node_core_pvf::{SandboxConfig, SandboxStatus};
+
+/// Enters the sandbox described by `config`, if any, and returns the protections that are active.
+///
+/// Filesystem access is always restricted if possible. System calls are only filtered if
+/// `filter_syscalls` is set.
+pub fn enter(config: Option<&SandboxConfig>, filter_syscalls: bool) -> SandboxStatus {
+	let config = match config {
+		Some(config) => config,
+		None => return SandboxStatus::default(),
+	};
+
+	#[cfg(target_os = "linux")]
+	{
+		let worker_pid = std::process::id();
+
+		let landlock = match crate::common::bytes_to_path(&config.cache_path) {
+			Some(cache_path) => match fs_access::restrict_to(&cache_path) {
+				Ok(enforced) => enforced,
+				Err(err) => {
+					gum::warn!(target: LOG_TARGET, %worker_pid, "failed to enable landlock: {}", err);
+					false
+				},
+			},
+			None => {
+				gum::warn!(target: LOG_TARGET, %worker_pid, "non utf-8 cache path for landlock");
+				false
+			},
+		};
+
+		let seccomp = filter_syscalls &&
+			match syscall_filter::block_unneeded_syscalls() {
+				Ok(()) => true,
+				Err(err) => {
+					gum::warn!(target: LOG_TARGET, %worker_pid, "failed to enable seccomp: {}", err);
+					false
+				},
+			};
+
+		SandboxStatus { landlock, seccomp }
+	}
+
+	#[cfg(not(target_os = "linux"))]
+	{
+		let _ = (config, filter_syscalls);
+		gum::warn!(
+			target: LOG_TARGET,
+			worker_pid = %std::process::id(),
+			"the sandbox is only supported on Linux",
+		);
+		SandboxStatus::default()
+	}
+}
+
+#[cfg(target_os = "linux")]
+mod fs_access {
+	use landlock::{
+		Access, AccessFs, PathBeneath, PathFd, Ruleset, RulesetAttr, RulesetCreatedAttr,
+		RulesetStatus, ABI,
+	};
+	use std::path::Path;
+
+	/// The Landlock ABI we rely on. Version 1 is supported since Linux 5.13.
+	const LANDLOCK_ABI: ABI = ABI::V1;
+
+	/// Forbids any filesystem access outside of `cache_path`. Returns whether the restriction is
+	/// fully enforced by the kernel.
+	pub fn restrict_to(cache_path: &Path) -> Result<bool, String> {
+		let access = AccessFs::from_all(LANDLOCK_ABI);
+		let cache_dir = PathFd::new(cache_path).map_err(|err| err.to_string())?;
+		let status = Ruleset::new()
+			.handle_access(access)
+			.and_then(|ruleset| ruleset.create())
+			.and_then(|ruleset| ruleset.add_rule(PathBeneath::new(cache_dir, access)))
+			.and_then(|ruleset| ruleset.restrict_self())
+			.map_err(|err| err.to_string())?;
+
+		Ok(status.ruleset == RulesetStatus::FullyEnforced)
+	}
+}
+
+#[cfg(target_os = "linux")]
+mod syscall_filter {
+	use seccompiler::{BpfProgram, SeccompAction, SeccompFilter, SeccompRule, TargetArch};
+	use std::collections::BTreeMap;
+
+	/// The system calls that are never needed to execute a PVF. Calling them fails with `EACCES`.
+	const BLOCKED_SYSCALLS: &[libc::c_long] = &[
+		// Networking.
+		libc::SYS_socket,
+		libc::SYS_socketpair,
+		libc::SYS_connect,
+		libc::SYS_bind,
+		libc::SYS_listen,
+		libc::SYS_accept,
+		libc::SYS_accept4,
+		// Spawning new programs.
+		libc::SYS_execve,
+		libc::SYS_execveat,
+		// Inspecting and tampering with other processes.
+		libc::SYS_ptrace,
+		libc::SYS_process_vm_readv,
+		libc::SYS_process_vm_writev,
+	];
+
+	/// Installs a seccomp filter rejecting the [`BLOCKED_SYSCALLS`] on all the threads of the
+	/// process.
+	pub fn block_unneeded_syscalls() -> Result<(), String> {
+		let program = blocked_syscalls_filter()?;
+		seccompiler::apply_filter_all_threads(&program).map_err(|err| err.to_string())
+	}
+
+	/// Compiles the seccomp filter rejecting the [`BLOCKED_SYSCALLS`].
+	pub fn blocked_syscalls_filter() -> Result<BpfProgram, String> {
+		let rules: BTreeMap<i64, Vec<SeccompRule>> =
+			BLOCKED_SYSCALLS.iter().map(|syscall| (*syscall as i64, vec![])).collect();
+		let arch = TargetArch::try_from(std::env::consts::ARCH).map_err(|err| err.to_string())?;
+		let filter = SeccompFilter::new(
+			rules,
+			SeccompAction::Allow,
+			SeccompAction::Errno(libc::EACCES as u32),
+			arch,
+		)
+		.map_err(|err| err.to_string())?;
+		BpfProgram::try_from(filter).map_err(|err| err.to_string())
+	}
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+	use super::*;
+	use std::{fs, io::ErrorKind, thread};
+
+	#[test]
+	fn landlock_denies_access_outside_of_the_cache() {
+		let cache_dir = tempfile::tempdir().unwrap();
+		let outside_dir = tempfile::tempdir().unwrap();
+		let inside = cache_dir.path().join("artifact");
+		let outside = outside_dir.path().join("secret");
+		fs::write(&inside, b"artifact").unwrap();
+		fs::write(&outside, b"secret").unwrap();
+
+		// Landlock only restricts the calling thread, so the rest of the test process is unaffected.
+		thread::spawn(move || {
+			// Nothing to check if landlock is not supported by the kernel.
+			if !fs_access::restrict_to(cache_dir.path()).unwrap_or(false) {
+				return
+			}
+
+			assert_eq!(fs::read(&inside).unwrap(), b"artifact");
+			assert_eq!(fs::read(&outside).unwrap_err().kind(), ErrorKind::PermissionDenied);
+
+			// Threads spawned afterwards, such as the job threads, inherit the restrictions.
+			thread::spawn(move || {
+				assert_eq!(fs::read(&outside).unwrap_err().kind(), ErrorKind::PermissionDenied);
+			})
+			.join()
+			.unwrap();
+		})
+		.join()
+		.unwrap();
+	}
+
+	#[test]
+	fn seccomp_denies_blocked_syscalls() {
+		// The filter is applied in a forked child, so that the test process is unaffected. The child
+		// must not allocate, hence the filter is compiled beforehand.
+		let program = syscall_filter::blocked_syscalls_filter().unwrap();
+
+		// SAFETY: The child only does raw system calls before exiting.
+		let pid = unsafe { libc::fork() };
+		assert!(pid >= 0, "fork failed");
+		if pid == 0 {
+			let code = match seccompiler::apply_filter_all_threads(&program) {
+				Err(_) => 2,
+				Ok(()) => {
+					let fd = unsafe { libc::socket(libc::AF_INET, libc::SOCK_STREAM, 0) };
+					let denied = fd == -1 &&
+						std::io::Error::last_os_error().raw_os_error() == Some(libc::EACCES);
+					if denied {
+						0
+					} else {
+						1
+					}
+				},
+			};
+			unsafe { libc::_exit(code) };
+		}
+
+		let mut status = 0;
+		assert_eq!(unsafe { libc::waitpid(pid, &mut status, 0) }, pid);
+		assert!(libc::WIFEXITED(status));
+		assert_eq!(libc::WEXITSTATUS(status), 0, "socket(2) was not denied by the seccomp filter");
+	}
+}
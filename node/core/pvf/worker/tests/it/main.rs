@@ -17,8 +17,8 @@
 use assert_matches::assert_matches;
 use parity_scale_codec::Encode as _;
 use polkadot_node_core_pvf::{
-	start, Config, InvalidCandidate, Metrics, PvfPrepData, ValidationError, ValidationHost,
-	JOB_TIMEOUT_WALL_CLOCK_FACTOR,
+	start, Config, InvalidCandidate, Metrics, PrepareError, PrepareResult, Priority, PvfPrepData,
+	ValidationError, ValidationHost, JOB_TIMEOUT_WALL_CLOCK_FACTOR,
 };
 use polkadot_parachain::primitives::{BlockData, ValidationParams, ValidationResult};
 use polkadot_primitives::{ExecutorParam, ExecutorParams};
@@ -60,6 +60,17 @@ impl TestHost {
 		code: &[u8],
 		params: ValidationParams,
 		executor_params: ExecutorParams,
+	) -> Result<ValidationResult, ValidationError> {
+		self.validate_candidate_with_priority(code, params, executor_params, Priority::Normal)
+			.await
+	}
+
+	async fn validate_candidate_with_priority(
+		&self,
+		code: &[u8],
+		params: ValidationParams,
+		executor_params: ExecutorParams,
+		priority: Priority,
 	) -> Result<ValidationResult, ValidationError> {
 		let (result_tx, result_rx) = futures::channel::oneshot::channel();
 
@@ -73,7 +84,25 @@ impl TestHost {
 				PvfPrepData::from_code(code.into(), executor_params, TEST_PREPARATION_TIMEOUT),
 				TEST_EXECUTION_TIMEOUT,
 				params.encode(),
-				polkadot_node_core_pvf::Priority::Normal,
+				priority,
+				result_tx,
+			)
+			.await
+			.unwrap();
+		result_rx.await.unwrap()
+	}
+
+	async fn precheck_pvf(&self, code: &[u8], executor_params: ExecutorParams) -> PrepareResult {
+		let (result_tx, result_rx) = futures::channel::oneshot::channel();
+
+		let code = sp_maybe_compressed_blob::decompress(code, 16 * 1024 * 1024)
+			.expect("Compression works");
+
+		self.host
+			.lock()
+			.await
+			.precheck_pvf(
+				PvfPrepData::from_code(code.into(), executor_params, TEST_PREPARATION_TIMEOUT),
 				result_tx,
 			)
 			.await
@@ -156,10 +185,64 @@ async fn ensure_parallel_execution() {
 	);
 }
 
+#[tokio::test]
+async fn critical_jobs_are_not_starved_by_normal_ones() {
+	let host = TestHost::new_with_config(|cfg| {
+		cfg.execute_workers_soft_max_num = 1;
+		cfg.execute_workers_hard_max_num = 2;
+	});
+
+	let validate = |priority| {
+		host.validate_candidate_with_priority(
+			halt::wasm_binary_unwrap(),
+			ValidationParams {
+				block_data: BlockData(Vec::new()),
+				parent_head: Default::default(),
+				relay_parent_number: 1,
+				relay_parent_storage_root: Default::default(),
+			},
+			Default::default(),
+			priority,
+		)
+	};
+
+	// Here we spawn two normal jobs, which have to share a single worker, and one critical job,
+	// which should run on the reserved worker in parallel with the first normal job.
+	let start = std::time::Instant::now();
+	let normal_jobs = futures::future::join(validate(Priority::Normal), validate(Priority::Normal));
+	let critical_job = async {
+		let result = validate(Priority::Critical).await;
+		(result, std::time::Instant::now().duration_since(start))
+	};
+	let (_, (critical_result, critical_duration)) = futures::join!(normal_jobs, critical_job);
+
+	assert_matches!(
+		critical_result,
+		Err(ValidationError::InvalidCandidate(InvalidCandidate::HardTimeout))
+	);
+	let max_duration = 2 * TEST_EXECUTION_TIMEOUT;
+	assert!(
+		critical_duration < max_duration,
+		"Expected duration {}ms to be less than {}ms",
+		critical_duration.as_millis(),
+		max_duration.as_millis()
+	);
+
+	// The normal jobs were executed one after another.
+	let duration = std::time::Instant::now().duration_since(start);
+	assert!(
+		duration >= max_duration,
+		"Expected duration {}ms to be greater than or equal to {}ms",
+		duration.as_millis(),
+		max_duration.as_millis()
+	);
+}
+
 #[tokio::test]
 async fn execute_queue_doesnt_stall_if_workers_died() {
 	let host = TestHost::new_with_config(|cfg| {
-		cfg.execute_workers_max_num = 5;
+		cfg.execute_workers_soft_max_num = 5;
+		cfg.execute_workers_hard_max_num = 5;
 	});
 
 	// Here we spawn 8 validation jobs for the `halt` PVF and share those between 5 workers. The
@@ -195,7 +278,8 @@ async fn execute_queue_doesnt_stall_if_workers_died() {
 #[tokio::test]
 async fn execute_queue_doesnt_stall_with_varying_executor_params() {
 	let host = TestHost::new_with_config(|cfg| {
-		cfg.execute_workers_max_num = 2;
+		cfg.execute_workers_soft_max_num = 2;
+		cfg.execute_workers_hard_max_num = 2;
 	});
 
 	let executor_params_1 = ExecutorParams::default();
@@ -295,3 +379,26 @@ async fn deleting_prepared_artifact_does_not_dispute() {
 		r => panic!("{:?}", r),
 	}
 }
+
+// The memory limit is enforced through the allocator statistics of jemalloc, which the puppet
+// worker only uses on Linux.
+#[cfg(target_os = "linux")]
+#[tokio::test]
+async fn prechecking_fails_deterministically_over_the_memory_limit() {
+	let host = TestHost::new();
+
+	// Compiling the PVF takes far more than a kilobyte.
+	let result = host
+		.precheck_pvf(
+			::adder::wasm_binary_unwrap(),
+			ExecutorParams::from(&[ExecutorParam::PrecheckingMaxMemory(1024)][..]),
+		)
+		.await;
+
+	assert_matches!(result, Err(PrepareError::OutOfMemory));
+	assert!(result.unwrap_err().is_deterministic());
+
+	// Without the limit the same PVF prepares just fine.
+	let result = host.precheck_pvf(::adder::wasm_binary_unwrap(), Default::default()).await;
+	assert_matches!(result, Ok(_));
+}
@@ -219,6 +219,7 @@ where
 						candidate_receipt,
 						pov,
 						timeout,
+						priority,
 						sender,
 					),
 			} => {
@@ -234,6 +235,7 @@ where
 									candidate_receipt,
 									pov,
 									timeout,
+									priority,
 									sender,
 								),
 							})
@@ -271,6 +273,7 @@ where
 										candidate_receipt,
 										pov,
 										timeout,
+										priority,
 										sender,
 									),
 								})
@@ -309,6 +312,7 @@ where
 										candidate_receipt,
 										pov,
 										timeout,
+										priority,
 										sender,
 									),
 								})
@@ -323,6 +327,7 @@ where
 							candidate_receipt,
 							pov,
 							timeout,
+							priority,
 							sender,
 						),
 					}),
@@ -335,6 +340,7 @@ where
 						candidate_receipt,
 						pov,
 						timeout,
+						priority,
 						response_sender,
 					),
 			} => {
@@ -348,6 +354,7 @@ where
 									candidate_receipt,
 									pov,
 									timeout,
+									priority,
 									response_sender,
 								),
 							})
@@ -376,6 +383,7 @@ where
 									candidate_receipt,
 									pov,
 									timeout,
+									priority,
 									response_sender,
 								),
 							}),
@@ -409,6 +417,7 @@ where
 										candidate_receipt,
 										pov,
 										timeout,
+										priority,
 										response_sender,
 									),
 								})
@@ -420,6 +429,7 @@ where
 							candidate_receipt,
 							pov,
 							timeout,
+							priority,
 							response_sender,
 						),
 					}),
@@ -24,12 +24,15 @@ use futures::{channel::oneshot, FutureExt as _};
 use polkadot_node_jaeger as jaeger;
 use polkadot_node_network_protocol::{
 	self as net_protocol,
+	gossip_topology::{GossipTopology, GridTopology},
 	grid_topology::{RandomRouting, RequiredRouting, SessionGridTopologies, SessionGridTopology},
-	peer_set::MAX_NOTIFICATION_SIZE,
-	v1 as protocol_v1, PeerId, UnifiedReputationChange as Rep, Versioned, View,
+	peer_set::{ProtocolVersion, ValidationVersion, MAX_NOTIFICATION_SIZE},
+	v1 as protocol_v1, v2 as protocol_v2, PeerId, UnifiedReputationChange as Rep, Versioned,
+	VersionedValidationProtocol, View,
 };
 use polkadot_node_primitives::approval::{
-	AssignmentCert, BlockApprovalMeta, IndirectAssignmentCert, IndirectSignedApprovalVote,
+	AssignmentCertV2, BlockApprovalMeta, CandidateBitfield, IndirectAssignmentCert,
+	IndirectAssignmentCertV2, IndirectSignedApprovalVote, IndirectSignedApprovalVoteV2,
 };
 use polkadot_node_subsystem::{
 	messages::{
@@ -42,7 +45,10 @@ use polkadot_primitives::{
 	BlockNumber, CandidateIndex, Hash, SessionIndex, ValidatorIndex, ValidatorSignature,
 };
 use rand::{CryptoRng, Rng, SeedableRng};
-use std::collections::{hash_map, BTreeMap, HashMap, HashSet, VecDeque};
+use std::{
+	collections::{hash_map, BTreeMap, HashMap, HashSet, VecDeque},
+	sync::Arc,
+};
 
 use self::metrics::Metrics;
 
@@ -67,6 +73,7 @@ const BENEFIT_VALID_MESSAGE_FIRST: Rep =
 /// The Approval Distribution subsystem.
 pub struct ApprovalDistribution {
 	metrics: Metrics,
+	gossip_topology: Arc<dyn GossipTopology>,
 }
 
 /// Contains recently finalized
@@ -171,7 +178,7 @@ struct State {
 	pending_known: HashMap<Hash, Vec<(PeerId, PendingMessage)>>,
 
 	/// Peer data is partially stored here, and partially inline within the [`BlockEntry`]s
-	peer_views: HashMap<PeerId, View>,
+	peer_views: HashMap<PeerId, PeerEntry>,
 
 	/// Keeps a topology for various different sessions.
 	topologies: SessionGridTopologies,
@@ -189,6 +196,13 @@ struct State {
 	approval_checking_lag: BlockNumber,
 }
 
+/// The view of a connected peer and the version of the validation protocol it negotiated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PeerEntry {
+	view: View,
+	version: ProtocolVersion,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum MessageKind {
 	Assignment,
@@ -267,24 +281,35 @@ struct BlockEntry {
 	session: SessionIndex,
 }
 
+// The assignment cert is kept together with the candidates it claims, which may be
+// more than the candidate entry holding the state. Likewise, the approval signature
+// is kept together with all the candidates it covers.
 #[derive(Debug)]
 enum ApprovalState {
-	Assigned(AssignmentCert),
-	Approved(AssignmentCert, ValidatorSignature),
+	Assigned(AssignmentCertV2, CandidateBitfield),
+	Approved(AssignmentCertV2, CandidateBitfield, CandidateBitfield, ValidatorSignature),
 }
 
 impl ApprovalState {
-	fn assignment_cert(&self) -> &AssignmentCert {
+	fn assignment_cert(&self) -> &AssignmentCertV2 {
+		match *self {
+			ApprovalState::Assigned(ref cert, _) => cert,
+			ApprovalState::Approved(ref cert, _, _, _) => cert,
+		}
+	}
+
+	fn claimed_candidates(&self) -> &CandidateBitfield {
 		match *self {
-			ApprovalState::Assigned(ref cert) => cert,
-			ApprovalState::Approved(ref cert, _) => cert,
+			ApprovalState::Assigned(_, ref claimed_candidates) => claimed_candidates,
+			ApprovalState::Approved(_, ref claimed_candidates, _, _) => claimed_candidates,
 		}
 	}
 
-	fn approval_signature(&self) -> Option<ValidatorSignature> {
+	fn approval(&self) -> Option<(&CandidateBitfield, ValidatorSignature)> {
 		match *self {
-			ApprovalState::Assigned(_) => None,
-			ApprovalState::Approved(_, ref sig) => Some(sig.clone()),
+			ApprovalState::Assigned(_, _) => None,
+			ApprovalState::Approved(_, _, ref approved_candidates, ref sig) =>
+				Some((approved_candidates, sig.clone())),
 		}
 	}
 }
@@ -324,8 +349,20 @@ impl MessageSource {
 }
 
 enum PendingMessage {
-	Assignment(IndirectAssignmentCert, CandidateIndex),
-	Approval(IndirectSignedApprovalVote),
+	Assignment(IndirectAssignmentCertV2, CandidateBitfield),
+	Approval(IndirectSignedApprovalVoteV2),
+}
+
+// The subjects of all the candidates claimed by an assignment.
+fn assignment_subjects(
+	block_hash: Hash,
+	claimed_candidates: &CandidateBitfield,
+	validator: ValidatorIndex,
+) -> Vec<MessageSubject> {
+	claimed_candidates
+		.iter_ones()
+		.map(|candidate_index| MessageSubject(block_hash, candidate_index, validator))
+		.collect()
 }
 
 #[overseer::contextbounds(ApprovalDistribution, prefix = self::overseer)]
@@ -338,10 +375,12 @@ impl State {
 		rng: &mut (impl CryptoRng + Rng),
 	) {
 		match event {
-			NetworkBridgeEvent::PeerConnected(peer_id, role, _, _) => {
+			NetworkBridgeEvent::PeerConnected(peer_id, role, version, _) => {
 				// insert a blank view if none already present
-				gum::trace!(target: LOG_TARGET, ?peer_id, ?role, "Peer connected");
-				self.peer_views.entry(peer_id).or_default();
+				gum::trace!(target: LOG_TARGET, ?peer_id, ?role, ?version, "Peer connected");
+				self.peer_views
+					.entry(peer_id)
+					.or_insert(PeerEntry { view: View::default(), version });
 			},
 			NetworkBridgeEvent::PeerDisconnected(peer_id) => {
 				gum::trace!(target: LOG_TARGET, ?peer_id, "Peer disconnected");
@@ -383,6 +422,9 @@ impl State {
 				});
 			},
 			NetworkBridgeEvent::PeerMessage(peer_id, Versioned::V1(msg)) => {
+				self.process_incoming_peer_message(ctx, metrics, peer_id, msg.into(), rng).await;
+			},
+			NetworkBridgeEvent::PeerMessage(peer_id, Versioned::V2(msg)) => {
 				self.process_incoming_peer_message(ctx, metrics, peer_id, msg, rng).await;
 			},
 		}
@@ -441,9 +483,10 @@ impl State {
 
 		{
 			let sender = ctx.sender();
-			for (peer_id, view) in self.peer_views.iter() {
-				let intersection = view.iter().filter(|h| new_hashes.contains(h));
-				let view_intersection = View::new(intersection.cloned(), view.finalized_number);
+			for (peer_id, peer_entry) in self.peer_views.iter() {
+				let intersection = peer_entry.view.iter().filter(|h| new_hashes.contains(h));
+				let view_intersection =
+					View::new(intersection.cloned(), peer_entry.view.finalized_number);
 				Self::unify_with_peer(
 					sender,
 					metrics,
@@ -451,6 +494,7 @@ impl State {
 					&self.topologies,
 					self.peer_views.len(),
 					*peer_id,
+					peer_entry.version,
 					view_intersection,
 					rng,
 				)
@@ -488,13 +532,13 @@ impl State {
 
 				for (peer_id, message) in to_import {
 					match message {
-						PendingMessage::Assignment(assignment, claimed_index) => {
+						PendingMessage::Assignment(assignment, claimed_candidates) => {
 							self.import_and_circulate_assignment(
 								ctx,
 								metrics,
 								MessageSource::Peer(peer_id),
 								assignment,
-								claimed_index,
+								claimed_candidates,
 								rng,
 							)
 							.await;
@@ -535,6 +579,7 @@ impl State {
 			ctx,
 			&mut self.blocks,
 			&self.topologies,
+			&self.peer_views,
 			|block_entry| block_entry.session == session,
 			|required_routing, local, validator_index| {
 				if *required_routing == RequiredRouting::PendingTopology {
@@ -552,36 +597,34 @@ impl State {
 		ctx: &mut Context,
 		metrics: &Metrics,
 		peer_id: PeerId,
-		msg: protocol_v1::ApprovalDistributionMessage,
+		msg: protocol_v2::ApprovalDistributionMessage,
 		rng: &mut R,
 	) where
 		R: CryptoRng + Rng,
 	{
 		match msg {
-			protocol_v1::ApprovalDistributionMessage::Assignments(assignments) => {
+			protocol_v2::ApprovalDistributionMessage::Assignments(assignments) => {
 				gum::trace!(
 					target: LOG_TARGET,
 					peer_id = %peer_id,
 					num = assignments.len(),
 					"Processing assignments from a peer",
 				);
-				for (assignment, claimed_index) in assignments.into_iter() {
+				for (assignment, claimed_candidates) in assignments.into_iter() {
 					if let Some(pending) = self.pending_known.get_mut(&assignment.block_hash) {
-						let message_subject = MessageSubject(
-							assignment.block_hash,
-							claimed_index,
-							assignment.validator,
-						);
-
 						gum::trace!(
 							target: LOG_TARGET,
 							%peer_id,
-							?message_subject,
+							block_hash = ?assignment.block_hash,
+							validator_index = ?assignment.validator,
+							?claimed_candidates,
 							"Pending assignment",
 						);
 
-						pending
-							.push((peer_id, PendingMessage::Assignment(assignment, claimed_index)));
+						pending.push((
+							peer_id,
+							PendingMessage::Assignment(assignment, claimed_candidates),
+						));
 
 						continue
 					}
@@ -591,13 +634,13 @@ impl State {
 						metrics,
 						MessageSource::Peer(peer_id),
 						assignment,
-						claimed_index,
+						claimed_candidates,
 						rng,
 					)
 					.await;
 				}
 			},
-			protocol_v1::ApprovalDistributionMessage::Approvals(approvals) => {
+			protocol_v2::ApprovalDistributionMessage::Approvals(approvals) => {
 				gum::trace!(
 					target: LOG_TARGET,
 					peer_id = %peer_id,
@@ -606,16 +649,12 @@ impl State {
 				);
 				for approval_vote in approvals.into_iter() {
 					if let Some(pending) = self.pending_known.get_mut(&approval_vote.block_hash) {
-						let message_subject = MessageSubject(
-							approval_vote.block_hash,
-							approval_vote.candidate_index,
-							approval_vote.validator,
-						);
-
 						gum::trace!(
 							target: LOG_TARGET,
 							%peer_id,
-							?message_subject,
+							block_hash = ?approval_vote.block_hash,
+							validator_index = ?approval_vote.validator,
+							candidate_indices = ?approval_vote.candidate_indices,
 							"Pending approval",
 						);
 
@@ -650,9 +689,16 @@ impl State {
 	{
 		gum::trace!(target: LOG_TARGET, ?view, "Peer view change");
 		let finalized_number = view.finalized_number;
-		let old_view =
-			self.peer_views.get_mut(&peer_id).map(|d| std::mem::replace(d, view.clone()));
+		let old_view = self
+			.peer_views
+			.get_mut(&peer_id)
+			.map(|d| std::mem::replace(&mut d.view, view.clone()));
 		let old_finalized_number = old_view.map(|v| v.finalized_number).unwrap_or(0);
+		// Peers which didn't tell us their version are only sent messages of the first one.
+		let protocol_version = self
+			.peer_views
+			.get(&peer_id)
+			.map_or(ValidationVersion::V1.into(), |peer_entry| peer_entry.version);
 
 		// we want to prune every block known_by peer up to (including) view.finalized_number
 		let blocks = &mut self.blocks;
@@ -679,6 +725,7 @@ impl State {
 			&self.topologies,
 			self.peer_views.len(),
 			peer_id,
+			protocol_version,
 			view,
 			rng,
 		)
@@ -719,8 +766,8 @@ impl State {
 		ctx: &mut Context,
 		metrics: &Metrics,
 		source: MessageSource,
-		assignment: IndirectAssignmentCert,
-		claimed_candidate_index: CandidateIndex,
+		assignment: IndirectAssignmentCertV2,
+		claimed_candidates: CandidateBitfield,
 		rng: &mut R,
 	) where
 		R: CryptoRng + Rng,
@@ -747,22 +794,45 @@ impl State {
 			},
 		};
 
-		// compute metadata on the assignment.
-		let message_subject = MessageSubject(block_hash, claimed_candidate_index, validator_index);
+		// compute metadata on the assignment, one subject per claimed candidate.
+		let message_subjects =
+			assignment_subjects(block_hash, &claimed_candidates, validator_index);
 		let message_kind = MessageKind::Assignment;
 
+		if message_subjects.is_empty() {
+			if let Some(peer_id) = source.peer_id() {
+				gum::debug!(
+					target: LOG_TARGET,
+					?peer_id,
+					hash = ?block_hash,
+					?validator_index,
+					"Assignment claims no candidates",
+				);
+				modify_reputation(ctx.sender(), peer_id, COST_INVALID_MESSAGE).await;
+			}
+			return
+		}
+
 		if let Some(peer_id) = source.peer_id() {
 			// check if our knowledge of the peer already contains this assignment
 			match entry.known_by.entry(peer_id) {
 				hash_map::Entry::Occupied(mut peer_knowledge) => {
 					let peer_knowledge = peer_knowledge.get_mut();
-					if peer_knowledge.contains(&message_subject, message_kind) {
+					if message_subjects
+						.iter()
+						.all(|subject| peer_knowledge.contains(subject, message_kind))
+					{
 						// wasn't included before
-						if !peer_knowledge.received.insert(message_subject.clone(), message_kind) {
+						let mut inserted = false;
+						for subject in &message_subjects {
+							inserted |=
+								peer_knowledge.received.insert(subject.clone(), message_kind);
+						}
+						if !inserted {
 							gum::debug!(
 								target: LOG_TARGET,
 								?peer_id,
-								?message_subject,
+								?message_subjects,
 								"Duplicate assignment",
 							);
 							modify_reputation(ctx.sender(), peer_id, COST_DUPLICATE_MESSAGE).await;
@@ -774,7 +844,7 @@ impl State {
 					gum::debug!(
 						target: LOG_TARGET,
 						?peer_id,
-						?message_subject,
+						?message_subjects,
 						"Assignment from a peer is out of view",
 					);
 					modify_reputation(ctx.sender(), peer_id, COST_UNEXPECTED_MESSAGE).await;
@@ -782,11 +852,16 @@ impl State {
 			}
 
 			// if the assignment is known to be valid, reward the peer
-			if entry.knowledge.contains(&message_subject, message_kind) {
+			if message_subjects
+				.iter()
+				.all(|subject| entry.knowledge.contains(subject, message_kind))
+			{
 				modify_reputation(ctx.sender(), peer_id, BENEFIT_VALID_MESSAGE).await;
 				if let Some(peer_knowledge) = entry.known_by.get_mut(&peer_id) {
-					gum::trace!(target: LOG_TARGET, ?peer_id, ?message_subject, "Known assignment");
-					peer_knowledge.received.insert(message_subject, message_kind);
+					gum::trace!(target: LOG_TARGET, ?peer_id, ?message_subjects, "Known assignment");
+					for subject in &message_subjects {
+						peer_knowledge.received.insert(subject.clone(), message_kind);
+					}
 				}
 				return
 			}
@@ -795,7 +870,7 @@ impl State {
 
 			ctx.send_message(ApprovalVotingMessage::CheckAndImportAssignment(
 				assignment.clone(),
-				claimed_candidate_index,
+				claimed_candidates.clone(),
 				tx,
 			))
 			.await;
@@ -813,16 +888,20 @@ impl State {
 			gum::trace!(
 				target: LOG_TARGET,
 				?source,
-				?message_subject,
+				?message_subjects,
 				?result,
 				"Checked assignment",
 			);
 			match result {
 				AssignmentCheckResult::Accepted => {
 					modify_reputation(ctx.sender(), peer_id, BENEFIT_VALID_MESSAGE_FIRST).await;
-					entry.knowledge.known_messages.insert(message_subject.clone(), message_kind);
+					for subject in &message_subjects {
+						entry.knowledge.known_messages.insert(subject.clone(), message_kind);
+					}
 					if let Some(peer_knowledge) = entry.known_by.get_mut(&peer_id) {
-						peer_knowledge.received.insert(message_subject.clone(), message_kind);
+						for subject in &message_subjects {
+							peer_knowledge.received.insert(subject.clone(), message_kind);
+						}
 					}
 				},
 				AssignmentCheckResult::AcceptedDuplicate => {
@@ -830,7 +909,9 @@ impl State {
 					// There is more than one way each validator can be assigned to each core.
 					// cf. https://github.com/paritytech/polkadot/pull/2160#discussion_r557628699
 					if let Some(peer_knowledge) = entry.known_by.get_mut(&peer_id) {
-						peer_knowledge.received.insert(message_subject.clone(), message_kind);
+						for subject in &message_subjects {
+							peer_knowledge.received.insert(subject.clone(), message_kind);
+						}
 					}
 					gum::debug!(
 						target: LOG_TARGET,
@@ -864,18 +945,33 @@ impl State {
 				},
 			}
 		} else {
-			if !entry.knowledge.insert(message_subject.clone(), message_kind) {
+			let mut inserted = false;
+			for subject in &message_subjects {
+				inserted |= entry.knowledge.insert(subject.clone(), message_kind);
+			}
+
+			if !inserted {
 				// if we already imported an assignment, there is no need to distribute it again
-				gum::warn!(
-					target: LOG_TARGET,
-					?message_subject,
-					"Importing locally an already known assignment",
-				);
+				if message_subjects.len() > 1 {
+					// Approval voting asks to distribute an assignment claiming several
+					// candidates once for each of them.
+					gum::debug!(
+						target: LOG_TARGET,
+						?message_subjects,
+						"Importing locally an already known assignment claiming several candidates",
+					);
+				} else {
+					gum::warn!(
+						target: LOG_TARGET,
+						?message_subjects,
+						"Importing locally an already known assignment",
+					);
+				}
 				return
 			} else {
 				gum::debug!(
 					target: LOG_TARGET,
-					?message_subject,
+					?message_subjects,
 					"Importing locally a new assignment",
 				);
 			}
@@ -891,27 +987,47 @@ impl State {
 			t.local_grid_neighbors().required_routing_by_index(validator_index, local)
 		});
 
-		let message_state = match entry.candidates.get_mut(claimed_candidate_index as usize) {
-			Some(candidate_entry) => {
-				// set the approval state for validator_index to Assigned
-				// unless the approval state is set already
+		if let Some(claimed_candidate_index) = claimed_candidates
+			.iter_ones()
+			.find(|candidate_index| entry.candidates.get(*candidate_index as usize).is_none())
+		{
+			gum::warn!(
+				target: LOG_TARGET,
+				hash = ?block_hash,
+				?claimed_candidate_index,
+				"Expected a candidate entry on import_and_circulate_assignment",
+			);
+
+			return
+		}
+
+		// set the approval state for validator_index to Assigned in every claimed candidate
+		// unless the approval state is set already
+		for claimed_candidate_index in claimed_candidates.iter_ones() {
+			if let Some(candidate_entry) =
+				entry.candidates.get_mut(claimed_candidate_index as usize)
+			{
 				candidate_entry.messages.entry(validator_index).or_insert_with(|| MessageState {
 					required_routing,
 					local,
 					random_routing: Default::default(),
-					approval_state: ApprovalState::Assigned(assignment.cert.clone()),
-				})
-			},
-			None => {
-				gum::warn!(
-					target: LOG_TARGET,
-					hash = ?block_hash,
-					?claimed_candidate_index,
-					"Expected a candidate entry on import_and_circulate_assignment",
-				);
+					approval_state: ApprovalState::Assigned(
+						assignment.cert.clone(),
+						claimed_candidates.clone(),
+					),
+				});
+			}
+		}
 
-				return
-			},
+		// The random routing of the assignment is tracked by the lowest claimed candidate.
+		let message_state = match claimed_candidates.first_one().and_then(|candidate_index| {
+			entry
+				.candidates
+				.get_mut(candidate_index as usize)
+				.and_then(|candidate_entry| candidate_entry.messages.get_mut(&validator_index))
+		}) {
+			Some(message_state) => message_state,
+			None => return,
 		};
 
 		// Dispatch the message to all peers in the routing set which
@@ -920,15 +1036,21 @@ impl State {
 		// If the topology isn't known yet (race with networking subsystems)
 		// then messages will be sent when we get it.
 
-		let assignments = vec![(assignment, claimed_candidate_index)];
+		let assignments = vec![(assignment, claimed_candidates.clone())];
 		let n_peers_total = self.peer_views.len();
 		let source_peer = source.peer_id();
+		let expressible_in_v1 = v1_assignment(&assignments[0]).is_some();
+		let peer_views = &self.peer_views;
 
 		let mut peer_filter = move |peer| {
 			if Some(peer) == source_peer.as_ref() {
 				return false
 			}
 
+			if !expressible_in_v1 && peer_is_v1(peer_views, peer) {
+				return false
+			}
+
 			if let Some(true) = topology
 				.as_ref()
 				.map(|t| t.local_grid_neighbors().route_to_peer(required_routing, peer))
@@ -954,7 +1076,9 @@ impl State {
 		for peer in peers.iter() {
 			// we already filtered peers above, so this should always be Some
 			if let Some(peer_knowledge) = entry.known_by.get_mut(peer) {
-				peer_knowledge.sent.insert(message_subject.clone(), message_kind);
+				for subject in &message_subjects {
+					peer_knowledge.sent.insert(subject.clone(), message_kind);
+				}
 			}
 		}
 
@@ -962,7 +1086,7 @@ impl State {
 			gum::trace!(
 				target: LOG_TARGET,
 				?block_hash,
-				?claimed_candidate_index,
+				?claimed_candidates,
 				local = source.peer_id().is_none(),
 				num_peers = peers.len(),
 				"Sending an assignment to peers",
@@ -970,9 +1094,7 @@ impl State {
 
 			ctx.send_message(NetworkBridgeTxMessage::SendValidationMessage(
 				peers,
-				Versioned::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
-					protocol_v1::ApprovalDistributionMessage::Assignments(assignments),
-				)),
+				versioned_assignments_packet(assignments),
 			))
 			.await;
 		}
@@ -983,14 +1105,18 @@ impl State {
 		ctx: &mut Context,
 		metrics: &Metrics,
 		source: MessageSource,
-		vote: IndirectSignedApprovalVote,
+		vote: IndirectSignedApprovalVoteV2,
 	) {
 		let block_hash = vote.block_hash;
 		let validator_index = vote.validator;
-		let candidate_index = vote.candidate_index;
+		let candidate_indices = vote.candidate_indices.clone();
 
 		let entry = match self.blocks.get_mut(&block_hash) {
-			Some(entry) if entry.candidates.get(candidate_index as usize).is_some() => entry,
+			Some(entry)
+				if candidate_indices.iter_ones().all(|candidate_index| {
+					entry.candidates.get(candidate_index as usize).is_some()
+				}) =>
+				entry,
 			_ => {
 				if let Some(peer_id) = source.peer_id() {
 					if !self.recent_outdated_blocks.is_recent_outdated(&block_hash) {
@@ -1001,16 +1127,33 @@ impl State {
 			},
 		};
 
-		// compute metadata on the assignment.
-		let message_subject = MessageSubject(block_hash, candidate_index, validator_index);
+		// compute metadata on the approval, one subject per approved candidate.
+		let message_subjects = assignment_subjects(block_hash, &candidate_indices, validator_index);
 		let message_kind = MessageKind::Approval;
 
+		if message_subjects.is_empty() {
+			if let Some(peer_id) = source.peer_id() {
+				gum::debug!(
+					target: LOG_TARGET,
+					?peer_id,
+					hash = ?block_hash,
+					?validator_index,
+					"Approval covers no candidates",
+				);
+				modify_reputation(ctx.sender(), peer_id, COST_INVALID_MESSAGE).await;
+			}
+			return
+		}
+
 		if let Some(peer_id) = source.peer_id() {
-			if !entry.knowledge.contains(&message_subject, MessageKind::Assignment) {
+			if !message_subjects
+				.iter()
+				.all(|subject| entry.knowledge.contains(subject, MessageKind::Assignment))
+			{
 				gum::debug!(
 					target: LOG_TARGET,
 					?peer_id,
-					?message_subject,
+					?message_subjects,
 					"Unknown approval assignment",
 				);
 				modify_reputation(ctx.sender(), peer_id, COST_UNEXPECTED_MESSAGE).await;
@@ -1021,12 +1164,20 @@ impl State {
 			match entry.known_by.entry(peer_id) {
 				hash_map::Entry::Occupied(mut knowledge) => {
 					let peer_knowledge = knowledge.get_mut();
-					if peer_knowledge.contains(&message_subject, message_kind) {
-						if !peer_knowledge.received.insert(message_subject.clone(), message_kind) {
+					if message_subjects
+						.iter()
+						.all(|subject| peer_knowledge.contains(subject, message_kind))
+					{
+						let mut inserted = false;
+						for subject in &message_subjects {
+							inserted |=
+								peer_knowledge.received.insert(subject.clone(), message_kind);
+						}
+						if !inserted {
 							gum::debug!(
 								target: LOG_TARGET,
 								?peer_id,
-								?message_subject,
+								?message_subjects,
 								"Duplicate approval",
 							);
 
@@ -1039,7 +1190,7 @@ impl State {
 					gum::debug!(
 						target: LOG_TARGET,
 						?peer_id,
-						?message_subject,
+						?message_subjects,
 						"Approval from a peer is out of view",
 					);
 					modify_reputation(ctx.sender(), peer_id, COST_UNEXPECTED_MESSAGE).await;
@@ -1047,11 +1198,16 @@ impl State {
 			}
 
 			// if the approval is known to be valid, reward the peer
-			if entry.knowledge.contains(&message_subject, message_kind) {
-				gum::trace!(target: LOG_TARGET, ?peer_id, ?message_subject, "Known approval");
+			if message_subjects
+				.iter()
+				.all(|subject| entry.knowledge.contains(subject, message_kind))
+			{
+				gum::trace!(target: LOG_TARGET, ?peer_id, ?message_subjects, "Known approval");
 				modify_reputation(ctx.sender(), peer_id, BENEFIT_VALID_MESSAGE).await;
 				if let Some(peer_knowledge) = entry.known_by.get_mut(&peer_id) {
-					peer_knowledge.received.insert(message_subject.clone(), message_kind);
+					for subject in &message_subjects {
+						peer_knowledge.received.insert(subject.clone(), message_kind);
+					}
 				}
 				return
 			}
@@ -1074,7 +1230,7 @@ impl State {
 			gum::trace!(
 				target: LOG_TARGET,
 				?peer_id,
-				?message_subject,
+				?message_subjects,
 				?result,
 				"Checked approval",
 			);
@@ -1082,9 +1238,13 @@ impl State {
 				ApprovalCheckResult::Accepted => {
 					modify_reputation(ctx.sender(), peer_id, BENEFIT_VALID_MESSAGE_FIRST).await;
 
-					entry.knowledge.insert(message_subject.clone(), message_kind);
+					for subject in &message_subjects {
+						entry.knowledge.insert(subject.clone(), message_kind);
+					}
 					if let Some(peer_knowledge) = entry.known_by.get_mut(&peer_id) {
-						peer_knowledge.received.insert(message_subject.clone(), message_kind);
+						for subject in &message_subjects {
+							peer_knowledge.received.insert(subject.clone(), message_kind);
+						}
 					}
 				},
 				ApprovalCheckResult::Bad(error) => {
@@ -1099,18 +1259,23 @@ impl State {
 				},
 			}
 		} else {
-			if !entry.knowledge.insert(message_subject.clone(), message_kind) {
+			let mut inserted = false;
+			for subject in &message_subjects {
+				inserted |= entry.knowledge.insert(subject.clone(), message_kind);
+			}
+
+			if !inserted {
 				// if we already imported an approval, there is no need to distribute it again
 				gum::warn!(
 					target: LOG_TARGET,
-					?message_subject,
+					?message_subjects,
 					"Importing locally an already known approval",
 				);
 				return
 			} else {
 				gum::debug!(
 					target: LOG_TARGET,
-					?message_subject,
+					?message_subjects,
 					"Importing locally a new approval",
 				);
 			}
@@ -1119,76 +1284,89 @@ impl State {
 		// Invariant: to our knowledge, none of the peers except for the `source` know about the approval.
 		metrics.on_approval_imported();
 
-		let required_routing = match entry.candidates.get_mut(candidate_index as usize) {
-			Some(candidate_entry) => {
-				// set the approval state for validator_index to Approved
-				// it should be in assigned state already
-				match candidate_entry.messages.remove(&validator_index) {
-					Some(MessageState {
-						approval_state: ApprovalState::Assigned(cert),
-						required_routing,
-						local,
-						random_routing,
-					}) => {
-						candidate_entry.messages.insert(
-							validator_index,
-							MessageState {
-								approval_state: ApprovalState::Approved(
-									cert,
-									vote.signature.clone(),
-								),
-								required_routing,
-								local,
-								random_routing,
-							},
-						);
+		// set the approval state for validator_index to Approved in every approved candidate,
+		// collecting the required routing of each of them.
+		let mut required_routings = Vec::with_capacity(message_subjects.len());
+		for candidate_index in candidate_indices.iter_ones() {
+			let candidate_entry = match entry.candidates.get_mut(candidate_index as usize) {
+				Some(candidate_entry) => candidate_entry,
+				None => {
+					gum::warn!(
+						target: LOG_TARGET,
+						hash = ?block_hash,
+						?candidate_index,
+						?validator_index,
+						"Expected a candidate entry on import_and_circulate_approval",
+					);
 
-						required_routing
-					},
-					Some(_) => {
-						unreachable!(
-							"we only insert it after the metadata, checked the metadata above; qed"
-						);
-					},
-					None => {
-						// this would indicate a bug in approval-voting
-						gum::warn!(
-							target: LOG_TARGET,
-							hash = ?block_hash,
-							?candidate_index,
-							?validator_index,
-							"Importing an approval we don't have an assignment for",
-						);
+					return
+				},
+			};
 
-						return
-					},
-				}
-			},
-			None => {
-				gum::warn!(
-					target: LOG_TARGET,
-					hash = ?block_hash,
-					?candidate_index,
-					?validator_index,
-					"Expected a candidate entry on import_and_circulate_approval",
-				);
+			// it should be in assigned state already
+			match candidate_entry.messages.remove(&validator_index) {
+				Some(MessageState {
+					approval_state: ApprovalState::Assigned(cert, claimed_candidates),
+					required_routing,
+					local,
+					random_routing,
+				}) => {
+					candidate_entry.messages.insert(
+						validator_index,
+						MessageState {
+							approval_state: ApprovalState::Approved(
+								cert,
+								claimed_candidates,
+								candidate_indices.clone(),
+								vote.signature.clone(),
+							),
+							required_routing,
+							local,
+							random_routing,
+						},
+					);
 
-				return
-			},
-		};
+					required_routings.push(required_routing);
+				},
+				Some(_) => {
+					unreachable!(
+						"we only insert it after the metadata, checked the metadata above; qed"
+					);
+				},
+				None => {
+					// this would indicate a bug in approval-voting
+					gum::warn!(
+						target: LOG_TARGET,
+						hash = ?block_hash,
+						?candidate_index,
+						?validator_index,
+						"Importing an approval we don't have an assignment for",
+					);
 
-		// Dispatch a ApprovalDistributionV1Message::Approval(vote)
+					return
+				},
+			}
+		}
+
+		// Dispatch a ApprovalDistributionMessage::Approval(vote)
 		// to all peers required by the topology, with the exception of the source peer.
 
 		let topology = self.topologies.get_topology(entry.session);
 		let source_peer = source.peer_id();
+		let expressible_in_v1 = v1_approval(&vote).is_some();
+		let peer_views = &self.peer_views;
 
-		let message_subject = &message_subject;
+		let message_subjects = &message_subjects;
+		let required_routings = &required_routings;
 		let peer_filter = move |peer, knowledge: &PeerKnowledge| {
 			if Some(peer) == source_peer.as_ref() {
 				return false
 			}
 
+			if !expressible_in_v1 && peer_is_v1(peer_views, peer) {
+				return false
+			}
+
 			// Here we're leaning on a few behaviors of assignment propagation:
 			//   1. At this point, the only peer we're aware of which has the approval
 			//      message is the source peer.
@@ -1198,9 +1376,17 @@ impl State {
 			//      the assignment to all aware peers in the required routing _except_ the original
 			//      source of the assignment. Hence the `in_topology_check`.
 			//   3. Any randomly selected peers have been sent the assignment already.
-			let in_topology = topology
-				.map_or(false, |t| t.local_grid_neighbors().route_to_peer(required_routing, peer));
-			in_topology || knowledge.sent.contains(message_subject, MessageKind::Assignment)
+			//
+			// An approval covering several candidates is only sent to peers which have been
+			// sent the assignments for all of them.
+			message_subjects.iter().zip(required_routings.iter()).all(
+				|(subject, required_routing)| {
+					let in_topology = topology.map_or(false, |t| {
+						t.local_grid_neighbors().route_to_peer(*required_routing, peer)
+					});
+					in_topology || knowledge.sent.contains(subject, MessageKind::Assignment)
+				},
+			)
 		};
 
 		let peers = entry
@@ -1211,11 +1397,13 @@ impl State {
 			.cloned()
 			.collect::<Vec<_>>();
 
-		// Add the metadata of the assignment to the knowledge of each peer.
+		// Add the metadata of the approval to the knowledge of each peer.
 		for peer in peers.iter() {
 			// we already filtered peers above, so this should always be Some
 			if let Some(entry) = entry.known_by.get_mut(peer) {
-				entry.sent.insert(message_subject.clone(), message_kind);
+				for subject in message_subjects {
+					entry.sent.insert(subject.clone(), message_kind);
+				}
 			}
 		}
 
@@ -1224,7 +1412,7 @@ impl State {
 			gum::trace!(
 				target: LOG_TARGET,
 				?block_hash,
-				?candidate_index,
+				?candidate_indices,
 				local = source.peer_id().is_none(),
 				num_peers = peers.len(),
 				"Sending an approval to peers",
@@ -1232,19 +1420,18 @@ impl State {
 
 			ctx.send_message(NetworkBridgeTxMessage::SendValidationMessage(
 				peers,
-				Versioned::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
-					protocol_v1::ApprovalDistributionMessage::Approvals(approvals),
-				)),
+				versioned_approvals_packet(approvals),
 			))
 			.await;
 		}
 	}
 
-	/// Retrieve approval signatures from state for the given relay block/indices:
+	/// Retrieve approval signatures from state for the given relay block/indices, together
+	/// with all the candidates of the block each signature covers:
 	fn get_approval_signatures(
 		&mut self,
 		indices: HashSet<(Hash, CandidateIndex)>,
-	) -> HashMap<ValidatorIndex, ValidatorSignature> {
+	) -> HashMap<ValidatorIndex, (Hash, Vec<CandidateIndex>, ValidatorSignature)> {
 		let mut all_sigs = HashMap::new();
 		for (hash, index) in indices {
 			let _span = self
@@ -1282,8 +1469,11 @@ impl State {
 			let sigs =
 				candidate_entry.messages.iter().filter_map(|(validator_index, message_state)| {
 					match &message_state.approval_state {
-						ApprovalState::Approved(_, sig) => Some((*validator_index, sig.clone())),
-						ApprovalState::Assigned(_) => None,
+						ApprovalState::Approved(_, _, approved_candidates, sig) => Some((
+							*validator_index,
+							(hash, approved_candidates.iter_ones().collect(), sig.clone()),
+						)),
+						ApprovalState::Assigned(_, _) => None,
 					}
 				});
 			all_sigs.extend(sigs);
@@ -1298,6 +1488,7 @@ impl State {
 		topologies: &SessionGridTopologies,
 		total_peers: usize,
 		peer_id: PeerId,
+		protocol_version: ProtocolVersion,
 		view: View,
 		rng: &mut (impl CryptoRng + Rng),
 	) {
@@ -1332,6 +1523,24 @@ impl State {
 					entry.candidates.iter_mut().enumerate().flat_map(|(c_i, c)| {
 						c.messages.iter_mut().map(move |(k, v)| (c_i as _, k, v))
 					}) {
+					let claimed_candidates = message_state.approval_state.claimed_candidates();
+					let assignment_message = (
+						IndirectAssignmentCertV2 {
+							block_hash: block,
+							validator: *validator,
+							cert: message_state.approval_state.assignment_cert().clone(),
+						},
+						claimed_candidates.clone(),
+					);
+
+					// Peers of the first version of the protocol can't be sent assignments which
+					// can't be expressed in it, nor the approvals which depend on them.
+					if protocol_version == ValidationVersion::V1.into() &&
+						v1_assignment(&assignment_message).is_none()
+					{
+						continue
+					}
+
 					// Propagate the message to all peers in the required routing set OR
 					// randomly sample peers.
 					{
@@ -1359,37 +1568,45 @@ impl State {
 
 					let message_subject = MessageSubject(block, candidate_index, *validator);
 
-					let assignment_message = (
-						IndirectAssignmentCert {
+					let approval_message = message_state
+						.approval_state
+						.approval()
+						.map(|(approved_candidates, signature)| IndirectSignedApprovalVoteV2 {
 							block_hash: block,
 							validator: *validator,
-							cert: message_state.approval_state.assignment_cert().clone(),
-						},
-						candidate_index,
-					);
-
-					let approval_message =
-						message_state.approval_state.approval_signature().map(|signature| {
-							IndirectSignedApprovalVote {
-								block_hash: block,
-								validator: *validator,
-								candidate_index,
-								signature,
-							}
+							candidate_indices: approved_candidates.clone(),
+							signature,
+						})
+						// Peers of the first version can't be sent approvals covering several
+						// candidates either.
+						.filter(|approval| {
+							protocol_version != ValidationVersion::V1.into() ||
+								v1_approval(approval).is_some()
 						});
 
+					// An assignment claiming several candidates is sent only once.
 					if !peer_knowledge.contains(&message_subject, MessageKind::Assignment) {
-						peer_knowledge
-							.sent
-							.insert(message_subject.clone(), MessageKind::Assignment);
+						for subject in assignment_subjects(block, claimed_candidates, *validator) {
+							peer_knowledge.sent.insert(subject, MessageKind::Assignment);
+						}
 						assignments_to_send.push(assignment_message);
 					}
 
+					// An approval covering several candidates is sent only once, after the
+					// assignments for all of them.
 					if let Some(approval_message) = approval_message {
-						if !peer_knowledge.contains(&message_subject, MessageKind::Approval) {
-							peer_knowledge
-								.sent
-								.insert(message_subject.clone(), MessageKind::Approval);
+						let approval_subjects = assignment_subjects(
+							block,
+							&approval_message.candidate_indices,
+							*validator,
+						);
+						if !peer_knowledge.contains(&message_subject, MessageKind::Approval) &&
+							approval_subjects.iter().all(|subject| {
+								peer_knowledge.contains(subject, MessageKind::Assignment)
+							}) {
+							for subject in approval_subjects {
+								peer_knowledge.sent.insert(subject, MessageKind::Approval);
+							}
 							approvals_to_send.push(approval_message);
 						}
 					}
@@ -1456,6 +1673,7 @@ impl State {
 			ctx,
 			&mut self.blocks,
 			&self.topologies,
+			&self.peer_views,
 			|block_entry| {
 				let block_age = max_age - block_entry.number;
 
@@ -1483,6 +1701,7 @@ impl State {
 			ctx,
 			&mut self.blocks,
 			&self.topologies,
+			&self.peer_views,
 			|block_entry| {
 				// Ramp up aggression only for the very oldest block(s).
 				// Approval voting can get stuck on a single block preventing
@@ -1542,6 +1761,7 @@ async fn adjust_required_routing_and_propagate<Context, BlockFilter, RoutingModi
 	ctx: &mut Context,
 	blocks: &mut HashMap<Hash, BlockEntry>,
 	topologies: &SessionGridTopologies,
+	peer_views: &HashMap<PeerId, PeerEntry>,
 	block_filter: BlockFilter,
 	routing_modifier: RoutingModifier,
 ) where
@@ -1579,23 +1799,28 @@ async fn adjust_required_routing_and_propagate<Context, BlockFilter, RoutingModi
 			// Propagate the message to all peers in the required routing set.
 			let message_subject = MessageSubject(*block_hash, candidate_index, *validator);
 
+			let claimed_candidates = message_state.approval_state.claimed_candidates();
 			let assignment_message = (
-				IndirectAssignmentCert {
+				IndirectAssignmentCertV2 {
 					block_hash: *block_hash,
 					validator: *validator,
 					cert: message_state.approval_state.assignment_cert().clone(),
 				},
-				candidate_index,
+				claimed_candidates.clone(),
 			);
 			let approval_message =
-				message_state.approval_state.approval_signature().map(|signature| {
-					IndirectSignedApprovalVote {
+				message_state.approval_state.approval().map(|(approved_candidates, signature)| {
+					IndirectSignedApprovalVoteV2 {
 						block_hash: *block_hash,
 						validator: *validator,
-						candidate_index,
+						candidate_indices: approved_candidates.clone(),
 						signature,
 					}
 				});
+			let expressible_in_v1 = v1_assignment(&assignment_message).is_some();
+			let approval_expressible_in_v1 = approval_message
+				.as_ref()
+				.map_or(true, |approval| v1_approval(approval).is_some());
 
 			for (peer, peer_knowledge) in &mut block_entry.known_by {
 				if !topology
@@ -1605,17 +1830,42 @@ async fn adjust_required_routing_and_propagate<Context, BlockFilter, RoutingModi
 					continue
 				}
 
+				// Peers of the first version of the protocol can't be sent assignments which
+				// can't be expressed in it, nor the approvals which depend on them.
+				if !expressible_in_v1 && peer_is_v1(peer_views, peer) {
+					continue
+				}
+
+				// An assignment claiming several candidates is sent only once.
 				if !peer_knowledge.contains(&message_subject, MessageKind::Assignment) {
-					peer_knowledge.sent.insert(message_subject.clone(), MessageKind::Assignment);
+					for subject in assignment_subjects(*block_hash, claimed_candidates, *validator)
+					{
+						peer_knowledge.sent.insert(subject, MessageKind::Assignment);
+					}
 					peer_assignments
 						.entry(*peer)
 						.or_insert_with(Vec::new)
 						.push(assignment_message.clone());
 				}
 
-				if let Some(approval_message) = approval_message.as_ref() {
-					if !peer_knowledge.contains(&message_subject, MessageKind::Approval) {
-						peer_knowledge.sent.insert(message_subject.clone(), MessageKind::Approval);
+				// An approval covering several candidates is sent only once, after the
+				// assignments for all of them, and only to peers of the second version.
+				if let Some(approval_message) = approval_message
+					.as_ref()
+					.filter(|_| approval_expressible_in_v1 || !peer_is_v1(peer_views, peer))
+				{
+					let approval_subjects = assignment_subjects(
+						*block_hash,
+						&approval_message.candidate_indices,
+						*validator,
+					);
+					if !peer_knowledge.contains(&message_subject, MessageKind::Approval) &&
+						approval_subjects.iter().all(|subject| {
+							peer_knowledge.contains(subject, MessageKind::Assignment)
+						}) {
+						for subject in approval_subjects {
+							peer_knowledge.sent.insert(subject, MessageKind::Approval);
+						}
 						peer_approvals
 							.entry(*peer)
 							.or_insert_with(Vec::new)
@@ -1657,11 +1907,20 @@ async fn modify_reputation(
 impl ApprovalDistribution {
 	/// Create a new instance of the [`ApprovalDistribution`] subsystem.
 	pub fn new(metrics: Metrics) -> Self {
-		Self { metrics }
+		Self { metrics, gossip_topology: Arc::new(GridTopology) }
+	}
+
+	/// Use the given gossip topology for assignments and approvals, instead of the grid.
+	pub fn with_gossip_topology(mut self, gossip_topology: Arc<dyn GossipTopology>) -> Self {
+		self.gossip_topology = gossip_topology;
+		self
 	}
 
 	async fn run<Context>(self, ctx: Context) {
-		let mut state = State::default();
+		let mut state = State {
+			topologies: SessionGridTopologies::with_gossip_topology(self.gossip_topology.clone()),
+			..Default::default()
+		};
 
 		// According to the docs of `rand`, this is a ChaCha12 RNG in practice
 		// and will always be chosen for strong performance and security properties.
@@ -1723,7 +1982,7 @@ impl ApprovalDistribution {
 			ApprovalDistributionMessage::NewBlocks(metas) => {
 				state.handle_new_blocks(ctx, metrics, metas, rng).await;
 			},
-			ApprovalDistributionMessage::DistributeAssignment(cert, candidate_index) => {
+			ApprovalDistributionMessage::DistributeAssignment(cert, claimed_candidates) => {
 				let _span = state
 					.spans
 					.get(&cert.block_hash)
@@ -1734,9 +1993,9 @@ impl ApprovalDistribution {
 
 				gum::debug!(
 					target: LOG_TARGET,
-					"Distributing our assignment on candidate (block={}, index={})",
+					"Distributing our assignment on candidates (block={}, indices={:?})",
 					cert.block_hash,
-					candidate_index,
+					claimed_candidates,
 				);
 
 				state
@@ -1745,7 +2004,7 @@ impl ApprovalDistribution {
 						&metrics,
 						MessageSource::Local,
 						cert,
-						candidate_index,
+						claimed_candidates,
 						rng,
 					)
 					.await;
@@ -1761,9 +2020,9 @@ impl ApprovalDistribution {
 
 				gum::debug!(
 					target: LOG_TARGET,
-					"Distributing our approval vote on candidate (block={}, index={})",
+					"Distributing our approval vote on candidates (block={}, indices={:?})",
 					vote.block_hash,
-					vote.candidate_index,
+					vote.candidate_indices,
 				);
 
 				state
@@ -1811,23 +2070,93 @@ const fn ensure_size_not_zero(size: usize) -> usize {
 /// configuration.
 pub const MAX_ASSIGNMENT_BATCH_SIZE: usize = ensure_size_not_zero(
 	MAX_NOTIFICATION_SIZE as usize /
-		std::mem::size_of::<(IndirectAssignmentCert, CandidateIndex)>() /
+		std::mem::size_of::<(IndirectAssignmentCertV2, CandidateBitfield)>() /
 		3,
 );
 
 /// The maximum amount of approvals per batch is 33% of maximum allowed by protocol.
 pub const MAX_APPROVAL_BATCH_SIZE: usize = ensure_size_not_zero(
-	MAX_NOTIFICATION_SIZE as usize / std::mem::size_of::<IndirectSignedApprovalVote>() / 3,
+	MAX_NOTIFICATION_SIZE as usize / std::mem::size_of::<IndirectSignedApprovalVoteV2>() / 3,
 );
 
+/// Build the network message carrying the given approvals.
+///
+/// Approvals which all cover a single candidate are sent in the first version of the protocol,
+/// otherwise the second version is used. Only peers of the second version must be sent the latter,
+/// the network bridge leaves them out for the others.
+fn versioned_approvals_packet(
+	approvals: Vec<IndirectSignedApprovalVoteV2>,
+) -> VersionedValidationProtocol {
+	let v1_approvals = approvals.iter().map(v1_approval).collect::<Option<Vec<_>>>();
+
+	match v1_approvals {
+		Some(v1_approvals) => Versioned::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
+			protocol_v1::ApprovalDistributionMessage::Approvals(v1_approvals),
+		)),
+		None => Versioned::V2(protocol_v2::ValidationProtocol::ApprovalDistribution(
+			protocol_v2::ApprovalDistributionMessage::Approvals(approvals),
+		)),
+	}
+}
+
+/// Whether the peer negotiated the first version of the validation protocol.
+///
+/// Peers which didn't tell us their version are treated as such.
+fn peer_is_v1(peer_views: &HashMap<PeerId, PeerEntry>, peer: &PeerId) -> bool {
+	peer_views
+		.get(peer)
+		.map_or(true, |peer_entry| peer_entry.version == ValidationVersion::V1.into())
+}
+
+/// Express the assignment in the first version of the protocol, which only supports
+/// certificates of the first version claiming a single candidate.
+fn v1_assignment(
+	(cert, claimed_candidates): &(IndirectAssignmentCertV2, CandidateBitfield),
+) -> Option<(IndirectAssignmentCert, CandidateIndex)> {
+	if claimed_candidates.count_ones() != 1 {
+		return None
+	}
+
+	Some((IndirectAssignmentCert::try_from(cert.clone()).ok()?, claimed_candidates.first_one()?))
+}
+
+/// Express the approval in the first version of the protocol, which only supports approvals of
+/// a single candidate.
+fn v1_approval(approval: &IndirectSignedApprovalVoteV2) -> Option<IndirectSignedApprovalVote> {
+	IndirectSignedApprovalVote::try_from(approval.clone()).ok()
+}
+
+/// Build the network message carrying the given assignments.
+///
+/// Assignments which can all be expressed in the first version of the protocol are sent as such,
+/// otherwise the second version is used. Only peers of the second version must be sent the latter,
+/// the network bridge leaves them out for the others.
+fn versioned_assignments_packet(
+	assignments: Vec<(IndirectAssignmentCertV2, CandidateBitfield)>,
+) -> VersionedValidationProtocol {
+	let v1_assignments = assignments.iter().map(v1_assignment).collect::<Option<Vec<_>>>();
+
+	match v1_assignments {
+		Some(v1_assignments) =>
+			Versioned::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
+				protocol_v1::ApprovalDistributionMessage::Assignments(v1_assignments),
+			)),
+		None => Versioned::V2(protocol_v2::ValidationProtocol::ApprovalDistribution(
+			protocol_v2::ApprovalDistributionMessage::Assignments(assignments),
+		)),
+	}
+}
+
 /// Send assignments while honoring the `max_notification_size` of the protocol.
 ///
 /// Splitting the messages into multiple notifications allows more granular processing at the
 /// destination, such that the subsystem doesn't get stuck for long processing a batch
 /// of assignments and can `select!` other tasks.
+///
+/// Peers of the first version of the protocol must only be given assignments expressible in it.
 pub(crate) async fn send_assignments_batched(
 	sender: &mut impl overseer::ApprovalDistributionSenderTrait,
-	assignments: Vec<(IndirectAssignmentCert, CandidateIndex)>,
+	assignments: Vec<(IndirectAssignmentCertV2, CandidateBitfield)>,
 	peer: PeerId,
 ) {
 	let mut batches = assignments.into_iter().peekable();
@@ -1838,18 +2167,18 @@ pub(crate) async fn send_assignments_batched(
 		sender
 			.send_message(NetworkBridgeTxMessage::SendValidationMessage(
 				vec![peer],
-				Versioned::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
-					protocol_v1::ApprovalDistributionMessage::Assignments(batch),
-				)),
+				versioned_assignments_packet(batch),
 			))
 			.await;
 	}
 }
 
 /// Send approvals while honoring the `max_notification_size` of the protocol.
+///
+/// Peers of the first version of the protocol must only be given approvals expressible in it.
 pub(crate) async fn send_approvals_batched(
 	sender: &mut impl overseer::ApprovalDistributionSenderTrait,
-	approvals: Vec<IndirectSignedApprovalVote>,
+	approvals: Vec<IndirectSignedApprovalVoteV2>,
 	peer: PeerId,
 ) {
 	let mut batches = approvals.into_iter().peekable();
@@ -1860,9 +2189,7 @@ pub(crate) async fn send_approvals_batched(
 		sender
 			.send_message(NetworkBridgeTxMessage::SendValidationMessage(
 				vec![peer],
-				Versioned::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
-					protocol_v1::ApprovalDistributionMessage::Approvals(batch),
-				)),
+				versioned_approvals_packet(batch),
 			))
 			.await;
 	}
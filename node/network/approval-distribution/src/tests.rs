@@ -24,12 +24,13 @@ use polkadot_node_network_protocol::{
 	view, ObservedRole,
 };
 use polkadot_node_primitives::approval::{
-	AssignmentCertKind, VrfOutput, VrfProof, VrfSignature, RELAY_VRF_MODULO_CONTEXT,
+	AssignmentCert, AssignmentCertKind, AssignmentCertKindV2, CoreBitfield, VrfOutput, VrfProof,
+	VrfSignature, RELAY_VRF_MODULO_CONTEXT,
 };
 use polkadot_node_subsystem::messages::{network_bridge_event, AllMessages, ApprovalCheckError};
 use polkadot_node_subsystem_test_helpers as test_helpers;
 use polkadot_node_subsystem_util::TimeoutExt as _;
-use polkadot_primitives::{AuthorityDiscoveryId, BlakeTwo256, HashT};
+use polkadot_primitives::{AuthorityDiscoveryId, BlakeTwo256, CoreIndex, HashT};
 use polkadot_primitives_test_helpers::dummy_signature;
 use rand::SeedableRng;
 use sp_authority_discovery::AuthorityPair as AuthorityDiscoveryPair;
@@ -216,13 +217,22 @@ async fn setup_peer_with_view(
 	virtual_overseer: &mut VirtualOverseer,
 	peer_id: &PeerId,
 	view: View,
+) {
+	setup_peer_with_view_and_version(virtual_overseer, peer_id, view, ValidationVersion::V1).await
+}
+
+async fn setup_peer_with_view_and_version(
+	virtual_overseer: &mut VirtualOverseer,
+	peer_id: &PeerId,
+	view: View,
+	version: ValidationVersion,
 ) {
 	overseer_send(
 		virtual_overseer,
 		ApprovalDistributionMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerConnected(
 			peer_id.clone(),
 			ObservedRole::Full,
-			ValidationVersion::V1.into(),
+			version.into(),
 			None,
 		)),
 	)
@@ -252,6 +262,21 @@ async fn send_message_from_peer(
 	.await;
 }
 
+async fn send_v2_message_from_peer(
+	virtual_overseer: &mut VirtualOverseer,
+	peer_id: &PeerId,
+	msg: protocol_v2::ApprovalDistributionMessage,
+) {
+	overseer_send(
+		virtual_overseer,
+		ApprovalDistributionMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerMessage(
+			peer_id.clone(),
+			Versioned::V2(msg),
+		)),
+	)
+	.await;
+}
+
 fn fake_assignment_cert(block_hash: Hash, validator: ValidatorIndex) -> IndirectAssignmentCert {
 	let ctx = schnorrkel::signing_context(RELAY_VRF_MODULO_CONTEXT);
 	let msg = b"WhenParachains?";
@@ -270,6 +295,16 @@ fn fake_assignment_cert(block_hash: Hash, validator: ValidatorIndex) -> Indirect
 	}
 }
 
+fn fake_compact_assignment_cert(
+	block_hash: Hash,
+	validator: ValidatorIndex,
+	core_bitfield: CoreBitfield,
+) -> IndirectAssignmentCertV2 {
+	let mut cert: IndirectAssignmentCertV2 = fake_assignment_cert(block_hash, validator).into();
+	cert.cert.kind = AssignmentCertKindV2::RelayVRFModuloCompact { core_bitfield };
+	cert
+}
+
 async fn expect_reputation_change(
 	virtual_overseer: &mut VirtualOverseer,
 	peer_id: &PeerId,
@@ -335,10 +370,11 @@ fn try_import_the_same_assignment() {
 			overseer_recv(overseer).await,
 			AllMessages::ApprovalVoting(ApprovalVotingMessage::CheckAndImportAssignment(
 				assignment,
-				0u32,
+				claimed_candidates,
 				tx,
 			)) => {
-				assert_eq!(assignment, cert);
+				assert_eq!(assignment, cert.clone().into());
+				assert_eq!(claimed_candidates, 0u32.into());
 				tx.send(AssignmentCheckResult::Accepted).unwrap();
 			}
 		);
@@ -373,6 +409,85 @@ fn try_import_the_same_assignment() {
 	});
 }
 
+/// A compact assignment is checked once for all the candidates it claims and
+/// is circulated in the second version of the protocol.
+#[test]
+fn import_compact_assignment_claiming_several_candidates() {
+	let peer_a = PeerId::random();
+	let peer_b = PeerId::random();
+	let parent_hash = Hash::repeat_byte(0xFF);
+	let hash = Hash::repeat_byte(0xAA);
+
+	let _ = test_harness(State::default(), |mut virtual_overseer| async move {
+		let overseer = &mut virtual_overseer;
+		// setup peers
+		setup_peer_with_view_and_version(overseer, &peer_a, view![hash], ValidationVersion::V2)
+			.await;
+		setup_peer_with_view_and_version(overseer, &peer_b, view![hash], ValidationVersion::V2)
+			.await;
+
+		// new block `hash` with 2 candidates
+		let meta = BlockApprovalMeta {
+			hash,
+			parent_hash,
+			number: 2,
+			candidates: vec![Default::default(); 2],
+			slot: 1.into(),
+			session: 1,
+		};
+		let msg = ApprovalDistributionMessage::NewBlocks(vec![meta]);
+		overseer_send(overseer, msg).await;
+
+		// send the compact assignment related to `hash`
+		let validator_index = ValidatorIndex(0);
+		let core_bitfield = CoreBitfield::try_from(vec![CoreIndex(0), CoreIndex(1)]).unwrap();
+		let cert = fake_compact_assignment_cert(hash, validator_index, core_bitfield);
+		let claimed_candidates = CandidateBitfield::try_from(vec![0u32, 1]).unwrap();
+		let assignments = vec![(cert.clone(), claimed_candidates.clone())];
+
+		let msg = protocol_v2::ApprovalDistributionMessage::Assignments(assignments.clone());
+		send_v2_message_from_peer(overseer, &peer_a, msg.clone()).await;
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::ApprovalVoting(ApprovalVotingMessage::CheckAndImportAssignment(
+				assignment,
+				claimed,
+				tx,
+			)) => {
+				assert_eq!(assignment, cert);
+				assert_eq!(claimed, claimed_candidates);
+				tx.send(AssignmentCheckResult::Accepted).unwrap();
+			}
+		);
+
+		expect_reputation_change(overseer, &peer_a, BENEFIT_VALID_MESSAGE_FIRST).await;
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridgeTx(NetworkBridgeTxMessage::SendValidationMessage(
+				peers,
+				Versioned::V2(protocol_v2::ValidationProtocol::ApprovalDistribution(
+					protocol_v2::ApprovalDistributionMessage::Assignments(sent_assignments)
+				))
+			)) => {
+				assert_eq!(peers, vec![peer_b]);
+				assert_eq!(sent_assignments, assignments);
+			}
+		);
+
+		// the assignment is known for both candidates, sending it again is a duplicate
+		send_v2_message_from_peer(overseer, &peer_a, msg.clone()).await;
+		expect_reputation_change(overseer, &peer_a, COST_DUPLICATE_MESSAGE).await;
+
+		// the peer we sent it to may send it back once
+		send_v2_message_from_peer(overseer, &peer_b, msg).await;
+
+		assert!(overseer.recv().timeout(TIMEOUT).await.is_none(), "no message should be sent");
+		virtual_overseer
+	});
+}
+
 /// <https://github.com/paritytech/polkadot/pull/2160#discussion_r547594835>
 ///
 /// 1. Send a view update that removes block B from their view.
@@ -427,8 +542,8 @@ fn spam_attack_results_in_negative_reputation_change() {
 					claimed_candidate_index,
 					tx,
 				)) => {
-					assert_eq!(assignment, assignments[i].0);
-					assert_eq!(claimed_candidate_index, assignments[i].1);
+					assert_eq!(assignment, assignments[i].0.clone().into());
+					assert_eq!(claimed_candidate_index, assignments[i].1.into());
 					tx.send(AssignmentCheckResult::Accepted).unwrap();
 				}
 			);
@@ -492,7 +607,10 @@ fn peer_sending_us_the_same_we_just_sent_them_is_ok() {
 		let cert = fake_assignment_cert(hash, validator_index);
 		overseer_send(
 			overseer,
-			ApprovalDistributionMessage::DistributeAssignment(cert.clone(), candidate_index),
+			ApprovalDistributionMessage::DistributeAssignment(
+				cert.clone().into(),
+				candidate_index.into(),
+			),
 		)
 		.await;
 
@@ -537,6 +655,453 @@ fn peer_sending_us_the_same_we_just_sent_them_is_ok() {
 	});
 }
 
+/// Approval voting asks to distribute our compact assignment once for each claimed candidate,
+/// the assignment is sent only once to each peer.
+#[test]
+fn local_compact_assignment_is_sent_once() {
+	let parent_hash = Hash::repeat_byte(0xFF);
+	let peer_a = PeerId::random();
+	let hash = Hash::repeat_byte(0xAA);
+
+	let _ = test_harness(State::default(), |mut virtual_overseer| async move {
+		let overseer = &mut virtual_overseer;
+		let peer = &peer_a;
+		setup_peer_with_view_and_version(overseer, peer, view![], ValidationVersion::V2).await;
+
+		// new block `hash` with 2 candidates
+		let meta = BlockApprovalMeta {
+			hash,
+			parent_hash,
+			number: 1,
+			candidates: vec![Default::default(); 2],
+			slot: 1.into(),
+			session: 1,
+		};
+		let msg = ApprovalDistributionMessage::NewBlocks(vec![meta]);
+		overseer_send(overseer, msg).await;
+
+		// import our compact assignment related to `hash`, once for each candidate
+		let validator_index = ValidatorIndex(0);
+		let core_bitfield = CoreBitfield::try_from(vec![CoreIndex(0), CoreIndex(1)]).unwrap();
+		let cert = fake_compact_assignment_cert(hash, validator_index, core_bitfield);
+		let claimed_candidates = CandidateBitfield::try_from(vec![0u32, 1]).unwrap();
+		for _ in 0..2 {
+			overseer_send(
+				overseer,
+				ApprovalDistributionMessage::DistributeAssignment(
+					cert.clone(),
+					claimed_candidates.clone(),
+				),
+			)
+			.await;
+		}
+
+		// update peer view to include the hash
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerViewChange(
+				peer.clone(),
+				view![hash],
+			)),
+		)
+		.await;
+
+		// we should send them the assignment once
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridgeTx(NetworkBridgeTxMessage::SendValidationMessage(
+				peers,
+				Versioned::V2(protocol_v2::ValidationProtocol::ApprovalDistribution(
+					protocol_v2::ApprovalDistributionMessage::Assignments(assignments)
+				))
+			)) => {
+				assert_eq!(peers.len(), 1);
+				assert_eq!(assignments, vec![(cert.clone(), claimed_candidates.clone())]);
+			}
+		);
+
+		// the approval of the second claimed candidate follows the assignment
+		let approval = IndirectSignedApprovalVote {
+			block_hash: hash,
+			candidate_index: 1,
+			validator: validator_index,
+			signature: dummy_signature(),
+		};
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::DistributeApproval(approval.clone().into()),
+		)
+		.await;
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridgeTx(NetworkBridgeTxMessage::SendValidationMessage(
+				peers,
+				Versioned::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
+					protocol_v1::ApprovalDistributionMessage::Approvals(approvals)
+				))
+			)) => {
+				assert_eq!(peers.len(), 1);
+				assert_eq!(approvals, vec![approval]);
+			}
+		);
+
+		assert!(overseer.recv().timeout(TIMEOUT).await.is_none(), "no message should be sent");
+		virtual_overseer
+	});
+}
+
+/// Assignments which can't be expressed in the first version of the protocol are neither sent to
+/// peers of that version nor considered known by them, also when sent in a batch with others.
+#[test]
+fn compact_assignment_is_not_sent_to_v1_peers() {
+	let parent_hash = Hash::repeat_byte(0xFF);
+	let peer_a = PeerId::random();
+	let peer_b = PeerId::random();
+	let peer_c = PeerId::random();
+	let hash = Hash::repeat_byte(0xAA);
+
+	let _ = test_harness(State::default(), |mut virtual_overseer| async move {
+		let overseer = &mut virtual_overseer;
+		setup_peer_with_view(overseer, &peer_a, view![hash]).await;
+		setup_peer_with_view_and_version(overseer, &peer_b, view![hash], ValidationVersion::V2)
+			.await;
+		setup_peer_with_view(overseer, &peer_c, view![]).await;
+
+		// new block `hash` with 2 candidates
+		let meta = BlockApprovalMeta {
+			hash,
+			parent_hash,
+			number: 1,
+			candidates: vec![Default::default(); 2],
+			slot: 1.into(),
+			session: 1,
+		};
+		let msg = ApprovalDistributionMessage::NewBlocks(vec![meta]);
+		overseer_send(overseer, msg).await;
+
+		// import our compact assignment claiming both candidates
+		let core_bitfield = CoreBitfield::try_from(vec![CoreIndex(0), CoreIndex(1)]).unwrap();
+		let compact_cert = fake_compact_assignment_cert(hash, ValidatorIndex(0), core_bitfield);
+		let claimed_candidates = CandidateBitfield::try_from(vec![0u32, 1]).unwrap();
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::DistributeAssignment(
+				compact_cert.clone(),
+				claimed_candidates.clone(),
+			),
+		)
+		.await;
+
+		// only the peer of the second version is sent it
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridgeTx(NetworkBridgeTxMessage::SendValidationMessage(
+				peers,
+				Versioned::V2(protocol_v2::ValidationProtocol::ApprovalDistribution(
+					protocol_v2::ApprovalDistributionMessage::Assignments(assignments)
+				))
+			)) => {
+				assert_eq!(peers, vec![peer_b]);
+				assert_eq!(assignments, vec![(compact_cert.clone(), claimed_candidates)]);
+			}
+		);
+
+		// an assignment of the first version is sent to both
+		let cert = fake_assignment_cert(hash, ValidatorIndex(1));
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::DistributeAssignment(cert.clone().into(), 0u32.into()),
+		)
+		.await;
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridgeTx(NetworkBridgeTxMessage::SendValidationMessage(
+				peers,
+				Versioned::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
+					protocol_v1::ApprovalDistributionMessage::Assignments(assignments)
+				))
+			)) => {
+				assert_eq!(peers.len(), 2);
+				assert!(peers.contains(&peer_a));
+				assert!(peers.contains(&peer_b));
+				assert_eq!(assignments, vec![(cert.clone(), 0)]);
+			}
+		);
+
+		// a peer of the first version learning about the block is only sent the latter
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerViewChange(
+				peer_c,
+				view![hash],
+			)),
+		)
+		.await;
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridgeTx(NetworkBridgeTxMessage::SendValidationMessage(
+				peers,
+				Versioned::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
+					protocol_v1::ApprovalDistributionMessage::Assignments(assignments)
+				))
+			)) => {
+				assert_eq!(peers, vec![peer_c]);
+				assert_eq!(assignments, vec![(cert, 0)]);
+			}
+		);
+
+		assert!(overseer.recv().timeout(TIMEOUT).await.is_none(), "no message should be sent");
+		virtual_overseer
+	});
+}
+
+/// An approval covering several candidates is not sent to peers of the first version of the
+/// protocol, even though the assignments it depends on are.
+#[test]
+fn coalesced_approval_is_not_sent_to_v1_peers() {
+	let parent_hash = Hash::repeat_byte(0xFF);
+	let peer_a = PeerId::random();
+	let peer_b = PeerId::random();
+	let peer_c = PeerId::random();
+	let hash = Hash::repeat_byte(0xAA);
+
+	let _ = test_harness(State::default(), |mut virtual_overseer| async move {
+		let overseer = &mut virtual_overseer;
+		setup_peer_with_view(overseer, &peer_a, view![hash]).await;
+		setup_peer_with_view_and_version(overseer, &peer_b, view![hash], ValidationVersion::V2)
+			.await;
+		setup_peer_with_view(overseer, &peer_c, view![]).await;
+
+		// new block `hash` with 2 candidates
+		let meta = BlockApprovalMeta {
+			hash,
+			parent_hash,
+			number: 1,
+			candidates: vec![Default::default(); 2],
+			slot: 1.into(),
+			session: 1,
+		};
+		let msg = ApprovalDistributionMessage::NewBlocks(vec![meta]);
+		overseer_send(overseer, msg).await;
+
+		// import our assignments of the first version for both candidates
+		let validator_index = ValidatorIndex(0);
+		let cert = fake_assignment_cert(hash, validator_index);
+		for candidate_index in 0u32..2 {
+			overseer_send(
+				overseer,
+				ApprovalDistributionMessage::DistributeAssignment(
+					cert.clone().into(),
+					candidate_index.into(),
+				),
+			)
+			.await;
+
+			assert_matches!(
+				overseer_recv(overseer).await,
+				AllMessages::NetworkBridgeTx(NetworkBridgeTxMessage::SendValidationMessage(
+					peers,
+					Versioned::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
+						protocol_v1::ApprovalDistributionMessage::Assignments(assignments)
+					))
+				)) => {
+					assert_eq!(peers.len(), 2);
+					assert!(peers.contains(&peer_a));
+					assert!(peers.contains(&peer_b));
+					assert_eq!(assignments, vec![(cert.clone(), candidate_index)]);
+				}
+			);
+		}
+
+		// import our approval covering both candidates, only the peer of the second version is
+		// sent it
+		let approval = IndirectSignedApprovalVoteV2 {
+			block_hash: hash,
+			candidate_indices: CandidateBitfield::try_from(vec![0u32, 1]).unwrap(),
+			validator: validator_index,
+			signature: dummy_signature(),
+		};
+		overseer_send(overseer, ApprovalDistributionMessage::DistributeApproval(approval.clone()))
+			.await;
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridgeTx(NetworkBridgeTxMessage::SendValidationMessage(
+				peers,
+				Versioned::V2(protocol_v2::ValidationProtocol::ApprovalDistribution(
+					protocol_v2::ApprovalDistributionMessage::Approvals(approvals)
+				))
+			)) => {
+				assert_eq!(peers, vec![peer_b]);
+				assert_eq!(approvals, vec![approval]);
+			}
+		);
+
+		// a peer of the first version learning about the block is only sent the assignments
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerViewChange(
+				peer_c,
+				view![hash],
+			)),
+		)
+		.await;
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridgeTx(NetworkBridgeTxMessage::SendValidationMessage(
+				peers,
+				Versioned::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
+					protocol_v1::ApprovalDistributionMessage::Assignments(assignments)
+				))
+			)) => {
+				assert_eq!(peers, vec![peer_c]);
+				assert_eq!(assignments, vec![(cert.clone(), 0), (cert, 1)]);
+			}
+		);
+
+		assert!(overseer.recv().timeout(TIMEOUT).await.is_none(), "no message should be sent");
+		virtual_overseer
+	});
+}
+
+/// An approval covering several candidates is sent once, in the second version of the protocol,
+/// both when imported and when unifying with a peer which learns about the block later.
+#[test]
+fn local_approval_for_several_candidates_is_sent_once() {
+	let parent_hash = Hash::repeat_byte(0xFF);
+	let peer_a = PeerId::random();
+	let peer_b = PeerId::random();
+	let hash = Hash::repeat_byte(0xAA);
+
+	let _ = test_harness(State::default(), |mut virtual_overseer| async move {
+		let overseer = &mut virtual_overseer;
+		setup_peer_with_view_and_version(overseer, &peer_a, view![hash], ValidationVersion::V2)
+			.await;
+		setup_peer_with_view_and_version(overseer, &peer_b, view![], ValidationVersion::V2).await;
+
+		// new block `hash` with 2 candidates
+		let meta = BlockApprovalMeta {
+			hash,
+			parent_hash,
+			number: 1,
+			candidates: vec![Default::default(); 2],
+			slot: 1.into(),
+			session: 1,
+		};
+		let msg = ApprovalDistributionMessage::NewBlocks(vec![meta]);
+		overseer_send(overseer, msg).await;
+
+		// import our compact assignment claiming both candidates
+		let validator_index = ValidatorIndex(0);
+		let core_bitfield = CoreBitfield::try_from(vec![CoreIndex(0), CoreIndex(1)]).unwrap();
+		let cert = fake_compact_assignment_cert(hash, validator_index, core_bitfield);
+		let candidate_indices = CandidateBitfield::try_from(vec![0u32, 1]).unwrap();
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::DistributeAssignment(
+				cert.clone(),
+				candidate_indices.clone(),
+			),
+		)
+		.await;
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridgeTx(NetworkBridgeTxMessage::SendValidationMessage(
+				peers,
+				Versioned::V2(protocol_v2::ValidationProtocol::ApprovalDistribution(
+					protocol_v2::ApprovalDistributionMessage::Assignments(assignments)
+				))
+			)) => {
+				assert_eq!(peers, vec![peer_a]);
+				assert_eq!(assignments, vec![(cert.clone(), candidate_indices.clone())]);
+			}
+		);
+
+		// import our approval covering both candidates
+		let approval = IndirectSignedApprovalVoteV2 {
+			block_hash: hash,
+			candidate_indices: candidate_indices.clone(),
+			validator: validator_index,
+			signature: dummy_signature(),
+		};
+		overseer_send(overseer, ApprovalDistributionMessage::DistributeApproval(approval.clone()))
+			.await;
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridgeTx(NetworkBridgeTxMessage::SendValidationMessage(
+				peers,
+				Versioned::V2(protocol_v2::ValidationProtocol::ApprovalDistribution(
+					protocol_v2::ApprovalDistributionMessage::Approvals(approvals)
+				))
+			)) => {
+				assert_eq!(peers, vec![peer_a]);
+				assert_eq!(approvals, vec![approval.clone()]);
+			}
+		);
+
+		// the signature is reported for both candidates
+		let (tx, rx) = oneshot::channel();
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::GetApprovalSignatures(
+				vec![(hash, 1)].into_iter().collect(),
+				tx,
+			),
+		)
+		.await;
+		assert_eq!(
+			rx.await.unwrap().get(&validator_index),
+			Some(&(hash, vec![0, 1], dummy_signature())),
+		);
+
+		// update the view of peer_b to include the hash
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerViewChange(
+				peer_b,
+				view![hash],
+			)),
+		)
+		.await;
+
+		// peer_b is sent the assignment and the approval once
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridgeTx(NetworkBridgeTxMessage::SendValidationMessage(
+				peers,
+				Versioned::V2(protocol_v2::ValidationProtocol::ApprovalDistribution(
+					protocol_v2::ApprovalDistributionMessage::Assignments(assignments)
+				))
+			)) => {
+				assert_eq!(peers, vec![peer_b]);
+				assert_eq!(assignments, vec![(cert.clone(), candidate_indices.clone())]);
+			}
+		);
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridgeTx(NetworkBridgeTxMessage::SendValidationMessage(
+				peers,
+				Versioned::V2(protocol_v2::ValidationProtocol::ApprovalDistribution(
+					protocol_v2::ApprovalDistributionMessage::Approvals(approvals)
+				))
+			)) => {
+				assert_eq!(peers, vec![peer_b]);
+				assert_eq!(approvals, vec![approval]);
+			}
+		);
+
+		assert!(overseer.recv().timeout(TIMEOUT).await.is_none(), "no message should be sent");
+		virtual_overseer
+	});
+}
+
 #[test]
 fn import_approval_happy_path() {
 	let peer_a = PeerId::random();
@@ -570,7 +1135,7 @@ fn import_approval_happy_path() {
 		let cert = fake_assignment_cert(hash, validator_index);
 		overseer_send(
 			overseer,
-			ApprovalDistributionMessage::DistributeAssignment(cert, candidate_index),
+			ApprovalDistributionMessage::DistributeAssignment(cert.into(), candidate_index.into()),
 		)
 		.await;
 
@@ -603,7 +1168,7 @@ fn import_approval_happy_path() {
 				vote,
 				tx,
 			)) => {
-				assert_eq!(vote, approval);
+				assert_eq!(vote, IndirectSignedApprovalVoteV2::from(approval.clone()));
 				tx.send(ApprovalCheckResult::Accepted).unwrap();
 			}
 		);
@@ -679,8 +1244,8 @@ fn import_approval_bad() {
 				i,
 				tx,
 			)) => {
-				assert_eq!(assignment, cert);
-				assert_eq!(i, candidate_index);
+				assert_eq!(assignment, cert.clone().into());
+				assert_eq!(i, candidate_index.into());
 				tx.send(AssignmentCheckResult::Accepted).unwrap();
 			}
 		);
@@ -697,7 +1262,7 @@ fn import_approval_bad() {
 				vote,
 				tx,
 			)) => {
-				assert_eq!(vote, approval);
+				assert_eq!(vote, IndirectSignedApprovalVoteV2::from(approval.clone()));
 				tx.send(ApprovalCheckResult::Bad(ApprovalCheckError::UnknownBlock(hash))).unwrap();
 			}
 		);
@@ -825,9 +1390,17 @@ fn update_peer_view() {
 		let cert_a = fake_assignment_cert(hash_a, ValidatorIndex(0));
 		let cert_b = fake_assignment_cert(hash_b, ValidatorIndex(0));
 
-		overseer_send(overseer, ApprovalDistributionMessage::DistributeAssignment(cert_a, 0)).await;
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::DistributeAssignment(cert_a.into(), 0u32.into()),
+		)
+		.await;
 
-		overseer_send(overseer, ApprovalDistributionMessage::DistributeAssignment(cert_b, 0)).await;
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::DistributeAssignment(cert_b.into(), 0u32.into()),
+		)
+		.await;
 
 		// connect a peer
 		setup_peer_with_view(overseer, peer, view![hash_a]).await;
@@ -848,7 +1421,7 @@ fn update_peer_view() {
 		virtual_overseer
 	});
 
-	assert_eq!(state.peer_views.get(peer).map(|v| v.finalized_number), Some(0));
+	assert_eq!(state.peer_views.get(peer).map(|v| v.view.finalized_number), Some(0));
 	assert_eq!(
 		state
 			.blocks
@@ -879,7 +1452,7 @@ fn update_peer_view() {
 
 		overseer_send(
 			overseer,
-			ApprovalDistributionMessage::DistributeAssignment(cert_c.clone(), 0),
+			ApprovalDistributionMessage::DistributeAssignment(cert_c.clone().into(), 0u32.into()),
 		)
 		.await;
 
@@ -900,7 +1473,7 @@ fn update_peer_view() {
 		virtual_overseer
 	});
 
-	assert_eq!(state.peer_views.get(peer).map(|v| v.finalized_number), Some(2));
+	assert_eq!(state.peer_views.get(peer).map(|v| v.view.finalized_number), Some(2));
 	assert_eq!(
 		state
 			.blocks
@@ -930,7 +1503,7 @@ fn update_peer_view() {
 		virtual_overseer
 	});
 
-	assert_eq!(state.peer_views.get(peer).map(|v| v.finalized_number), Some(finalized_number));
+	assert_eq!(state.peer_views.get(peer).map(|v| v.view.finalized_number), Some(finalized_number));
 	assert!(state.blocks.get(&hash_c).unwrap().known_by.get(peer).is_none());
 }
 
@@ -975,8 +1548,8 @@ fn import_remotely_then_locally() {
 				i,
 				tx,
 			)) => {
-				assert_eq!(assignment, cert);
-				assert_eq!(i, candidate_index);
+				assert_eq!(assignment, cert.clone().into());
+				assert_eq!(i, candidate_index.into());
 				tx.send(AssignmentCheckResult::Accepted).unwrap();
 			}
 		);
@@ -986,7 +1559,7 @@ fn import_remotely_then_locally() {
 		// import the same assignment locally
 		overseer_send(
 			overseer,
-			ApprovalDistributionMessage::DistributeAssignment(cert, candidate_index),
+			ApprovalDistributionMessage::DistributeAssignment(cert.into(), candidate_index.into()),
 		)
 		.await;
 
@@ -1008,14 +1581,15 @@ fn import_remotely_then_locally() {
 				vote,
 				tx,
 			)) => {
-				assert_eq!(vote, approval);
+				assert_eq!(vote, IndirectSignedApprovalVoteV2::from(approval.clone()));
 				tx.send(ApprovalCheckResult::Accepted).unwrap();
 			}
 		);
 		expect_reputation_change(overseer, peer, BENEFIT_VALID_MESSAGE_FIRST).await;
 
 		// import the same approval locally
-		overseer_send(overseer, ApprovalDistributionMessage::DistributeApproval(approval)).await;
+		overseer_send(overseer, ApprovalDistributionMessage::DistributeApproval(approval.into()))
+			.await;
 
 		assert!(overseer.recv().timeout(TIMEOUT).await.is_none(), "no message should be sent");
 		virtual_overseer
@@ -1058,12 +1632,18 @@ fn sends_assignments_even_when_state_is_approved() {
 
 		overseer_send(
 			overseer,
-			ApprovalDistributionMessage::DistributeAssignment(cert.clone(), candidate_index),
+			ApprovalDistributionMessage::DistributeAssignment(
+				cert.clone().into(),
+				candidate_index.into(),
+			),
 		)
 		.await;
 
-		overseer_send(overseer, ApprovalDistributionMessage::DistributeApproval(approval.clone()))
-			.await;
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::DistributeApproval(approval.clone().into()),
+		)
+		.await;
 
 		// connect the peer.
 		setup_peer_with_view(overseer, peer, view![hash]).await;
@@ -1168,8 +1748,8 @@ fn race_condition_in_local_vs_remote_view_update() {
 					claimed_candidate_index,
 					tx,
 				)) => {
-					assert_eq!(assignment, assignments[i].0);
-					assert_eq!(claimed_candidate_index, assignments[i].1);
+					assert_eq!(assignment, assignments[i].0.clone().into());
+					assert_eq!(claimed_candidate_index, assignments[i].1.into());
 					tx.send(AssignmentCheckResult::Accepted).unwrap();
 				}
 			);
@@ -1236,12 +1816,18 @@ fn propagates_locally_generated_assignment_to_both_dimensions() {
 
 		overseer_send(
 			overseer,
-			ApprovalDistributionMessage::DistributeAssignment(cert.clone(), candidate_index),
+			ApprovalDistributionMessage::DistributeAssignment(
+				cert.clone().into(),
+				candidate_index.into(),
+			),
 		)
 		.await;
 
-		overseer_send(overseer, ApprovalDistributionMessage::DistributeApproval(approval.clone()))
-			.await;
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::DistributeApproval(approval.clone().into()),
+		)
+		.await;
 
 		let assignments = vec![(cert.clone(), candidate_index)];
 		let approvals = vec![approval.clone()];
@@ -1484,12 +2070,18 @@ fn propagates_to_required_after_connect() {
 
 		overseer_send(
 			overseer,
-			ApprovalDistributionMessage::DistributeAssignment(cert.clone(), candidate_index),
+			ApprovalDistributionMessage::DistributeAssignment(
+				cert.clone().into(),
+				candidate_index.into(),
+			),
 		)
 		.await;
 
-		overseer_send(overseer, ApprovalDistributionMessage::DistributeApproval(approval.clone()))
-			.await;
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::DistributeApproval(approval.clone().into()),
+		)
+		.await;
 
 		let assignments = vec![(cert.clone(), candidate_index)];
 		let approvals = vec![approval.clone()];
@@ -1609,12 +2201,18 @@ fn sends_to_more_peers_after_getting_topology() {
 
 		overseer_send(
 			overseer,
-			ApprovalDistributionMessage::DistributeAssignment(cert.clone(), candidate_index),
+			ApprovalDistributionMessage::DistributeAssignment(
+				cert.clone().into(),
+				candidate_index.into(),
+			),
 		)
 		.await;
 
-		overseer_send(overseer, ApprovalDistributionMessage::DistributeApproval(approval.clone()))
-			.await;
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::DistributeApproval(approval.clone().into()),
+		)
+		.await;
 
 		let assignments = vec![(cert.clone(), candidate_index)];
 		let approvals = vec![approval.clone()];
@@ -1768,12 +2366,18 @@ fn originator_aggression_l1() {
 
 		overseer_send(
 			overseer,
-			ApprovalDistributionMessage::DistributeAssignment(cert.clone(), candidate_index),
+			ApprovalDistributionMessage::DistributeAssignment(
+				cert.clone().into(),
+				candidate_index.into(),
+			),
 		)
 		.await;
 
-		overseer_send(overseer, ApprovalDistributionMessage::DistributeApproval(approval.clone()))
-			.await;
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::DistributeApproval(approval.clone().into()),
+		)
+		.await;
 
 		let assignments = vec![(cert.clone(), candidate_index)];
 		let approvals = vec![approval.clone()];
@@ -2305,15 +2909,23 @@ fn batch_test_round(message_count: usize) {
 		let validators = 0..message_count;
 		let assignments: Vec<_> = validators
 			.clone()
-			.map(|index| (fake_assignment_cert(Hash::zero(), ValidatorIndex(index as u32)), 0))
+			.map(|index| {
+				(
+					fake_assignment_cert(Hash::zero(), ValidatorIndex(index as u32)).into(),
+					0u32.into(),
+				)
+			})
 			.collect();
 
-		let approvals: Vec<_> = validators
-			.map(|index| IndirectSignedApprovalVote {
-				block_hash: Hash::zero(),
-				candidate_index: 0,
-				validator: ValidatorIndex(index as u32),
-				signature: dummy_signature(),
+		let approvals: Vec<IndirectSignedApprovalVoteV2> = validators
+			.map(|index| {
+				IndirectSignedApprovalVote {
+					block_hash: Hash::zero(),
+					candidate_index: 0,
+					validator: ValidatorIndex(index as u32),
+					signature: dummy_signature(),
+				}
+				.into()
 			})
 			.collect();
 
@@ -2341,7 +2953,10 @@ fn batch_test_round(message_count: usize) {
 					assert_eq!(peers.len(), 1);
 
 					for (message_index,  assignment) in sent_assignments.iter().enumerate() {
-						assert_eq!(assignment.0, assignments[assignment_index + message_index].0);
+						assert_eq!(
+							IndirectAssignmentCertV2::from(assignment.0.clone()),
+							assignments[assignment_index + message_index].0,
+						);
 						assert_eq!(assignment.1, 0);
 					}
 				}
@@ -2368,7 +2983,10 @@ fn batch_test_round(message_count: usize) {
 					assert_eq!(peers.len(), 1);
 
 					for (message_index,  approval) in sent_approvals.iter().enumerate() {
-						assert_eq!(approval, &approvals[approval_index + message_index]);
+						assert_eq!(
+							IndirectSignedApprovalVoteV2::from(approval.clone()),
+							approvals[approval_index + message_index],
+						);
 					}
 				}
 			);
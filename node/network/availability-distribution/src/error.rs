@@ -47,6 +47,9 @@ pub enum Error {
 	#[error("Error while accessing runtime information: {0}")]
 	Runtime(#[from] runtime::Error),
 
+	#[error("Fetching the node features failed: {0}")]
+	NodeFeatures(#[source] polkadot_node_subsystem_util::Error),
+
 	#[fatal]
 	#[error("Oneshot for receiving response from Chain API got cancelled")]
 	ChainApiSenderDropped(#[source] oneshot::Canceled),
@@ -104,6 +107,7 @@ pub fn log_error(result: Result<()>, ctx: &'static str) -> std::result::Result<(
 				JfyiError::FetchPoV(_) |
 				JfyiError::SendResponse |
 				JfyiError::NoSuchPoV |
+				JfyiError::NodeFeatures(_) |
 				JfyiError::Runtime(_) => gum::debug!(target: LOG_TARGET, error = ?jfyi, ctx),
 			}
 			Ok(())
@@ -25,17 +25,17 @@ use futures::{
 use polkadot_erasure_coding::branch_hash;
 use polkadot_node_network_protocol::request_response::{
 	outgoing::{OutgoingRequest, Recipient, RequestError, Requests},
-	v1::{ChunkFetchingRequest, ChunkFetchingResponse},
+	v2::{ChunkFetchingRequest, ChunkFetchingResponse},
 };
-use polkadot_node_primitives::ErasureChunk;
+use polkadot_node_primitives::{AvailabilityChunkMapping, ErasureChunk};
 use polkadot_node_subsystem::{
 	jaeger,
 	messages::{AvailabilityStoreMessage, IfDisconnected, NetworkBridgeTxMessage},
 	overseer,
 };
 use polkadot_primitives::{
-	AuthorityDiscoveryId, BlakeTwo256, CandidateHash, GroupIndex, Hash, HashT, OccupiedCore,
-	SessionIndex,
+	AuthorityDiscoveryId, BlakeTwo256, BlockNumber, CandidateHash, GroupIndex, Hash, HashT,
+	OccupiedCore, SessionIndex,
 };
 
 use crate::{
@@ -137,6 +137,7 @@ impl FetchTaskConfig {
 	pub fn new(
 		leaf: Hash,
 		core: &OccupiedCore,
+		relay_parent_number: BlockNumber,
 		sender: mpsc::Sender<FromFetchTask>,
 		metrics: Metrics,
 		session_info: &SessionInfo,
@@ -159,6 +160,14 @@ impl FetchTaskConfig {
 			return FetchTaskConfig { live_in, prepared_running: None }
 		}
 
+		let chunk_mapping = AvailabilityChunkMapping::new(
+			&session_info.node_features,
+			session_info.n_validators,
+			&session_info.random_seed,
+			relay_parent_number,
+			core.candidate_descriptor.para_id,
+		);
+
 		let prepared_running = RunningTask {
 			session_index: session_info.session_index,
 			group_index: core.group_responsible,
@@ -167,7 +176,7 @@ impl FetchTaskConfig {
 				.clone(),
 			request: ChunkFetchingRequest {
 				candidate_hash: core.candidate_hash,
-				index: session_info.our_index,
+				index: chunk_mapping.chunk_index(session_info.our_index),
 			},
 			erasure_root: core.candidate_descriptor.erasure_root,
 			relay_parent: core.candidate_descriptor.relay_parent,
@@ -294,7 +303,7 @@ impl RunningTask {
 				.with_chunk_index(self.request.index.0)
 				.with_stage(jaeger::Stage::AvailabilityDistribution);
 			let chunk = match resp {
-				ChunkFetchingResponse::Chunk(resp) => resp.recombine_into_chunk(&self.request),
+				ChunkFetchingResponse::Chunk(chunk) => chunk,
 				ChunkFetchingResponse::NoSuchChunk => {
 					gum::debug!(
 						target: LOG_TARGET,
@@ -354,9 +363,11 @@ impl RunningTask {
 			"Starting chunk request",
 		);
 
-		let (full_request, response_recv) =
-			OutgoingRequest::new(Recipient::Authority(validator.clone()), self.request);
-		let requests = Requests::ChunkFetchingV1(full_request);
+		let (full_request, response_recv) = OutgoingRequest::new_with_fallback(
+			Recipient::Authority(validator.clone()),
+			self.request,
+		);
+		let requests = Requests::ChunkFetchingV2(full_request);
 
 		self.sender
 			.send(FromFetchTask::Message(
@@ -416,6 +427,17 @@ impl RunningTask {
 	}
 
 	fn validate_chunk(&self, validator: &AuthorityDiscoveryId, chunk: &ErasureChunk) -> bool {
+		if chunk.index != self.request.index {
+			gum::warn!(
+				target: LOG_TARGET,
+				candidate_hash = ?self.request.candidate_hash,
+				origin = ?validator,
+				chunk_index = ?chunk.index,
+				expected_chunk_index = ?self.request.index,
+				"Received chunk with unexpected index",
+			);
+			return false
+		}
 		let anticipated_hash =
 			match branch_hash(&self.erasure_root, chunk.proof(), chunk.index.0 as usize) {
 				Ok(hash) => hash,
@@ -28,7 +28,7 @@ use futures::{
 use sc_network as network;
 use sp_keyring::Sr25519Keyring;
 
-use polkadot_node_network_protocol::request_response::{v1, Recipient};
+use polkadot_node_network_protocol::request_response::Recipient;
 use polkadot_node_primitives::{BlockData, PoV, Proof};
 use polkadot_node_subsystem::messages::AllMessages;
 use polkadot_primitives::{CandidateHash, ValidatorIndex};
@@ -59,8 +59,9 @@ fn task_does_not_accept_invalid_chunk() {
 			let mut m = HashMap::new();
 			m.insert(
 				Recipient::Authority(Sr25519Keyring::Alice.public().into()),
-				ChunkFetchingResponse::Chunk(v1::ChunkResponse {
+				ChunkFetchingResponse::Chunk(ErasureChunk {
 					chunk: vec![1, 2, 3],
+					index: ValidatorIndex(0),
 					proof: Proof::try_from(vec![vec![9, 8, 2], vec![2, 3, 4]]).unwrap(),
 				}),
 			);
@@ -87,10 +88,7 @@ fn task_stores_valid_chunk() {
 			let mut m = HashMap::new();
 			m.insert(
 				Recipient::Authority(Sr25519Keyring::Alice.public().into()),
-				ChunkFetchingResponse::Chunk(v1::ChunkResponse {
-					chunk: chunk.chunk.clone(),
-					proof: chunk.proof,
-				}),
+				ChunkFetchingResponse::Chunk(chunk.clone()),
 			);
 			m
 		},
@@ -119,10 +117,7 @@ fn task_does_not_accept_wrongly_indexed_chunk() {
 			let mut m = HashMap::new();
 			m.insert(
 				Recipient::Authority(Sr25519Keyring::Alice.public().into()),
-				ChunkFetchingResponse::Chunk(v1::ChunkResponse {
-					chunk: chunk.chunk.clone(),
-					proof: chunk.proof,
-				}),
+				ChunkFetchingResponse::Chunk(chunk.clone()),
 			);
 			m
 		},
@@ -158,10 +153,7 @@ fn task_stores_valid_chunk_if_there_is_one() {
 			let mut m = HashMap::new();
 			m.insert(
 				Recipient::Authority(Sr25519Keyring::Alice.public().into()),
-				ChunkFetchingResponse::Chunk(v1::ChunkResponse {
-					chunk: chunk.chunk.clone(),
-					proof: chunk.proof,
-				}),
+				ChunkFetchingResponse::Chunk(chunk.clone()),
 			);
 			m.insert(
 				Recipient::Authority(Sr25519Keyring::Bob.public().into()),
@@ -169,8 +161,9 @@ fn task_stores_valid_chunk_if_there_is_one() {
 			);
 			m.insert(
 				Recipient::Authority(Sr25519Keyring::Charlie.public().into()),
-				ChunkFetchingResponse::Chunk(v1::ChunkResponse {
+				ChunkFetchingResponse::Chunk(ErasureChunk {
 					chunk: vec![1, 2, 3],
+					index: ValidatorIndex(0),
 					proof: Proof::try_from(vec![vec![9, 8, 2], vec![2, 3, 4]]).unwrap(),
 				}),
 			);
@@ -240,7 +233,7 @@ impl TestRun {
 				let mut valid_responses = 0;
 				for req in reqs {
 					let req = match req {
-						Requests::ChunkFetchingV1(req) => req,
+						Requests::ChunkFetchingV2(req) => req,
 						_ => panic!("Unexpected request"),
 					};
 					let response =
@@ -38,7 +38,7 @@ use polkadot_node_subsystem::{
 	overseer, ActivatedLeaf, ActiveLeavesUpdate, LeafStatus,
 };
 use polkadot_node_subsystem_util::runtime::{get_occupied_cores, RuntimeInfo};
-use polkadot_primitives::{CandidateHash, Hash, OccupiedCore, SessionIndex};
+use polkadot_primitives::{BlockNumber, CandidateHash, Hash, OccupiedCore, SessionIndex};
 
 use super::{FatalError, Metrics, Result, LOG_TARGET};
 
@@ -218,6 +218,19 @@ impl Requester {
 				},
 				Entry::Vacant(e) => {
 					span.add_string_tag("already-requested-chunk", "false");
+					let relay_parent = core.candidate_descriptor.relay_parent;
+					let relay_parent_number =
+						match get_block_number(context.sender(), relay_parent).await? {
+							Some(number) => number,
+							None => {
+								gum::debug!(
+									target: LOG_TARGET,
+									?relay_parent,
+									"Relay parent of occupied core is unknown"
+								);
+								continue
+							},
+						};
 					let tx = self.tx.clone();
 					let metrics = self.metrics.clone();
 
@@ -232,7 +245,17 @@ impl Requester {
 							// be fetchable by the state trie.
 							leaf,
 							leaf_session_index,
-							|info| FetchTaskConfig::new(leaf, &core, tx, metrics, info, span),
+							|info| {
+								FetchTaskConfig::new(
+									leaf,
+									&core,
+									relay_parent_number,
+									tx,
+									metrics,
+									info,
+									span,
+								)
+							},
 						)
 						.await
 						.map_err(|err| {
@@ -350,3 +373,18 @@ where
 		.map_err(FatalError::ChainApi)?;
 	Ok(ancestors)
 }
+
+/// Request the number of the given block from the Chain API.
+async fn get_block_number<Sender>(sender: &mut Sender, hash: Hash) -> Result<Option<BlockNumber>>
+where
+	Sender: overseer::SubsystemSender<ChainApiMessage>,
+{
+	let (tx, rx) = oneshot::channel();
+	sender.send_message(ChainApiMessage::BlockNumber(hash, tx)).await;
+
+	let number = rx
+		.await
+		.map_err(FatalError::ChainApiSenderDropped)?
+		.map_err(FatalError::ChainApi)?;
+	Ok(number)
+}
@@ -20,8 +20,10 @@ use lru::LruCache;
 use rand::{seq::SliceRandom, thread_rng};
 
 use polkadot_node_subsystem::overseer;
-use polkadot_node_subsystem_util::runtime::RuntimeInfo;
-use polkadot_primitives::{AuthorityDiscoveryId, GroupIndex, Hash, SessionIndex, ValidatorIndex};
+use polkadot_node_subsystem_util::{node_features_at_relay_parent, runtime::RuntimeInfo};
+use polkadot_primitives::{
+	vstaging::NodeFeatures, AuthorityDiscoveryId, GroupIndex, Hash, SessionIndex, ValidatorIndex,
+};
 
 use crate::{
 	error::{Error, Result},
@@ -62,6 +64,17 @@ pub struct SessionInfo {
 	///
 	/// `None`, if we are not in fact part of any group.
 	pub our_group: Option<GroupIndex>,
+
+	/// The number of validators in this session, each of them holding one chunk per candidate.
+	pub n_validators: usize,
+
+	/// The node features enabled in this session, determining which chunk we are supposed to
+	/// fetch.
+	pub node_features: NodeFeatures,
+
+	/// The randomness of this session, used together with the node features to determine which
+	/// chunk we are supposed to fetch.
+	pub random_seed: [u8; 32],
 }
 
 /// Report of bad validators.
@@ -208,7 +221,19 @@ impl SessionCache {
 				})
 				.collect();
 
-			let info = SessionInfo { validator_groups, our_index, session_index, our_group };
+			let node_features = node_features_at_relay_parent(relay_parent, ctx.sender())
+				.await
+				.map_err(Error::NodeFeatures)?;
+
+			let info = SessionInfo {
+				validator_groups,
+				our_index,
+				session_index,
+				our_group,
+				n_validators: info.session_info.validators.len(),
+				node_features,
+				random_seed: info.session_info.random_seed,
+			};
 			return Ok(Some(info))
 		}
 		return Ok(None)
@@ -116,6 +116,9 @@ fn spawn_virtual_overseer(
 								tx.send(Ok(session_index_for_block(block_number as u32 + 1)))
 									.expect("Receiver should still be alive");
 							},
+							RuntimeApiRequest::NodeFeatures(tx) => {
+								tx.send(Ok(Default::default())).expect("Receiver should be alive.");
+							},
 							RuntimeApiRequest::SessionInfo(_, tx) => {
 								tx.send(Ok(Some(test_state.session_info.clone())))
 									.expect("Receiver should be alive.");
@@ -167,6 +170,11 @@ fn spawn_virtual_overseer(
 							.send(Ok(ancestors))
 							.expect("Receiver is expected to be alive");
 					},
+					AllMessages::ChainApi(ChainApiMessage::BlockNumber(hash, tx)) => {
+						let number = test_state.relay_chain.iter().position(|h| *h == hash);
+						tx.send(Ok(number.map(|n| n as BlockNumber)))
+							.expect("Receiver is expected to be alive");
+					},
 					msg => panic!("Unexpected overseer message: {:?}", msg),
 				}
 			}
@@ -21,8 +21,10 @@ use std::sync::Arc;
 use futures::channel::oneshot;
 
 use fatality::Nested;
+use parity_scale_codec::{Decode, Encode};
+
 use polkadot_node_network_protocol::{
-	request_response::{v1, IncomingRequest, IncomingRequestReceiver},
+	request_response::{v1, IncomingRequest, IncomingRequestReceiver, IsRequest},
 	UnifiedReputationChange as Rep,
 };
 use polkadot_node_primitives::{AvailableData, ErasureChunk};
@@ -66,12 +68,16 @@ pub async fn run_pov_receiver<Sender>(
 }
 
 /// Receiver task to be forked as a separate task to handle chunk requests.
-pub async fn run_chunk_receiver<Sender>(
+///
+/// Used for all versions of the chunk fetching protocol.
+pub async fn run_chunk_receiver<Sender, Req>(
 	mut sender: Sender,
-	mut receiver: IncomingRequestReceiver<v1::ChunkFetchingRequest>,
+	mut receiver: IncomingRequestReceiver<Req>,
 	metrics: Metrics,
 ) where
 	Sender: SubsystemSender<AvailabilityStoreMessage>,
+	Req: IsRequest + Decode + Encode + Into<v1::ChunkFetchingRequest>,
+	Req::Response: Encode + From<Option<ErasureChunk>>,
 {
 	loop {
 		match receiver.recv(|| vec![COST_INVALID_REQUEST]).await.into_nested() {
@@ -124,13 +130,15 @@ pub async fn answer_pov_request_log<Sender>(
 /// Variant of `answer_chunk_request` that does Prometheus metric and logging on errors.
 ///
 /// Any errors of `answer_request` will simply be logged.
-pub async fn answer_chunk_request_log<Sender>(
+pub async fn answer_chunk_request_log<Sender, Req>(
 	sender: &mut Sender,
-	req: IncomingRequest<v1::ChunkFetchingRequest>,
+	req: IncomingRequest<Req>,
 	metrics: &Metrics,
 ) -> ()
 where
 	Sender: SubsystemSender<AvailabilityStoreMessage>,
+	Req: IsRequest + Decode + Encode + Into<v1::ChunkFetchingRequest>,
+	Req::Response: Encode + From<Option<ErasureChunk>>,
 {
 	let res = answer_chunk_request(sender, req).await;
 	match res {
@@ -177,39 +185,42 @@ where
 /// Answer an incoming chunk request by querying the av store.
 ///
 /// Returns: `Ok(true)` if chunk was found and served.
-pub async fn answer_chunk_request<Sender>(
+pub async fn answer_chunk_request<Sender, Req>(
 	sender: &mut Sender,
-	req: IncomingRequest<v1::ChunkFetchingRequest>,
+	req: IncomingRequest<Req>,
 ) -> Result<bool>
 where
 	Sender: SubsystemSender<AvailabilityStoreMessage>,
+	Req: IsRequest + Decode + Encode + Into<v1::ChunkFetchingRequest>,
+	Req::Response: Encode + From<Option<ErasureChunk>>,
 {
-	let span = jaeger::Span::new(req.payload.candidate_hash, "answer-chunk-request");
+	let IncomingRequest { peer, payload, pending_response } = req;
+	let payload: v1::ChunkFetchingRequest = payload.into();
+
+	let span = jaeger::Span::new(payload.candidate_hash, "answer-chunk-request");
 
 	let _child_span = span
 		.child("answer-chunk-request")
-		.with_trace_id(req.payload.candidate_hash)
-		.with_chunk_index(req.payload.index.0);
+		.with_trace_id(payload.candidate_hash)
+		.with_chunk_index(payload.index.0);
 
-	let chunk = query_chunk(sender, req.payload.candidate_hash, req.payload.index).await?;
+	let chunk = query_chunk(sender, payload.candidate_hash, payload.index).await?;
 
 	let result = chunk.is_some();
 
 	gum::trace!(
 		target: LOG_TARGET,
-		hash = ?req.payload.candidate_hash,
-		index = ?req.payload.index,
-		peer = ?req.peer,
+		hash = ?payload.candidate_hash,
+		index = ?payload.index,
+		?peer,
+		protocol = ?Req::PROTOCOL,
 		has_data = ?chunk.is_some(),
 		"Serving chunk",
 	);
 
-	let response = match chunk {
-		None => v1::ChunkFetchingResponse::NoSuchChunk,
-		Some(chunk) => v1::ChunkFetchingResponse::Chunk(chunk.into()),
-	};
-
-	req.send_response(response).map_err(|_| JfyiError::SendResponse)?;
+	pending_response
+		.send_response(chunk.into())
+		.map_err(|_| JfyiError::SendResponse)?;
 	Ok(result)
 }
 
@@ -217,22 +228,20 @@ where
 async fn query_chunk<Sender>(
 	sender: &mut Sender,
 	candidate_hash: CandidateHash,
-	validator_index: ValidatorIndex,
+	chunk_index: ValidatorIndex,
 ) -> std::result::Result<Option<ErasureChunk>, JfyiError>
 where
 	Sender: SubsystemSender<AvailabilityStoreMessage>,
 {
 	let (tx, rx) = oneshot::channel();
 	sender
-		.send_message(
-			AvailabilityStoreMessage::QueryChunk(candidate_hash, validator_index, tx).into(),
-		)
+		.send_message(AvailabilityStoreMessage::QueryChunk(candidate_hash, chunk_index, tx).into())
 		.await;
 
 	let result = rx.await.map_err(|e| {
 		gum::trace!(
 			target: LOG_TARGET,
-			?validator_index,
+			?chunk_index,
 			?candidate_hash,
 			error = ?e,
 			"Error retrieving chunk",
@@ -36,7 +36,7 @@ use sp_keystore::KeystorePtr;
 
 use polkadot_node_network_protocol::{
 	jaeger,
-	request_response::{v1, IncomingRequest, OutgoingRequest, Requests},
+	request_response::{v2, IncomingRequest, OutgoingRequest, Requests},
 };
 use polkadot_node_primitives::ErasureChunk;
 use polkadot_node_subsystem::{
@@ -48,8 +48,8 @@ use polkadot_node_subsystem::{
 };
 use polkadot_node_subsystem_test_helpers as test_helpers;
 use polkadot_primitives::{
-	CandidateHash, CoreState, GroupIndex, Hash, Id as ParaId, ScheduledCore, SessionInfo,
-	ValidatorIndex,
+	BlockNumber, CandidateHash, CoreState, GroupIndex, Hash, Id as ParaId, ScheduledCore,
+	SessionInfo, ValidatorIndex,
 };
 use test_helpers::mock::make_ferdie_keystore;
 
@@ -60,7 +60,7 @@ type VirtualOverseer = test_helpers::TestSubsystemContextHandle<AvailabilityDist
 pub struct TestHarness {
 	pub virtual_overseer: VirtualOverseer,
 	pub pov_req_cfg: RequestResponseConfig,
-	pub chunk_req_cfg: RequestResponseConfig,
+	pub chunk_req_v2_cfg: RequestResponseConfig,
 	pub pool: TaskExecutor,
 }
 
@@ -222,7 +222,7 @@ impl TestState {
 						// Forward requests:
 						let in_req = to_incoming_req(&harness.pool, req);
 						harness
-							.chunk_req_cfg
+							.chunk_req_v2_cfg
 							.inbound_queue
 							.as_mut()
 							.unwrap()
@@ -264,6 +264,9 @@ impl TestState {
 							// Always session index 1 for now:
 							tx.send(Ok(1)).expect("Receiver should still be alive");
 						},
+						RuntimeApiRequest::NodeFeatures(tx) => {
+							tx.send(Ok(Default::default())).expect("Receiver should be alive.");
+						},
 						RuntimeApiRequest::SessionInfo(_, tx) => {
 							tx.send(Ok(Some(self.session_info.clone())))
 								.expect("Receiver should be alive.");
@@ -286,6 +289,11 @@ impl TestState {
 						.unwrap_or_default();
 					response_channel.send(Ok(ancestors)).expect("Receiver is expected to be alive");
 				},
+				AllMessages::ChainApi(ChainApiMessage::BlockNumber(hash, tx)) => {
+					let number = self.relay_chain.iter().position(|h| *h == hash);
+					tx.send(Ok(number.map(|n| n as BlockNumber)))
+						.expect("Receiver is expected to be alive");
+				},
 				_ => {},
 			}
 		}
@@ -313,9 +321,9 @@ async fn overseer_recv(rx: &mut mpsc::UnboundedReceiver<AllMessages>) -> AllMess
 fn to_incoming_req(
 	executor: &TaskExecutor,
 	outgoing: Requests,
-) -> IncomingRequest<v1::ChunkFetchingRequest> {
+) -> IncomingRequest<v2::ChunkFetchingRequest> {
 	match outgoing {
-		Requests::ChunkFetchingV1(OutgoingRequest { payload, pending_response, .. }) => {
+		Requests::ChunkFetchingV2(OutgoingRequest { payload, pending_response, .. }) => {
 			let (tx, rx): (oneshot::Sender<netconfig::OutgoingResponse>, oneshot::Receiver<_>) =
 				oneshot::channel();
 			executor.spawn(
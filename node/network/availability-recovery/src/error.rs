@@ -31,6 +31,12 @@ pub enum Error {
 	#[error("failed to query session info")]
 	CanceledSessionInfo(#[source] oneshot::Canceled),
 
+	#[error("failed to query relay parent number")]
+	CanceledBlockNumber(#[source] oneshot::Canceled),
+
+	#[error(transparent)]
+	ChainApi(#[from] polkadot_node_subsystem::errors::ChainApiError),
+
 	#[error("failed to send response")]
 	CanceledResponseSender,
 
@@ -22,12 +22,12 @@ use std::{
 	collections::{HashMap, VecDeque},
 	num::NonZeroUsize,
 	pin::Pin,
-	time::Duration,
+	time::{Duration, Instant},
 };
 
 use futures::{
 	channel::oneshot,
-	future::{FutureExt, RemoteHandle},
+	future::{BoxFuture, FutureExt, RemoteHandle},
 	pin_mut,
 	prelude::*,
 	stream::FuturesUnordered,
@@ -37,28 +37,34 @@ use lru::LruCache;
 use rand::seq::SliceRandom;
 
 use fatality::Nested;
-use polkadot_erasure_coding::{branch_hash, branches, obtain_chunks_v1, recovery_threshold};
-#[cfg(not(test))]
-use polkadot_node_network_protocol::request_response::CHUNK_REQUEST_TIMEOUT;
+use polkadot_erasure_coding::{
+	branch_hash, branches, obtain_chunks_v1, recovery_threshold, systematic_recovery_threshold,
+};
 use polkadot_node_network_protocol::{
 	request_response::{
 		self as req_res, outgoing::RequestError, v1 as request_v1, IncomingRequestReceiver,
-		OutgoingRequest, Recipient, Requests,
+		OutgoingRequest, PeerPerformance, Recipient, Requests, CHUNK_REQUEST_TIMEOUT,
 	},
 	IfDisconnected, UnifiedReputationChange as Rep,
 };
-use polkadot_node_primitives::{AvailableData, ErasureChunk};
+use polkadot_node_primitives::{AvailabilityChunkMapping, AvailableData, ErasureChunk};
 use polkadot_node_subsystem::{
 	errors::RecoveryError,
 	jaeger,
-	messages::{AvailabilityRecoveryMessage, AvailabilityStoreMessage, NetworkBridgeTxMessage},
+	messages::{
+		AvailabilityRecoveryMessage, AvailabilityStoreMessage, ChainApiMessage,
+		NetworkBridgeTxMessage,
+	},
 	overseer, ActiveLeavesUpdate, FromOrchestra, OverseerSignal, SpawnedSubsystem, SubsystemError,
 	SubsystemResult,
 };
-use polkadot_node_subsystem_util::request_session_info;
+use polkadot_node_subsystem_util::{
+	node_features_at_relay_parent, request_session_info, TimeoutExt,
+};
 use polkadot_primitives::{
-	AuthorityDiscoveryId, BlakeTwo256, BlockNumber, CandidateHash, CandidateReceipt, GroupIndex,
-	Hash, HashT, IndexedVec, SessionIndex, SessionInfo, ValidatorId, ValidatorIndex,
+	vstaging::NodeFeatures, AuthorityDiscoveryId, BlakeTwo256, BlockNumber, CandidateHash,
+	CandidateReceipt, GroupIndex, Hash, HashT, IndexedVec, SessionIndex, SessionInfo, ValidatorId,
+	ValidatorIndex,
 };
 
 mod error;
@@ -99,6 +105,12 @@ const TIMEOUT_START_NEW_REQUESTS: Duration = CHUNK_REQUEST_TIMEOUT;
 #[cfg(test)]
 const TIMEOUT_START_NEW_REQUESTS: Duration = Duration::from_millis(100);
 
+/// Lower bound of the timeout of chunk requests to validators known to respond fast.
+///
+/// Chunk requests to validators time out after a multiple of their usual response time, but never
+/// earlier than this and never later than `CHUNK_REQUEST_TIMEOUT`.
+const MIN_CHUNK_REQUEST_TIMEOUT: Duration = Duration::from_millis(250);
+
 /// PoV size limit in bytes for which prefer fetching from backers.
 const SMALL_POV_LIMIT: usize = 128 * 1024;
 
@@ -111,6 +123,9 @@ pub enum RecoveryStrategy {
 	BackersFirstIfSizeLower(usize),
 	/// We always recover using validator chunks.
 	ChunksAlways,
+	/// We try to recover from the systematic chunks first, which requires no erasure decoding, then
+	/// fallback to validator chunks.
+	SystematicChunks,
 	/// Do not request data from the availability store.
 	/// This is the useful for nodes where the
 	/// availability-store subsystem is not expected to run,
@@ -147,11 +162,24 @@ pub struct AvailabilityRecoverySubsystem {
 }
 
 struct RequestFromBackers {
-	// a random shuffling of the validators from the backing group which indicates the order
-	// in which we connect to them and request the chunk.
+	// a random shuffling of the validators from the backing group, ranked by their observed
+	// performance, which indicates the order in which we connect to them and request the data.
 	shuffled_backers: Vec<ValidatorIndex>,
 }
 
+/// The result of a chunk request, see [`make_chunk_request`].
+type ChunkRequestResult = Result<Option<ErasureChunk>, (ValidatorIndex, RequestError)>;
+
+struct RequestSystematicChunks {
+	/// The number of systematic chunks, which are the chunks with the lowest indices.
+	threshold: usize,
+	/// The validators holding systematic chunks which have not been requested yet.
+	validators: Vec<ValidatorIndex>,
+	received_chunks: HashMap<ValidatorIndex, ErasureChunk>,
+	/// Pending chunk requests with soft timeout.
+	requesting_chunks: FuturesUndead<ChunkRequestResult>,
+}
+
 struct RequestChunksFromValidators {
 	/// How many request have been unsuccessful so far.
 	error_count: usize,
@@ -159,12 +187,12 @@ struct RequestChunksFromValidators {
 	///
 	/// including failed ones.
 	total_received_responses: usize,
-	/// a random shuffling of the validators which indicates the order in which we connect to the validators and
-	/// request the chunk from them.
+	/// a random shuffling of the validators, ranked by their observed performance, which indicates
+	/// the order in which we connect to the validators and request the chunk from them.
 	shuffling: VecDeque<ValidatorIndex>,
 	received_chunks: HashMap<ValidatorIndex, ErasureChunk>,
 	/// Pending chunk requests with soft timeout.
-	requesting_chunks: FuturesUndead<Result<Option<ErasureChunk>, (ValidatorIndex, RequestError)>>,
+	requesting_chunks: FuturesUndead<ChunkRequestResult>,
 }
 
 struct RecoveryParams {
@@ -177,6 +205,9 @@ struct RecoveryParams {
 	/// The number of pieces needed.
 	threshold: usize,
 
+	/// Which validator holds which chunk of the candidate.
+	chunk_mapping: AvailabilityChunkMapping,
+
 	/// A hash of the relevant candidate.
 	candidate_hash: CandidateHash,
 
@@ -186,6 +217,9 @@ struct RecoveryParams {
 	/// Metrics to report
 	metrics: Metrics,
 
+	/// Observed performance of validators, for ranking them and adapting timeouts.
+	peer_performance: PeerPerformance<AuthorityDiscoveryId>,
+
 	/// Do not request data from availability-store
 	bypass_availability_store: bool,
 }
@@ -195,6 +229,7 @@ struct RecoveryParams {
 /// backers (a.k.a. fast-path), or recover from chunks.
 enum Source {
 	RequestFromBackers(RequestFromBackers),
+	RequestSystematicChunks(RequestSystematicChunks),
 	RequestChunks(RequestChunksFromValidators),
 }
 
@@ -211,8 +246,9 @@ struct RecoveryTask<Sender> {
 }
 
 impl RequestFromBackers {
-	fn new(mut backers: Vec<ValidatorIndex>) -> Self {
+	fn new(mut backers: Vec<ValidatorIndex>, params: &RecoveryParams) -> Self {
 		backers.shuffle(&mut rand::thread_rng());
+		rank_validators(params, &mut backers);
 
 		RequestFromBackers { shuffled_backers: backers }
 	}
@@ -301,6 +337,26 @@ impl RequestChunksFromValidators {
 		}
 	}
 
+	/// Like `new`, but with the validators ranked by their observed performance.
+	fn ranked(params: &RecoveryParams) -> Self {
+		let mut phase = Self::new(params.validators.len() as _);
+		rank_validators(params, phase.shuffling.make_contiguous());
+		phase
+	}
+
+	/// Like `ranked`, but starting out with chunks which have been received already.
+	fn with_received_chunks(
+		params: &RecoveryParams,
+		received_chunks: HashMap<ValidatorIndex, ErasureChunk>,
+	) -> Self {
+		let mut phase = Self::ranked(params);
+		phase
+			.shuffling
+			.retain(|i| !received_chunks.contains_key(&params.chunk_mapping.chunk_index(*i)));
+		phase.received_chunks = received_chunks;
+		phase
+	}
+
 	fn is_unavailable(&self, params: &RecoveryParams) -> bool {
 		is_unavailable(
 			self.received_chunks.len(),
@@ -364,36 +420,9 @@ impl RequestChunksFromValidators {
 
 		while self.requesting_chunks.len() < num_requests {
 			if let Some(validator_index) = self.shuffling.pop_back() {
-				let validator = params.validator_authority_keys[validator_index.0 as usize].clone();
-				gum::trace!(
-					target: LOG_TARGET,
-					?validator,
-					?validator_index,
-					?candidate_hash,
-					"Requesting chunk",
-				);
-
-				// Request data.
-				let raw_request = req_res::v1::ChunkFetchingRequest {
-					candidate_hash: params.candidate_hash,
-					index: validator_index,
-				};
-
-				let (req, res) = OutgoingRequest::new(Recipient::Authority(validator), raw_request);
-				requests.push(Requests::ChunkFetchingV1(req));
-
-				params.metrics.on_chunk_request_issued();
-				let timer = params.metrics.time_chunk_request();
-
-				self.requesting_chunks.push(Box::pin(async move {
-					let _timer = timer;
-					match res.await {
-						Ok(req_res::v1::ChunkFetchingResponse::Chunk(chunk)) =>
-							Ok(Some(chunk.recombine_into_chunk(&raw_request))),
-						Ok(req_res::v1::ChunkFetchingResponse::NoSuchChunk) => Ok(None),
-						Err(e) => Err((validator_index, e)),
-					}
-				}));
+				let (request, response) = make_chunk_request(params, validator_index);
+				requests.push(request);
+				self.requesting_chunks.push(response);
 			} else {
 				break
 			}
@@ -519,7 +548,8 @@ impl RequestChunksFromValidators {
 					// This should either be length 1 or 0. If we had the whole data,
 					// we wouldn't have reached this stage.
 					let chunk_indices: Vec<_> = chunks.iter().map(|c| c.index).collect();
-					self.shuffling.retain(|i| !chunk_indices.contains(i));
+					self.shuffling
+						.retain(|i| !chunk_indices.contains(&params.chunk_mapping.chunk_index(*i)));
 
 					for chunk in chunks {
 						if is_chunk_valid(params, &chunk) {
@@ -575,56 +605,334 @@ impl RequestChunksFromValidators {
 			// If that fails, or a re-encoding of it doesn't match the expected erasure root,
 			// return Err(RecoveryError::Invalid)
 			if self.received_chunks.len() >= params.threshold {
-				let recovery_duration = metrics.time_erasure_recovery();
-
-				return match polkadot_erasure_coding::reconstruct_v1(
-					params.validators.len(),
-					self.received_chunks.values().map(|c| (&c.chunk[..], c.index.0 as usize)),
-				) {
-					Ok(data) => {
-						if reconstructed_data_matches_root(
-							params.validators.len(),
-							&params.erasure_root,
-							&data,
-						) {
-							gum::trace!(
-								target: LOG_TARGET,
-								candidate_hash = ?params.candidate_hash,
-								erasure_root = ?params.erasure_root,
-								"Data recovery complete",
-							);
-							metrics.on_recovery_succeeded();
+				return recover_and_check(params, || {
+					polkadot_erasure_coding::reconstruct_v1(
+						params.validators.len(),
+						self.received_chunks.values().map(|c| (&c.chunk[..], c.index.0 as usize)),
+					)
+				})
+			}
+		}
+	}
+}
 
-							Ok(data)
-						} else {
-							recovery_duration.map(|rd| rd.stop_and_discard());
-							gum::trace!(
-								target: LOG_TARGET,
-								candidate_hash = ?params.candidate_hash,
-								erasure_root = ?params.erasure_root,
-								"Data recovery - root mismatch",
-							);
-							metrics.on_recovery_invalid();
+impl RequestSystematicChunks {
+	fn new(threshold: usize, chunk_mapping: &AvailabilityChunkMapping) -> Self {
+		RequestSystematicChunks {
+			threshold,
+			// Reversed, so that the chunks are requested in order.
+			validators: (0..threshold as u32)
+				.rev()
+				.map(|chunk_index| chunk_mapping.validator_index(ValidatorIndex(chunk_index)))
+				.collect(),
+			received_chunks: HashMap::new(),
+			requesting_chunks: FuturesUndead::new(),
+		}
+	}
 
-							Err(RecoveryError::Invalid)
-						}
-					},
-					Err(err) => {
-						recovery_duration.map(|rd| rd.stop_and_discard());
+	async fn launch_parallel_requests<Sender>(
+		&mut self,
+		params: &RecoveryParams,
+		sender: &mut Sender,
+	) where
+		Sender: overseer::AvailabilityRecoverySenderTrait,
+	{
+		let mut requests = Vec::new();
+		while self.requesting_chunks.len() < N_PARALLEL {
+			if let Some(validator_index) = self.validators.pop() {
+				let (request, response) = make_chunk_request(params, validator_index);
+				requests.push(request);
+				self.requesting_chunks.push(response);
+			} else {
+				break
+			}
+		}
+
+		if !requests.is_empty() {
+			sender
+				.send_message(NetworkBridgeTxMessage::SendRequests(
+					requests,
+					IfDisconnected::TryConnect,
+				))
+				.await;
+		}
+	}
+
+	/// Fetch the systematic chunks. Returns the reason for giving up as soon as any of them cannot
+	/// be fetched.
+	async fn fetch_chunks<Sender>(
+		&mut self,
+		params: &RecoveryParams,
+		sender: &mut Sender,
+	) -> Result<(), &'static str>
+	where
+		Sender: overseer::AvailabilityRecoverySenderTrait,
+	{
+		let metrics = &params.metrics;
+
+		while self.received_chunks.len() < self.threshold {
+			self.launch_parallel_requests(params, sender).await;
+
+			match self.requesting_chunks.next_with_timeout(TIMEOUT_START_NEW_REQUESTS).await {
+				Some(Ok(Some(chunk))) =>
+					if is_chunk_valid(params, &chunk) {
+						metrics.on_chunk_request_succeeded();
 						gum::trace!(
 							target: LOG_TARGET,
 							candidate_hash = ?params.candidate_hash,
-							erasure_root = ?params.erasure_root,
-							?err,
-							"Data recovery error ",
+							validator_index = ?chunk.index,
+							"Received valid systematic chunk",
 						);
-						metrics.on_recovery_invalid();
-
-						Err(RecoveryError::Invalid)
+						self.received_chunks.insert(chunk.index, chunk);
+					} else {
+						metrics.on_chunk_request_invalid();
+						return Err("invalid chunk")
 					},
+				Some(Ok(None)) => {
+					metrics.on_chunk_request_no_such_chunk();
+					return Err("no such chunk")
+				},
+				Some(Err((_, RequestError::InvalidResponse(_)))) => {
+					metrics.on_chunk_request_invalid();
+					return Err("invalid response")
+				},
+				Some(Err((_, RequestError::NetworkError(err)))) => {
+					if let RequestFailure::Network(OutboundFailure::Timeout) = err {
+						metrics.on_chunk_request_timeout();
+					} else {
+						metrics.on_chunk_request_error();
+					}
+					return Err("network error")
+				},
+				Some(Err((_, RequestError::Canceled(_)))) => {
+					metrics.on_chunk_request_error();
+					return Err("request canceled")
+				},
+				None => return Err("requests timed out"),
+			}
+		}
+
+		Ok(())
+	}
+
+	// Run this phase to completion. Fails with `RecoveryError::Unavailable` as soon as any of the
+	// systematic chunks cannot be fetched, in which case the chunks received so far can still be
+	// used to recover the data from chunks of any validators.
+	async fn run<Sender>(
+		&mut self,
+		params: &RecoveryParams,
+		sender: &mut Sender,
+	) -> Result<AvailableData, RecoveryError>
+	where
+		Sender: overseer::AvailabilityRecoverySenderTrait,
+	{
+		if !params.bypass_availability_store {
+			for chunk in query_stored_chunks(params, sender).await {
+				if (chunk.index.0 as usize) < self.threshold && is_chunk_valid(params, &chunk) {
+					self.validators.retain(|i| params.chunk_mapping.chunk_index(*i) != chunk.index);
+					self.received_chunks.insert(chunk.index, chunk);
 				}
 			}
 		}
+
+		gum::trace!(
+			target: LOG_TARGET,
+			candidate_hash = ?params.candidate_hash,
+			erasure_root = ?params.erasure_root,
+			threshold = ?self.threshold,
+			"Requesting systematic chunks",
+		);
+
+		let recovery_timer = params.metrics.time_full_recovery();
+
+		if let Err(reason) = self.fetch_chunks(params, sender).await {
+			// The recovery continues with the regular chunks, which is timed on its own.
+			recovery_timer.map(|rt| rt.stop_and_discard());
+			gum::debug!(
+				target: LOG_TARGET,
+				candidate_hash = ?params.candidate_hash,
+				received_chunks_count = ?self.received_chunks.len(),
+				threshold = ?self.threshold,
+				reason,
+				"Falling back to regular chunk recovery",
+			);
+			params.metrics.on_systematic_recovery_fallback();
+
+			return Err(RecoveryError::Unavailable)
+		}
+
+		recover_and_check(params, || {
+			let mut chunks: Vec<_> = self.received_chunks.values().collect();
+			chunks.sort_by_key(|chunk| chunk.index);
+			polkadot_erasure_coding::reconstruct_from_systematic_v1(
+				params.validators.len(),
+				chunks.into_iter().map(|chunk| chunk.chunk.clone()).collect(),
+			)
+		})
+	}
+}
+
+/// Order validators by their observed performance, such that the best ones get popped first.
+fn rank_validators(params: &RecoveryParams, validators: &mut [ValidatorIndex]) {
+	params.peer_performance.sort_by_preference(validators, 0, |validator_index| {
+		params.validator_authority_keys[validator_index.0 as usize].clone()
+	});
+	validators.reverse();
+}
+
+/// Build a request for the chunk held by the given validator, along with the future resolving to
+/// the response.
+///
+/// The response times out according to the observed performance of the validator, which also gets
+/// updated with the outcome of the request.
+fn make_chunk_request(
+	params: &RecoveryParams,
+	validator_index: ValidatorIndex,
+) -> (Requests, BoxFuture<'static, ChunkRequestResult>) {
+	let validator = params.validator_authority_keys[validator_index.0 as usize].clone();
+	let chunk_index = params.chunk_mapping.chunk_index(validator_index);
+	gum::trace!(
+		target: LOG_TARGET,
+		?validator,
+		?validator_index,
+		?chunk_index,
+		candidate_hash = ?params.candidate_hash,
+		"Requesting chunk",
+	);
+
+	// Request data.
+	let raw_request = req_res::v2::ChunkFetchingRequest {
+		candidate_hash: params.candidate_hash,
+		index: chunk_index,
+	};
+
+	let timeout = params.peer_performance.timeout(&validator, 0);
+	let peer_performance = params.peer_performance.clone();
+	let metrics = params.metrics.clone();
+	let candidate_hash = params.candidate_hash;
+
+	let (req, res) =
+		OutgoingRequest::new_with_fallback(Recipient::Authority(validator.clone()), raw_request);
+
+	params.metrics.on_chunk_request_issued();
+	let timer = params.metrics.time_chunk_request();
+
+	let response = async move {
+		let _timer = timer;
+		let started = Instant::now();
+		let res = res.timeout(timeout).await.unwrap_or_else(|| {
+			Err(RequestError::NetworkError(RequestFailure::Network(OutboundFailure::Timeout)))
+		});
+
+		// Only the requested chunk is of use, since the validator is expected to hold exactly that
+		// one. A chunk with another index is treated as an invalid response.
+		let res = match res {
+			Ok(req_res::v2::ChunkFetchingResponse::Chunk(chunk)) if chunk.index != chunk_index => {
+				gum::debug!(
+					target: LOG_TARGET,
+					?candidate_hash,
+					?validator,
+					?chunk_index,
+					received_chunk_index = ?chunk.index,
+					"Received a chunk with an unexpected index",
+				);
+				Err(RequestError::InvalidResponse("Unexpected chunk index".into()))
+			},
+			res => res,
+		};
+
+		let estimate = match &res {
+			Ok(req_res::v2::ChunkFetchingResponse::Chunk(chunk)) =>
+				peer_performance.note_response(&validator, started.elapsed(), chunk.chunk.len()),
+			Ok(req_res::v2::ChunkFetchingResponse::NoSuchChunk) =>
+				peer_performance.note_response(&validator, started.elapsed(), 0),
+			Err(_) => peer_performance.note_failure(&validator),
+		};
+		metrics.on_peer_performance(&estimate);
+
+		match res {
+			Ok(req_res::v2::ChunkFetchingResponse::Chunk(chunk)) => Ok(Some(chunk)),
+			Ok(req_res::v2::ChunkFetchingResponse::NoSuchChunk) => Ok(None),
+			Err(e) => Err((validator_index, e)),
+		}
+	};
+
+	(Requests::ChunkFetchingV2(req), response.boxed())
+}
+
+/// Query the availability store for any chunks we've got.
+async fn query_stored_chunks(
+	params: &RecoveryParams,
+	sender: &mut impl overseer::AvailabilityRecoverySenderTrait,
+) -> Vec<ErasureChunk> {
+	let (tx, rx) = oneshot::channel();
+	sender
+		.send_message(AvailabilityStoreMessage::QueryAllChunks(params.candidate_hash, tx))
+		.await;
+
+	match rx.await {
+		Ok(chunks) => chunks,
+		Err(oneshot::Canceled) => {
+			gum::warn!(
+				target: LOG_TARGET,
+				candidate_hash = ?params.candidate_hash,
+				"Failed to reach the availability store"
+			);
+			Vec::new()
+		},
+	}
+}
+
+/// Recover the data from the chunks and check it against the erasure root.
+///
+/// Returns `Err(RecoveryError::Invalid)` if that fails or if a re-encoding of the data doesn't match
+/// the erasure root.
+fn recover_and_check(
+	params: &RecoveryParams,
+	recover: impl FnOnce() -> Result<AvailableData, polkadot_erasure_coding::Error>,
+) -> Result<AvailableData, RecoveryError> {
+	let metrics = &params.metrics;
+	let recovery_duration = metrics.time_erasure_recovery();
+
+	match recover() {
+		Ok(data) => {
+			if reconstructed_data_matches_root(params.validators.len(), &params.erasure_root, &data)
+			{
+				gum::trace!(
+					target: LOG_TARGET,
+					candidate_hash = ?params.candidate_hash,
+					erasure_root = ?params.erasure_root,
+					"Data recovery complete",
+				);
+				metrics.on_recovery_succeeded();
+
+				Ok(data)
+			} else {
+				recovery_duration.map(|rd| rd.stop_and_discard());
+				gum::trace!(
+					target: LOG_TARGET,
+					candidate_hash = ?params.candidate_hash,
+					erasure_root = ?params.erasure_root,
+					"Data recovery - root mismatch",
+				);
+				metrics.on_recovery_invalid();
+
+				Err(RecoveryError::Invalid)
+			}
+		},
+		Err(err) => {
+			recovery_duration.map(|rd| rd.stop_and_discard());
+			gum::trace!(
+				target: LOG_TARGET,
+				candidate_hash = ?params.candidate_hash,
+				erasure_root = ?params.erasure_root,
+				?err,
+				"Data recovery error ",
+			);
+			metrics.on_recovery_invalid();
+
+			Err(RecoveryError::Invalid)
+		},
 	}
 }
 
@@ -742,9 +1050,24 @@ where
 						Ok(data) => break Ok(data),
 						Err(RecoveryError::Invalid) => break Err(RecoveryError::Invalid),
 						Err(RecoveryError::Unavailable) =>
-							self.source = Source::RequestChunks(RequestChunksFromValidators::new(
-								self.params.validators.len() as _,
-							)),
+							self.source = Source::RequestChunks(
+								RequestChunksFromValidators::ranked(&self.params),
+							),
+					}
+				},
+				Source::RequestSystematicChunks(ref mut systematic) => {
+					match systematic.run(&self.params, &mut self.sender).await {
+						Ok(data) => break Ok(data),
+						Err(RecoveryError::Invalid) => break Err(RecoveryError::Invalid),
+						Err(RecoveryError::Unavailable) => {
+							let received_chunks = std::mem::take(&mut systematic.received_chunks);
+							self.source = Source::RequestChunks(
+								RequestChunksFromValidators::with_received_chunks(
+									&self.params,
+									received_chunks,
+								),
+							)
+						},
 					}
 				},
 				Source::RequestChunks(ref mut from_all) =>
@@ -847,6 +1170,9 @@ struct State {
 
 	/// An LRU cache of recently recovered data.
 	availability_lru: LruCache<CandidateHash, CachedRecovery>,
+
+	/// Observed performance of validators, shared by all recovery tasks.
+	peer_performance: PeerPerformance<AuthorityDiscoveryId>,
 }
 
 impl Default for State {
@@ -855,6 +1181,10 @@ impl Default for State {
 			ongoing_recoveries: FuturesUnordered::new(),
 			live_block: (0, Hash::default()),
 			availability_lru: LruCache::new(LRU_SIZE),
+			peer_performance: PeerPerformance::new(
+				MIN_CHUNK_REQUEST_TIMEOUT,
+				CHUNK_REQUEST_TIMEOUT,
+			),
 		}
 	}
 }
@@ -894,6 +1224,8 @@ async fn launch_recovery_task<Context>(
 	state: &mut State,
 	ctx: &mut Context,
 	session_info: SessionInfo,
+	node_features: NodeFeatures,
+	relay_parent_number: BlockNumber,
 	receipt: CandidateReceipt,
 	mut backing_group: Option<GroupIndex>,
 	response_sender: oneshot::Sender<Result<AvailableData, RecoveryError>>,
@@ -906,9 +1238,17 @@ async fn launch_recovery_task<Context>(
 		validator_authority_keys: session_info.discovery_keys.clone(),
 		validators: session_info.validators.clone(),
 		threshold: recovery_threshold(session_info.validators.len())?,
+		chunk_mapping: AvailabilityChunkMapping::new(
+			&node_features,
+			session_info.validators.len(),
+			&session_info.random_seed,
+			relay_parent_number,
+			receipt.descriptor.para_id,
+		),
 		candidate_hash,
 		erasure_root: receipt.descriptor.erasure_root,
 		metrics: metrics.clone(),
+		peer_performance: state.peer_performance.clone(),
 		bypass_availability_store: recovery_strategy == &RecoveryStrategy::BypassAvailabilityStore,
 	};
 
@@ -936,12 +1276,15 @@ async fn launch_recovery_task<Context>(
 		}
 	}
 
-	let phase = backing_group
-		.and_then(|g| session_info.validator_groups.get(g))
-		.map(|group| Source::RequestFromBackers(RequestFromBackers::new(group.clone())))
-		.unwrap_or_else(|| {
-			Source::RequestChunks(RequestChunksFromValidators::new(params.validators.len() as _))
-		});
+	let phase = match backing_group.and_then(|g| session_info.validator_groups.get(g)) {
+		Some(group) => Source::RequestFromBackers(RequestFromBackers::new(group.clone(), &params)),
+		None if recovery_strategy == &RecoveryStrategy::SystematicChunks =>
+			Source::RequestSystematicChunks(RequestSystematicChunks::new(
+				systematic_recovery_threshold(session_info.validators.len())?,
+				&params.chunk_mapping,
+			)),
+		None => Source::RequestChunks(RequestChunksFromValidators::ranked(&params)),
+	};
 
 	let recovery_task = RecoveryTask { sender: ctx.sender().clone(), params, source: phase };
 
@@ -1008,26 +1351,45 @@ async fn handle_recover<Context>(
 		.map_err(error::Error::CanceledSessionInfo)??;
 
 	let _span = span.child("session-info-ctx-received");
-	match session_info {
-		Some(session_info) =>
+	let relay_parent = receipt.descriptor.relay_parent;
+	let (tx, rx) = oneshot::channel();
+	ctx.send_message(ChainApiMessage::BlockNumber(relay_parent, tx)).await;
+	let relay_parent_number = rx.await.map_err(error::Error::CanceledBlockNumber)??;
+
+	match (session_info, relay_parent_number) {
+		(Some(session_info), Some(relay_parent_number)) => {
+			// The chunk holders use the node features of the session the candidate was backed in,
+			// which may have changed since at the live block.
+			let node_features = node_features_at_relay_parent(relay_parent, ctx.sender()).await?;
+
 			launch_recovery_task(
 				state,
 				ctx,
 				session_info,
+				node_features,
+				relay_parent_number,
 				receipt,
 				backing_group,
 				response_sender,
 				metrics,
 				recovery_strategy,
 			)
-			.await,
-		None => {
+			.await
+		},
+		(None, _) => {
 			gum::warn!(target: LOG_TARGET, "SessionInfo is `None` at {:?}", state.live_block);
 			response_sender
 				.send(Err(RecoveryError::Unavailable))
 				.map_err(|_| error::Error::CanceledResponseSender)?;
 			Ok(())
 		},
+		(Some(_), None) => {
+			gum::warn!(target: LOG_TARGET, ?relay_parent, "Relay parent of the candidate is unknown");
+			response_sender
+				.send(Err(RecoveryError::Unavailable))
+				.map_err(|_| error::Error::CanceledResponseSender)?;
+			Ok(())
+		},
 	}
 }
 
@@ -1084,6 +1446,15 @@ impl AvailabilityRecoverySubsystem {
 		Self { recovery_strategy: RecoveryStrategy::ChunksAlways, req_receiver, metrics }
 	}
 
+	/// Create a new instance of `AvailabilityRecoverySubsystem` which first requests the systematic
+	/// chunks, which do not need to be erasure decoded, and falls back to requesting any chunks.
+	pub fn with_systematic_chunks(
+		req_receiver: IncomingRequestReceiver<request_v1::AvailableDataFetchingRequest>,
+		metrics: Metrics,
+	) -> Self {
+		Self { recovery_strategy: RecoveryStrategy::SystematicChunks, req_receiver, metrics }
+	}
+
 	/// Create a new instance of `AvailabilityRecoverySubsystem` which requests chunks if PoV is
 	/// above a threshold.
 	pub fn with_chunks_if_pov_large(
@@ -14,6 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
+use polkadot_node_network_protocol::request_response::PerformanceEstimate;
 use polkadot_node_subsystem_util::metrics::{
 	self,
 	prometheus::{self, Counter, CounterVec, Histogram, Opts, PrometheusError, Registry, U64},
@@ -58,6 +59,20 @@ struct MetricsInner {
 	/// Note: Those are only recoveries which could not get served locally already - so in other
 	/// words: Only real recoveries.
 	full_recoveries_started: Counter<U64>,
+
+	/// Number of recoveries from systematic chunks which had to fall back to regular chunks.
+	systematic_recovery_fallbacks: Counter<U64>,
+
+	/// Estimated chunk request response times of validators in seconds, observed on every update.
+	///
+	/// Not labelled by validator, to keep the number of time series bounded.
+	peer_response_time: Histogram,
+
+	/// Estimated chunk response throughputs of validators in bytes per second.
+	peer_throughput: Histogram,
+
+	/// Estimated fractions of failed chunk requests of validators.
+	peer_failure_rate: Histogram,
 }
 
 impl Metrics {
@@ -150,6 +165,24 @@ impl Metrics {
 			metrics.full_recoveries_started.inc()
 		}
 	}
+
+	/// A recovery from systematic chunks fell back to regular chunks.
+	pub fn on_systematic_recovery_fallback(&self) {
+		if let Some(metrics) = &self.0 {
+			metrics.systematic_recovery_fallbacks.inc()
+		}
+	}
+
+	/// The performance estimate of a validator got updated.
+	pub fn on_peer_performance(&self, estimate: &PerformanceEstimate) {
+		if let Some(metrics) = &self.0 {
+			metrics.peer_response_time.observe(estimate.response_time.as_secs_f64());
+			if let Some(throughput) = estimate.throughput {
+				metrics.peer_throughput.observe(throughput);
+			}
+			metrics.peer_failure_rate.observe(estimate.failure_rate);
+		}
+	}
 }
 
 impl metrics::Metrics for Metrics {
@@ -210,6 +243,43 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			systematic_recovery_fallbacks: prometheus::register(
+				Counter::new(
+					"polkadot_parachain_availability_recovery_systematic_fallbacks",
+					"Total number of recoveries from systematic chunks which fell back to regular chunks.",
+				)?,
+				registry,
+			)?,
+			peer_response_time: prometheus::register(
+				prometheus::Histogram::with_opts(prometheus::HistogramOpts::new(
+					"polkadot_parachain_availability_recovery_peer_response_time",
+					"Estimated response times of validators to chunk requests in seconds.",
+				))?,
+				registry,
+			)?,
+			peer_throughput: prometheus::register(
+				prometheus::Histogram::with_opts(
+					prometheus::HistogramOpts::new(
+						"polkadot_parachain_availability_recovery_peer_throughput",
+						"Estimated throughputs of validators' chunk responses in bytes per second.",
+					)
+					.buckets(
+						prometheus::exponential_buckets(16384.0, 2.0, 12)
+							.expect("arguments are always valid; qed"),
+					),
+				)?,
+				registry,
+			)?,
+			peer_failure_rate: prometheus::register(
+				prometheus::Histogram::with_opts(
+					prometheus::HistogramOpts::new(
+						"polkadot_parachain_availability_recovery_peer_failure_rate",
+						"Estimated fractions of failed chunk requests to validators.",
+					)
+					.buckets(vec![0.01, 0.05, 0.1, 0.2, 0.3, 0.5, 0.75, 1.0]),
+				)?,
+				registry,
+			)?,
 		};
 		Ok(Metrics(Some(metrics)))
 	}
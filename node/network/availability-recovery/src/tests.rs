@@ -37,7 +37,8 @@ use polkadot_node_subsystem::{
 use polkadot_node_subsystem_test_helpers::{make_subsystem_context, TestSubsystemContextHandle};
 use polkadot_node_subsystem_util::TimeoutExt;
 use polkadot_primitives::{
-	AuthorityDiscoveryId, Hash, HeadData, IndexedVec, PersistedValidationData, ValidatorId,
+	vstaging::NodeFeatures, AuthorityDiscoveryId, Hash, HeadData, IndexedVec,
+	PersistedValidationData, ValidatorId,
 };
 use polkadot_primitives_test_helpers::{dummy_candidate_receipt, dummy_hash};
 
@@ -117,6 +118,42 @@ fn test_harness_chunks_only<T: Future<Output = (VirtualOverseer, RequestResponse
 	.unwrap();
 }
 
+fn test_harness_systematic_chunks<T: Future<Output = (VirtualOverseer, RequestResponseConfig)>>(
+	test: impl FnOnce(VirtualOverseer, RequestResponseConfig) -> T,
+) {
+	let _ = env_logger::builder()
+		.is_test(true)
+		.filter(Some("polkadot_availability_recovery"), log::LevelFilter::Trace)
+		.try_init();
+
+	let pool = sp_core::testing::TaskExecutor::new();
+
+	let (context, virtual_overseer) = make_subsystem_context(pool.clone());
+
+	let (collation_req_receiver, req_cfg) =
+		IncomingRequest::get_config_receiver(&ReqProtocolNames::new(&GENESIS_HASH, None));
+	let subsystem = AvailabilityRecoverySubsystem::with_systematic_chunks(
+		collation_req_receiver,
+		Metrics::new_dummy(),
+	);
+	let subsystem = subsystem.run(context);
+
+	let test_fut = test(virtual_overseer, req_cfg);
+
+	futures::pin_mut!(test_fut);
+	futures::pin_mut!(subsystem);
+
+	executor::block_on(future::join(
+		async move {
+			let (mut overseer, _req_cfg) = test_fut.await;
+			overseer_signal(&mut overseer, OverseerSignal::Conclude).await;
+		},
+		subsystem,
+	))
+	.1
+	.unwrap();
+}
+
 fn test_harness_chunks_if_pov_large<
 	T: Future<Output = (VirtualOverseer, RequestResponseConfig)>,
 >(
@@ -224,6 +261,9 @@ struct TestState {
 	current: Hash,
 	candidate: CandidateReceipt,
 	session_index: SessionIndex,
+	node_features: NodeFeatures,
+	random_seed: [u8; 32],
+	relay_parent_number: BlockNumber,
 
 	persisted_validation_data: PersistedValidationData,
 
@@ -237,10 +277,41 @@ impl TestState {
 		recovery_threshold(self.validators.len()).unwrap()
 	}
 
+	fn systematic_threshold(&self) -> usize {
+		systematic_recovery_threshold(self.validators.len()).unwrap()
+	}
+
 	fn impossibility_threshold(&self) -> usize {
 		self.validators.len() - self.threshold() + 1
 	}
 
+	fn chunk_mapping(&self) -> AvailabilityChunkMapping {
+		AvailabilityChunkMapping::new(
+			&self.node_features,
+			self.validators.len(),
+			&self.random_seed,
+			self.relay_parent_number,
+			self.candidate.descriptor.para_id,
+		)
+	}
+
+	fn recovery_params(
+		&self,
+		peer_performance: PeerPerformance<AuthorityDiscoveryId>,
+	) -> RecoveryParams {
+		RecoveryParams {
+			validator_authority_keys: self.validator_authority_id.clone(),
+			validators: self.validator_public.clone(),
+			threshold: self.threshold(),
+			chunk_mapping: self.chunk_mapping(),
+			candidate_hash: self.candidate.hash(),
+			erasure_root: self.candidate.descriptor.erasure_root,
+			metrics: Metrics::default(),
+			peer_performance,
+			bypass_availability_store: false,
+		}
+	}
+
 	async fn test_runtime_api(&self, virtual_overseer: &mut VirtualOverseer) {
 		assert_matches!(
 			overseer_recv(virtual_overseer).await,
@@ -268,10 +339,31 @@ impl TestState {
 					needed_approvals: 0,
 					active_validator_indices: vec![],
 					dispute_period: 6,
-					random_seed: [0u8; 32],
+					random_seed: self.random_seed,
 				}))).unwrap();
 			}
 		);
+
+		assert_matches!(
+			overseer_recv(virtual_overseer).await,
+			AllMessages::ChainApi(ChainApiMessage::BlockNumber(relay_parent, tx)) => {
+				assert_eq!(relay_parent, self.candidate.descriptor.relay_parent);
+
+				tx.send(Ok(Some(self.relay_parent_number))).unwrap();
+			}
+		);
+
+		assert_matches!(
+			overseer_recv(virtual_overseer).await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				relay_parent,
+				RuntimeApiRequest::NodeFeatures(tx)
+			)) => {
+				assert_eq!(relay_parent, self.candidate.descriptor.relay_parent);
+
+				tx.send(Ok(self.node_features.clone())).unwrap();
+			}
+		);
 	}
 
 	async fn respond_to_available_data_query(
@@ -358,13 +450,22 @@ impl TestState {
 						i += 1;
 						assert_matches!(
 							req,
-							Requests::ChunkFetchingV1(req) => {
+							Requests::ChunkFetchingV2(req) => {
 								assert_eq!(req.payload.candidate_hash, candidate_hash);
 
-								let validator_index = req.payload.index.0 as usize;
-								let available_data = match who_has(validator_index) {
+								// The chunk must be requested from the validator holding it.
+								let holder = self.chunk_mapping().validator_index(req.payload.index);
+								assert_eq!(
+									req.peer,
+									Recipient::Authority(
+										self.validator_authority_id[holder.0 as usize].clone()
+									)
+								);
+
+								let chunk_index = req.payload.index.0 as usize;
+								let available_data = match who_has(chunk_index) {
 									Has::No => Ok(None),
-									Has::Yes => Ok(Some(self.chunks[validator_index].clone().into())),
+									Has::Yes => Ok(Some(self.chunks[chunk_index].clone())),
 									Has::NetworkError(e) => Err(e),
 									Has::DoesNotReturn => {
 										senders.push(req.pending_response);
@@ -374,7 +475,7 @@ impl TestState {
 
 								let _ = req.pending_response.send(
 									available_data.map(|r|
-										req_res::v1::ChunkFetchingResponse::from(r).encode()
+										req_res::v2::ChunkFetchingResponse::from(r).encode()
 									)
 								);
 							}
@@ -542,6 +643,9 @@ impl Default for TestState {
 			current,
 			candidate,
 			session_index,
+			node_features: NodeFeatures::new(),
+			random_seed: [0u8; 32],
+			relay_parent_number: 1,
 			persisted_validation_data,
 			available_data,
 			chunks,
@@ -722,6 +826,262 @@ fn availability_is_recovered_from_chunks_even_if_backing_group_supplied_if_chunk
 	});
 }
 
+#[test]
+fn availability_is_recovered_from_systematic_chunks() {
+	let test_state = TestState::default();
+
+	test_harness_systematic_chunks(|mut virtual_overseer, req_cfg| async move {
+		overseer_signal(
+			&mut virtual_overseer,
+			OverseerSignal::ActiveLeaves(ActiveLeavesUpdate::start_work(ActivatedLeaf {
+				hash: test_state.current.clone(),
+				number: 1,
+				status: LeafStatus::Fresh,
+				span: Arc::new(jaeger::Span::Disabled),
+			})),
+		)
+		.await;
+
+		let (tx, rx) = oneshot::channel();
+
+		overseer_send(
+			&mut virtual_overseer,
+			AvailabilityRecoveryMessage::RecoverAvailableData(
+				test_state.candidate.clone(),
+				test_state.session_index,
+				None,
+				tx,
+			),
+		)
+		.await;
+
+		test_state.test_runtime_api(&mut virtual_overseer).await;
+
+		let candidate_hash = test_state.candidate.hash();
+
+		test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+		test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
+
+		let systematic_threshold = test_state.systematic_threshold();
+		test_state
+			.test_chunk_requests(candidate_hash, &mut virtual_overseer, systematic_threshold, |i| {
+				if i < systematic_threshold {
+					Has::Yes
+				} else {
+					panic!("not systematic")
+				}
+			})
+			.await;
+
+		// Recovered data should match the original one.
+		assert_eq!(rx.await.unwrap().unwrap(), test_state.available_data);
+		(virtual_overseer, req_cfg)
+	});
+}
+
+#[test]
+fn availability_is_recovered_from_shuffled_systematic_chunks() {
+	let mut test_state = TestState::default();
+	test_state.node_features = NodeFeatures::repeat(true, 1);
+	test_state.random_seed = [42u8; 32];
+	// Pick a relay parent for which the systematic chunks are not held by the first validators.
+	test_state.relay_parent_number = (0..=BlockNumber::from(u8::MAX))
+		.find(|relay_parent_number| {
+			AvailabilityChunkMapping::new(
+				&test_state.node_features,
+				test_state.validators.len(),
+				&test_state.random_seed,
+				*relay_parent_number,
+				test_state.candidate.descriptor.para_id,
+			)
+			.chunk_index(ValidatorIndex(0)) !=
+				ValidatorIndex(0)
+		})
+		.unwrap();
+
+	test_harness_systematic_chunks(|mut virtual_overseer, req_cfg| async move {
+		overseer_signal(
+			&mut virtual_overseer,
+			OverseerSignal::ActiveLeaves(ActiveLeavesUpdate::start_work(ActivatedLeaf {
+				hash: test_state.current.clone(),
+				number: 1,
+				status: LeafStatus::Fresh,
+				span: Arc::new(jaeger::Span::Disabled),
+			})),
+		)
+		.await;
+
+		let (tx, rx) = oneshot::channel();
+
+		overseer_send(
+			&mut virtual_overseer,
+			AvailabilityRecoveryMessage::RecoverAvailableData(
+				test_state.candidate.clone(),
+				test_state.session_index,
+				None,
+				tx,
+			),
+		)
+		.await;
+
+		test_state.test_runtime_api(&mut virtual_overseer).await;
+
+		let candidate_hash = test_state.candidate.hash();
+
+		test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+		test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
+
+		// The requests are checked to go to the validators the chunks are assigned to.
+		let systematic_threshold = test_state.systematic_threshold();
+		test_state
+			.test_chunk_requests(candidate_hash, &mut virtual_overseer, systematic_threshold, |i| {
+				if i < systematic_threshold {
+					Has::Yes
+				} else {
+					panic!("not systematic")
+				}
+			})
+			.await;
+
+		// Recovered data should match the original one.
+		assert_eq!(rx.await.unwrap().unwrap(), test_state.available_data);
+		(virtual_overseer, req_cfg)
+	});
+}
+
+#[test]
+fn systematic_recovery_falls_back_to_regular_chunks() {
+	let test_state = TestState::default();
+
+	test_harness_systematic_chunks(|mut virtual_overseer, req_cfg| async move {
+		overseer_signal(
+			&mut virtual_overseer,
+			OverseerSignal::ActiveLeaves(ActiveLeavesUpdate::start_work(ActivatedLeaf {
+				hash: test_state.current.clone(),
+				number: 1,
+				status: LeafStatus::Fresh,
+				span: Arc::new(jaeger::Span::Disabled),
+			})),
+		)
+		.await;
+
+		let (tx, rx) = oneshot::channel();
+
+		overseer_send(
+			&mut virtual_overseer,
+			AvailabilityRecoveryMessage::RecoverAvailableData(
+				test_state.candidate.clone(),
+				test_state.session_index,
+				None,
+				tx,
+			),
+		)
+		.await;
+
+		test_state.test_runtime_api(&mut virtual_overseer).await;
+
+		let candidate_hash = test_state.candidate.hash();
+
+		test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+		test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
+
+		// None of the systematic chunk holders has its chunk.
+		test_state
+			.test_chunk_requests(
+				candidate_hash,
+				&mut virtual_overseer,
+				test_state.systematic_threshold(),
+				|_| Has::No,
+			)
+			.await;
+
+		// Regular recovery from any chunks.
+		test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
+		test_state
+			.test_chunk_requests(
+				candidate_hash,
+				&mut virtual_overseer,
+				test_state.threshold(),
+				|_| Has::Yes,
+			)
+			.await;
+
+		// Recovered data should match the original one.
+		assert_eq!(rx.await.unwrap().unwrap(), test_state.available_data);
+		(virtual_overseer, req_cfg)
+	});
+}
+
+#[test]
+fn systematic_recovery_falls_back_on_wrong_chunk_index() {
+	let mut test_state = TestState::default();
+
+	test_harness_systematic_chunks(|mut virtual_overseer, req_cfg| async move {
+		overseer_signal(
+			&mut virtual_overseer,
+			OverseerSignal::ActiveLeaves(ActiveLeavesUpdate::start_work(ActivatedLeaf {
+				hash: test_state.current.clone(),
+				number: 1,
+				status: LeafStatus::Fresh,
+				span: Arc::new(jaeger::Span::Disabled),
+			})),
+		)
+		.await;
+
+		let (tx, rx) = oneshot::channel();
+
+		overseer_send(
+			&mut virtual_overseer,
+			AvailabilityRecoveryMessage::RecoverAvailableData(
+				test_state.candidate.clone(),
+				test_state.session_index,
+				None,
+				tx,
+			),
+		)
+		.await;
+
+		test_state.test_runtime_api(&mut virtual_overseer).await;
+
+		let candidate_hash = test_state.candidate.hash();
+
+		test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+		test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
+
+		// The systematic chunk holders respond with a valid chunk, which is not the requested one.
+		let chunks = test_state.chunks.clone();
+		let systematic_threshold = test_state.systematic_threshold();
+		let wrong_chunk = chunks.last().unwrap().clone();
+		for chunk in &mut test_state.chunks[..systematic_threshold] {
+			*chunk = wrong_chunk.clone();
+		}
+		test_state
+			.test_chunk_requests(
+				candidate_hash,
+				&mut virtual_overseer,
+				systematic_threshold,
+				|_| Has::Yes,
+			)
+			.await;
+
+		// Regular recovery from any chunks, which are served correctly this time.
+		test_state.chunks = chunks;
+		test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
+		test_state
+			.test_chunk_requests(
+				candidate_hash,
+				&mut virtual_overseer,
+				test_state.threshold(),
+				|_| Has::Yes,
+			)
+			.await;
+
+		// Recovered data should match the original one.
+		assert_eq!(rx.await.unwrap().unwrap(), test_state.available_data);
+		(virtual_overseer, req_cfg)
+	});
+}
+
 #[test]
 fn bad_merkle_path_leads_to_recovery_error() {
 	let mut test_state = TestState::default();
@@ -1606,3 +1966,57 @@ fn parallel_request_calculation_works_as_expected() {
 	// With error count zero - we should fetch exactly as needed:
 	assert_eq!(phase.get_desired_request_count(threshold), threshold - phase.received_chunks.len());
 }
+
+#[test]
+fn validators_are_ranked_by_observed_performance() {
+	let test_state = TestState::default();
+	let peer_performance = PeerPerformance::new(MIN_CHUNK_REQUEST_TIMEOUT, CHUNK_REQUEST_TIMEOUT);
+	let keys = &test_state.validator_authority_id;
+	peer_performance.note_response(&keys[4], Duration::from_millis(100), 0);
+	peer_performance.note_response(&keys[2], Duration::from_millis(200), 0);
+	peer_performance.note_response(&keys[3], Duration::from_millis(300), 0);
+	peer_performance.note_failure(&keys[1]);
+	let params = test_state.recovery_params(peer_performance);
+
+	// The best performing validators are asked first, the failing one last. Validator 0 is not
+	// known and ranked like the median validator 3.
+	let mut phase = RequestChunksFromValidators::ranked(&params);
+	assert_eq!(phase.shuffling.pop_back(), Some(ValidatorIndex(4)));
+	assert_eq!(phase.shuffling.pop_back(), Some(ValidatorIndex(2)));
+	assert_eq!(phase.shuffling.pop_front(), Some(ValidatorIndex(1)));
+	assert_eq!(phase.shuffling.len(), 2);
+
+	let mut phase = RequestFromBackers::new(vec![ValidatorIndex(1), ValidatorIndex(4)], &params);
+	assert_eq!(phase.shuffled_backers.pop(), Some(ValidatorIndex(4)));
+	assert_eq!(phase.shuffled_backers.pop(), Some(ValidatorIndex(1)));
+}
+
+#[test]
+fn chunk_requests_time_out_according_to_observed_performance() {
+	let test_state = TestState::default();
+	let validator_index = ValidatorIndex(1);
+	let validator = test_state.validator_authority_id[validator_index.0 as usize].clone();
+	let peer_performance = PeerPerformance::new(MIN_CHUNK_REQUEST_TIMEOUT, CHUNK_REQUEST_TIMEOUT);
+	peer_performance.note_response(&validator, Duration::from_millis(10), 0);
+	let params = test_state.recovery_params(peer_performance.clone());
+
+	let (req, response) = make_chunk_request(&params, validator_index);
+	// Keep the request pending without ever answering it.
+	let _pending_response =
+		assert_matches!(req, Requests::ChunkFetchingV2(req) => req.pending_response);
+
+	// A validator known to respond fast is given up on well before `CHUNK_REQUEST_TIMEOUT`.
+	let started = Instant::now();
+	assert_matches!(
+		executor::block_on(response),
+		Err((index, RequestError::NetworkError(RequestFailure::Network(OutboundFailure::Timeout)))) => {
+			assert_eq!(index, validator_index);
+		}
+	);
+	let elapsed = started.elapsed();
+	assert!(elapsed >= MIN_CHUNK_REQUEST_TIMEOUT);
+	assert!(elapsed < CHUNK_REQUEST_TIMEOUT);
+
+	// The timeout counts as a failure of the validator.
+	assert!(peer_performance.estimate(&validator).unwrap().failure_rate > 0.0);
+}
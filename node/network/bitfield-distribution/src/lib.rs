@@ -26,6 +26,7 @@ use futures::{channel::oneshot, FutureExt};
 
 use polkadot_node_network_protocol::{
 	self as net_protocol,
+	gossip_topology::{GossipTopology, GridTopology},
 	grid_topology::{
 		GridNeighbors, RandomRouting, RequiredRouting, SessionBoundGridTopologyStorage,
 	},
@@ -39,7 +40,10 @@ use polkadot_node_subsystem_util::{self as util};
 
 use polkadot_primitives::{Hash, SignedAvailabilityBitfield, SigningContext, ValidatorId};
 use rand::{CryptoRng, Rng, SeedableRng};
-use std::collections::{HashMap, HashSet};
+use std::{
+	collections::{HashMap, HashSet},
+	sync::Arc,
+};
 
 use self::metrics::Metrics;
 
@@ -165,18 +169,30 @@ const LOG_TARGET: &str = "parachain::bitfield-distribution";
 /// The bitfield distribution subsystem.
 pub struct BitfieldDistribution {
 	metrics: Metrics,
+	gossip_topology: Arc<dyn GossipTopology>,
 }
 
 #[overseer::contextbounds(BitfieldDistribution, prefix = self::overseer)]
 impl BitfieldDistribution {
 	/// Create a new instance of the `BitfieldDistribution` subsystem.
 	pub fn new(metrics: Metrics) -> Self {
-		Self { metrics }
+		Self { metrics, gossip_topology: Arc::new(GridTopology) }
+	}
+
+	/// Use the given gossip topology for bitfields, instead of the grid.
+	pub fn with_gossip_topology(mut self, gossip_topology: Arc<dyn GossipTopology>) -> Self {
+		self.gossip_topology = gossip_topology;
+		self
 	}
 
 	/// Start processing work as passed on from the Overseer.
 	async fn run<Context>(self, ctx: Context) {
-		let mut state = ProtocolState::default();
+		let mut state = ProtocolState {
+			topologies: SessionBoundGridTopologyStorage::with_gossip_topology(
+				self.gossip_topology.clone(),
+			),
+			..Default::default()
+		};
 		let mut rng = rand::rngs::StdRng::from_entropy();
 		self.run_inner(ctx, &mut state, &mut rng).await
 	}
@@ -617,8 +633,10 @@ async fn handle_network_msg<Context>(
 			gum::trace!(target: LOG_TARGET, ?new_view, "Our view change");
 			handle_our_view_change(state, new_view);
 		},
-		NetworkBridgeEvent::PeerMessage(remote, Versioned::V1(message)) =>
-			process_incoming_peer_message(ctx, state, metrics, remote, message, rng).await,
+		NetworkBridgeEvent::PeerMessage(
+			remote,
+			Versioned::V1(message) | Versioned::V2(message),
+		) => process_incoming_peer_message(ctx, state, metrics, remote, message, rng).await,
 	}
 }
 
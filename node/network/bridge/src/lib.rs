@@ -87,12 +87,15 @@ pub(crate) struct PeerData {
 	/// The Latest view sent by the peer.
 	view: View,
 	version: ProtocolVersion,
+	/// Whether the peer negotiated compressed notifications.
+	compressed: bool,
 }
 
 /// Shared state between incoming and outgoing.
-
+///
+/// The same instance must be passed to [`NetworkBridgeRx::new`] and [`NetworkBridgeTx::new`].
 #[derive(Default, Clone)]
-pub(crate) struct Shared(Arc<Mutex<SharedInner>>);
+pub struct Shared(Arc<Mutex<SharedInner>>);
 
 #[derive(Default)]
 struct SharedInner {
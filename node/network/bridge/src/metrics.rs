@@ -94,6 +94,35 @@ impl Metrics {
 		}
 	}
 
+	pub fn on_notification_compressed(
+		&self,
+		peer_set: PeerSet,
+		version: ProtocolVersion,
+		saved: usize,
+		to_peers: usize,
+	) {
+		if let Some(metrics) = self.0.as_ref() {
+			metrics
+				.compression_bytes_saved
+				.with_label_values(&[peer_set_label(peer_set, version), "sent"])
+				.inc_by((saved * to_peers) as u64);
+		}
+	}
+
+	pub fn on_notification_decompressed(
+		&self,
+		peer_set: PeerSet,
+		version: ProtocolVersion,
+		saved: usize,
+	) {
+		if let Some(metrics) = self.0.as_ref() {
+			metrics
+				.compression_bytes_saved
+				.with_label_values(&[peer_set_label(peer_set, version), "received"])
+				.inc_by(saved as u64);
+		}
+	}
+
 	pub fn note_desired_peer_count(&self, peer_set: PeerSet, size: usize) {
 		self.0.as_ref().map(|metrics| {
 			metrics
@@ -123,6 +152,8 @@ pub(crate) struct MetricsInner {
 
 	bytes_received: prometheus::CounterVec<prometheus::U64>,
 	bytes_sent: prometheus::CounterVec<prometheus::U64>,
+
+	compression_bytes_saved: prometheus::CounterVec<prometheus::U64>,
 }
 
 impl metrics::Metrics for Metrics {
@@ -217,6 +248,16 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			compression_bytes_saved: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_notification_compression_bytes_saved_total",
+						"The number of bytes saved by compressing notifications on a parachain notification protocol",
+					),
+					&["protocol", "direction"]
+				)?,
+				registry,
+			)?,
 		};
 
 		Ok(Metrics(Some(metrics)))
@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::{collections::HashSet, sync::Arc};
+use std::{borrow::Cow, collections::HashSet, sync::Arc};
 
 use async_trait::async_trait;
 use futures::{prelude::*, stream::BoxStream};
@@ -28,45 +28,97 @@ use sc_network::{
 };
 
 use polkadot_node_network_protocol::{
-	peer_set::{PeerSet, PeerSetProtocolNames, ProtocolVersion},
+	peer_set::{PeerSet, PeerSetProtocolNames, ProtocolVersion, MAX_NOTIFICATION_SIZE},
 	request_response::{OutgoingRequest, Recipient, ReqProtocolNames, Requests},
 	PeerId, UnifiedReputationChange as Rep,
 };
 use polkadot_primitives::{AuthorityDiscoveryId, Block, Hash};
 
-use crate::validator_discovery::AuthorityDiscovery;
+use crate::{validator_discovery::AuthorityDiscovery, Shared};
 
 // network bridge network abstraction log target
 const LOG_TARGET: &'static str = "parachain::network-bridge-net";
 
+/// Notifications smaller than this are never compressed, as compression would hardly save any
+/// bandwidth on them.
+const MIN_COMPRESSED_NOTIFICATION_SIZE: usize = 512;
+
 /// Send a message to the network.
 ///
 /// This function is only used internally by the network-bridge, which is responsible to only send
 /// messages that are compatible with the passed peer set, as that is currently not enforced by
 /// this function. These are messages of type `WireMessage` parameterized on the matching type.
+///
+/// Peers which negotiated notification compression receive the message compressed, if that makes
+/// it smaller.
 pub(crate) fn send_message<M>(
 	net: &mut impl Network,
-	mut peers: Vec<PeerId>,
+	peers: Vec<PeerId>,
 	peer_set: PeerSet,
 	version: ProtocolVersion,
 	protocol_names: &PeerSetProtocolNames,
 	message: M,
 	metrics: &super::Metrics,
+	shared: &Shared,
 ) where
 	M: Encode + Clone,
 {
-	let message = {
-		let encoded = message.encode();
-		metrics.on_notification_sent(peer_set, version, encoded.len(), peers.len());
-		encoded
+	let (compressed_peers, mut peers): (Vec<_>, Vec<_>) = {
+		let shared = shared.0.lock();
+		let peer_map = match peer_set {
+			PeerSet::Validation => &shared.validation_peers,
+			PeerSet::Collation => &shared.collation_peers,
+		};
+
+		peers
+			.into_iter()
+			.partition(|peer| peer_map.get(peer).map_or(false, |peer_data| peer_data.compressed))
 	};
 
+	let message = message.encode();
+	// Notifications are always written on the main protocol name of the peer set, the
+	// network routes them over whichever version or fallback the peer negotiated.
+	// optimization: generate the protocol name once.
+	let protocol_name = protocol_names.get_main_name(peer_set);
+
+	if !compressed_peers.is_empty() {
+		match compress_notification(&message) {
+			Some(compressed) => {
+				metrics.on_notification_sent(
+					peer_set,
+					version,
+					compressed.len(),
+					compressed_peers.len(),
+				);
+				metrics.on_notification_compressed(
+					peer_set,
+					version,
+					message.len() - compressed.len(),
+					compressed_peers.len(),
+				);
+				write_notifications(net, compressed_peers, protocol_name.clone(), compressed);
+			},
+			None => peers.extend(compressed_peers),
+		}
+	}
+
+	if !peers.is_empty() {
+		metrics.on_notification_sent(peer_set, version, message.len(), peers.len());
+		write_notifications(net, peers, protocol_name, message);
+	}
+}
+
+/// Write the same notification to all given peers.
+fn write_notifications(
+	net: &mut impl Network,
+	mut peers: Vec<PeerId>,
+	protocol_name: ProtocolName,
+	message: Vec<u8>,
+) {
 	// optimization: avoid cloning the message for the last peer in the
 	// list. The message payload can be quite large. If the underlying
 	// network used `Bytes` this would not be necessary.
 	let last_peer = peers.pop();
-	// optimization: generate the protocol name once.
-	let protocol_name = protocol_names.get_name(peer_set, version);
 	peers.into_iter().for_each(|peer| {
 		net.write_notification(peer, protocol_name.clone(), message.clone());
 	});
@@ -75,6 +127,25 @@ pub(crate) fn send_message<M>(
 	}
 }
 
+/// Compress an encoded notification, if it is large enough and compression makes it smaller.
+fn compress_notification(encoded: &[u8]) -> Option<Vec<u8>> {
+	if encoded.len() < MIN_COMPRESSED_NOTIFICATION_SIZE {
+		return None
+	}
+
+	sp_maybe_compressed_blob::compress(encoded, MAX_NOTIFICATION_SIZE as usize)
+		.filter(|compressed| compressed.len() < encoded.len())
+}
+
+/// Decompress a notification received from a peer which negotiated notification compression.
+///
+/// Notifications which were not compressed by the sender are returned as they are.
+pub(crate) fn decompress_notification(
+	message: &[u8],
+) -> Result<Cow<[u8]>, sp_maybe_compressed_blob::Error> {
+	sp_maybe_compressed_blob::decompress(message, MAX_NOTIFICATION_SIZE as usize)
+}
+
 /// An abstraction over networking for the purposes of this subsystem.
 #[async_trait]
 pub trait Network: Clone + Send + 'static {
@@ -152,7 +223,8 @@ impl Network for Arc<NetworkService<Block, Hash>> {
 		req_protocol_names: &ReqProtocolNames,
 		if_disconnected: IfDisconnected,
 	) {
-		let (protocol, OutgoingRequest { peer, payload, pending_response }) = req.encode_request();
+		let (protocol, OutgoingRequest { peer, payload, pending_response, .. }) =
+			req.encode_request();
 
 		let peer_id = match peer {
 			Recipient::Peer(peer_id) => Some(peer_id),
@@ -32,7 +32,8 @@ use polkadot_node_network_protocol::{
 		CollationVersion, PeerSet, PeerSetProtocolNames, PerPeerSet, ProtocolVersion,
 		ValidationVersion,
 	},
-	v1 as protocol_v1, ObservedRole, OurView, PeerId, UnifiedReputationChange as Rep, View,
+	v1 as protocol_v1, v2 as protocol_v2, ObservedRole, OurView, PeerId,
+	UnifiedReputationChange as Rep, View,
 };
 
 use polkadot_node_subsystem::{
@@ -53,6 +54,7 @@ use polkadot_primitives::{AuthorityDiscoveryId, BlockNumber, Hash, ValidatorInde
 pub use polkadot_node_network_protocol::peer_set::{peer_sets_info, IsAuthority};
 
 use std::{
+	borrow::Cow,
 	collections::{hash_map, HashMap},
 	iter::ExactSizeIterator,
 };
@@ -62,7 +64,7 @@ use super::validator_discovery;
 /// Actual interfacing to the network based on the `Network` trait.
 ///
 /// Defines the `Network` trait with an implementation for an `Arc<NetworkService>`.
-use crate::network::{send_message, Network};
+use crate::network::{decompress_notification, send_message, Network};
 
 use crate::network::get_peer_id_by_authority_id;
 
@@ -96,8 +98,8 @@ impl<N, AD> NetworkBridgeRx<N, AD> {
 		sync_oracle: Box<dyn SyncOracle + Send>,
 		metrics: Metrics,
 		peerset_protocol_names: PeerSetProtocolNames,
+		shared: Shared,
 	) -> Self {
-		let shared = Shared::default();
 		Self {
 			network_service,
 			authority_discovery_service,
@@ -154,6 +156,8 @@ where
 				received_handshake: _,
 			}) => {
 				let role = ObservedRole::from(role);
+				let compressed = peerset_protocol_names
+					.is_compressed(negotiated_fallback.as_ref().unwrap_or(&protocol));
 				let (peer_set, version) = {
 					let (peer_set, version) =
 						match peerset_protocol_names.try_get_protocol(&protocol) {
@@ -201,6 +205,7 @@ where
 					action = "PeerConnected",
 					peer_set = ?peer_set,
 					version = %version,
+					compressed,
 					peer = ?peer,
 					role = ?role
 				);
@@ -215,7 +220,7 @@ where
 					match peer_map.entry(peer) {
 						hash_map::Entry::Occupied(_) => continue,
 						hash_map::Entry::Vacant(vacant) => {
-							vacant.insert(PeerData { view: View::default(), version });
+							vacant.insert(PeerData { view: View::default(), version, compressed });
 						},
 					}
 
@@ -252,6 +257,7 @@ where
 							&peerset_protocol_names,
 							WireMessage::<protocol_v1::ValidationProtocol>::ViewUpdate(local_view),
 							&metrics,
+							&shared,
 						);
 					},
 					PeerSet::Collation => {
@@ -277,6 +283,7 @@ where
 							&peerset_protocol_names,
 							WireMessage::<protocol_v1::CollationProtocol>::ViewUpdate(local_view),
 							&metrics,
+							&shared,
 						);
 					},
 				}
@@ -415,30 +422,39 @@ where
 				);
 
 				if !v_messages.is_empty() {
-					let (events, reports) =
-						if expected_versions[PeerSet::Validation] ==
-							Some(ValidationVersion::V1.into())
-						{
-							handle_v1_peer_messages::<protocol_v1::ValidationProtocol, _>(
-								remote,
-								PeerSet::Validation,
-								&mut shared.0.lock().validation_peers,
-								v_messages,
-								&metrics,
-							)
-						} else {
-							gum::warn!(
-								target: LOG_TARGET,
-								version = ?expected_versions[PeerSet::Validation],
-								"Major logic bug. Peer somehow has unsupported validation protocol version."
-							);
+					let (events, reports) = if expected_versions[PeerSet::Validation] ==
+						Some(ValidationVersion::V1.into())
+					{
+						handle_peer_messages::<protocol_v1::ValidationProtocol, _>(
+							remote,
+							PeerSet::Validation,
+							&mut shared.0.lock().validation_peers,
+							v_messages,
+							&metrics,
+						)
+					} else if expected_versions[PeerSet::Validation] ==
+						Some(ValidationVersion::V2.into())
+					{
+						handle_peer_messages::<protocol_v2::ValidationProtocol, _>(
+							remote,
+							PeerSet::Validation,
+							&mut shared.0.lock().validation_peers,
+							v_messages,
+							&metrics,
+						)
+					} else {
+						gum::warn!(
+							target: LOG_TARGET,
+							version = ?expected_versions[PeerSet::Validation],
+							"Major logic bug. Peer somehow has unsupported validation protocol version."
+						);
 
-							never!("Only version 1 is supported; peer set connection checked above; qed");
+						never!("Only versions 1 and 2 are supported; peer set connection checked above; qed");
 
-							// If a peer somehow triggers this, we'll disconnect them
-							// eventually.
-							(Vec::new(), vec![UNCONNECTED_PEERSET_COST])
-						};
+						// If a peer somehow triggers this, we'll disconnect them
+						// eventually.
+						(Vec::new(), vec![UNCONNECTED_PEERSET_COST])
+					};
 
 					for report in reports {
 						network_service.report_peer(remote, report);
@@ -452,7 +468,7 @@ where
 						if expected_versions[PeerSet::Collation] ==
 							Some(CollationVersion::V1.into())
 						{
-							handle_v1_peer_messages::<protocol_v1::CollationProtocol, _>(
+							handle_peer_messages::<protocol_v1::CollationProtocol, _>(
 								remote,
 								PeerSet::Collation,
 								&mut shared.0.lock().collation_peers,
@@ -726,6 +742,7 @@ fn update_our_view<Net, Context>(
 		peerset_protocol_names,
 		WireMessage::ViewUpdate(new_view.clone()),
 		metrics,
+		shared,
 	);
 
 	send_collation_message_v1(
@@ -734,6 +751,7 @@ fn update_our_view<Net, Context>(
 		peerset_protocol_names,
 		WireMessage::ViewUpdate(new_view),
 		metrics,
+		shared,
 	);
 
 	let our_view = OurView::new(
@@ -752,9 +770,9 @@ fn update_our_view<Net, Context>(
 	);
 }
 
-// Handle messages on a specific v1 peer-set. The peer is expected to be connected on that
-// peer-set.
-fn handle_v1_peer_messages<RawMessage: Decode, OutMessage: From<RawMessage>>(
+// Handle messages on a specific peer-set, decoded as `RawMessage` of the version the peer
+// negotiated. The peer is expected to be connected on that peer-set.
+fn handle_peer_messages<RawMessage: Decode, OutMessage: From<RawMessage>>(
 	peer: PeerId,
 	peer_set: PeerSet,
 	peers: &mut HashMap<PeerId, PeerData>,
@@ -771,6 +789,27 @@ fn handle_v1_peer_messages<RawMessage: Decode, OutMessage: From<RawMessage>>(
 
 	for message in messages {
 		metrics.on_notification_received(peer_set, peer_data.version, message.len());
+		let message = if peer_data.compressed {
+			match decompress_notification(&message) {
+				Err(_) => {
+					reports.push(MALFORMED_MESSAGE_COST);
+					continue
+				},
+				Ok(decompressed) => {
+					if let Cow::Owned(ref decompressed) = decompressed {
+						metrics.on_notification_decompressed(
+							peer_set,
+							peer_data.version,
+							decompressed.len().saturating_sub(message.len()),
+						);
+					}
+					decompressed
+				},
+			}
+		} else {
+			Cow::Borrowed(message.as_ref())
+		};
+
 		let message = match WireMessage::<RawMessage>::decode_all(&mut message.as_ref()) {
 			Err(_) => {
 				reports.push(MALFORMED_MESSAGE_COST);
@@ -811,6 +850,7 @@ fn send_validation_message_v1(
 	peerset_protocol_names: &PeerSetProtocolNames,
 	message: WireMessage<protocol_v1::ValidationProtocol>,
 	metrics: &Metrics,
+	shared: &Shared,
 ) {
 	send_message(
 		net,
@@ -820,6 +860,7 @@ fn send_validation_message_v1(
 		peerset_protocol_names,
 		message,
 		metrics,
+		shared,
 	);
 }
 
@@ -829,6 +870,7 @@ fn send_collation_message_v1(
 	peerset_protocol_names: &PeerSetProtocolNames,
 	message: WireMessage<protocol_v1::CollationProtocol>,
 	metrics: &Metrics,
+	shared: &Shared,
 ) {
 	send_message(
 		net,
@@ -838,6 +880,7 @@ fn send_collation_message_v1(
 		peerset_protocol_names,
 		message,
 		metrics,
+		shared,
 	);
 }
 
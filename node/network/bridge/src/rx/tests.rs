@@ -34,6 +34,7 @@ use polkadot_node_network_protocol::{
 	request_response::{outgoing::Requests, ReqProtocolNames},
 	view, ObservedRole, Versioned,
 };
+use polkadot_node_primitives::approval::IndirectSignedApprovalVote;
 use polkadot_node_subsystem::{
 	jaeger,
 	messages::{
@@ -47,6 +48,7 @@ use polkadot_node_subsystem_test_helpers::{
 };
 use polkadot_node_subsystem_util::metered;
 use polkadot_primitives::{AuthorityDiscoveryId, Hash};
+use polkadot_primitives_test_helpers::dummy_signature;
 
 use sc_network::Multiaddr;
 use sp_keyring::Sr25519Keyring;
@@ -200,6 +202,23 @@ impl TestNetworkHandle {
 		.await;
 	}
 
+	async fn connect_peer_with_version(
+		&mut self,
+		peer: PeerId,
+		peer_set: PeerSet,
+		version: ProtocolVersion,
+		role: ObservedRole,
+	) {
+		self.send_network_event(NetworkEvent::NotificationStreamOpened {
+			remote: peer,
+			protocol: self.protocol_names.get_main_name(peer_set),
+			negotiated_fallback: Some(self.protocol_names.get_name(peer_set, version)),
+			role: role.into(),
+			received_handshake: vec![],
+		})
+		.await;
+	}
+
 	async fn disconnect_peer(&mut self, peer: PeerId, peer_set: PeerSet) {
 		self.send_network_event(NetworkEvent::NotificationStreamClosed {
 			remote: peer,
@@ -296,10 +315,19 @@ struct TestHarness {
 fn test_harness<T: Future<Output = VirtualOverseer>>(
 	sync_oracle: Box<dyn SyncOracle + Send>,
 	test: impl FnOnce(TestHarness) -> T,
+) {
+	test_harness_with_compression(sync_oracle, false, test)
+}
+
+fn test_harness_with_compression<T: Future<Output = VirtualOverseer>>(
+	sync_oracle: Box<dyn SyncOracle + Send>,
+	notification_compression: bool,
+	test: impl FnOnce(TestHarness) -> T,
 ) {
 	let genesis_hash = Hash::repeat_byte(0xff);
 	let fork_id = None;
-	let peerset_protocol_names = PeerSetProtocolNames::new(genesis_hash, fork_id);
+	let peerset_protocol_names = PeerSetProtocolNames::new(genesis_hash, fork_id)
+		.with_notification_compression(notification_compression);
 
 	let pool = sp_core::testing::TaskExecutor::new();
 	let (mut network, network_handle, discovery) = new_test_network(peerset_protocol_names.clone());
@@ -676,7 +704,7 @@ fn peer_view_updates_sent_via_overseer() {
 				NetworkBridgeEvent::PeerConnected(
 					peer.clone(),
 					ObservedRole::Full,
-					ValidationVersion::V1.into(),
+					ValidationVersion::V2.into(),
 					None,
 				),
 				&mut virtual_overseer,
@@ -724,7 +752,7 @@ fn peer_messages_sent_via_overseer() {
 				NetworkBridgeEvent::PeerConnected(
 					peer.clone(),
 					ObservedRole::Full,
-					ValidationVersion::V1.into(),
+					ValidationVersion::V2.into(),
 					None,
 				),
 				&mut virtual_overseer,
@@ -762,11 +790,11 @@ fn peer_messages_sent_via_overseer() {
 			virtual_overseer.recv().await,
 			AllMessages::ApprovalDistribution(
 				ApprovalDistributionMessage::NetworkBridgeUpdate(
-					NetworkBridgeEvent::PeerMessage(p, Versioned::V1(m))
+					NetworkBridgeEvent::PeerMessage(p, Versioned::V2(m))
 				)
 			) => {
 				assert_eq!(p, peer);
-				assert_eq!(m, approval_distribution_message);
+				assert_eq!(m, approval_distribution_message.into());
 			}
 		);
 
@@ -779,6 +807,143 @@ fn peer_messages_sent_via_overseer() {
 	});
 }
 
+#[test]
+fn v1_peer_messages_are_sent_as_v1() {
+	test_harness(done_syncing_oracle(), |test_harness| async move {
+		let TestHarness { mut network_handle, mut virtual_overseer } = test_harness;
+
+		let peer = PeerId::random();
+
+		network_handle
+			.connect_peer_with_version(
+				peer.clone(),
+				PeerSet::Validation,
+				ValidationVersion::V1.into(),
+				ObservedRole::Full,
+			)
+			.await;
+
+		// bridge will inform about all connected peers.
+		{
+			assert_sends_validation_event_to_all(
+				NetworkBridgeEvent::PeerConnected(
+					peer.clone(),
+					ObservedRole::Full,
+					ValidationVersion::V1.into(),
+					None,
+				),
+				&mut virtual_overseer,
+			)
+			.await;
+
+			assert_sends_validation_event_to_all(
+				NetworkBridgeEvent::PeerViewChange(peer.clone(), View::default()),
+				&mut virtual_overseer,
+			)
+			.await;
+		}
+
+		let approval_distribution_message =
+			protocol_v1::ApprovalDistributionMessage::Approvals(Vec::new());
+
+		let message_v1 = protocol_v1::ValidationProtocol::ApprovalDistribution(
+			approval_distribution_message.clone(),
+		);
+
+		network_handle
+			.peer_message(
+				peer.clone(),
+				PeerSet::Validation,
+				WireMessage::ProtocolMessage(message_v1.clone()).encode(),
+			)
+			.await;
+
+		assert_matches!(
+			virtual_overseer.recv().await,
+			AllMessages::ApprovalDistribution(
+				ApprovalDistributionMessage::NetworkBridgeUpdate(
+					NetworkBridgeEvent::PeerMessage(p, Versioned::V1(m))
+				)
+			) => {
+				assert_eq!(p, peer);
+				assert_eq!(m, approval_distribution_message);
+			}
+		);
+		virtual_overseer
+	});
+}
+
+#[test]
+fn compressed_peer_messages_are_decompressed() {
+	test_harness_with_compression(done_syncing_oracle(), true, |test_harness| async move {
+		let TestHarness { mut network_handle, mut virtual_overseer } = test_harness;
+
+		let peer = PeerId::random();
+
+		network_handle
+			.connect_peer(peer.clone(), PeerSet::Validation, ObservedRole::Full)
+			.await;
+
+		// bridge will inform about all connected peers.
+		{
+			assert_sends_validation_event_to_all(
+				NetworkBridgeEvent::PeerConnected(
+					peer.clone(),
+					ObservedRole::Full,
+					ValidationVersion::V2.into(),
+					None,
+				),
+				&mut virtual_overseer,
+			)
+			.await;
+
+			assert_sends_validation_event_to_all(
+				NetworkBridgeEvent::PeerViewChange(peer.clone(), View::default()),
+				&mut virtual_overseer,
+			)
+			.await;
+		}
+
+		let approvals = (0..100)
+			.map(|i| IndirectSignedApprovalVote {
+				block_hash: Hash::repeat_byte(1),
+				candidate_index: i % 2,
+				validator: ValidatorIndex(i),
+				signature: dummy_signature(),
+			})
+			.collect::<Vec<_>>();
+		let approval_distribution_message =
+			protocol_v1::ApprovalDistributionMessage::Approvals(approvals);
+		let message_v1 = protocol_v1::ValidationProtocol::ApprovalDistribution(
+			approval_distribution_message.clone(),
+		);
+
+		let encoded = WireMessage::ProtocolMessage(message_v1.clone()).encode();
+		let compressed = sp_maybe_compressed_blob::compress(&encoded, encoded.len()).unwrap();
+		assert!(compressed.len() < encoded.len());
+
+		// Peers which negotiated compression may send both compressed and uncompressed
+		// notifications.
+		for message in [compressed, encoded] {
+			network_handle.peer_message(peer.clone(), PeerSet::Validation, message).await;
+
+			assert_matches!(
+				virtual_overseer.recv().await,
+				AllMessages::ApprovalDistribution(
+					ApprovalDistributionMessage::NetworkBridgeUpdate(
+						NetworkBridgeEvent::PeerMessage(p, Versioned::V2(m))
+					)
+				) => {
+					assert_eq!(p, peer);
+					assert_eq!(m, approval_distribution_message.clone().into());
+				}
+			);
+		}
+
+		virtual_overseer
+	});
+}
+
 #[test]
 fn peer_disconnect_from_just_one_peerset() {
 	test_harness(done_syncing_oracle(), |test_harness| async move {
@@ -799,7 +964,7 @@ fn peer_disconnect_from_just_one_peerset() {
 				NetworkBridgeEvent::PeerConnected(
 					peer.clone(),
 					ObservedRole::Full,
-					ValidationVersion::V1.into(),
+					ValidationVersion::V2.into(),
 					None,
 				),
 				&mut virtual_overseer,
@@ -892,7 +1057,7 @@ fn relays_collation_protocol_messages() {
 				NetworkBridgeEvent::PeerConnected(
 					peer_a.clone(),
 					ObservedRole::Full,
-					ValidationVersion::V1.into(),
+					ValidationVersion::V2.into(),
 					None,
 				),
 				&mut virtual_overseer,
@@ -995,7 +1160,7 @@ fn different_views_on_different_peer_sets() {
 				NetworkBridgeEvent::PeerConnected(
 					peer.clone(),
 					ObservedRole::Full,
-					ValidationVersion::V1.into(),
+					ValidationVersion::V2.into(),
 					None,
 				),
 				&mut virtual_overseer,
@@ -17,10 +17,13 @@
 //! The Network Bridge Subsystem - handles _outgoing_ messages, from subsystem to the network.
 use super::*;
 
+use futures::{future::BoxFuture, stream::FuturesUnordered};
+use sc_network::IfDisconnected;
+
 use polkadot_node_network_protocol::{
 	peer_set::{CollationVersion, PeerSet, PeerSetProtocolNames, ValidationVersion},
-	request_response::ReqProtocolNames,
-	v1 as protocol_v1, PeerId, Versioned,
+	request_response::{ReqProtocolNames, Requests},
+	v1 as protocol_v1, v2 as protocol_v2, PeerId, Versioned, VersionedValidationProtocol,
 };
 
 use polkadot_node_subsystem::{
@@ -48,6 +51,12 @@ mod tests;
 // network bridge log target
 const LOG_TARGET: &'static str = "parachain::network-bridge-tx";
 
+/// Fallback requests waiting for the response to the request they are the fallback of.
+///
+/// Each of them resolves as soon as networking answers the original request or gives up on it, so
+/// there are never more of them than requests in flight.
+type PendingFallbacks = FuturesUnordered<BoxFuture<'static, Option<(Requests, IfDisconnected)>>>;
+
 /// The network bridge subsystem.
 pub struct NetworkBridgeTx<N, AD> {
 	/// `Network` trait implementing type.
@@ -56,6 +65,7 @@ pub struct NetworkBridgeTx<N, AD> {
 	metrics: Metrics,
 	req_protocol_names: ReqProtocolNames,
 	peerset_protocol_names: PeerSetProtocolNames,
+	shared: Shared,
 }
 
 impl<N, AD> NetworkBridgeTx<N, AD> {
@@ -69,6 +79,7 @@ impl<N, AD> NetworkBridgeTx<N, AD> {
 		metrics: Metrics,
 		req_protocol_names: ReqProtocolNames,
 		peerset_protocol_names: PeerSetProtocolNames,
+		shared: Shared,
 	) -> Self {
 		Self {
 			network_service,
@@ -76,6 +87,7 @@ impl<N, AD> NetworkBridgeTx<N, AD> {
 			metrics,
 			req_protocol_names,
 			peerset_protocol_names,
+			shared,
 		}
 	}
 }
@@ -102,6 +114,7 @@ async fn handle_subsystem_messages<Context, N, AD>(
 	metrics: Metrics,
 	req_protocol_names: ReqProtocolNames,
 	peerset_protocol_names: PeerSetProtocolNames,
+	shared: Shared,
 ) -> Result<(), Error>
 where
 	N: Network,
@@ -109,24 +122,47 @@ where
 {
 	let mut validator_discovery =
 		validator_discovery::Service::<N, AD>::new(peerset_protocol_names.clone());
+	let mut pending_fallbacks = PendingFallbacks::new();
 
 	loop {
-		match ctx.recv().fuse().await? {
-			FromOrchestra::Signal(OverseerSignal::Conclude) => return Ok(()),
-			FromOrchestra::Signal(_) => { /* handled by incoming */ },
-			FromOrchestra::Communication { msg } => {
-				(network_service, authority_discovery_service) =
-					handle_incoming_subsystem_communication(
-						&mut ctx,
-						network_service,
-						&mut validator_discovery,
-						authority_discovery_service.clone(),
-						msg,
-						&metrics,
-						&req_protocol_names,
-						&peerset_protocol_names,
-					)
-					.await;
+		futures::select! {
+			msg = ctx.recv().fuse() => match msg? {
+				FromOrchestra::Signal(OverseerSignal::Conclude) => return Ok(()),
+				FromOrchestra::Signal(_) => { /* handled by incoming */ },
+				FromOrchestra::Communication { msg } => {
+					(network_service, authority_discovery_service) =
+						handle_incoming_subsystem_communication(
+							&mut ctx,
+							network_service,
+							&mut validator_discovery,
+							authority_discovery_service.clone(),
+							msg,
+							&metrics,
+							&req_protocol_names,
+							&peerset_protocol_names,
+							&shared,
+							&mut pending_fallbacks,
+						)
+						.await;
+				},
+			},
+			fallback = pending_fallbacks.select_next_some() => {
+				// The peer did not support the protocol of the original request.
+				if let Some((req, if_disconnected)) = fallback {
+					gum::trace!(
+						target: LOG_TARGET,
+						protocol = ?req.get_protocol(),
+						"Peer does not support request protocol, sending fallback request",
+					);
+					network_service
+						.start_request(
+							&mut authority_discovery_service,
+							req,
+							&req_protocol_names,
+							if_disconnected,
+						)
+						.await;
+				}
 			},
 		}
 	}
@@ -142,6 +178,8 @@ async fn handle_incoming_subsystem_communication<Context, N, AD>(
 	metrics: &Metrics,
 	req_protocol_names: &ReqProtocolNames,
 	peerset_protocol_names: &PeerSetProtocolNames,
+	shared: &Shared,
+	pending_fallbacks: &mut PendingFallbacks,
 ) -> (N, AD)
 where
 	N: Network,
@@ -175,15 +213,14 @@ where
 				num_messages = 1usize,
 			);
 
-			match msg {
-				Versioned::V1(msg) => send_validation_message_v1(
-					&mut network_service,
-					peers,
-					peerset_protocol_names,
-					WireMessage::ProtocolMessage(msg),
-					&metrics,
-				),
-			}
+			send_validation_message(
+				&mut network_service,
+				peers,
+				peerset_protocol_names,
+				msg,
+				&metrics,
+				shared,
+			);
 		},
 		NetworkBridgeTxMessage::SendValidationMessages(msgs) => {
 			gum::trace!(
@@ -193,15 +230,14 @@ where
 			);
 
 			for (peers, msg) in msgs {
-				match msg {
-					Versioned::V1(msg) => send_validation_message_v1(
-						&mut network_service,
-						peers,
-						peerset_protocol_names,
-						WireMessage::ProtocolMessage(msg),
-						&metrics,
-					),
-				}
+				send_validation_message(
+					&mut network_service,
+					peers,
+					peerset_protocol_names,
+					msg,
+					&metrics,
+					shared,
+				);
 			}
 		},
 		NetworkBridgeTxMessage::SendCollationMessage(peers, msg) => {
@@ -212,12 +248,13 @@ where
 			);
 
 			match msg {
-				Versioned::V1(msg) => send_collation_message_v1(
+				Versioned::V1(msg) | Versioned::V2(msg) => send_collation_message_v1(
 					&mut network_service,
 					peers,
 					peerset_protocol_names,
 					WireMessage::ProtocolMessage(msg),
 					&metrics,
+					shared,
 				),
 			}
 		},
@@ -230,12 +267,13 @@ where
 
 			for (peers, msg) in msgs {
 				match msg {
-					Versioned::V1(msg) => send_collation_message_v1(
+					Versioned::V1(msg) | Versioned::V2(msg) => send_collation_message_v1(
 						&mut network_service,
 						peers,
 						peerset_protocol_names,
 						WireMessage::ProtocolMessage(msg),
 						&metrics,
+						shared,
 					),
 				}
 			}
@@ -247,7 +285,9 @@ where
 				num_requests = %reqs.len(),
 			);
 
-			for req in reqs {
+			for mut req in reqs {
+				let fallback = req.take_fallback();
+
 				network_service
 					.start_request(
 						&mut authority_discovery_service,
@@ -256,6 +296,17 @@ where
 						if_disconnected,
 					)
 					.await;
+
+				// Requests with a fallback need to wait for the response, to see whether the peer
+				// supports the newer protocol. The fallback is sent from the main loop if needed.
+				if let Some(fallback) = fallback {
+					pending_fallbacks.push(
+						fallback
+							.wait()
+							.map(move |req| req.map(|req| (req, if_disconnected)))
+							.boxed(),
+					);
+				}
 			}
 		},
 		NetworkBridgeTxMessage::ConnectToValidators { validator_ids, peer_set, failed } => {
@@ -317,6 +368,7 @@ where
 		metrics,
 		req_protocol_names,
 		peerset_protocol_names,
+		shared,
 	} = bridge;
 
 	handle_subsystem_messages(
@@ -326,18 +378,84 @@ where
 		metrics,
 		req_protocol_names,
 		peerset_protocol_names,
+		shared,
 	)
 	.await?;
 
 	Ok(())
 }
 
+/// Send a validation message to peers of all protocol versions.
+///
+/// Peers are grouped by the protocol version they negotiated and the message is translated to
+/// each version. Messages which can't be expressed in `v1` are not sent to `v1` peers.
+fn send_validation_message(
+	net: &mut impl Network,
+	peers: Vec<PeerId>,
+	protocol_names: &PeerSetProtocolNames,
+	message: VersionedValidationProtocol,
+	metrics: &Metrics,
+	shared: &Shared,
+) {
+	let (v2_peers, v1_peers): (Vec<_>, Vec<_>) = {
+		let shared = shared.0.lock();
+		peers.into_iter().partition(|peer| {
+			shared
+				.validation_peers
+				.get(peer)
+				.map_or(false, |peer_data| peer_data.version == ValidationVersion::V2.into())
+		})
+	};
+
+	let (v1_message, v2_message) = match message {
+		Versioned::V1(msg) => (Some(msg.clone()), protocol_v2::ValidationProtocol::from(msg)),
+		Versioned::V2(msg) => match protocol_v1::ValidationProtocol::try_from(msg.clone()) {
+			Ok(v1_msg) => (Some(v1_msg), msg),
+			Err(_) => {
+				if !v1_peers.is_empty() {
+					gum::debug!(
+						target: LOG_TARGET,
+						num_peers = v1_peers.len(),
+						"Validation message can't be expressed in v1, not sending it to v1 peers",
+					);
+				}
+				(None, msg)
+			},
+		},
+	};
+
+	if let Some(v1_message) = v1_message {
+		if !v1_peers.is_empty() {
+			send_validation_message_v1(
+				net,
+				v1_peers,
+				protocol_names,
+				WireMessage::ProtocolMessage(v1_message),
+				metrics,
+				shared,
+			);
+		}
+	}
+
+	if !v2_peers.is_empty() {
+		send_validation_message_v2(
+			net,
+			v2_peers,
+			protocol_names,
+			WireMessage::ProtocolMessage(v2_message),
+			metrics,
+			shared,
+		);
+	}
+}
+
 fn send_validation_message_v1(
 	net: &mut impl Network,
 	peers: Vec<PeerId>,
 	protocol_names: &PeerSetProtocolNames,
 	message: WireMessage<protocol_v1::ValidationProtocol>,
 	metrics: &Metrics,
+	shared: &Shared,
 ) {
 	send_message(
 		net,
@@ -347,6 +465,27 @@ fn send_validation_message_v1(
 		protocol_names,
 		message,
 		metrics,
+		shared,
+	);
+}
+
+fn send_validation_message_v2(
+	net: &mut impl Network,
+	peers: Vec<PeerId>,
+	protocol_names: &PeerSetProtocolNames,
+	message: WireMessage<protocol_v2::ValidationProtocol>,
+	metrics: &Metrics,
+	shared: &Shared,
+) {
+	send_message(
+		net,
+		peers,
+		PeerSet::Validation,
+		ValidationVersion::V2.into(),
+		protocol_names,
+		message,
+		metrics,
+		shared,
 	);
 }
 
@@ -356,6 +495,7 @@ fn send_collation_message_v1(
 	protocol_names: &PeerSetProtocolNames,
 	message: WireMessage<protocol_v1::CollationProtocol>,
 	metrics: &Metrics,
+	shared: &Shared,
 ) {
 	send_message(
 		net,
@@ -365,5 +505,6 @@ fn send_collation_message_v1(
 		protocol_names,
 		message,
 		metrics,
+		shared,
 	);
 }
@@ -18,6 +18,7 @@ use super::*;
 use futures::{executor, stream::BoxStream};
 use polkadot_node_subsystem_util::TimeoutExt;
 
+use assert_matches::assert_matches;
 use async_trait::async_trait;
 use parking_lot::Mutex;
 use std::collections::HashSet;
@@ -26,15 +27,25 @@ use sc_network::{Event as NetworkEvent, IfDisconnected, ProtocolName};
 
 use polkadot_node_network_protocol::{
 	peer_set::PeerSetProtocolNames,
-	request_response::{outgoing::Requests, ReqProtocolNames},
+	request_response::{
+		outgoing::{OutgoingRequest, Recipient, Requests},
+		v1, v2, Protocol, ReqProtocolNames,
+	},
 	ObservedRole, Versioned,
 };
+use polkadot_node_primitives::{
+	approval::{
+		AssignmentCertKindV2, AssignmentCertV2, CoreBitfield, IndirectAssignmentCertV2,
+		IndirectSignedApprovalVote, VrfOutput, VrfProof, VrfSignature, RELAY_VRF_MODULO_CONTEXT,
+	},
+	Proof,
+};
 use polkadot_node_subsystem::{FromOrchestra, OverseerSignal};
 use polkadot_node_subsystem_test_helpers::TestSubsystemContextHandle;
 use polkadot_node_subsystem_util::metered;
-use polkadot_primitives::{AuthorityDiscoveryId, Hash};
-use polkadot_primitives_test_helpers::dummy_collator_signature;
-use sc_network::Multiaddr;
+use polkadot_primitives::{AuthorityDiscoveryId, CandidateHash, CoreIndex, Hash, ValidatorIndex};
+use polkadot_primitives_test_helpers::{dummy_collator_signature, dummy_signature};
+use sc_network::{Multiaddr, OutboundFailure, RequestFailure};
 use sp_keyring::Sr25519Keyring;
 
 const TIMEOUT: std::time::Duration = polkadot_node_subsystem_test_helpers::TestSubsystemContextHandle::<NetworkBridgeTxMessage>::TIMEOUT;
@@ -56,6 +67,7 @@ pub enum NetworkAction {
 struct TestNetwork {
 	net_events: Arc<Mutex<Option<metered::MeteredReceiver<NetworkEvent>>>>,
 	action_tx: Arc<Mutex<metered::UnboundedMeteredSender<NetworkAction>>>,
+	request_tx: Arc<Mutex<metered::UnboundedMeteredSender<Requests>>>,
 	peerset_protocol_names: Arc<PeerSetProtocolNames>,
 }
 
@@ -66,6 +78,7 @@ struct TestAuthorityDiscovery;
 // of `NetworkAction`s.
 struct TestNetworkHandle {
 	action_rx: metered::UnboundedMeteredReceiver<NetworkAction>,
+	request_rx: metered::UnboundedMeteredReceiver<Requests>,
 	net_tx: metered::MeteredSender<NetworkEvent>,
 	peerset_protocol_names: PeerSetProtocolNames,
 }
@@ -75,14 +88,16 @@ fn new_test_network(
 ) -> (TestNetwork, TestNetworkHandle, TestAuthorityDiscovery) {
 	let (net_tx, net_rx) = metered::channel(10);
 	let (action_tx, action_rx) = metered::unbounded();
+	let (request_tx, request_rx) = metered::unbounded();
 
 	(
 		TestNetwork {
 			net_events: Arc::new(Mutex::new(Some(net_rx))),
 			action_tx: Arc::new(Mutex::new(action_tx)),
+			request_tx: Arc::new(Mutex::new(request_tx)),
 			peerset_protocol_names: Arc::new(peerset_protocol_names.clone()),
 		},
-		TestNetworkHandle { action_rx, net_tx, peerset_protocol_names },
+		TestNetworkHandle { action_rx, request_rx, net_tx, peerset_protocol_names },
 		TestAuthorityDiscovery,
 	)
 }
@@ -110,10 +125,11 @@ impl Network for TestNetwork {
 	async fn start_request<AD: AuthorityDiscovery>(
 		&self,
 		_: &mut AD,
-		_: Requests,
+		req: Requests,
 		_: &ReqProtocolNames,
 		_: IfDisconnected,
 	) {
+		self.request_tx.lock().unbounded_send(req).unwrap();
 	}
 
 	fn report_peer(&self, who: PeerId, cost_benefit: Rep) {
@@ -167,6 +183,11 @@ impl TestNetworkHandle {
 		self.action_rx.next().await.expect("subsystem concluded early")
 	}
 
+	// Get the next request started by the subsystem.
+	async fn next_request(&mut self) -> Requests {
+		self.request_rx.next().await.expect("subsystem concluded early")
+	}
+
 	async fn connect_peer(&mut self, peer: PeerId, peer_set: PeerSet, role: ObservedRole) {
 		self.send_network_event(NetworkEvent::NotificationStreamOpened {
 			remote: peer,
@@ -188,13 +209,16 @@ type VirtualOverseer = TestSubsystemContextHandle<NetworkBridgeTxMessage>;
 struct TestHarness {
 	network_handle: TestNetworkHandle,
 	virtual_overseer: VirtualOverseer,
+	shared: Shared,
 }
 
 fn test_harness<T: Future<Output = VirtualOverseer>>(test: impl FnOnce(TestHarness) -> T) {
 	let genesis_hash = Hash::repeat_byte(0xff);
 	let fork_id = None;
 	let req_protocol_names = ReqProtocolNames::new(genesis_hash, fork_id);
-	let peerset_protocol_names = PeerSetProtocolNames::new(genesis_hash, fork_id);
+	let peerset_protocol_names =
+		PeerSetProtocolNames::new(genesis_hash, fork_id).with_notification_compression(true);
+	let shared = Shared::default();
 
 	let pool = sp_core::testing::TaskExecutor::new();
 	let (network, network_handle, discovery) = new_test_network(peerset_protocol_names.clone());
@@ -208,13 +232,14 @@ fn test_harness<T: Future<Output = VirtualOverseer>>(test: impl FnOnce(TestHarne
 		Metrics(None),
 		req_protocol_names,
 		peerset_protocol_names,
+		shared.clone(),
 	);
 
 	let network_bridge_out_fut = run_network_out(bridge_out, context)
 		.map_err(|e| panic!("bridge-out subsystem execution failed {:?}", e))
 		.map(|_| ());
 
-	let test_fut = test(TestHarness { network_handle, virtual_overseer });
+	let test_fut = test(TestHarness { network_handle, virtual_overseer, shared });
 
 	futures::pin_mut!(test_fut);
 	futures::pin_mut!(network_bridge_out_fut);
@@ -231,7 +256,7 @@ fn test_harness<T: Future<Output = VirtualOverseer>>(test: impl FnOnce(TestHarne
 #[test]
 fn send_messages_to_peers() {
 	test_harness(|test_harness| async move {
-		let TestHarness { mut network_handle, mut virtual_overseer } = test_harness;
+		let TestHarness { mut network_handle, mut virtual_overseer, .. } = test_harness;
 
 		let peer = PeerId::random();
 
@@ -322,3 +347,290 @@ fn send_messages_to_peers() {
 		virtual_overseer
 	});
 }
+
+#[test]
+fn notifications_are_compressed_for_peers_which_negotiated_it() {
+	test_harness(|test_harness| async move {
+		let TestHarness { mut network_handle, mut virtual_overseer, shared } = test_harness;
+
+		let compressing_peer = PeerId::random();
+		let plain_peer = PeerId::random();
+
+		// The incoming side records the negotiated compression of connected peers.
+		{
+			let mut shared = shared.0.lock();
+			for (peer, compressed) in [(compressing_peer, true), (plain_peer, false)] {
+				shared.validation_peers.insert(
+					peer,
+					PeerData {
+						view: View::default(),
+						version: ValidationVersion::V1.into(),
+						compressed,
+					},
+				);
+			}
+		}
+
+		let approvals = (0..100)
+			.map(|i| IndirectSignedApprovalVote {
+				block_hash: Hash::repeat_byte(1),
+				candidate_index: i % 2,
+				validator: ValidatorIndex(i),
+				signature: dummy_signature(),
+			})
+			.collect::<Vec<_>>();
+		let message_v1 = protocol_v1::ValidationProtocol::ApprovalDistribution(
+			protocol_v1::ApprovalDistributionMessage::Approvals(approvals),
+		);
+
+		virtual_overseer
+			.send(FromOrchestra::Communication {
+				msg: NetworkBridgeTxMessage::SendValidationMessage(
+					vec![plain_peer, compressing_peer],
+					Versioned::V1(message_v1.clone()),
+				),
+			})
+			.timeout(TIMEOUT)
+			.await
+			.expect("Timeout does not occur");
+
+		let encoded = WireMessage::ProtocolMessage(message_v1).encode();
+		let compressed = sp_maybe_compressed_blob::compress(&encoded, encoded.len()).unwrap();
+		assert!(compressed.len() < encoded.len());
+
+		let mut actions = Vec::new();
+		for _ in 0..2 {
+			actions.push(
+				network_handle
+					.next_network_action()
+					.timeout(TIMEOUT)
+					.await
+					.expect("Timeout does not occur"),
+			);
+		}
+		assert!(actions.contains(&NetworkAction::WriteNotification(
+			compressing_peer,
+			PeerSet::Validation,
+			compressed,
+		)));
+		assert!(actions.contains(&NetworkAction::WriteNotification(
+			plain_peer,
+			PeerSet::Validation,
+			encoded,
+		)));
+
+		// Small notifications are not worth compressing.
+		let message_v1 = protocol_v1::ValidationProtocol::ApprovalDistribution(
+			protocol_v1::ApprovalDistributionMessage::Approvals(Vec::new()),
+		);
+
+		virtual_overseer
+			.send(FromOrchestra::Communication {
+				msg: NetworkBridgeTxMessage::SendValidationMessage(
+					vec![compressing_peer],
+					Versioned::V1(message_v1.clone()),
+				),
+			})
+			.timeout(TIMEOUT)
+			.await
+			.expect("Timeout does not occur");
+
+		assert_eq!(
+			network_handle
+				.next_network_action()
+				.timeout(TIMEOUT)
+				.await
+				.expect("Timeout does not occur"),
+			NetworkAction::WriteNotification(
+				compressing_peer,
+				PeerSet::Validation,
+				WireMessage::ProtocolMessage(message_v1).encode(),
+			)
+		);
+
+		virtual_overseer
+	});
+}
+
+fn fake_assignment_cert_v2(kind: AssignmentCertKindV2) -> IndirectAssignmentCertV2 {
+	let ctx = schnorrkel::signing_context(RELAY_VRF_MODULO_CONTEXT);
+	let msg = b"WhenParachains?";
+	let keypair = schnorrkel::Keypair::generate_with(&mut rand_core::OsRng);
+	let (inout, proof, _) = keypair.vrf_sign(ctx.bytes(msg));
+
+	IndirectAssignmentCertV2 {
+		block_hash: Hash::repeat_byte(1),
+		validator: ValidatorIndex(0),
+		cert: AssignmentCertV2 {
+			kind,
+			vrf: VrfSignature { output: VrfOutput(inout.to_output()), proof: VrfProof(proof) },
+		},
+	}
+}
+
+#[test]
+fn validation_messages_are_translated_to_the_peer_version() {
+	test_harness(|test_harness| async move {
+		let TestHarness { mut network_handle, mut virtual_overseer, shared } = test_harness;
+
+		let v1_peer = PeerId::random();
+		let v2_peer = PeerId::random();
+
+		{
+			let mut shared = shared.0.lock();
+			for (peer, version) in
+				[(v1_peer, ValidationVersion::V1), (v2_peer, ValidationVersion::V2)]
+			{
+				shared.validation_peers.insert(
+					peer,
+					PeerData { view: View::default(), version: version.into(), compressed: false },
+				);
+			}
+		}
+
+		let modulo_cert =
+			fake_assignment_cert_v2(AssignmentCertKindV2::RelayVRFModulo { sample: 0 });
+		let compact_cert = fake_assignment_cert_v2(AssignmentCertKindV2::RelayVRFModuloCompact {
+			core_bitfield: CoreBitfield::try_from(vec![CoreIndex(0), CoreIndex(1)]).unwrap(),
+		});
+
+		let message_v2 = protocol_v2::ValidationProtocol::ApprovalDistribution(
+			protocol_v2::ApprovalDistributionMessage::Assignments(vec![
+				(modulo_cert.clone(), 1u32.into()),
+				(compact_cert, vec![0u32, 1].try_into().unwrap()),
+			]),
+		);
+
+		virtual_overseer
+			.send(FromOrchestra::Communication {
+				msg: NetworkBridgeTxMessage::SendValidationMessage(
+					vec![v1_peer, v2_peer],
+					Versioned::V2(message_v2.clone()),
+				),
+			})
+			.timeout(TIMEOUT)
+			.await
+			.expect("Timeout does not occur");
+
+		// The compact assignment can't be expressed in v1 and is left out for the v1 peer.
+		let message_v1 = protocol_v1::ValidationProtocol::ApprovalDistribution(
+			protocol_v1::ApprovalDistributionMessage::Assignments(vec![(
+				modulo_cert.try_into().unwrap(),
+				1,
+			)]),
+		);
+
+		let mut actions = Vec::new();
+		for _ in 0..2 {
+			actions.push(
+				network_handle
+					.next_network_action()
+					.timeout(TIMEOUT)
+					.await
+					.expect("Timeout does not occur"),
+			);
+		}
+		assert!(actions.contains(&NetworkAction::WriteNotification(
+			v1_peer,
+			PeerSet::Validation,
+			WireMessage::ProtocolMessage(message_v1).encode(),
+		)));
+		assert!(actions.contains(&NetworkAction::WriteNotification(
+			v2_peer,
+			PeerSet::Validation,
+			WireMessage::ProtocolMessage(message_v2).encode(),
+		)));
+
+		// Messages which can't be expressed in v1 at all are only sent to v2 peers.
+		let compact_cert = fake_assignment_cert_v2(AssignmentCertKindV2::RelayVRFModuloCompact {
+			core_bitfield: CoreBitfield::try_from(vec![CoreIndex(0), CoreIndex(1)]).unwrap(),
+		});
+		let message_v2 = protocol_v2::ValidationProtocol::ApprovalDistribution(
+			protocol_v2::ApprovalDistributionMessage::Assignments(vec![(
+				compact_cert,
+				vec![0u32, 1].try_into().unwrap(),
+			)]),
+		);
+
+		virtual_overseer
+			.send(FromOrchestra::Communication {
+				msg: NetworkBridgeTxMessage::SendValidationMessage(
+					vec![v1_peer, v2_peer],
+					Versioned::V2(message_v2.clone()),
+				),
+			})
+			.timeout(TIMEOUT)
+			.await
+			.expect("Timeout does not occur");
+
+		assert_eq!(
+			network_handle
+				.next_network_action()
+				.timeout(TIMEOUT)
+				.await
+				.expect("Timeout does not occur"),
+			NetworkAction::WriteNotification(
+				v2_peer,
+				PeerSet::Validation,
+				WireMessage::ProtocolMessage(message_v2).encode(),
+			)
+		);
+
+		virtual_overseer
+	});
+}
+
+#[test]
+fn fallback_request_is_sent_if_peer_does_not_support_protocol() {
+	test_harness(|test_harness| async move {
+		let TestHarness { mut network_handle, mut virtual_overseer, .. } = test_harness;
+
+		let (req, response) = OutgoingRequest::new_with_fallback(
+			Recipient::Peer(PeerId::random()),
+			v2::ChunkFetchingRequest {
+				candidate_hash: CandidateHash::default(),
+				index: ValidatorIndex(3),
+			},
+		);
+		virtual_overseer
+			.send(FromOrchestra::Communication {
+				msg: NetworkBridgeTxMessage::SendRequests(
+					vec![Requests::ChunkFetchingV2(req)],
+					IfDisconnected::ImmediateError,
+				),
+			})
+			.timeout(TIMEOUT)
+			.await
+			.expect("Timeout does not occur");
+
+		let req = network_handle
+			.next_request()
+			.timeout(TIMEOUT)
+			.await
+			.expect("Timeout does not occur");
+		assert_eq!(req.get_protocol(), Protocol::ChunkFetchingV2);
+		let (_, OutgoingRequest { pending_response, .. }) = req.encode_request();
+		pending_response
+			.send(Err(RequestFailure::Network(OutboundFailure::UnsupportedProtocols)))
+			.unwrap();
+
+		let req = network_handle
+			.next_request()
+			.timeout(TIMEOUT)
+			.await
+			.expect("Timeout does not occur");
+		assert_eq!(req.get_protocol(), Protocol::ChunkFetchingV1);
+		let (_, OutgoingRequest { pending_response, .. }) = req.encode_request();
+		let chunk = v1::ChunkResponse { chunk: vec![1, 2, 3], proof: Proof::dummy_proof() };
+		pending_response
+			.send(Ok(v1::ChunkFetchingResponse::Chunk(chunk).encode()))
+			.unwrap();
+
+		assert_matches!(
+			response.timeout(TIMEOUT).await.expect("Timeout does not occur"),
+			Ok(v2::ChunkFetchingResponse::Chunk(chunk)) if chunk.index == ValidatorIndex(3)
+		);
+
+		virtual_overseer
+	});
+}
@@ -39,7 +39,7 @@ use polkadot_node_network_protocol::{
 	request_response::{
 		outgoing::{Recipient, RequestError},
 		v1::{CollationFetchingRequest, CollationFetchingResponse},
-		OutgoingRequest, Requests,
+		OutgoingRequest, PeerPerformance, PerformanceEstimate, Requests,
 	},
 	v1 as protocol_v1, OurView, PeerId, UnifiedReputationChange as Rep, Versioned, View,
 };
@@ -143,6 +143,17 @@ impl Metrics {
 	) -> Option<metrics::prometheus::prometheus::HistogramTimer> {
 		self.0.as_ref().map(|metrics| metrics.collation_request_duration.start_timer())
 	}
+
+	/// Note the updated performance estimate of a collator.
+	fn on_peer_performance(&self, estimate: &PerformanceEstimate) {
+		if let Some(metrics) = &self.0 {
+			metrics.collator_response_time.observe(estimate.response_time.as_secs_f64());
+			if let Some(throughput) = estimate.throughput {
+				metrics.collator_throughput.observe(throughput);
+			}
+			metrics.collator_failure_rate.observe(estimate.failure_rate);
+		}
+	}
 }
 
 #[derive(Clone)]
@@ -152,6 +163,9 @@ struct MetricsInner {
 	handle_collation_request_result: prometheus::Histogram,
 	collator_peer_count: prometheus::Gauge<prometheus::U64>,
 	collation_request_duration: prometheus::Histogram,
+	collator_response_time: prometheus::Histogram,
+	collator_throughput: prometheus::Histogram,
+	collator_failure_rate: prometheus::Histogram,
 }
 
 impl metrics::Metrics for Metrics {
@@ -203,6 +217,36 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			collator_response_time: prometheus::register(
+				prometheus::Histogram::with_opts(
+					prometheus::HistogramOpts::new(
+						"polkadot_parachain_collator_protocol_validator_collator_response_time",
+						"Estimated response times of collators to collation requests in seconds, observed on every update",
+					).buckets(vec![0.05, 0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.75, 0.9, 1.0, 1.2, 1.5, 1.75]),
+				)?,
+				registry,
+			)?,
+			collator_throughput: prometheus::register(
+				prometheus::Histogram::with_opts(
+					prometheus::HistogramOpts::new(
+						"polkadot_parachain_collator_protocol_validator_collator_throughput",
+						"Estimated throughputs of collators' collation responses in bytes per second, observed on every update",
+					).buckets(
+						prometheus::exponential_buckets(16384.0, 2.0, 12)
+							.expect("arguments are always valid; qed"),
+					),
+				)?,
+				registry,
+			)?,
+			collator_failure_rate: prometheus::register(
+				prometheus::Histogram::with_opts(
+					prometheus::HistogramOpts::new(
+						"polkadot_parachain_collator_protocol_validator_collator_failure_rate",
+						"Estimated fractions of failed collation requests to collators, observed on every update",
+					).buckets(vec![0.01, 0.05, 0.1, 0.2, 0.3, 0.5, 0.75, 1.0]),
+				)?,
+				registry,
+			)?,
 		};
 
 		Ok(Metrics(Some(metrics)))
@@ -218,6 +262,8 @@ struct PerRequest {
 	span: Option<jaeger::Span>,
 	/// A metric histogram for the lifetime of the request
 	_lifetime_timer: Option<metrics::prometheus::prometheus::HistogramTimer>,
+	/// When the request was sent, for tracking the performance of the collator.
+	started: Instant,
 }
 
 #[derive(Debug)]
@@ -541,9 +587,13 @@ impl CollationsPerRelayParent {
 	///
 	/// Returns `Some(_)` if there is any collation to fetch, the `status` is not `Seconded` and
 	/// the passed in `finished_one` is the currently `waiting_collation`.
+	///
+	/// Collations get fetched from the best performing collators first, among equally ranked ones
+	/// the latest advertisement is preferred.
 	pub fn get_next_collation_to_fetch(
 		&mut self,
 		finished_one: Option<&CollatorId>,
+		peer_performance: &PeerPerformance<PeerId>,
 	) -> Option<(PendingCollation, CollatorId)> {
 		// If finished one does not match waiting_collation, then we already dequeued another fetch
 		// to replace it.
@@ -562,6 +612,15 @@ impl CollationsPerRelayParent {
 			// We don't need to fetch any other collation when we already have seconded one.
 			CollationStatus::Seconded => None,
 			CollationStatus::Waiting => {
+				// Reversing before and after the stable sort keeps the latest advertisement last
+				// among equally ranked collators.
+				self.unfetched_collations.reverse();
+				peer_performance.sort_by_preference(
+					&mut self.unfetched_collations,
+					0,
+					|(pc, _)| pc.peer_id,
+				);
+				self.unfetched_collations.reverse();
 				let next = self.unfetched_collations.pop();
 				self.waiting_collation = next.as_ref().map(|(_, collator_id)| collator_id.clone());
 				next
@@ -612,6 +671,9 @@ struct State {
 
 	/// Keep track of all pending candidate collations
 	pending_candidates: HashMap<Hash, CollationEvent>,
+
+	/// Observed performance of collators, for choosing whom to fetch from first.
+	peer_performance: PeerPerformance<PeerId>,
 }
 
 // O(n) search for collator ID by iterating through the peers map. This should be fast enough
@@ -778,6 +840,7 @@ async fn request_collation(
 			.get(&relay_parent)
 			.map(|s| s.child("collation-request").with_para_id(para_id)),
 		_lifetime_timer: state.metrics.time_collation_request_duration(),
+		started: Instant::now(),
 	};
 
 	state
@@ -1071,7 +1134,7 @@ async fn handle_network_msg<Context>(
 		OurViewChange(view) => {
 			handle_our_view_change(ctx, state, keystore, view).await?;
 		},
-		PeerMessage(remote, Versioned::V1(msg)) => {
+		PeerMessage(remote, Versioned::V1(msg) | Versioned::V2(msg)) => {
 			process_incoming_peer_message(ctx, state, remote, msg).await;
 		},
 	}
@@ -1214,6 +1277,7 @@ pub(crate) async fn run<Context>(
 					&mut state.requested_collations,
 					&state.metrics,
 					&state.span_per_relay_parent,
+					&state.peer_performance,
 				).await;
 
 				for (peer_id, rep) in reputation_changes {
@@ -1230,14 +1294,20 @@ async fn poll_requests(
 	requested_collations: &mut HashMap<PendingCollation, PerRequest>,
 	metrics: &Metrics,
 	span_per_relay_parent: &HashMap<Hash, PerLeafSpan>,
+	peer_performance: &PeerPerformance<PeerId>,
 ) -> Vec<(PeerId, Rep)> {
 	let mut retained_requested = HashSet::new();
 	let mut reputation_changes = Vec::new();
 	for (pending_collation, per_req) in requested_collations.iter_mut() {
 		// Despite the await, this won't block on the response itself.
-		let result =
-			poll_collation_response(metrics, span_per_relay_parent, pending_collation, per_req)
-				.await;
+		let result = poll_collation_response(
+			metrics,
+			span_per_relay_parent,
+			peer_performance,
+			pending_collation,
+			per_req,
+		)
+		.await;
 
 		if !result.is_ready() {
 			retained_requested.insert(pending_collation.clone());
@@ -1262,7 +1332,7 @@ async fn dequeue_next_collation_and_fetch<Context>(
 	if let Some((next, id)) = state
 		.collations_per_relay_parent
 		.get_mut(&relay_parent)
-		.and_then(|c| c.get_next_collation_to_fetch(Some(&previous_fetch)))
+		.and_then(|c| c.get_next_collation_to_fetch(Some(&previous_fetch), &state.peer_performance))
 	{
 		gum::debug!(
 			target: LOG_TARGET,
@@ -1375,10 +1445,12 @@ impl CollationFetchResult {
 /// Poll collation response, return immediately if there is none.
 ///
 /// Ready responses are handled, by logging and by
-/// forwarding proper responses to the requester.
+/// forwarding proper responses to the requester. They also update the performance estimate of the
+/// collator.
 async fn poll_collation_response(
 	metrics: &Metrics,
 	spans: &HashMap<Hash, PerLeafSpan>,
+	peer_performance: &PeerPerformance<PeerId>,
 	pending_collation: &PendingCollation,
 	per_req: &mut PerRequest,
 ) -> CollationFetchResult {
@@ -1396,6 +1468,16 @@ async fn poll_collation_response(
 			.map(|s| s.child("received-collation"));
 		let _timer = metrics.time_handle_collation_request_result();
 
+		let estimate = match &response {
+			Ok(CollationFetchingResponse::Collation(_, pov)) => peer_performance.note_response(
+				&pending_collation.peer_id,
+				per_req.started.elapsed(),
+				pov.block_data.0.len(),
+			),
+			Err(_) => peer_performance.note_failure(&pending_collation.peer_id),
+		};
+		metrics.on_peer_performance(&estimate);
+
 		let mut metrics_result = Err(());
 		let mut success = "false";
 
@@ -1078,3 +1078,42 @@ fn view_change_clears_old_collators() {
 		virtual_overseer
 	})
 }
+
+#[test]
+fn next_collation_is_fetched_from_best_performing_collator() {
+	let relay_parent = Hash::repeat_byte(1);
+	let para_id = ParaId::from(1);
+	let peers: Vec<PeerId> = (0..4).map(|_| PeerId::random()).collect();
+
+	let peer_performance = PeerPerformance::default();
+	peer_performance.note_response(&peers[1], Duration::from_millis(100), 0);
+	peer_performance.note_response(&peers[2], Duration::from_millis(500), 0);
+	peer_performance.note_failure(&peers[3]);
+
+	let mut collations = CollationsPerRelayParent {
+		unfetched_collations: peers
+			.iter()
+			.map(|peer_id| {
+				(
+					PendingCollation::new(relay_parent, &para_id, peer_id),
+					CollatorPair::generate().0.public(),
+				)
+			})
+			.collect(),
+		..Default::default()
+	};
+
+	// The unknown collator is ranked like the median one, which advertised later and so is
+	// preferred. The failing collator comes last.
+	let mut finished_one = None;
+	for expected in [1, 2, 0, 3] {
+		let (pending_collation, collator_id) = collations
+			.get_next_collation_to_fetch(finished_one.as_ref(), &peer_performance)
+			.unwrap();
+		assert_eq!(pending_collation.peer_id, peers[expected]);
+		finished_one = Some(collator_id);
+	}
+	assert!(collations
+		.get_next_collation_to_fetch(finished_one.as_ref(), &peer_performance)
+		.is_none());
+}
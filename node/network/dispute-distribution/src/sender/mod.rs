@@ -30,8 +30,11 @@ use polkadot_node_primitives::{DisputeMessage, DisputeStatus};
 use polkadot_node_subsystem::{
 	messages::DisputeCoordinatorMessage, overseer, ActiveLeavesUpdate, SubsystemSender,
 };
-use polkadot_node_subsystem_util::{nesting_sender::NestingSender, runtime::RuntimeInfo};
-use polkadot_primitives::{CandidateHash, Hash, SessionIndex};
+use polkadot_node_subsystem_util::{
+	nesting_sender::NestingSender,
+	runtime::{is_approval_coalescing_enabled, RuntimeInfo},
+};
+use polkadot_primitives::{CandidateHash, Hash, SessionIndex, ValidDisputeStatementKind};
 
 /// For each ongoing dispute we have a `SendTask` which takes care of it.
 ///
@@ -130,6 +133,23 @@ impl<M: 'static + Send + Sync> DisputeSender<M> {
 	) -> Result<()> {
 		let req: DisputeRequest = msg.into();
 		let candidate_hash = req.0.candidate_receipt.hash();
+		// Nodes not yet supporting coalesced approvals would fail to decode the request.
+		if let ValidDisputeStatementKind::ApprovalCheckingMultipleCandidates(_) =
+			req.0.valid_vote.kind
+		{
+			let supported = match self.active_heads.last() {
+				Some(head) => is_approval_coalescing_enabled(ctx.sender(), *head).await,
+				None => false,
+			};
+			if !supported {
+				gum::debug!(
+					target: LOG_TARGET,
+					?candidate_hash,
+					"Not sending dispute with a coalesced approval vote, not enabled by the runtime."
+				);
+				return Ok(())
+			}
+		}
 		match self.disputes.entry(candidate_hash) {
 			Entry::Occupied(_) => {
 				gum::trace!(target: LOG_TARGET, ?candidate_hash, "Dispute sending already active.");
@@ -34,8 +34,9 @@ use sp_keystore::{Keystore, KeystorePtr};
 
 use polkadot_node_primitives::{DisputeMessage, SignedDisputeStatement};
 use polkadot_primitives::{
-	AuthorityDiscoveryId, CandidateHash, CandidateReceipt, Hash, SessionIndex, SessionInfo,
-	ValidatorId, ValidatorIndex,
+	ApprovalVoteMultipleCandidates, AuthorityDiscoveryId, CandidateHash, CandidateReceipt,
+	DisputeStatement, Hash, SessionIndex, SessionInfo, ValidDisputeStatementKind, ValidatorId,
+	ValidatorIndex,
 };
 use polkadot_primitives_test_helpers::dummy_candidate_descriptor;
 
@@ -146,6 +147,30 @@ pub fn make_explicit_signed(
 	.expect("Signing should work.")
 }
 
+/// Approval vote coalesced over the given candidate and another one.
+pub fn make_coalesced_approval_signed(
+	validator: Sr25519Keyring,
+	candidate_hash: CandidateHash,
+) -> SignedDisputeStatement {
+	let candidate_hashes = vec![candidate_hash, CandidateHash(Hash::random())];
+	let signature = validator
+		.sign(
+			&ApprovalVoteMultipleCandidates(&candidate_hashes).signing_payload(MOCK_SESSION_INDEX),
+		)
+		.into();
+
+	SignedDisputeStatement::new_checked(
+		DisputeStatement::Valid(ValidDisputeStatementKind::ApprovalCheckingMultipleCandidates(
+			candidate_hashes,
+		)),
+		candidate_hash,
+		MOCK_SESSION_INDEX,
+		validator.public().into(),
+		signature,
+	)
+	.expect("Signature should be valid.")
+}
+
 pub fn make_dispute_message(
 	candidate: CandidateReceipt,
 	valid_validator: ValidatorIndex,
@@ -159,6 +184,16 @@ pub fn make_dispute_message(
 		"Passed time for valid vote: {:#?}",
 		Instant::now().saturating_duration_since(before_request)
 	);
+	make_dispute_message_with_valid_vote(candidate, valid_vote, valid_validator, invalid_validator)
+}
+
+pub fn make_dispute_message_with_valid_vote(
+	candidate: CandidateReceipt,
+	valid_vote: SignedDisputeStatement,
+	valid_validator: ValidatorIndex,
+	invalid_validator: ValidatorIndex,
+) -> DisputeMessage {
+	let candidate_hash = candidate.hash();
 	let before_request = Instant::now();
 	let invalid_vote =
 		make_explicit_signed(MOCK_VALIDATORS[invalid_validator.0 as usize], candidate_hash, false);
@@ -57,11 +57,13 @@ use polkadot_node_subsystem_test_helpers::{
 	mock::make_ferdie_keystore, subsystem_test_harness, TestSubsystemContextHandle,
 };
 use polkadot_primitives::{
+	vstaging::{node_features::FeatureIndex, NodeFeatures},
 	AuthorityDiscoveryId, CandidateHash, CandidateReceipt, Hash, SessionIndex, SessionInfo,
 };
 
 use self::mock::{
-	make_candidate_receipt, make_dispute_message, ALICE_INDEX, FERDIE_DISCOVERY_KEY, FERDIE_INDEX,
+	make_candidate_receipt, make_coalesced_approval_signed, make_dispute_message,
+	make_dispute_message_with_valid_vote, ALICE_INDEX, FERDIE_DISCOVERY_KEY, FERDIE_INDEX,
 	MOCK_AUTHORITY_DISCOVERY, MOCK_NEXT_SESSION_INDEX, MOCK_NEXT_SESSION_INFO, MOCK_SESSION_INDEX,
 	MOCK_SESSION_INFO,
 };
@@ -116,6 +118,55 @@ fn send_honors_rate_limit() {
 	test_harness(test);
 }
 
+#[test]
+fn coalesced_approval_dispute_is_only_sent_if_enabled_by_the_runtime() {
+	let test = |mut handle: TestSubsystemContextHandle<DisputeDistributionMessage>, _req_cfg| async move {
+		let head = handle_subsystem_startup(&mut handle, None).await;
+
+		let mut enabled_features = NodeFeatures::new();
+		enabled_features.resize(FeatureIndex::ApprovalCoalescing as usize + 1, false);
+		enabled_features.set(FeatureIndex::ApprovalCoalescing as usize, true);
+
+		for node_features in [NodeFeatures::new(), enabled_features] {
+			let candidate = make_candidate_receipt(Hash::random());
+			let valid_vote =
+				make_coalesced_approval_signed(Sr25519Keyring::Alice, candidate.hash());
+			let message = make_dispute_message_with_valid_vote(
+				candidate,
+				valid_vote,
+				ALICE_INDEX,
+				FERDIE_INDEX,
+			);
+			handle
+				.send(FromOrchestra::Communication {
+					msg: DisputeDistributionMessage::SendDispute(message),
+				})
+				.await;
+			assert_matches!(
+				handle.recv().await,
+				AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					h,
+					RuntimeApiRequest::NodeFeatures(tx)
+				)) => {
+					assert_eq!(h, head);
+					tx.send(Ok(node_features)).expect("Receiver should stay alive.");
+				}
+			);
+		}
+
+		// Only the dispute sent while the runtime enables coalesced approvals goes out:
+		let expected_receivers = MOCK_SESSION_INFO
+			.discovery_keys
+			.clone()
+			.into_iter()
+			.filter(|a| a != &Sr25519Keyring::Ferdie.public().into())
+			.collect();
+		check_sent_requests(&mut handle, expected_receivers, true).await;
+		conclude(&mut handle).await;
+	};
+	test_harness(test);
+}
+
 /// Helper for sending a new dispute to dispute-distribution sender and handling resulting messages.
 async fn send_dispute(
 	handle: &mut TestSubsystemContextHandle<DisputeDistributionMessage>,
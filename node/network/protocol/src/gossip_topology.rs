@@ -0,0 +1,344 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pluggable gossip topologies.
+//!
+//! A gossip topology decides which validators send messages to each other directly. All
+//! validators derive it from the same [`SessionGridTopology`], i.e. the canonical shuffling of
+//! validators for the session, so they agree on who is whose neighbor.
+//!
+//! Two topologies are implemented:
+//!   * [`GridTopology`]: the 2D grid, see [`crate::grid_topology`]. Messages reach every
+//!     validator in 2 hops, at the cost of every validator having about `2 * sqrt(n)` neighbors.
+//!   * [`RandomRegularTopology`]: a random regular graph of a fixed degree. Random regular graphs
+//!     are good expanders, so flooding messages reaches every validator in `O(log(n))` hops,
+//!     while every validator only has `degree` neighbors. The price is that validators receive
+//!     every message from most of their neighbors, where the grid delivers it about twice.
+//!
+//! Distribution subsystems pick a topology per message class by creating their topology storage
+//! with [`SessionGridTopologies::with_gossip_topology`] or
+//! [`SessionBoundGridTopologyStorage::with_gossip_topology`]. The node uses the grid, unless
+//! `--gossip-topology-degree` selects a [`RandomRegularTopology`] for approvals, bitfields and
+//! statements.
+//!
+//! [`SessionGridTopologies::with_gossip_topology`]: crate::grid_topology::SessionGridTopologies::with_gossip_topology
+//! [`SessionBoundGridTopologyStorage::with_gossip_topology`]: crate::grid_topology::SessionBoundGridTopologyStorage::with_gossip_topology
+
+use crate::grid_topology::{Forwarding, GridNeighbors, SessionGridTopology};
+use polkadot_primitives::ValidatorIndex;
+use std::{collections::HashSet, fmt::Debug};
+
+/// The default degree of the [`RandomRegularTopology`].
+pub const DEFAULT_RANDOM_REGULAR_DEGREE: usize = 8;
+
+/// A topology for gossiping messages between validators.
+pub trait GossipTopology: Debug + Send + Sync {
+	/// Compute the neighbors of the given validator in the topology for a session, together with
+	/// how it forwards messages to them.
+	///
+	/// Returns `None` if the validator index is out of bounds.
+	fn compute_neighbors_for(
+		&self,
+		session_topology: &SessionGridTopology,
+		v: ValidatorIndex,
+	) -> Option<GridNeighbors>;
+}
+
+/// The 2D grid topology.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GridTopology;
+
+impl GossipTopology for GridTopology {
+	fn compute_neighbors_for(
+		&self,
+		session_topology: &SessionGridTopology,
+		v: ValidatorIndex,
+	) -> Option<GridNeighbors> {
+		session_topology.compute_grid_neighbors_for(v)
+	}
+}
+
+/// A random regular graph topology.
+///
+/// The graph is the union of `degree / 2` Hamiltonian cycles through all validators. The first
+/// cycle follows the canonical shuffling, the others follow fixed pseudo-random permutations of
+/// it. Cycles might share edges, so some validators can have less than `degree` neighbors.
+///
+/// All neighbors are in the X dimension of the resulting [`GridNeighbors`] and messages are
+/// flooded to them.
+#[derive(Debug, Clone, Copy)]
+pub struct RandomRegularTopology {
+	cycles: usize,
+}
+
+impl RandomRegularTopology {
+	/// Create a topology in which every validator has up to `degree` neighbors.
+	///
+	/// Odd degrees are rounded down, the degree is at least 2.
+	pub fn new(degree: usize) -> Self {
+		RandomRegularTopology { cycles: std::cmp::max(degree / 2, 1) }
+	}
+
+	/// The maximum number of neighbors of a validator.
+	pub fn degree(&self) -> usize {
+		self.cycles * 2
+	}
+}
+
+impl Default for RandomRegularTopology {
+	fn default() -> Self {
+		Self::new(DEFAULT_RANDOM_REGULAR_DEGREE)
+	}
+}
+
+impl GossipTopology for RandomRegularTopology {
+	fn compute_neighbors_for(
+		&self,
+		session_topology: &SessionGridTopology,
+		v: ValidatorIndex,
+	) -> Option<GridNeighbors> {
+		let shuffled_val_index = session_topology.shuffled_index_of(v)?;
+		let canonical_shuffling = session_topology.canonical_shuffling();
+		let neighbors =
+			cycle_neighbors(shuffled_val_index, canonical_shuffling.len(), self.cycles)?;
+
+		let mut grid_subset = GridNeighbors::empty();
+		grid_subset.forwarding = Forwarding::Flood;
+		for n in neighbors {
+			let n = &canonical_shuffling[n];
+			grid_subset.validator_indices_x.insert(n.validator_index);
+			grid_subset.peers_x.extend(n.peer_ids.iter().cloned());
+		}
+
+		Some(grid_subset)
+	}
+}
+
+/// Compute the neighbors of `val_index` in the union of `cycles` Hamiltonian cycles through
+/// `len` nodes.
+fn cycle_neighbors(val_index: usize, len: usize, cycles: usize) -> Option<HashSet<usize>> {
+	if val_index >= len {
+		return None
+	}
+
+	let mut neighbors = HashSet::new();
+	for cycle in 0..cycles {
+		let order = if cycle == 0 {
+			(0..len).collect::<Vec<_>>()
+		} else {
+			pseudo_random_permutation(len, cycle as u64)
+		};
+
+		let pos = order.iter().position(|i| *i == val_index).expect("permutation of 0..len; qed");
+		neighbors.insert(order[(pos + len - 1) % len]);
+		neighbors.insert(order[(pos + 1) % len]);
+	}
+	neighbors.remove(&val_index);
+
+	Some(neighbors)
+}
+
+/// A permutation of `0..len` by a Fisher-Yates shuffle.
+///
+/// All validators need to arrive at the same permutation, so this uses a fixed and portable
+/// generator (SplitMix64) instead of the `rand` RNGs, whose algorithms might change.
+fn pseudo_random_permutation(len: usize, seed: u64) -> Vec<usize> {
+	let mut state = seed;
+	let mut next = move || {
+		state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+		let mut z = state;
+		z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+		z ^ (z >> 31)
+	};
+
+	let mut permutation = (0..len).collect::<Vec<_>>();
+	for i in (1..len).rev() {
+		let j = (next() % (i as u64 + 1)) as usize;
+		permutation.swap(i, j);
+	}
+
+	permutation
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::grid_topology::{RequiredRouting, TopologyPeerInfo};
+	use parity_scale_codec::Decode;
+	use polkadot_primitives::AuthorityDiscoveryId;
+	use std::collections::VecDeque;
+
+	fn session_topology(n: usize) -> SessionGridTopology {
+		// Shuffle by reversing, so that validator indices and positions differ.
+		let canonical_shuffling = (0..n)
+			.rev()
+			.map(|i| TopologyPeerInfo {
+				peer_ids: Vec::new(),
+				validator_index: ValidatorIndex(i as u32),
+				discovery_id: AuthorityDiscoveryId::decode(&mut &[i as u8; 32][..]).unwrap(),
+			})
+			.collect();
+		let shuffled_indices = (0..n).map(|i| n - 1 - i).collect();
+
+		SessionGridTopology::new(shuffled_indices, canonical_shuffling)
+	}
+
+	/// Propagation of a single message through the topology.
+	#[derive(Debug)]
+	struct Propagation {
+		/// The number of validators that received the message, including the originator.
+		reached: usize,
+		/// The number of hops until the last validator received the message.
+		hops: usize,
+		/// The number of messages sent.
+		messages: usize,
+		/// The maximum number of neighbors of a validator.
+		max_neighbors: usize,
+	}
+
+	/// Simulate the propagation of a message, sent by `originator` at the same time to all its
+	/// required neighbors. Validators don't send the message back to the validator they received
+	/// it from first.
+	fn simulate(
+		gossip_topology: &dyn GossipTopology,
+		session_topology: &SessionGridTopology,
+		originator: ValidatorIndex,
+	) -> Propagation {
+		let n = session_topology.canonical_shuffling().len();
+		let neighbors: Vec<_> = (0..n)
+			.map(|v| {
+				gossip_topology
+					.compute_neighbors_for(session_topology, ValidatorIndex(v as u32))
+					.unwrap()
+			})
+			.collect();
+
+		let mut received_at = vec![None; n];
+		received_at[originator.0 as usize] = Some(0);
+		let mut queue = VecDeque::from(vec![(originator, None)]);
+		let max_neighbors = neighbors
+			.iter()
+			.map(|n| n.validator_indices_x.union(&n.validator_indices_y).count())
+			.max()
+			.unwrap_or(0);
+		let mut propagation = Propagation { reached: 1, hops: 0, messages: 0, max_neighbors };
+
+		while let Some((validator, from)) = queue.pop_front() {
+			let neighbors = &neighbors[validator.0 as usize];
+			let required_routing =
+				neighbors.required_routing_by_index(originator, validator == originator);
+			let targets: HashSet<_> = match required_routing {
+				RequiredRouting::GridX => neighbors.validator_indices_x.clone(),
+				RequiredRouting::GridY => neighbors.validator_indices_y.clone(),
+				RequiredRouting::GridXY => neighbors
+					.validator_indices_x
+					.union(&neighbors.validator_indices_y)
+					.cloned()
+					.collect(),
+				_ => HashSet::new(),
+			};
+
+			let hops = received_at[validator.0 as usize].unwrap() + 1;
+			for target in targets.into_iter().filter(|target| Some(*target) != from) {
+				propagation.messages += 1;
+				if received_at[target.0 as usize].is_none() {
+					received_at[target.0 as usize] = Some(hops);
+					propagation.reached += 1;
+					propagation.hops = std::cmp::max(propagation.hops, hops);
+					queue.push_back((target, Some(validator)));
+				}
+			}
+		}
+
+		propagation
+	}
+
+	#[test]
+	fn random_regular_neighbors_are_symmetric() {
+		let n = 300;
+		let session_topology = session_topology(n);
+		let topology = RandomRegularTopology::default();
+
+		let neighbors: Vec<_> = (0..n)
+			.map(|v| {
+				topology
+					.compute_neighbors_for(&session_topology, ValidatorIndex(v as u32))
+					.unwrap()
+			})
+			.collect();
+
+		for (v, v_neighbors) in neighbors.iter().enumerate() {
+			let v = ValidatorIndex(v as u32);
+			assert_eq!(v_neighbors.forwarding, Forwarding::Flood);
+			assert!(v_neighbors.validator_indices_y.is_empty());
+			assert!(!v_neighbors.validator_indices_x.contains(&v));
+			assert!(v_neighbors.validator_indices_x.len() >= 2);
+			assert!(v_neighbors.validator_indices_x.len() <= topology.degree());
+
+			for neighbor in &v_neighbors.validator_indices_x {
+				assert!(neighbors[neighbor.0 as usize].validator_indices_x.contains(&v));
+			}
+		}
+
+		assert!(topology
+			.compute_neighbors_for(&session_topology, ValidatorIndex(n as u32))
+			.is_none());
+	}
+
+	#[test]
+	fn small_random_regular_topologies_work() {
+		for n in 1..4 {
+			let session_topology = session_topology(n);
+			let topology = RandomRegularTopology::default();
+			let propagation = simulate(&topology, &session_topology, ValidatorIndex(0));
+
+			assert_eq!(propagation.reached, n);
+		}
+	}
+
+	#[test]
+	fn propagation_latency_versus_bandwidth() {
+		let n = 1000;
+		let session_topology = session_topology(n);
+
+		for originator in [0, 1, 500, 999].into_iter().map(ValidatorIndex) {
+			let grid = simulate(&GridTopology, &session_topology, originator);
+			let random_regular =
+				simulate(&RandomRegularTopology::default(), &session_topology, originator);
+
+			// Both reach every validator.
+			assert_eq!(grid.reached, n);
+			assert_eq!(random_regular.reached, n);
+
+			// The grid reaches every validator in 2 hops, the random regular graph needs more
+			// but stays logarithmic.
+			assert_eq!(grid.hops, 2);
+			assert!(random_regular.hops > grid.hops);
+			assert!(random_regular.hops <= 6, "{:?}", random_regular);
+
+			// The grid delivers the message about twice to every validator. Flooding the random
+			// regular graph delivers it up to `degree` times.
+			assert!(grid.messages <= 2 * n, "{:?}", grid);
+			assert!(random_regular.messages > grid.messages);
+			assert!(random_regular.messages <= n * DEFAULT_RANDOM_REGULAR_DEGREE);
+
+			// In exchange, validators need far fewer connections.
+			assert!(random_regular.max_neighbors <= DEFAULT_RANDOM_REGULAR_DEGREE);
+			assert!(grid.max_neighbors > 6 * random_regular.max_neighbors, "{:?}", grid);
+		}
+	}
+}
@@ -28,13 +28,20 @@
 //! an adversary doesn't know which peers a validator will send to.
 //! This is combined with the property that the adversary doesn't know which validators will elect to check a block.
 //!
+//! The grid is the default [`GossipTopology`]. The storages in this module compute the local
+//! neighbors with the gossip topology they were created with, see
+//! [`crate::gossip_topology`] for alternatives.
 
-use crate::PeerId;
+use crate::{
+	gossip_topology::{GossipTopology, GridTopology},
+	PeerId,
+};
 use polkadot_primitives::{AuthorityDiscoveryId, SessionIndex, ValidatorIndex};
 use rand::{CryptoRng, Rng};
 use std::{
 	collections::{hash_map, HashMap, HashSet},
 	fmt::Debug,
+	sync::Arc,
 };
 
 const LOG_TARGET: &str = "parachain::grid-topology";
@@ -108,6 +115,21 @@ impl SessionGridTopology {
 
 		Some(grid_subset)
 	}
+
+	/// The position of the given validator in the canonical shuffling.
+	///
+	/// Returns `None` if the validator index is out of bounds.
+	pub fn shuffled_index_of(&self, v: ValidatorIndex) -> Option<usize> {
+		if self.shuffled_indices.len() != self.canonical_shuffling.len() {
+			return None
+		}
+		self.shuffled_indices.get(v.0 as usize).copied()
+	}
+
+	/// The canonical shuffling of validators for the session.
+	pub fn canonical_shuffling(&self) -> &[TopologyPeerInfo] {
+		&self.canonical_shuffling
+	}
 }
 
 struct MatrixNeighbors<R, C> {
@@ -145,7 +167,21 @@ fn matrix_neighbors(
 	})
 }
 
+/// How messages originating from other validators are forwarded to the neighbors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Forwarding {
+	/// Messages originating from X neighbors are forwarded to the Y neighbors and vice versa.
+	/// Messages originating from other validators are not forwarded. Used by the 2D grid, which
+	/// reaches every validator in 2 hops.
+	CrossDimension,
+	/// All messages are forwarded to all neighbors. Used by topologies in which messages travel
+	/// more than 2 hops, relying on peers' knowledge of messages to stop the flood.
+	Flood,
+}
+
 /// Information about the grid neighbors for a particular node in the topology.
+///
+/// Topologies without dimensions only use the X axis.
 #[derive(Debug, Clone, PartialEq)]
 pub struct GridNeighbors {
 	/// Represent peers in the X axis
@@ -156,6 +192,8 @@ pub struct GridNeighbors {
 	pub peers_y: HashSet<PeerId>,
 	/// Represent validators in the Y axis
 	pub validator_indices_y: HashSet<ValidatorIndex>,
+	/// How messages originating from other validators are forwarded.
+	pub forwarding: Forwarding,
 }
 
 impl GridNeighbors {
@@ -167,6 +205,7 @@ impl GridNeighbors {
 			validator_indices_x: HashSet::new(),
 			peers_y: HashSet::new(),
 			validator_indices_y: HashSet::new(),
+			forwarding: Forwarding::CrossDimension,
 		}
 	}
 
@@ -177,7 +216,7 @@ impl GridNeighbors {
 		originator: ValidatorIndex,
 		local: bool,
 	) -> RequiredRouting {
-		if local {
+		if local || self.forwarding == Forwarding::Flood {
 			return RequiredRouting::GridXY
 		}
 
@@ -195,7 +234,7 @@ impl GridNeighbors {
 	/// Given the originator of a message as a peer index, indicates the part of the topology
 	/// we're meant to send the message to.
 	pub fn required_routing_by_peer_id(&self, originator: PeerId, local: bool) -> RequiredRouting {
-		if local {
+		if local || self.forwarding == Forwarding::Flood {
 			return RequiredRouting::GridXY
 		}
 
@@ -271,12 +310,24 @@ impl SessionGridTopologyEntry {
 }
 
 /// A set of topologies indexed by session
-#[derive(Default)]
 pub struct SessionGridTopologies {
 	inner: HashMap<SessionIndex, (Option<SessionGridTopologyEntry>, usize)>,
+	gossip_topology: Arc<dyn GossipTopology>,
+}
+
+impl Default for SessionGridTopologies {
+	fn default() -> Self {
+		Self::with_gossip_topology(Arc::new(GridTopology))
+	}
 }
 
 impl SessionGridTopologies {
+	/// Create an empty set of topologies, computing local neighbors with the given gossip
+	/// topology.
+	pub fn with_gossip_topology(gossip_topology: Arc<dyn GossipTopology>) -> Self {
+		SessionGridTopologies { inner: HashMap::new(), gossip_topology }
+	}
+
 	/// Returns a topology for the specific session index
 	pub fn get_topology(&self, session: SessionIndex) -> Option<&SessionGridTopologyEntry> {
 		self.inner.get(&session).and_then(|val| val.0.as_ref())
@@ -307,7 +358,7 @@ impl SessionGridTopologies {
 		let entry = self.inner.entry(session).or_insert((None, 0));
 		if entry.0.is_none() {
 			let local_neighbors = local_index
-				.and_then(|l| topology.compute_grid_neighbors_for(l))
+				.and_then(|l| self.gossip_topology.compute_neighbors_for(&topology, l))
 				.unwrap_or_else(GridNeighbors::empty);
 
 			entry.0 = Some(SessionGridTopologyEntry { topology, local_neighbors });
@@ -327,12 +378,21 @@ struct GridTopologySessionBound {
 pub struct SessionBoundGridTopologyStorage {
 	current_topology: GridTopologySessionBound,
 	prev_topology: Option<GridTopologySessionBound>,
+	gossip_topology: Arc<dyn GossipTopology>,
 }
 
 impl Default for SessionBoundGridTopologyStorage {
 	fn default() -> Self {
 		// having this struct be `Default` is objectively stupid
 		// but used in a few places
+		Self::with_gossip_topology(Arc::new(GridTopology))
+	}
+}
+
+impl SessionBoundGridTopologyStorage {
+	/// Create a storage without topologies, computing local neighbors with the given gossip
+	/// topology.
+	pub fn with_gossip_topology(gossip_topology: Arc<dyn GossipTopology>) -> Self {
 		SessionBoundGridTopologyStorage {
 			current_topology: GridTopologySessionBound {
 				// session 0 is valid so we should use the upper bound
@@ -347,11 +407,10 @@ impl Default for SessionBoundGridTopologyStorage {
 				},
 			},
 			prev_topology: None,
+			gossip_topology,
 		}
 	}
-}
 
-impl SessionBoundGridTopologyStorage {
 	/// Return a grid topology based on the session index:
 	/// If we need a previous session and it is registered in the storage, then return that session.
 	/// Otherwise, return a current session to have some grid topology in any case
@@ -382,7 +441,7 @@ impl SessionBoundGridTopologyStorage {
 		local_index: Option<ValidatorIndex>,
 	) {
 		let local_neighbors = local_index
-			.and_then(|l| topology.compute_grid_neighbors_for(l))
+			.and_then(|l| self.gossip_topology.compute_neighbors_for(&topology, l))
 			.unwrap_or_else(GridNeighbors::empty);
 
 		let old_current = std::mem::replace(
@@ -40,6 +40,8 @@ pub mod request_response;
 
 /// Accessing authority discovery service
 pub mod authority_discovery;
+/// Pluggable gossip topologies
+pub mod gossip_topology;
 /// Grid topology support module
 pub mod grid_topology;
 
@@ -56,6 +58,16 @@ impl fmt::Display for WrongVariant {
 	}
 }
 
+/// An error indicating that a message can't be expressed in an older protocol version.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IncompatibleVersion;
+
+impl fmt::Display for IncompatibleVersion {
+	fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(formatter, "Message can't be expressed in the protocol version")
+	}
+}
+
 impl std::error::Error for WrongVariant {}
 
 /// The advertised role of a node.
@@ -250,23 +262,28 @@ impl View {
 }
 
 /// A protocol-versioned type.
+///
+/// Protocols without a second version use the types of the first version for both.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub enum Versioned<V1> {
+pub enum Versioned<V1, V2 = V1> {
 	/// V1 type.
 	V1(V1),
+	/// V2 type.
+	V2(V2),
 }
 
-impl<V1: Clone> Versioned<&'_ V1> {
+impl<V1: Clone, V2: Clone> Versioned<&'_ V1, &'_ V2> {
 	/// Convert to a fully-owned version of the message.
-	pub fn clone_inner(&self) -> Versioned<V1> {
+	pub fn clone_inner(&self) -> Versioned<V1, V2> {
 		match *self {
 			Versioned::V1(inner) => Versioned::V1(inner.clone()),
+			Versioned::V2(inner) => Versioned::V2(inner.clone()),
 		}
 	}
 }
 
 /// All supported versions of the validation protocol message.
-pub type VersionedValidationProtocol = Versioned<v1::ValidationProtocol>;
+pub type VersionedValidationProtocol = Versioned<v1::ValidationProtocol, v2::ValidationProtocol>;
 
 impl From<v1::ValidationProtocol> for VersionedValidationProtocol {
 	fn from(v1: v1::ValidationProtocol) -> Self {
@@ -274,6 +291,12 @@ impl From<v1::ValidationProtocol> for VersionedValidationProtocol {
 	}
 }
 
+impl From<v2::ValidationProtocol> for VersionedValidationProtocol {
+	fn from(v2: v2::ValidationProtocol) -> Self {
+		VersionedValidationProtocol::V2(v2)
+	}
+}
+
 /// All supported versions of the collation protocol message.
 pub type VersionedCollationProtocol = Versioned<v1::CollationProtocol>;
 
@@ -289,6 +312,7 @@ macro_rules! impl_versioned_full_protocol_from {
 			fn from(versioned_from: $from) -> $out {
 				match versioned_from {
 					Versioned::V1(x) => Versioned::V1(x.into()),
+					Versioned::V2(x) => Versioned::V2(x.into()),
 				}
 			}
 		}
@@ -298,7 +322,7 @@ macro_rules! impl_versioned_full_protocol_from {
 /// Implement `TryFrom` for one versioned enum variant into the inner type.
 /// `$m_ty::$variant(inner) -> Ok(inner)`
 macro_rules! impl_versioned_try_from {
-	($from:ty, $out:ty, $v1_pat:pat => $v1_out:expr) => {
+	($from:ty, $out:ty, $v1_pat:pat => $v1_out:expr, $v2_pat:pat => $v2_out:expr) => {
 		impl TryFrom<$from> for $out {
 			type Error = crate::WrongVariant;
 
@@ -306,6 +330,7 @@ macro_rules! impl_versioned_try_from {
 				#[allow(unreachable_patterns)] // when there is only one variant
 				match x {
 					Versioned::V1($v1_pat) => Ok(Versioned::V1($v1_out)),
+					Versioned::V2($v2_pat) => Ok(Versioned::V2($v2_out)),
 					_ => Err(crate::WrongVariant),
 				}
 			}
@@ -318,6 +343,7 @@ macro_rules! impl_versioned_try_from {
 				#[allow(unreachable_patterns)] // when there is only one variant
 				match x {
 					Versioned::V1($v1_pat) => Ok(Versioned::V1($v1_out.clone())),
+					Versioned::V2($v2_pat) => Ok(Versioned::V2($v2_out.clone())),
 					_ => Err(crate::WrongVariant),
 				}
 			}
@@ -326,7 +352,8 @@ macro_rules! impl_versioned_try_from {
 }
 
 /// Version-annotated messages used by the bitfield distribution subsystem.
-pub type BitfieldDistributionMessage = Versioned<v1::BitfieldDistributionMessage>;
+pub type BitfieldDistributionMessage =
+	Versioned<v1::BitfieldDistributionMessage, v2::BitfieldDistributionMessage>;
 impl_versioned_full_protocol_from!(
 	BitfieldDistributionMessage,
 	VersionedValidationProtocol,
@@ -335,11 +362,13 @@ impl_versioned_full_protocol_from!(
 impl_versioned_try_from!(
 	VersionedValidationProtocol,
 	BitfieldDistributionMessage,
-	v1::ValidationProtocol::BitfieldDistribution(x) => x
+	v1::ValidationProtocol::BitfieldDistribution(x) => x,
+	v2::ValidationProtocol::BitfieldDistribution(x) => x
 );
 
 /// Version-annotated messages used by the statement distribution subsystem.
-pub type StatementDistributionMessage = Versioned<v1::StatementDistributionMessage>;
+pub type StatementDistributionMessage =
+	Versioned<v1::StatementDistributionMessage, v2::StatementDistributionMessage>;
 impl_versioned_full_protocol_from!(
 	StatementDistributionMessage,
 	VersionedValidationProtocol,
@@ -348,11 +377,13 @@ impl_versioned_full_protocol_from!(
 impl_versioned_try_from!(
 	VersionedValidationProtocol,
 	StatementDistributionMessage,
-	v1::ValidationProtocol::StatementDistribution(x) => x
+	v1::ValidationProtocol::StatementDistribution(x) => x,
+	v2::ValidationProtocol::StatementDistribution(x) => x
 );
 
 /// Version-annotated messages used by the approval distribution subsystem.
-pub type ApprovalDistributionMessage = Versioned<v1::ApprovalDistributionMessage>;
+pub type ApprovalDistributionMessage =
+	Versioned<v1::ApprovalDistributionMessage, v2::ApprovalDistributionMessage>;
 impl_versioned_full_protocol_from!(
 	ApprovalDistributionMessage,
 	VersionedValidationProtocol,
@@ -361,11 +392,13 @@ impl_versioned_full_protocol_from!(
 impl_versioned_try_from!(
 	VersionedValidationProtocol,
 	ApprovalDistributionMessage,
-	v1::ValidationProtocol::ApprovalDistribution(x) => x
+	v1::ValidationProtocol::ApprovalDistribution(x) => x,
+	v2::ValidationProtocol::ApprovalDistribution(x) => x
 );
 
 /// Version-annotated messages used by the gossip-support subsystem (this is void).
-pub type GossipSupportNetworkMessage = Versioned<v1::GossipSupportNetworkMessage>;
+pub type GossipSupportNetworkMessage =
+	Versioned<v1::GossipSupportNetworkMessage, v2::GossipSupportNetworkMessage>;
 // This is a void enum placeholder, so never gets sent over the wire.
 impl TryFrom<VersionedValidationProtocol> for GossipSupportNetworkMessage {
 	type Error = WrongVariant;
@@ -391,6 +424,7 @@ impl_versioned_full_protocol_from!(
 impl_versioned_try_from!(
 	VersionedCollationProtocol,
 	CollatorProtocolMessage,
+	v1::CollationProtocol::CollatorProtocol(x) => x,
 	v1::CollationProtocol::CollatorProtocol(x) => x
 );
 
@@ -551,3 +585,142 @@ pub mod v1 {
 		payload
 	}
 }
+
+/// v2 notification protocol types.
+///
+/// Only the approval distribution messages differ from [`v1`]: an assignment may claim
+/// several candidates of a block at once and an approval vote may approve several candidates
+/// of a block with a single signature.
+pub mod v2 {
+	use parity_scale_codec::{Decode, Encode};
+
+	use polkadot_node_primitives::approval::{
+		CandidateBitfield, IndirectAssignmentCertV2, IndirectSignedApprovalVoteV2,
+	};
+
+	pub use super::v1::{
+		BitfieldDistributionMessage, GossipSupportNetworkMessage, StatementDistributionMessage,
+		StatementMetadata,
+	};
+
+	use super::{v1, IncompatibleVersion};
+
+	/// Network messages used by the approval distribution subsystem.
+	#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+	pub enum ApprovalDistributionMessage {
+		/// Assignments for candidates in recent, unfinalized blocks. Each assignment claims
+		/// the candidates of the bitfield.
+		///
+		/// Actually checking the assignment may yield a different result.
+		#[codec(index = 0)]
+		Assignments(Vec<(IndirectAssignmentCertV2, CandidateBitfield)>),
+		/// Approvals for candidates in some recent, unfinalized block. Each approval covers
+		/// the candidates of its bitfield.
+		#[codec(index = 1)]
+		Approvals(Vec<IndirectSignedApprovalVoteV2>),
+	}
+
+	/// All network messages on the validation peer-set.
+	#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq, derive_more::From)]
+	pub enum ValidationProtocol {
+		/// Bitfield distribution messages
+		#[codec(index = 1)]
+		#[from]
+		BitfieldDistribution(BitfieldDistributionMessage),
+		/// Statement distribution messages
+		#[codec(index = 3)]
+		#[from]
+		StatementDistribution(StatementDistributionMessage),
+		/// Approval distribution messages
+		#[codec(index = 4)]
+		#[from]
+		ApprovalDistribution(ApprovalDistributionMessage),
+	}
+
+	impl From<v1::ApprovalDistributionMessage> for ApprovalDistributionMessage {
+		fn from(message: v1::ApprovalDistributionMessage) -> Self {
+			match message {
+				v1::ApprovalDistributionMessage::Assignments(assignments) =>
+					ApprovalDistributionMessage::Assignments(
+						assignments
+							.into_iter()
+							.map(|(cert, candidate_index)| (cert.into(), candidate_index.into()))
+							.collect(),
+					),
+				v1::ApprovalDistributionMessage::Approvals(approvals) =>
+					ApprovalDistributionMessage::Approvals(
+						approvals.into_iter().map(Into::into).collect(),
+					),
+			}
+		}
+	}
+
+	/// Assignments which can't be expressed in [`v1`], because they claim several candidates
+	/// or use a cert kind unknown to [`v1`], are left out, as are approvals covering several
+	/// candidates. Fails if none of the assignments or approvals are left.
+	impl TryFrom<ApprovalDistributionMessage> for v1::ApprovalDistributionMessage {
+		type Error = IncompatibleVersion;
+
+		fn try_from(message: ApprovalDistributionMessage) -> Result<Self, Self::Error> {
+			match message {
+				ApprovalDistributionMessage::Assignments(assignments) => {
+					let had_assignments = !assignments.is_empty();
+					let assignments = assignments
+						.into_iter()
+						.filter(|(_, claimed_candidates)| claimed_candidates.count_ones() == 1)
+						.filter_map(|(cert, claimed_candidates)| {
+							Some((cert.try_into().ok()?, claimed_candidates.first_one()?))
+						})
+						.collect::<Vec<_>>();
+
+					if had_assignments && assignments.is_empty() {
+						Err(IncompatibleVersion)
+					} else {
+						Ok(v1::ApprovalDistributionMessage::Assignments(assignments))
+					}
+				},
+				ApprovalDistributionMessage::Approvals(approvals) => {
+					let had_approvals = !approvals.is_empty();
+					let approvals = approvals
+						.into_iter()
+						.filter_map(|approval| approval.try_into().ok())
+						.collect::<Vec<_>>();
+
+					if had_approvals && approvals.is_empty() {
+						Err(IncompatibleVersion)
+					} else {
+						Ok(v1::ApprovalDistributionMessage::Approvals(approvals))
+					}
+				},
+			}
+		}
+	}
+
+	impl From<v1::ValidationProtocol> for ValidationProtocol {
+		fn from(message: v1::ValidationProtocol) -> Self {
+			match message {
+				v1::ValidationProtocol::BitfieldDistribution(m) =>
+					ValidationProtocol::BitfieldDistribution(m),
+				v1::ValidationProtocol::StatementDistribution(m) =>
+					ValidationProtocol::StatementDistribution(m),
+				v1::ValidationProtocol::ApprovalDistribution(m) =>
+					ValidationProtocol::ApprovalDistribution(m.into()),
+			}
+		}
+	}
+
+	impl TryFrom<ValidationProtocol> for v1::ValidationProtocol {
+		type Error = IncompatibleVersion;
+
+		fn try_from(message: ValidationProtocol) -> Result<Self, Self::Error> {
+			Ok(match message {
+				ValidationProtocol::BitfieldDistribution(m) =>
+					v1::ValidationProtocol::BitfieldDistribution(m),
+				ValidationProtocol::StatementDistribution(m) =>
+					v1::ValidationProtocol::StatementDistribution(m),
+				ValidationProtocol::ApprovalDistribution(m) =>
+					v1::ValidationProtocol::ApprovalDistribution(m.try_into()?),
+			})
+		}
+	}
+}
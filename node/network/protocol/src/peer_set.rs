@@ -38,6 +38,9 @@ const LEGACY_PROTOCOL_VERSION_V1: u32 = 1;
 /// Max notification size is currently constant.
 pub const MAX_NOTIFICATION_SIZE: u64 = 100 * 1024;
 
+/// Suffix of the protocol names on which notifications may be sent compressed.
+const COMPRESSED_PROTOCOL_SUFFIX: &str = "zstd";
+
 /// The peer-sets and thus the protocols which are used for the network.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter)]
 pub enum PeerSet {
@@ -72,7 +75,7 @@ impl PeerSet {
 		// Networking layer relies on `get_main_name()` being the main name of the protocol
 		// for peersets and connection management.
 		let protocol = peerset_protocol_names.get_main_name(self);
-		let fallback_names = PeerSetProtocolNames::get_fallback_names(self);
+		let fallback_names = peerset_protocol_names.get_fallback_names(self);
 		let max_notification_size = self.get_max_notification_size(is_authority);
 
 		match self {
@@ -118,11 +121,19 @@ impl PeerSet {
 	/// of the main protocol name reported by [`PeerSetProtocolNames::get_main_name()`].
 	pub fn get_main_version(self) -> ProtocolVersion {
 		match self {
-			PeerSet::Validation => ValidationVersion::V1.into(),
+			PeerSet::Validation => ValidationVersion::V2.into(),
 			PeerSet::Collation => CollationVersion::V1.into(),
 		}
 	}
 
+	/// Get all protocol versions of this peer set, from the newest to the oldest.
+	fn get_versions(self) -> Vec<ProtocolVersion> {
+		match self {
+			PeerSet::Validation => ValidationVersion::iter().rev().map(Into::into).collect(),
+			PeerSet::Collation => CollationVersion::iter().rev().map(Into::into).collect(),
+		}
+	}
+
 	/// Get the max notification size for this peer set.
 	pub fn get_max_notification_size(self, _: IsAuthority) -> u64 {
 		MAX_NOTIFICATION_SIZE
@@ -144,6 +155,8 @@ impl PeerSet {
 			PeerSet::Validation =>
 				if version == ValidationVersion::V1.into() {
 					Some("validation/1")
+				} else if version == ValidationVersion::V2.into() {
+					Some("validation/2")
 				} else {
 					None
 				},
@@ -211,6 +224,8 @@ impl From<ProtocolVersion> for u32 {
 pub enum ValidationVersion {
 	/// The first version.
 	V1 = 1,
+	/// The second version, which allows assignments to claim several candidates at once.
+	V2 = 2,
 }
 
 /// Supported collation protocol versions. Only versions defined here must be used in the codebase.
@@ -237,6 +252,8 @@ impl From<CollationVersion> for ProtocolVersion {
 pub struct PeerSetProtocolNames {
 	protocols: HashMap<ProtocolName, (PeerSet, ProtocolVersion)>,
 	names: HashMap<(PeerSet, ProtocolVersion), ProtocolName>,
+	compressed_names: HashMap<(PeerSet, ProtocolVersion), ProtocolName>,
+	notification_compression: bool,
 }
 
 impl PeerSetProtocolNames {
@@ -244,34 +261,39 @@ impl PeerSetProtocolNames {
 	pub fn new(genesis_hash: Hash, fork_id: Option<&str>) -> Self {
 		let mut protocols = HashMap::new();
 		let mut names = HashMap::new();
+		let mut compressed_names = HashMap::new();
 		for protocol in PeerSet::iter() {
-			match protocol {
-				PeerSet::Validation =>
-					for version in ValidationVersion::iter() {
-						Self::register_main_protocol(
-							&mut protocols,
-							&mut names,
-							protocol,
-							version.into(),
-							&genesis_hash,
-							fork_id,
-						);
-					},
-				PeerSet::Collation =>
-					for version in CollationVersion::iter() {
-						Self::register_main_protocol(
-							&mut protocols,
-							&mut names,
-							protocol,
-							version.into(),
-							&genesis_hash,
-							fork_id,
-						);
-					},
+			for version in protocol.get_versions() {
+				Self::register_main_protocol(
+					&mut protocols,
+					&mut names,
+					protocol,
+					version,
+					&genesis_hash,
+					fork_id,
+				);
+				Self::register_compressed_protocol(
+					&mut protocols,
+					&mut compressed_names,
+					protocol,
+					version,
+					&genesis_hash,
+					fork_id,
+				);
 			}
 			Self::register_legacy_protocol(&mut protocols, protocol);
 		}
-		Self { protocols, names }
+		Self { protocols, names, compressed_names, notification_compression: false }
+	}
+
+	/// Enable or disable compression of notifications.
+	///
+	/// If enabled, the compressed variant of the main protocol becomes the main name of the peer
+	/// set, with the uncompressed one as fallback. Peers negotiating the compressed protocol may
+	/// be sent compressed notifications, all other peers keep receiving uncompressed ones.
+	pub fn with_notification_compression(mut self, enabled: bool) -> Self {
+		self.notification_compression = enabled;
+		self
 	}
 
 	/// Helper function to register main protocol.
@@ -301,6 +323,21 @@ impl PeerSetProtocolNames {
 		)
 	}
 
+	/// Helper function to register the compressed variant of a protocol version.
+	fn register_compressed_protocol(
+		protocols: &mut HashMap<ProtocolName, (PeerSet, ProtocolVersion)>,
+		compressed_names: &mut HashMap<(PeerSet, ProtocolVersion), ProtocolName>,
+		protocol: PeerSet,
+		version: ProtocolVersion,
+		genesis_hash: &Hash,
+		fork_id: Option<&str>,
+	) {
+		let protocol_name =
+			Self::generate_compressed_name(genesis_hash, fork_id, protocol, version);
+		compressed_names.insert((protocol, version), protocol_name.clone());
+		Self::insert_protocol_or_panic(protocols, protocol_name, protocol, version);
+	}
+
 	/// Helper function to make sure no protocols have the same name.
 	fn insert_protocol_or_panic(
 		protocols: &mut HashMap<ProtocolName, (PeerSet, ProtocolVersion)>,
@@ -337,13 +374,40 @@ impl PeerSetProtocolNames {
 	}
 
 	/// Get the protocol name for specific version.
+	///
+	/// For the main version this is the name of the compressed protocol if notification
+	/// compression is enabled.
 	pub fn get_name(&self, protocol: PeerSet, version: ProtocolVersion) -> ProtocolName {
+		if self.notification_compression && version == protocol.get_main_version() {
+			return self.get_compressed_name(protocol, version)
+		}
+
+		self.get_uncompressed_name(protocol, version)
+	}
+
+	/// Get the protocol name for specific version, ignoring notification compression.
+	fn get_uncompressed_name(&self, protocol: PeerSet, version: ProtocolVersion) -> ProtocolName {
 		self.names
 			.get(&(protocol, version))
 			.expect("Protocols & versions are specified via enums defined above, and they are all registered in `new()`; qed")
 			.clone()
 	}
 
+	/// Get the name of the protocol on which notifications may be sent compressed.
+	fn get_compressed_name(&self, protocol: PeerSet, version: ProtocolVersion) -> ProtocolName {
+		self.compressed_names
+			.get(&(protocol, version))
+			.expect(
+				"Compressed protocols are registered for all peer sets & versions in `new()`; qed",
+			)
+			.clone()
+	}
+
+	/// Whether notifications on the given protocol may be sent compressed.
+	pub fn is_compressed(&self, name: &ProtocolName) -> bool {
+		self.compressed_names.values().any(|compressed| compressed == name)
+	}
+
 	/// The protocol name of this protocol based on `genesis_hash` and `fork_id`.
 	fn generate_name(
 		genesis_hash: &Hash,
@@ -365,6 +429,17 @@ impl PeerSetProtocolNames {
 		format!("{}/{}/{}", prefix, short_name, version).into()
 	}
 
+	/// The name of the compressed variant of a protocol based on `genesis_hash` and `fork_id`.
+	fn generate_compressed_name(
+		genesis_hash: &Hash,
+		fork_id: Option<&str>,
+		protocol: PeerSet,
+		version: ProtocolVersion,
+	) -> ProtocolName {
+		let name = Self::generate_name(genesis_hash, fork_id, protocol, version);
+		format!("{}/{}", name, COMPRESSED_PROTOCOL_SUFFIX).into()
+	}
+
 	/// Get the legacy protocol name, only `LEGACY_PROTOCOL_VERSION` = 1 is supported.
 	fn get_legacy_name(protocol: PeerSet) -> ProtocolName {
 		match protocol {
@@ -374,17 +449,34 @@ impl PeerSetProtocolNames {
 		.into()
 	}
 
-	/// Get the protocol fallback names. Currently only holds the legacy name
-	/// for `LEGACY_PROTOCOL_VERSION` = 1.
-	fn get_fallback_names(protocol: PeerSet) -> Vec<ProtocolName> {
-		std::iter::once(Self::get_legacy_name(protocol)).collect()
+	/// Get the protocol fallback names, from the newest version to the oldest. If notification
+	/// compression is enabled, each version is offered compressed first. Ends with the legacy
+	/// name for `LEGACY_PROTOCOL_VERSION` = 1.
+	fn get_fallback_names(&self, protocol: PeerSet) -> Vec<ProtocolName> {
+		let main_name = self.get_main_name(protocol);
+
+		protocol
+			.get_versions()
+			.into_iter()
+			.flat_map(|version| {
+				let compressed = self
+					.notification_compression
+					.then(|| self.get_compressed_name(protocol, version));
+				compressed
+					.into_iter()
+					.chain(std::iter::once(self.get_uncompressed_name(protocol, version)))
+			})
+			.filter(|name| name != &main_name)
+			.chain(std::iter::once(Self::get_legacy_name(protocol)))
+			.collect()
 	}
 }
 
 #[cfg(test)]
 mod tests {
 	use super::{
-		CollationVersion, Hash, PeerSet, PeerSetProtocolNames, ProtocolVersion, ValidationVersion,
+		CollationVersion, Hash, IsAuthority, PeerSet, PeerSetProtocolNames, ProtocolVersion,
+		ValidationVersion,
 	};
 	use strum::IntoEnumIterator;
 
@@ -453,9 +545,16 @@ mod tests {
 		let protocol_names = PeerSetProtocolNames::new(genesis_hash, None);
 
 		let validation_main =
-			"/7ac8741de8b7146d8a5617fd462914557fe63c265a7f1c10e7dae32858eebb80/validation/1";
+			"/7ac8741de8b7146d8a5617fd462914557fe63c265a7f1c10e7dae32858eebb80/validation/2";
 		assert_eq!(
 			protocol_names.try_get_protocol(&validation_main.into()),
+			Some((PeerSet::Validation, TestVersion(2).into())),
+		);
+
+		let validation_v1 =
+			"/7ac8741de8b7146d8a5617fd462914557fe63c265a7f1c10e7dae32858eebb80/validation/1";
+		assert_eq!(
+			protocol_names.try_get_protocol(&validation_v1.into()),
 			Some((PeerSet::Validation, TestVersion(1).into())),
 		);
 
@@ -479,6 +578,58 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn compressed_protocols_are_negotiated_with_fallback() {
+		let genesis_hash = Hash::from([
+			122, 200, 116, 29, 232, 183, 20, 109, 138, 86, 23, 253, 70, 41, 20, 85, 127, 230, 60,
+			38, 90, 127, 28, 16, 231, 218, 227, 40, 88, 238, 187, 128,
+		]);
+		let validation_main =
+			"/7ac8741de8b7146d8a5617fd462914557fe63c265a7f1c10e7dae32858eebb80/validation/2";
+		let validation_compressed =
+			"/7ac8741de8b7146d8a5617fd462914557fe63c265a7f1c10e7dae32858eebb80/validation/2/zstd";
+		let validation_v1 =
+			"/7ac8741de8b7146d8a5617fd462914557fe63c265a7f1c10e7dae32858eebb80/validation/1";
+		let validation_v1_compressed =
+			"/7ac8741de8b7146d8a5617fd462914557fe63c265a7f1c10e7dae32858eebb80/validation/1/zstd";
+
+		let protocol_names = PeerSetProtocolNames::new(genesis_hash, None);
+		assert_eq!(protocol_names.get_main_name(PeerSet::Validation), validation_main.into());
+		assert_eq!(
+			protocol_names.try_get_protocol(&validation_compressed.into()),
+			Some((PeerSet::Validation, TestVersion(2).into())),
+		);
+		assert_eq!(
+			protocol_names.try_get_protocol(&validation_v1_compressed.into()),
+			Some((PeerSet::Validation, TestVersion(1).into())),
+		);
+		assert!(protocol_names.is_compressed(&validation_compressed.into()));
+		assert!(protocol_names.is_compressed(&validation_v1_compressed.into()));
+		assert!(!protocol_names.is_compressed(&validation_main.into()));
+
+		let info = PeerSet::Validation.get_info(IsAuthority::Yes, &protocol_names);
+		assert_eq!(info.notifications_protocol, validation_main.into());
+		assert_eq!(
+			info.fallback_names,
+			vec![validation_v1.into(), "/polkadot/validation/1".into()],
+		);
+
+		let protocol_names = protocol_names.with_notification_compression(true);
+		assert_eq!(protocol_names.get_main_name(PeerSet::Validation), validation_compressed.into());
+
+		let info = PeerSet::Validation.get_info(IsAuthority::Yes, &protocol_names);
+		assert_eq!(info.notifications_protocol, validation_compressed.into());
+		assert_eq!(
+			info.fallback_names,
+			vec![
+				validation_main.into(),
+				validation_v1_compressed.into(),
+				validation_v1.into(),
+				"/polkadot/validation/1".into(),
+			],
+		);
+	}
+
 	#[test]
 	fn all_protocol_versions_are_registered() {
 		let genesis_hash = Hash::from([
@@ -30,7 +30,13 @@
 //! `trait IsRequest` .... A trait describing a particular request. It is used for gathering meta
 //! data, like what is the corresponding response type.
 //!
-//!  Versioned (v1 module): The actual requests and responses as sent over the network.
+//! `trait HasFallback` .... A request that has an older version, which gets requested instead if
+//! the peer does not support the newer protocol yet.
+//!
+//! `struct PeerPerformance` .... Tracks response times and throughput of peers, for adapting
+//! timeouts and choosing whom to ask first.
+//!
+//!  Versioned (v1, v2 modules): The actual requests and responses as sent over the network.
 
 use std::{collections::HashMap, time::Duration, u64};
 
@@ -47,7 +53,14 @@ pub mod outgoing;
 
 pub use incoming::{IncomingRequest, IncomingRequestReceiver};
 
-pub use outgoing::{OutgoingRequest, OutgoingResult, Recipient, Requests, ResponseSender};
+pub use outgoing::{
+	OutgoingRequest, OutgoingResult, PendingFallback, Recipient, Requests, ResponseSender,
+};
+
+/// Tracking of the request/response performance of peers, for adaptive timeouts and peer ranking.
+pub mod peer_performance;
+
+pub use peer_performance::{PeerPerformance, PerformanceEstimate};
 
 ///// Multiplexer for incoming requests.
 // pub mod multiplexer;
@@ -55,12 +68,18 @@ pub use outgoing::{OutgoingRequest, OutgoingResult, Recipient, Requests, Respons
 /// Actual versioned requests and responses, that are sent over the wire.
 pub mod v1;
 
+/// Version 2 of requests and responses, for protocols which got a new version.
+pub mod v2;
+
 /// A protocol per subsystem seems to make the most sense, this way we don't need any dispatching
 /// within protocols.
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, EnumIter)]
 pub enum Protocol {
 	/// Protocol for chunk fetching, used by availability distribution and availability recovery.
 	ChunkFetchingV1,
+	/// Protocol for chunk fetching which also returns the index of the chunk, falls back to
+	/// `ChunkFetchingV1` for peers not supporting it.
+	ChunkFetchingV2,
 	/// Protocol for fetching collations from collators.
 	CollationFetchingV1,
 	/// Protocol for fetching seconded PoVs from validators of the same group.
@@ -158,7 +177,7 @@ impl Protocol {
 		let name = req_protocol_names.get_name(self);
 		let fallback_names = self.get_fallback_names();
 		match self {
-			Protocol::ChunkFetchingV1 => RequestResponseConfig {
+			Protocol::ChunkFetchingV1 | Protocol::ChunkFetchingV2 => RequestResponseConfig {
 				name,
 				fallback_names,
 				max_request_size: 1_000,
@@ -232,7 +251,7 @@ impl Protocol {
 			// times (due to network delays), 100 seems big enough to accomodate for "bursts",
 			// assuming we can service requests relatively quickly, which would need to be measured
 			// as well.
-			Protocol::ChunkFetchingV1 => 100,
+			Protocol::ChunkFetchingV1 | Protocol::ChunkFetchingV2 => 100,
 			// 10 seems reasonable, considering group sizes of max 10 validators.
 			Protocol::CollationFetchingV1 => 10,
 			// 10 seems reasonable, considering group sizes of max 10 validators.
@@ -270,18 +289,21 @@ impl Protocol {
 
 	/// Fallback protocol names of this protocol, as understood by substrate networking.
 	fn get_fallback_names(self) -> Vec<ProtocolName> {
-		std::iter::once(self.get_legacy_name().into()).collect()
+		self.get_legacy_name().into_iter().map(Into::into).collect()
 	}
 
 	/// Legacy protocol name associated with each peer set.
-	const fn get_legacy_name(self) -> &'static str {
+	///
+	/// Protocols introduced after the switch to genesis hash based names have none.
+	const fn get_legacy_name(self) -> Option<&'static str> {
 		match self {
-			Protocol::ChunkFetchingV1 => "/polkadot/req_chunk/1",
-			Protocol::CollationFetchingV1 => "/polkadot/req_collation/1",
-			Protocol::PoVFetchingV1 => "/polkadot/req_pov/1",
-			Protocol::AvailableDataFetchingV1 => "/polkadot/req_available_data/1",
-			Protocol::StatementFetchingV1 => "/polkadot/req_statement/1",
-			Protocol::DisputeSendingV1 => "/polkadot/send_dispute/1",
+			Protocol::ChunkFetchingV1 => Some("/polkadot/req_chunk/1"),
+			Protocol::CollationFetchingV1 => Some("/polkadot/req_collation/1"),
+			Protocol::PoVFetchingV1 => Some("/polkadot/req_pov/1"),
+			Protocol::AvailableDataFetchingV1 => Some("/polkadot/req_available_data/1"),
+			Protocol::StatementFetchingV1 => Some("/polkadot/req_statement/1"),
+			Protocol::DisputeSendingV1 => Some("/polkadot/send_dispute/1"),
+			Protocol::ChunkFetchingV2 => None,
 		}
 	}
 }
@@ -295,7 +317,28 @@ pub trait IsRequest {
 	const PROTOCOL: Protocol;
 }
 
+/// A request with an older version, to be used with peers not supporting the request's protocol.
+///
+/// Requests implementing this can be sent with [`OutgoingRequest::new_with_fallback`]: the newer
+/// version is tried first and the fallback is only requested if the peer turns out not to support
+/// the newer protocol. Versions can be chained, by implementing this for the fallback as well.
+pub trait HasFallback: IsRequest {
+	/// The request of the older protocol version.
+	type Fallback: IsRequest;
+
+	/// Get the equivalent request for the older protocol version.
+	fn fallback(&self) -> Self::Fallback;
+
+	/// Convert a response received via the older protocol version into a response of this
+	/// version.
+	fn response_from_fallback(
+		fallback: Self::Fallback,
+		response: <Self::Fallback as IsRequest>::Response,
+	) -> Self::Response;
+}
+
 /// Type for getting on the wire [`Protocol`] names using genesis hash & fork id.
+#[derive(Clone)]
 pub struct ReqProtocolNames {
 	names: HashMap<Protocol, ProtocolName>,
 }
@@ -332,6 +375,7 @@ impl ReqProtocolNames {
 
 		let short_name = match protocol {
 			Protocol::ChunkFetchingV1 => "/req_chunk/1",
+			Protocol::ChunkFetchingV2 => "/req_chunk/2",
 			Protocol::CollationFetchingV1 => "/req_collation/1",
 			Protocol::PoVFetchingV1 => "/req_pov/1",
 			Protocol::AvailableDataFetchingV1 => "/req_available_data/1",
@@ -23,13 +23,16 @@ use sc_network::PeerId;
 
 use polkadot_primitives::AuthorityDiscoveryId;
 
-use super::{v1, IsRequest, Protocol};
+use super::{v1, v2, HasFallback, IsRequest, Protocol};
 
 /// All requests that can be sent to the network bridge via `NetworkBridgeTxMessage::SendRequest`.
 #[derive(Debug)]
 pub enum Requests {
 	/// Request an availability chunk from a node.
 	ChunkFetchingV1(OutgoingRequest<v1::ChunkFetchingRequest>),
+	/// Request an availability chunk from a node, falling back to `ChunkFetchingV1` for nodes
+	/// which don't support `ChunkFetchingV2`.
+	ChunkFetchingV2(OutgoingRequest<v2::ChunkFetchingRequest, v1::ChunkFetchingRequest>),
 	/// Fetch a collation from a collator which previously announced it.
 	CollationFetchingV1(OutgoingRequest<v1::CollationFetchingRequest>),
 	/// Fetch a PoV from a validator which previously sent out a seconded statement.
@@ -47,6 +50,7 @@ impl Requests {
 	pub fn get_protocol(&self) -> Protocol {
 		match self {
 			Self::ChunkFetchingV1(_) => Protocol::ChunkFetchingV1,
+			Self::ChunkFetchingV2(_) => Protocol::ChunkFetchingV2,
 			Self::CollationFetchingV1(_) => Protocol::CollationFetchingV1,
 			Self::PoVFetchingV1(_) => Protocol::PoVFetchingV1,
 			Self::AvailableDataFetchingV1(_) => Protocol::AvailableDataFetchingV1,
@@ -65,6 +69,7 @@ impl Requests {
 	pub fn encode_request(self) -> (Protocol, OutgoingRequest<Vec<u8>>) {
 		match self {
 			Self::ChunkFetchingV1(r) => r.encode_request(),
+			Self::ChunkFetchingV2(r) => r.encode_request(),
 			Self::CollationFetchingV1(r) => r.encode_request(),
 			Self::PoVFetchingV1(r) => r.encode_request(),
 			Self::AvailableDataFetchingV1(r) => r.encode_request(),
@@ -72,6 +77,54 @@ impl Requests {
 			Self::DisputeSendingV1(r) => r.encode_request(),
 		}
 	}
+
+	/// Take out the fallback of this request, if it has one.
+	///
+	/// The response sender of the request gets replaced, so the returned [`PendingFallback`] sees
+	/// the response first and can tell whether the fallback request needs to be sent.
+	pub fn take_fallback(&mut self) -> Option<PendingFallback> {
+		match self {
+			Self::ChunkFetchingV2(r) => r.take_fallback(Self::ChunkFetchingV1),
+			Self::ChunkFetchingV1(_) |
+			Self::CollationFetchingV1(_) |
+			Self::PoVFetchingV1(_) |
+			Self::AvailableDataFetchingV1(_) |
+			Self::StatementFetchingV1(_) |
+			Self::DisputeSendingV1(_) => None,
+		}
+	}
+}
+
+/// A fallback request, waiting for the response to the request it is the fallback of.
+#[derive(Debug)]
+pub struct PendingFallback {
+	/// Receiver of the response to the original request.
+	response: oneshot::Receiver<Result<Vec<u8>, network::RequestFailure>>,
+	/// The requester's sender for the response to the original request.
+	pending_response: ResponseSender,
+	/// The fallback request.
+	request: Requests,
+}
+
+impl PendingFallback {
+	/// Wait for the response to the original request and forward it to the requester.
+	///
+	/// Returns the fallback request if it needs to be sent, because the peer did not support the
+	/// protocol of the original request.
+	pub async fn wait(self) -> Option<Requests> {
+		let PendingFallback { response, pending_response, request } = self;
+		// If networking dropped the sender, dropping ours will let the requester know.
+		let response = response.await.ok()?;
+
+		let unsupported = matches!(
+			response,
+			Err(network::RequestFailure::Network(network::OutboundFailure::UnsupportedProtocols))
+		);
+		// Requester might no longer be interested.
+		let _ = pending_response.send(response);
+
+		unsupported.then_some(request)
+	}
 }
 
 /// Used by the network to send us a response to a request.
@@ -118,11 +171,14 @@ impl RequestError {
 /// When using `Recipient::Authority`, the addresses can be found thanks to the authority
 /// discovery system.
 #[derive(Debug)]
-pub struct OutgoingRequest<Req> {
+pub struct OutgoingRequest<Req, FallbackReq = Req> {
 	/// Intended recipient of this request.
 	pub peer: Recipient,
 	/// The actual request to send over the wire.
 	pub payload: Req,
+	/// Request for an older version of the protocol, to be sent instead if the peer does not
+	/// support the protocol of `payload`, together with the sender for its response.
+	pub fallback_request: Option<(FallbackReq, ResponseSender)>,
 	/// Sender which is used by networking to get us back a response.
 	pub pending_response: ResponseSender,
 }
@@ -153,19 +209,75 @@ where
 		payload: Req,
 	) -> (Self, impl Future<Output = OutgoingResult<Req::Response>>) {
 		let (tx, rx) = oneshot::channel();
-		let r = Self { peer, payload, pending_response: tx };
+		let r = Self { peer, payload, fallback_request: None, pending_response: tx };
 		(r, receive_response::<Req>(rx))
 	}
+}
 
+impl<Req> OutgoingRequest<Req, Req::Fallback>
+where
+	Req: HasFallback + Encode,
+	Req::Response: Decode,
+	<Req::Fallback as IsRequest>::Response: Decode,
+{
+	/// Create a new `OutgoingRequest`, which falls back to the older version of the request if the
+	/// peer does not support the newer protocol.
+	///
+	/// Responses received via the older protocol are converted, so the returned future always
+	/// resolves to a response of the newer version.
+	pub fn new_with_fallback(
+		peer: Recipient,
+		payload: Req,
+	) -> (Self, impl Future<Output = OutgoingResult<Req::Response>>) {
+		let (tx, rx) = oneshot::channel();
+		let (fallback_tx, fallback_rx) = oneshot::channel();
+		let fallback = payload.fallback();
+		let r = Self {
+			peer,
+			fallback_request: Some((payload.fallback(), fallback_tx)),
+			payload,
+			pending_response: tx,
+		};
+		(r, receive_response_with_fallback::<Req>(rx, fallback_rx, fallback))
+	}
+}
+
+impl<Req, FallbackReq> OutgoingRequest<Req, FallbackReq>
+where
+	Req: IsRequest + Encode,
+{
 	/// Encode a request into a `Vec<u8>`.
 	///
 	/// As this throws away type information, we also return the `Protocol` this encoded request
-	/// adheres to.
+	/// adheres to. A fallback request still contained is dropped, see
+	/// [`Requests::take_fallback`].
 	pub fn encode_request(self) -> (Protocol, OutgoingRequest<Vec<u8>>) {
-		let OutgoingRequest { peer, payload, pending_response } = self;
-		let encoded = OutgoingRequest { peer, payload: payload.encode(), pending_response };
+		let OutgoingRequest { peer, payload, fallback_request: _, pending_response } = self;
+		let encoded = OutgoingRequest {
+			peer,
+			payload: payload.encode(),
+			fallback_request: None,
+			pending_response,
+		};
 		(Req::PROTOCOL, encoded)
 	}
+
+	/// Take out the fallback request, replacing our response sender.
+	fn take_fallback(
+		&mut self,
+		into_requests: impl FnOnce(OutgoingRequest<FallbackReq>) -> Requests,
+	) -> Option<PendingFallback> {
+		let (payload, fallback_response) = self.fallback_request.take()?;
+		let (tx, rx) = oneshot::channel();
+		let pending_response = std::mem::replace(&mut self.pending_response, tx);
+		let request = into_requests(OutgoingRequest {
+			peer: self.peer.clone(),
+			payload,
+			fallback_request: None,
+			pending_response: fallback_response,
+		});
+		Some(PendingFallback { response: rx, pending_response, request })
+	}
 }
 
 /// Future for actually receiving a typed response for an `OutgoingRequest`.
@@ -179,3 +291,96 @@ where
 	let raw = rec.await??;
 	Ok(Decode::decode(&mut raw.as_ref())?)
 }
+
+/// Future for receiving a typed response for an `OutgoingRequest` with a fallback.
+///
+/// If the peer did not support the protocol of the request, the response arrives via the fallback
+/// request instead and gets converted.
+async fn receive_response_with_fallback<Req>(
+	rec: oneshot::Receiver<Result<Vec<u8>, network::RequestFailure>>,
+	fallback_rec: oneshot::Receiver<Result<Vec<u8>, network::RequestFailure>>,
+	fallback: Req::Fallback,
+) -> OutgoingResult<Req::Response>
+where
+	Req: HasFallback,
+	Req::Response: Decode,
+	<Req::Fallback as IsRequest>::Response: Decode,
+{
+	match rec.await? {
+		Err(network::RequestFailure::Network(network::OutboundFailure::UnsupportedProtocols)) => {
+			let raw = fallback_rec.await??;
+			let response = Decode::decode(&mut raw.as_ref())?;
+			Ok(Req::response_from_fallback(fallback, response))
+		},
+		response => Ok(Decode::decode(&mut response?.as_ref())?),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use futures::executor::block_on;
+	use polkadot_node_primitives::{ErasureChunk, Proof};
+	use polkadot_primitives::{CandidateHash, ValidatorIndex};
+
+	fn chunk_request() -> (Requests, impl Future<Output = OutgoingResult<v2::ChunkFetchingResponse>>)
+	{
+		let (req, response) = OutgoingRequest::new_with_fallback(
+			Recipient::Peer(PeerId::random()),
+			v2::ChunkFetchingRequest {
+				candidate_hash: CandidateHash::default(),
+				index: ValidatorIndex(3),
+			},
+		);
+		(Requests::ChunkFetchingV2(req), response)
+	}
+
+	#[test]
+	fn fallback_is_not_sent_if_protocol_is_supported() {
+		let (mut req, response) = chunk_request();
+		let fallback = req.take_fallback().expect("`ChunkFetchingV2` has a fallback");
+
+		let (_, OutgoingRequest { pending_response, .. }) = req.encode_request();
+		let chunk = ErasureChunk {
+			chunk: vec![1, 2, 3],
+			index: ValidatorIndex(5),
+			proof: Proof::dummy_proof(),
+		};
+		pending_response
+			.send(Ok(v2::ChunkFetchingResponse::Chunk(chunk.clone()).encode()))
+			.unwrap();
+
+		assert!(block_on(fallback.wait()).is_none());
+		assert!(matches!(
+			block_on(response),
+			Ok(v2::ChunkFetchingResponse::Chunk(c)) if c == chunk
+		));
+	}
+
+	#[test]
+	fn fallback_is_sent_if_protocol_is_not_supported() {
+		let (mut req, response) = chunk_request();
+		let fallback = req.take_fallback().expect("`ChunkFetchingV2` has a fallback");
+
+		let (_, OutgoingRequest { pending_response, .. }) = req.encode_request();
+		pending_response
+			.send(Err(network::RequestFailure::Network(
+				network::OutboundFailure::UnsupportedProtocols,
+			)))
+			.unwrap();
+
+		let fallback_req = block_on(fallback.wait()).expect("Fallback needs to be sent");
+		assert_eq!(fallback_req.get_protocol(), Protocol::ChunkFetchingV1);
+		let (_, OutgoingRequest { pending_response, .. }) = fallback_req.encode_request();
+		let chunk = v1::ChunkResponse { chunk: vec![1, 2, 3], proof: Proof::dummy_proof() };
+		pending_response
+			.send(Ok(v1::ChunkFetchingResponse::Chunk(chunk).encode()))
+			.unwrap();
+
+		// The index is taken from the request.
+		assert!(matches!(
+			block_on(response),
+			Ok(v2::ChunkFetchingResponse::Chunk(c)) if c.index == ValidatorIndex(3)
+		));
+	}
+}
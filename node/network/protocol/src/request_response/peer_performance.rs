@@ -0,0 +1,314 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tracking of the observed request/response performance of peers.
+//!
+//! `PeerPerformance` keeps an exponentially weighted moving average of the response time, the
+//! throughput and the failure rate of each peer we sent requests to. Based on these estimates it
+//! derives request timeouts adapted to the individual peer and a preference order for choosing
+//! whom to ask first.
+
+use std::{collections::HashMap, hash::Hash, num::NonZeroUsize, sync::Arc, time::Duration};
+
+use lru::LruCache;
+use parking_lot::Mutex;
+
+use super::DEFAULT_REQUEST_TIMEOUT_CONNECTED;
+
+/// Weight of a new observation in the moving averages.
+///
+/// With a weight of 0.2 an observation has lost most of its influence after about ten further
+/// observations, so estimates follow changes in network conditions reasonably fast without
+/// jumping around on every single outlier.
+const EWMA_WEIGHT: f64 = 0.2;
+
+/// Factor applied to the expected duration of a request when deriving its timeout.
+///
+/// Response times vary quite a bit even for well behaving peers, we only want to time out on peers
+/// which are considerably slower than they used to be.
+const TIMEOUT_MARGIN: u32 = 3;
+
+/// How much a failure rate of 100% inflates the expected duration of a request when ranking peers.
+const FAILURE_PENALTY: f64 = 10.0;
+
+/// Lower bound of the timeouts handed out by a `PeerPerformance` created with `Default`.
+const DEFAULT_MIN_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Maximum number of peers we keep estimates for.
+///
+/// This is comfortably above the number of validators in any session, peers not heard of for a
+/// long time get evicted first.
+pub const MAX_TRACKED_PEERS: NonZeroUsize = match NonZeroUsize::new(2000) {
+	Some(cap) => cap,
+	None => panic!("Number of tracked peers must be non-zero."),
+};
+
+/// Estimated performance of a single peer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PerformanceEstimate {
+	/// Average time it took the peer to answer our requests.
+	pub response_time: Duration,
+	/// Average throughput of responses in bytes per second, if any non empty response has been
+	/// received yet.
+	pub throughput: Option<f64>,
+	/// Fraction of requests which failed, between 0 and 1.
+	pub failure_rate: f64,
+}
+
+impl PerformanceEstimate {
+	/// Expected duration of a request with a response of `expected_bytes` bytes.
+	///
+	/// The response time alone is used if `expected_bytes` is zero or if we don't know the
+	/// throughput of the peer yet.
+	pub fn expected_duration(&self, expected_bytes: usize) -> Duration {
+		match self.throughput {
+			Some(throughput) if expected_bytes > 0 && throughput > 0.0 => std::cmp::max(
+				self.response_time,
+				Duration::from_secs_f64(expected_bytes as f64 / throughput),
+			),
+			_ => self.response_time,
+		}
+	}
+
+	/// Score used for ranking peers, lower is better.
+	fn score(&self, expected_bytes: usize) -> f64 {
+		self.expected_duration(expected_bytes).as_secs_f64() *
+			(1.0 + FAILURE_PENALTY * self.failure_rate)
+	}
+}
+
+/// Shared tracker of the request/response performance of peers.
+///
+/// Cloning is cheap, all clones refer to the same estimates. This way recovery tasks or other
+/// spawned request handling code can update the estimates of the subsystem they belong to.
+#[derive(Clone)]
+pub struct PeerPerformance<P: Hash + Eq> {
+	inner: Arc<Mutex<Inner<P>>>,
+}
+
+struct Inner<P: Hash + Eq> {
+	estimates: LruCache<P, PerformanceEstimate>,
+	min_timeout: Duration,
+	max_timeout: Duration,
+}
+
+impl<P: Hash + Eq + Clone> PeerPerformance<P> {
+	/// Create a new tracker handing out timeouts between `min_timeout` and `max_timeout`.
+	///
+	/// Peers we don't know anything about yet get `max_timeout`.
+	pub fn new(min_timeout: Duration, max_timeout: Duration) -> Self {
+		Self {
+			inner: Arc::new(Mutex::new(Inner {
+				estimates: LruCache::new(MAX_TRACKED_PEERS),
+				min_timeout,
+				max_timeout: std::cmp::max(min_timeout, max_timeout),
+			})),
+		}
+	}
+
+	/// Note a successful response of `bytes` bytes, which took `elapsed` from sending the request.
+	///
+	/// Returns the updated estimate.
+	pub fn note_response(&self, peer: &P, elapsed: Duration, bytes: usize) -> PerformanceEstimate {
+		let throughput =
+			(bytes > 0 && !elapsed.is_zero()).then(|| bytes as f64 / elapsed.as_secs_f64());
+		self.update(peer, |estimate| match estimate {
+			Some(estimate) => PerformanceEstimate {
+				response_time: ewma_duration(estimate.response_time, elapsed),
+				throughput: match (estimate.throughput, throughput) {
+					(Some(old), Some(new)) => Some(ewma(old, new)),
+					(old, new) => new.or(old),
+				},
+				failure_rate: ewma(estimate.failure_rate, 0.0),
+			},
+			None => PerformanceEstimate { response_time: elapsed, throughput, failure_rate: 0.0 },
+		})
+	}
+
+	/// Note a failed request, which did not result in a usable response.
+	///
+	/// This includes timeouts. Returns the updated estimate.
+	pub fn note_failure(&self, peer: &P) -> PerformanceEstimate {
+		let max_timeout = self.inner.lock().max_timeout;
+		self.update(peer, |estimate| match estimate {
+			Some(estimate) =>
+				PerformanceEstimate { failure_rate: ewma(estimate.failure_rate, 1.0), ..estimate },
+			// We have no idea about the response time, so we assume the worst.
+			None => PerformanceEstimate {
+				response_time: max_timeout,
+				throughput: None,
+				failure_rate: 1.0,
+			},
+		})
+	}
+
+	/// Get the current estimate for the given peer, if any.
+	pub fn estimate(&self, peer: &P) -> Option<PerformanceEstimate> {
+		self.inner.lock().estimates.peek(peer).copied()
+	}
+
+	/// Get the estimates of all tracked peers.
+	pub fn estimates(&self) -> HashMap<P, PerformanceEstimate> {
+		self.inner.lock().estimates.iter().map(|(p, e)| (p.clone(), *e)).collect()
+	}
+
+	/// Timeout for a request to the given peer with a response of about `expected_bytes` bytes.
+	///
+	/// Pass zero, if the size of the response is not known.
+	pub fn timeout(&self, peer: &P, expected_bytes: usize) -> Duration {
+		let inner = self.inner.lock();
+		match inner.estimates.peek(peer) {
+			Some(estimate) => (estimate.expected_duration(expected_bytes) * TIMEOUT_MARGIN)
+				.clamp(inner.min_timeout, inner.max_timeout),
+			None => inner.max_timeout,
+		}
+	}
+
+	/// Sort `items` by the preference of the peers they map to, best peers first.
+	///
+	/// Peers we don't know yet are ranked like the median of the known ones, so they get a chance
+	/// of proving themselves, without being preferred over peers known to perform well. The sort is
+	/// stable, so the existing order of equally ranked items (e.g. a random shuffling) is kept.
+	pub fn sort_by_preference<T>(
+		&self,
+		items: &mut [T],
+		expected_bytes: usize,
+		peer_of: impl Fn(&T) -> P,
+	) {
+		let inner = self.inner.lock();
+		let known_score = |item: &T| {
+			inner
+				.estimates
+				.peek(&peer_of(item))
+				.map(|estimate| estimate.score(expected_bytes))
+		};
+
+		let mut known_scores: Vec<f64> =
+			items.iter().filter_map(|item| known_score(item)).collect();
+		if known_scores.is_empty() {
+			return
+		}
+		known_scores.sort_by(f64::total_cmp);
+		let median = known_scores[known_scores.len() / 2];
+
+		// Scores are never negative, so their bit patterns are ordered just like the scores.
+		items.sort_by_cached_key(|item| known_score(item).unwrap_or(median).to_bits());
+	}
+
+	fn update(
+		&self,
+		peer: &P,
+		f: impl FnOnce(Option<PerformanceEstimate>) -> PerformanceEstimate,
+	) -> PerformanceEstimate {
+		let mut inner = self.inner.lock();
+		let estimate = f(inner.estimates.get(peer).copied());
+		inner.estimates.put(peer.clone(), estimate);
+		estimate
+	}
+}
+
+impl<P: Hash + Eq + Clone> Default for PeerPerformance<P> {
+	/// Timeouts are bounded by `DEFAULT_REQUEST_TIMEOUT_CONNECTED`.
+	fn default() -> Self {
+		Self::new(DEFAULT_MIN_TIMEOUT, DEFAULT_REQUEST_TIMEOUT_CONNECTED)
+	}
+}
+
+fn ewma(old: f64, new: f64) -> f64 {
+	old + EWMA_WEIGHT * (new - old)
+}
+
+fn ewma_duration(old: Duration, new: Duration) -> Duration {
+	Duration::from_secs_f64(ewma(old.as_secs_f64(), new.as_secs_f64()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const MIN: Duration = Duration::from_millis(100);
+	const MAX: Duration = Duration::from_secs(2);
+
+	#[test]
+	fn unknown_peers_get_max_timeout() {
+		let tracker = PeerPerformance::<u32>::new(MIN, MAX);
+		assert_eq!(tracker.timeout(&1, 0), MAX);
+		assert_eq!(tracker.estimate(&1), None);
+	}
+
+	#[test]
+	fn timeout_adapts_to_observed_performance() {
+		let tracker = PeerPerformance::<u32>::new(MIN, MAX);
+
+		tracker.note_response(&1, Duration::from_millis(200), 1000);
+		assert_eq!(tracker.timeout(&1, 0), Duration::from_millis(600));
+		// 1000 bytes took 200ms, so 10_000 bytes are expected to take two seconds.
+		assert_eq!(tracker.timeout(&1, 10_000), MAX);
+		assert!(tracker.timeout(&1, 2_000) > Duration::from_millis(600));
+
+		tracker.note_response(&3, Duration::from_millis(1), 0);
+		assert_eq!(tracker.timeout(&3, 0), MIN);
+		assert_eq!(tracker.estimate(&3).unwrap().throughput, None);
+
+		tracker.note_response(&4, Duration::from_secs(10), 0);
+		assert_eq!(tracker.timeout(&4, 0), MAX);
+	}
+
+	#[test]
+	fn estimates_are_smoothed() {
+		let tracker = PeerPerformance::<u32>::new(MIN, MAX);
+
+		tracker.note_response(&1, Duration::from_millis(100), 0);
+		let estimate = tracker.note_response(&1, Duration::from_millis(200), 0);
+		assert!((estimate.response_time.as_secs_f64() - 0.12).abs() < 1e-6);
+		assert_eq!(estimate.failure_rate, 0.0);
+
+		let response_time = estimate.response_time;
+		let estimate = tracker.note_failure(&1);
+		assert_eq!(estimate.response_time, response_time);
+		assert!((estimate.failure_rate - EWMA_WEIGHT).abs() < f64::EPSILON);
+
+		assert_eq!(tracker.note_failure(&2).failure_rate, 1.0);
+		assert_eq!(tracker.estimates().len(), 2);
+	}
+
+	#[test]
+	fn peers_are_sorted_by_preference() {
+		let tracker = PeerPerformance::<u32>::new(MIN, MAX);
+
+		tracker.note_response(&1, Duration::from_millis(300), 0);
+		tracker.note_response(&2, Duration::from_millis(100), 0);
+		tracker.note_response(&3, Duration::from_millis(200), 0);
+		// Fast, but unreliable.
+		tracker.note_response(&4, Duration::from_millis(50), 0);
+		for _ in 0..4 {
+			tracker.note_failure(&4);
+		}
+
+		let mut peers = vec![5, 4, 3, 2, 1, 6];
+		tracker.sort_by_preference(&mut peers, 0, |p| *p);
+		// Unknown peers 5 and 6 are ranked like the median peer 1, keeping their order.
+		assert_eq!(peers, vec![2, 3, 5, 1, 6, 4]);
+	}
+
+	#[test]
+	fn sorting_unknown_peers_keeps_order() {
+		let tracker = PeerPerformance::<u32>::new(MIN, MAX);
+		let mut peers = vec![3, 1, 2];
+		tracker.sort_by_preference(&mut peers, 0, |p| *p);
+		assert_eq!(peers, vec![3, 1, 2]);
+	}
+}
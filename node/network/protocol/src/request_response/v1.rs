@@ -56,6 +56,12 @@ impl From<Option<ChunkResponse>> for ChunkFetchingResponse {
 	}
 }
 
+impl From<Option<ErasureChunk>> for ChunkFetchingResponse {
+	fn from(x: Option<ErasureChunk>) -> Self {
+		x.map(ChunkResponse::from).into()
+	}
+}
+
 /// Skimmed down variant of `ErasureChunk`.
 ///
 /// Instead of transmitting a full `ErasureChunk` we transmit `ChunkResponse` in
@@ -0,0 +1,92 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Requests and responses as sent over the wire for the protocols which got a second version.
+
+use parity_scale_codec::{Decode, Encode};
+
+use polkadot_node_primitives::ErasureChunk;
+use polkadot_primitives::{CandidateHash, ValidatorIndex};
+
+use super::{v1, HasFallback, IsRequest, Protocol};
+
+/// Request an availability chunk.
+#[derive(Debug, Copy, Clone, Encode, Decode)]
+pub struct ChunkFetchingRequest {
+	/// Hash of candidate we want a chunk for.
+	pub candidate_hash: CandidateHash,
+	/// The index of the chunk to fetch.
+	pub index: ValidatorIndex,
+}
+
+/// Receive a requested erasure chunk.
+#[derive(Debug, Clone, Encode, Decode)]
+pub enum ChunkFetchingResponse {
+	/// The requested chunk data.
+	///
+	/// Unlike in `v1`, the full `ErasureChunk` including its index is transmitted, so that the
+	/// requester can check that it got the chunk with the index it asked for.
+	#[codec(index = 0)]
+	Chunk(ErasureChunk),
+	/// Node was not in possession of the requested chunk.
+	#[codec(index = 1)]
+	NoSuchChunk,
+}
+
+impl From<Option<ErasureChunk>> for ChunkFetchingResponse {
+	fn from(x: Option<ErasureChunk>) -> Self {
+		match x {
+			Some(c) => ChunkFetchingResponse::Chunk(c),
+			None => ChunkFetchingResponse::NoSuchChunk,
+		}
+	}
+}
+
+impl From<v1::ChunkFetchingRequest> for ChunkFetchingRequest {
+	fn from(v1::ChunkFetchingRequest { candidate_hash, index }: v1::ChunkFetchingRequest) -> Self {
+		Self { candidate_hash, index }
+	}
+}
+
+impl From<ChunkFetchingRequest> for v1::ChunkFetchingRequest {
+	fn from(ChunkFetchingRequest { candidate_hash, index }: ChunkFetchingRequest) -> Self {
+		Self { candidate_hash, index }
+	}
+}
+
+impl IsRequest for ChunkFetchingRequest {
+	type Response = ChunkFetchingResponse;
+	const PROTOCOL: Protocol = Protocol::ChunkFetchingV2;
+}
+
+impl HasFallback for ChunkFetchingRequest {
+	type Fallback = v1::ChunkFetchingRequest;
+
+	fn fallback(&self) -> Self::Fallback {
+		(*self).into()
+	}
+
+	fn response_from_fallback(
+		fallback: Self::Fallback,
+		response: v1::ChunkFetchingResponse,
+	) -> Self::Response {
+		match response {
+			v1::ChunkFetchingResponse::Chunk(chunk) =>
+				ChunkFetchingResponse::Chunk(chunk.recombine_into_chunk(&fallback)),
+			v1::ChunkFetchingResponse::NoSuchChunk => ChunkFetchingResponse::NoSuchChunk,
+		}
+	}
+}
@@ -27,6 +27,7 @@ use parity_scale_codec::Encode;
 
 use polkadot_node_network_protocol::{
 	self as net_protocol,
+	gossip_topology::{GossipTopology, GridTopology},
 	grid_topology::{GridNeighbors, RequiredRouting, SessionBoundGridTopologyStorage},
 	peer_set::{IsAuthority, PeerSet},
 	request_response::{v1 as request_v1, IncomingRequestReceiver},
@@ -60,7 +61,10 @@ use indexmap::{map::Entry as IEntry, IndexMap};
 use sp_keystore::KeystorePtr;
 use util::runtime::RuntimeInfo;
 
-use std::collections::{hash_map::Entry, HashMap, HashSet, VecDeque};
+use std::{
+	collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
+	sync::Arc,
+};
 
 use fatality::Nested;
 
@@ -126,6 +130,8 @@ pub struct StatementDistributionSubsystem<R> {
 	metrics: Metrics,
 	/// Pseudo-random generator for peers selection logic
 	rng: R,
+	/// The gossip topology statements are distributed with.
+	gossip_topology: Arc<dyn GossipTopology>,
 }
 
 #[overseer::subsystem(StatementDistribution, error=SubsystemError, prefix=self::overseer)]
@@ -1700,7 +1706,7 @@ async fn handle_network_update<Context, R>(
 				}
 			}
 		},
-		NetworkBridgeEvent::PeerMessage(peer, Versioned::V1(message)) => {
+		NetworkBridgeEvent::PeerMessage(peer, Versioned::V1(message) | Versioned::V2(message)) => {
 			handle_incoming_message_and_circulate(
 				peer,
 				topology_storage,
@@ -1750,12 +1756,25 @@ impl<R: rand::Rng> StatementDistributionSubsystem<R> {
 		metrics: Metrics,
 		rng: R,
 	) -> Self {
-		Self { keystore, req_receiver: Some(req_receiver), metrics, rng }
+		Self {
+			keystore,
+			req_receiver: Some(req_receiver),
+			metrics,
+			rng,
+			gossip_topology: Arc::new(GridTopology),
+		}
+	}
+
+	/// Use the given gossip topology for statements, instead of the grid.
+	pub fn with_gossip_topology(mut self, gossip_topology: Arc<dyn GossipTopology>) -> Self {
+		self.gossip_topology = gossip_topology;
+		self
 	}
 
 	async fn run<Context>(mut self, mut ctx: Context) -> std::result::Result<(), FatalError> {
 		let mut peers: HashMap<PeerId, PeerData> = HashMap::new();
-		let mut topology_storage: SessionBoundGridTopologyStorage = Default::default();
+		let mut topology_storage =
+			SessionBoundGridTopologyStorage::with_gossip_topology(self.gossip_topology.clone());
 		let mut authorities: HashMap<AuthorityDiscoveryId, PeerId> = HashMap::new();
 		let mut active_heads: HashMap<Hash, ActiveHeadData> = HashMap::new();
 		let mut recent_outdated_heads = RecentOutdatedHeads::default();
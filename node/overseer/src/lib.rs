@@ -495,6 +495,7 @@ pub struct Overseer<SupportsParachains> {
 		NetworkBridgeTxMessage,
 		RuntimeApiMessage,
 		AvailabilityStoreMessage,
+		ChainApiMessage,
 	])]
 	availability_recovery: AvailabilityRecovery,
 
@@ -502,6 +503,7 @@ pub struct Overseer<SupportsParachains> {
 		AvailabilityStoreMessage,
 		RuntimeApiMessage,
 		BitfieldDistributionMessage,
+		ChainApiMessage,
 	])]
 	bitfield_signing: BitfieldSigning,
 
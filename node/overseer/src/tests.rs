@@ -26,7 +26,7 @@ use polkadot_node_primitives::{
 };
 use polkadot_node_subsystem_types::{
 	jaeger,
-	messages::{NetworkBridgeEvent, RuntimeApiRequest},
+	messages::{NetworkBridgeEvent, PvfExecPriority, RuntimeApiRequest},
 	ActivatedLeaf, LeafStatus,
 };
 use polkadot_primitives::{
@@ -107,6 +107,7 @@ where
 							candidate_receipt,
 							PoV { block_data: BlockData(Vec::new()) }.into(),
 							PvfExecTimeoutKind::Backing,
+							PvfExecPriority::Critical,
 							tx,
 						))
 						.await;
@@ -780,6 +781,7 @@ fn test_candidate_validation_msg() -> CandidateValidationMessage {
 		candidate_receipt,
 		pov,
 		PvfExecTimeoutKind::Backing,
+		PvfExecPriority::Critical,
 		sender,
 	)
 }
@@ -18,6 +18,7 @@
 
 pub use sp_consensus_babe::{Randomness, Slot, VrfOutput, VrfProof, VrfSignature, VrfTranscript};
 
+use bitvec::{order::Lsb0, vec::BitVec};
 use parity_scale_codec::{Decode, Encode};
 use polkadot_primitives::{
 	BlockNumber, CandidateHash, CandidateIndex, CoreIndex, Hash, Header, SessionIndex,
@@ -25,6 +26,7 @@ use polkadot_primitives::{
 };
 use sp_application_crypto::ByteArray;
 use sp_consensus_babe as babe_primitives;
+use std::marker::PhantomData;
 
 /// Validators assigning to check a particular candidate are split up into tranches.
 /// Earlier tranches of validators check first, with later tranches serving as backup.
@@ -37,12 +39,18 @@ pub const RELAY_VRF_STORY_CONTEXT: &[u8] = b"A&V RC-VRF";
 /// A static context used for all relay-vrf-modulo VRFs.
 pub const RELAY_VRF_MODULO_CONTEXT: &[u8] = b"A&V MOD";
 
+/// A static context used for all relay-vrf-modulo-compact VRFs.
+pub const RELAY_VRF_MODULO_COMPACT_CONTEXT: &[u8] = b"A&V MOD v2";
+
 /// A static context used for all relay-vrf-modulo VRFs.
 pub const RELAY_VRF_DELAY_CONTEXT: &[u8] = b"A&V DELAY";
 
 /// A static context used for transcripts indicating assigned availability core.
 pub const ASSIGNED_CORE_CONTEXT: &[u8] = b"A&V ASSIGNED";
 
+/// A static context used for transcripts indicating several assigned availability cores.
+pub const ASSIGNED_CORES_CONTEXT: &[u8] = b"A&V ASSIGNED v2";
+
 /// A static context associated with producing randomness for a core.
 pub const CORE_RANDOMNESS_CONTEXT: &[u8] = b"A&V CORE";
 
@@ -97,6 +105,228 @@ pub struct IndirectAssignmentCert {
 	pub cert: AssignmentCert,
 }
 
+/// Errors that can occur when building a [`Bitfield`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum BitfieldError {
+	/// The bitfield would not have any bit set.
+	#[error("Bitfield has no bit set")]
+	NullAssignment,
+}
+
+/// An index which can be stored in a [`Bitfield`].
+pub trait BitIndex: Copy {
+	/// The position of the index in the bitfield.
+	fn bit_index(self) -> usize;
+	/// Build the index from its position in the bitfield.
+	fn from_bit_index(index: usize) -> Self;
+}
+
+impl BitIndex for CandidateIndex {
+	fn bit_index(self) -> usize {
+		self as usize
+	}
+
+	fn from_bit_index(index: usize) -> Self {
+		index as CandidateIndex
+	}
+}
+
+impl BitIndex for CoreIndex {
+	fn bit_index(self) -> usize {
+		self.0 as usize
+	}
+
+	fn from_bit_index(index: usize) -> Self {
+		CoreIndex(index as u32)
+	}
+}
+
+/// A set of indices of the same kind, e.g. candidates or cores, stored as a bitfield.
+///
+/// A bitfield always has at least one bit set.
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq, Hash)]
+pub struct Bitfield<T>(BitVec<u8, Lsb0>, PhantomData<T>);
+
+/// A bitfield of candidate indices, claimed by an assignment.
+pub type CandidateBitfield = Bitfield<CandidateIndex>;
+
+/// A bitfield of core indices, covered by an assignment.
+pub type CoreBitfield = Bitfield<CoreIndex>;
+
+impl<T: BitIndex> Bitfield<T> {
+	/// Whether the given index is set.
+	pub fn bit_at(&self, index: T) -> bool {
+		self.0.get(index.bit_index()).map_or(false, |bit| *bit)
+	}
+
+	/// The number of set bits.
+	pub fn count_ones(&self) -> usize {
+		self.0.count_ones()
+	}
+
+	/// The lowest set index.
+	pub fn first_one(&self) -> Option<T> {
+		self.0.first_one().map(T::from_bit_index)
+	}
+
+	/// Iterate the set indices in ascending order.
+	pub fn iter_ones(&self) -> impl Iterator<Item = T> + '_ {
+		self.0.iter_ones().map(T::from_bit_index)
+	}
+
+	/// The underlying bits.
+	pub fn inner(&self) -> &BitVec<u8, Lsb0> {
+		&self.0
+	}
+}
+
+impl<T: BitIndex> TryFrom<Vec<T>> for Bitfield<T> {
+	type Error = BitfieldError;
+
+	fn try_from(indices: Vec<T>) -> Result<Self, Self::Error> {
+		let len = match indices.iter().map(|index| index.bit_index()).max() {
+			None => return Err(BitfieldError::NullAssignment),
+			Some(max) => max + 1,
+		};
+
+		let mut bits = BitVec::repeat(false, len);
+		for index in indices {
+			bits.set(index.bit_index(), true);
+		}
+
+		Ok(Bitfield(bits, PhantomData))
+	}
+}
+
+impl From<CandidateIndex> for CandidateBitfield {
+	fn from(index: CandidateIndex) -> Self {
+		let mut bits = BitVec::repeat(false, index.bit_index() + 1);
+		bits.set(index.bit_index(), true);
+		Bitfield(bits, PhantomData)
+	}
+}
+
+impl From<CoreIndex> for CoreBitfield {
+	fn from(index: CoreIndex) -> Self {
+		let mut bits = BitVec::repeat(false, index.bit_index() + 1);
+		bits.set(index.bit_index(), true);
+		Bitfield(bits, PhantomData)
+	}
+}
+
+/// Different kinds of input data or criteria that can prove a validator's assignment
+/// to check one or several parachain candidates.
+///
+/// The first variants have the same encoding as [`AssignmentCertKind`].
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub enum AssignmentCertKindV2 {
+	/// An assignment story based on the VRF that authorized the relay-chain block where the
+	/// candidate was included combined with a sample number.
+	///
+	/// The context used to produce bytes is [`RELAY_VRF_MODULO_CONTEXT`]
+	#[codec(index = 0)]
+	RelayVRFModulo {
+		/// The sample number used in this cert.
+		sample: u32,
+	},
+	/// An assignment story based on the VRF that authorized the relay-chain block where the
+	/// candidate was included combined with the index of a particular core.
+	///
+	/// The context is [`RELAY_VRF_DELAY_CONTEXT`]
+	#[codec(index = 1)]
+	RelayVRFDelay {
+		/// The core index chosen in this cert.
+		core_index: CoreIndex,
+	},
+	/// An assignment story based on the VRF that authorized the relay-chain block where the
+	/// candidates were included. A single VRF output assigns the validator to all the
+	/// cores of the bitfield.
+	///
+	/// The context is [`RELAY_VRF_MODULO_COMPACT_CONTEXT`]
+	#[codec(index = 2)]
+	RelayVRFModuloCompact {
+		/// The cores the validator is assigned to.
+		core_bitfield: CoreBitfield,
+	},
+}
+
+/// A certification of assignment to one or several candidates.
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub struct AssignmentCertV2 {
+	/// The criterion which is claimed to be met by this cert.
+	pub kind: AssignmentCertKindV2,
+	/// The VRF signature showing the criterion is met.
+	pub vrf: VrfSignature,
+}
+
+impl From<AssignmentCert> for AssignmentCertV2 {
+	fn from(cert: AssignmentCert) -> Self {
+		let kind = match cert.kind {
+			AssignmentCertKind::RelayVRFModulo { sample } =>
+				AssignmentCertKindV2::RelayVRFModulo { sample },
+			AssignmentCertKind::RelayVRFDelay { core_index } =>
+				AssignmentCertKindV2::RelayVRFDelay { core_index },
+		};
+
+		AssignmentCertV2 { kind, vrf: cert.vrf }
+	}
+}
+
+/// The assignment cert can't be expressed in the first version of the protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AssignmentConversionError;
+
+impl TryFrom<AssignmentCertV2> for AssignmentCert {
+	type Error = AssignmentConversionError;
+
+	fn try_from(cert: AssignmentCertV2) -> Result<Self, Self::Error> {
+		let kind = match cert.kind {
+			AssignmentCertKindV2::RelayVRFModulo { sample } =>
+				AssignmentCertKind::RelayVRFModulo { sample },
+			AssignmentCertKindV2::RelayVRFDelay { core_index } =>
+				AssignmentCertKind::RelayVRFDelay { core_index },
+			AssignmentCertKindV2::RelayVRFModuloCompact { .. } =>
+				return Err(AssignmentConversionError),
+		};
+
+		Ok(AssignmentCert { kind, vrf: cert.vrf })
+	}
+}
+
+/// An assignment criterion to one or several candidates, which refers to the candidates
+/// under which the assignment is relevant by block hash.
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub struct IndirectAssignmentCertV2 {
+	/// A block hash where the candidates appear.
+	pub block_hash: Hash,
+	/// The validator index.
+	pub validator: ValidatorIndex,
+	/// The cert itself.
+	pub cert: AssignmentCertV2,
+}
+
+impl From<IndirectAssignmentCert> for IndirectAssignmentCertV2 {
+	fn from(indirect_cert: IndirectAssignmentCert) -> Self {
+		IndirectAssignmentCertV2 {
+			block_hash: indirect_cert.block_hash,
+			validator: indirect_cert.validator,
+			cert: indirect_cert.cert.into(),
+		}
+	}
+}
+
+impl TryFrom<IndirectAssignmentCertV2> for IndirectAssignmentCert {
+	type Error = AssignmentConversionError;
+
+	fn try_from(indirect_cert: IndirectAssignmentCertV2) -> Result<Self, Self::Error> {
+		Ok(IndirectAssignmentCert {
+			block_hash: indirect_cert.block_hash,
+			validator: indirect_cert.validator,
+			cert: indirect_cert.cert.try_into()?,
+		})
+	}
+}
+
 /// A signed approval vote which references the candidate indirectly via the block.
 ///
 /// In practice, we have a look-up from block hash and candidate index to candidate hash,
@@ -113,6 +343,56 @@ pub struct IndirectSignedApprovalVote {
 	pub signature: ValidatorSignature,
 }
 
+/// A signed approval vote for one or several candidates of a block, which references the
+/// candidates indirectly via the block.
+///
+/// The signature covers the hashes of all the approved candidates, ordered by candidate index.
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub struct IndirectSignedApprovalVoteV2 {
+	/// A block hash where the candidates appear.
+	pub block_hash: Hash,
+	/// The indices of the approved candidates in the list of candidates fully included as-of
+	/// the block.
+	pub candidate_indices: CandidateBitfield,
+	/// The validator index.
+	pub validator: ValidatorIndex,
+	/// The signature by the validator.
+	pub signature: ValidatorSignature,
+}
+
+impl From<IndirectSignedApprovalVote> for IndirectSignedApprovalVoteV2 {
+	fn from(vote: IndirectSignedApprovalVote) -> Self {
+		IndirectSignedApprovalVoteV2 {
+			block_hash: vote.block_hash,
+			candidate_indices: vote.candidate_index.into(),
+			validator: vote.validator,
+			signature: vote.signature,
+		}
+	}
+}
+
+/// The approval vote covers several candidates, so it can't be expressed in the first
+/// version of the protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ApprovalConversionError;
+
+impl TryFrom<IndirectSignedApprovalVoteV2> for IndirectSignedApprovalVote {
+	type Error = ApprovalConversionError;
+
+	fn try_from(vote: IndirectSignedApprovalVoteV2) -> Result<Self, Self::Error> {
+		if vote.candidate_indices.count_ones() != 1 {
+			return Err(ApprovalConversionError)
+		}
+
+		Ok(IndirectSignedApprovalVote {
+			block_hash: vote.block_hash,
+			candidate_index: vote.candidate_indices.first_one().ok_or(ApprovalConversionError)?,
+			validator: vote.validator,
+			signature: vote.signature,
+		})
+	}
+}
+
 /// Metadata about a block which is now live in the approval protocol.
 #[derive(Debug)]
 pub struct BlockApprovalMeta {
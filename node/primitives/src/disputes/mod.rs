@@ -36,6 +36,36 @@ pub use message::{DisputeMessage, Error as DisputeMessageCheckError, UncheckedDi
 mod status;
 pub use status::{dispute_is_inactive, DisputeStatus, Timestamp, ACTIVE_DURATION_SECS};
 
+/// A change of a dispute, notified by the dispute coordinator to its subscribers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DisputeUpdate {
+	/// The session the disputed candidate appeared in.
+	pub session: SessionIndex,
+	/// The hash of the disputed candidate.
+	pub candidate_hash: CandidateHash,
+	/// The status of the dispute after the change.
+	pub status: DisputeStatus,
+	/// The number of votes for the validity of the candidate.
+	pub valid_votes: u32,
+	/// The number of votes against the validity of the candidate.
+	pub invalid_votes: u32,
+}
+
+/// The state of the local participation in a dispute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisputeParticipation {
+	/// The candidate is currently being validated for participation.
+	Running,
+	/// Participation is waiting in the queue. `priority` is set if it is in the priority queue.
+	Queued {
+		/// Whether the participation is in the priority queue.
+		priority: bool,
+	},
+	/// Participation is neither running nor queued. Either we participated already or
+	/// participation is not needed, e.g. because we cannot vote in the session.
+	Idle,
+}
+
 /// A checked dispute statement from an associated validator.
 #[derive(Debug, Clone)]
 pub struct SignedDisputeStatement {
@@ -107,8 +137,9 @@ impl ValidCandidateVotes {
 				ValidDisputeStatementKind::BackingValid(_) |
 				ValidDisputeStatementKind::BackingSeconded(_) => false,
 				ValidDisputeStatementKind::Explicit |
-				ValidDisputeStatementKind::ApprovalChecking => {
-					occupied.insert((kind, sig));
+				ValidDisputeStatementKind::ApprovalChecking |
+				ValidDisputeStatementKind::ApprovalCheckingMultipleCandidates(_) => {
+					occupied.insert((kind.clone(), sig));
 					kind != occupied.get().0
 				},
 			},
@@ -30,6 +30,7 @@ use parity_scale_codec::{Decode, Encode, Error as CodecError, Input};
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
 use polkadot_primitives::{
+	vstaging::{node_features, NodeFeatures},
 	BlakeTwo256, BlockNumber, CandidateCommitments, CandidateHash, CollatorPair,
 	CommittedCandidateReceipt, CompactStatement, EncodeAs, Hash, HashT, HeadData, Id as ParaId,
 	PersistedValidationData, SessionIndex, Signed, UncheckedSigned, ValidationCode, ValidatorIndex,
@@ -46,9 +47,9 @@ pub mod approval;
 /// Disputes related types.
 pub mod disputes;
 pub use disputes::{
-	dispute_is_inactive, CandidateVotes, DisputeMessage, DisputeMessageCheckError, DisputeStatus,
-	InvalidDisputeVote, SignedDisputeStatement, Timestamp, UncheckedDisputeMessage,
-	ValidDisputeVote, ACTIVE_DURATION_SECS,
+	dispute_is_inactive, CandidateVotes, DisputeMessage, DisputeMessageCheckError,
+	DisputeParticipation, DisputeStatus, DisputeUpdate, InvalidDisputeVote, SignedDisputeStatement,
+	Timestamp, UncheckedDisputeMessage, ValidDisputeVote, ACTIVE_DURATION_SECS,
 };
 
 // For a 16-ary Merkle Prefix Trie, we can expect at most 16 32-byte hashes per node
@@ -511,6 +512,111 @@ impl ErasureChunk {
 	}
 }
 
+/// The assignment of erasure chunks of a candidate to the validators holding them.
+///
+/// Chunk `i` is held by validator `i`, unless the `AvailabilityChunkShuffling` node feature is
+/// enabled. In that case the chunk indices are rotated by an offset derived from the randomness of
+/// the session, the number of the relay parent and the para of the candidate, so that the
+/// systematic chunks are held by different validators for every relay block and core. None of
+/// these inputs can be chosen by the author of the relay parent, so block authors cannot grind
+/// for a favourable assignment.
+///
+/// Like [`ErasureChunk::index`], chunk indices are represented as `ValidatorIndex`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AvailabilityChunkMapping {
+	n_validators: u32,
+	offset: u32,
+}
+
+impl AvailabilityChunkMapping {
+	/// Create the mapping for a candidate of the given para built on top of the relay parent with
+	/// number `relay_parent_number`, in a session with `n_validators` validators, the given node
+	/// features and the given `randomness`, i.e. [`SessionInfo::random_seed`].
+	///
+	/// [`SessionInfo::random_seed`]: polkadot_primitives::SessionInfo::random_seed
+	pub fn new(
+		node_features: &NodeFeatures,
+		n_validators: usize,
+		randomness: &[u8; 32],
+		relay_parent_number: BlockNumber,
+		para_id: ParaId,
+	) -> Self {
+		let n_validators = n_validators as u32;
+		let shuffling_enabled = node_features::is_enabled(
+			node_features,
+			node_features::FeatureIndex::AvailabilityChunkShuffling,
+		);
+
+		let offset = if shuffling_enabled && n_validators > 0 {
+			let seed = BlakeTwo256::hash_of(&(randomness, relay_parent_number, para_id));
+			let mut offset = [0u8; 4];
+			offset.copy_from_slice(&seed.as_bytes()[..4]);
+			u32::from_le_bytes(offset) % n_validators
+		} else {
+			0
+		};
+
+		AvailabilityChunkMapping { n_validators, offset }
+	}
+
+	/// The index of the chunk held by the given validator.
+	pub fn chunk_index(&self, validator_index: ValidatorIndex) -> ValidatorIndex {
+		if self.offset == 0 {
+			return validator_index
+		}
+		ValidatorIndex((validator_index.0 + self.offset) % self.n_validators)
+	}
+
+	/// The index of the validator holding the given chunk.
+	pub fn validator_index(&self, chunk_index: ValidatorIndex) -> ValidatorIndex {
+		if self.offset == 0 {
+			return chunk_index
+		}
+		ValidatorIndex((chunk_index.0 + self.n_validators - self.offset) % self.n_validators)
+	}
+}
+
+/// The state of a candidate in the availability store.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum StoredCandidateState {
+	/// The candidate was first observed at the given UNIX timestamp, but is not included in any
+	/// known block.
+	Unavailable {
+		/// The time the candidate was first observed at, in seconds since the UNIX epoch.
+		since: u64,
+	},
+	/// The candidate is included in the given unfinalized blocks.
+	Unfinalized {
+		/// The time the candidate was first observed at, in seconds since the UNIX epoch.
+		since: u64,
+		/// The unfinalized blocks the candidate is included in, sorted by number and hash.
+		blocks: Vec<(BlockNumber, Hash)>,
+	},
+	/// The candidate is included in a finalized block.
+	Finalized {
+		/// The time the block got finalized at, in seconds since the UNIX epoch.
+		since: u64,
+	},
+}
+
+/// What the availability store keeps about a candidate.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct StoredCandidateInfo {
+	/// The hash of the candidate.
+	pub candidate_hash: CandidateHash,
+	/// The state of the candidate.
+	pub state: StoredCandidateState,
+	/// Whether the full `AvailableData` of the candidate is stored.
+	pub data_available: bool,
+	/// Whether the chunk is stored, by chunk index. Has one entry per validator of the session.
+	pub chunks_stored: Vec<bool>,
+	/// The number of bytes used for the available data and chunks of the candidate.
+	pub stored_bytes: u64,
+	/// The time the candidate is scheduled to be pruned at, in seconds since the UNIX epoch.
+	/// `None` while the candidate is included in unfinalized blocks.
+	pub prune_at: Option<u64>,
+}
+
 /// Compress a PoV, unless it exceeds the [`POV_BOMB_LIMIT`].
 #[cfg(not(target_os = "unknown"))]
 pub fn maybe_compress_pov(pov: PoV) -> PoV {
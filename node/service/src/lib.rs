@@ -234,6 +234,10 @@ pub enum Error {
 	#[error("Creating a custom database is required for validators")]
 	DatabasePathRequired,
 
+	#[cfg(feature = "full-node")]
+	#[error("Failed to read the approval voting state from the parachain's DB: {0}")]
+	ApprovalVotingDb(polkadot_overseer::SubsystemError),
+
 	#[cfg(feature = "full-node")]
 	#[error("Expected at least one of polkadot, kusama, westend or rococo runtime feature")]
 	NoRuntime,
@@ -289,33 +293,131 @@ impl IdentifyVariant for Box<dyn ChainSpec> {
 
 #[cfg(feature = "full-node")]
 pub fn open_database(db_source: &DatabaseSource) -> Result<Arc<dyn Database>, Error> {
-	let parachains_db = match db_source {
-		DatabaseSource::RocksDb { path, .. } => parachains_db::open_creating_rocksdb(
-			path.clone(),
-			parachains_db::CacheSizes::default(),
-		)?,
-		DatabaseSource::ParityDb { path, .. } => parachains_db::open_creating_paritydb(
+	// Carry over the parachains DB of a node switching from RocksDB to ParityDB.
+	if let DatabaseSource::ParityDb { path, .. } = db_source {
+		if let Some(rocksdb_path) = rocksdb_path_next_to(path) {
+			parachains_db::migrate_rocksdb_to_paritydb(
+				&rocksdb_path,
+				path.parent().ok_or(Error::DatabasePathRequired)?,
+			)?;
+		}
+	}
+
+	let parachains_db = match parachains_db_root(db_source)? {
+		(parachains_db::DatabaseKind::RocksDB, root) =>
+			parachains_db::open_creating_rocksdb(root, parachains_db::CacheSizes::default())?,
+		(parachains_db::DatabaseKind::ParityDB, root) =>
+			parachains_db::open_creating_paritydb(root, parachains_db::CacheSizes::default())?,
+	};
+	Ok(parachains_db)
+}
+
+/// Returns the path of the RocksDB database of a node, given the path of its ParityDB database,
+/// following the directory layout of `sc-cli`.
+#[cfg(feature = "full-node")]
+fn rocksdb_path_next_to(paritydb_path: &std::path::Path) -> Option<std::path::PathBuf> {
+	let role_dir = paritydb_path.file_name()?;
+	Some(paritydb_path.parent()?.parent()?.join("db").join(role_dir))
+}
+
+/// Export the parachain's DB to a new archive at `output`, which can be imported with
+/// [`import_parachains_db`] on another machine or into another database backend.
+///
+/// Returns the number of exported entries. The parachain's DB is opened in read-only mode and must
+/// be at the current version. The export from ParityDB fails if the DB contains entries whose keys
+/// are not kept by ParityDB.
+#[cfg(feature = "full-node")]
+pub fn export_parachains_db(
+	db_source: &DatabaseSource,
+	output: &std::path::Path,
+) -> Result<u64, Error> {
+	let (db_kind, root) = parachains_db_root(db_source)?;
+	Ok(parachains_db::export(db_kind, &root, output)?)
+}
+
+/// Import an archive created by [`export_parachains_db`] into a new parachain's DB.
+///
+/// Returns the number of imported entries. Fails if the parachain's DB exists already.
+#[cfg(feature = "full-node")]
+pub fn import_parachains_db(
+	db_source: &DatabaseSource,
+	input: &std::path::Path,
+) -> Result<u64, Error> {
+	let (db_kind, root) = parachains_db_root(db_source)?;
+	Ok(parachains_db::import(db_kind, &root, input)?)
+}
+
+/// Report the blocks which are not approved yet according to the approval voting state stored in
+/// the parachain's DB, along with why their candidates are not approved.
+///
+/// The parachain's DB is opened in read-only mode, so this can be used while the node is running.
+#[cfg(feature = "full-node")]
+pub fn inspect_approvals(
+	db_source: &DatabaseSource,
+	slot_duration_millis: u64,
+) -> Result<Vec<approval_voting_subsystem::inspect::BlockReport>, Error> {
+	let (db_kind, root) = parachains_db_root(db_source)?;
+	let db = parachains_db::open_read_only(db_kind, &root)?;
+	let config = approval_voting_subsystem::inspect::InspectConfig {
+		col_approval_data: parachains_db::REAL_COLUMNS.col_approval_data,
+		col_session_data: parachains_db::REAL_COLUMNS.col_session_window_data,
+		slot_duration_millis,
+	};
+	approval_voting_subsystem::inspect::inspect_unapproved_blocks(db.db(), &config)
+		.map_err(Error::ApprovalVotingDb)
+}
+
+/// Returns the dispute window of the chain at its best block, i.e. how long a candidate can be
+/// disputed for after its inclusion, unless the runtime does not provide it yet.
+#[cfg(feature = "full-node")]
+fn dispute_window<Client>(
+	client: &Client,
+	babe_config: &sp_consensus_babe::BabeConfiguration,
+) -> Option<Duration>
+where
+	Client: ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	Client::Api: ParachainHost<Block>,
+{
+	let best_hash = client.info().best_hash;
+	let api = client.runtime_api();
+	let session_index = api.session_index_for_child(best_hash).ok()?;
+	let dispute_period = api.session_info(best_hash, session_index).ok()??.dispute_period;
+
+	// A session of the relay chain lasts one BABE epoch.
+	let session_duration = babe_config
+		.slot_duration()
+		.as_duration()
+		.saturating_mul(babe_config.epoch_length as u32);
+	Some(session_duration.saturating_mul(dispute_period))
+}
+
+/// Returns the backend and the root directory of the parachain's DB for the given database source.
+#[cfg(feature = "full-node")]
+fn parachains_db_root(
+	db_source: &DatabaseSource,
+) -> Result<(parachains_db::DatabaseKind, std::path::PathBuf), Error> {
+	let root = match db_source {
+		DatabaseSource::RocksDb { path, .. } =>
+			(parachains_db::DatabaseKind::RocksDB, path.clone()),
+		DatabaseSource::ParityDb { path, .. } => (
+			parachains_db::DatabaseKind::ParityDB,
 			path.parent().ok_or(Error::DatabasePathRequired)?.into(),
-			parachains_db::CacheSizes::default(),
-		)?,
+		),
 		DatabaseSource::Auto { paritydb_path, rocksdb_path, .. } => {
 			if paritydb_path.is_dir() && paritydb_path.exists() {
-				parachains_db::open_creating_paritydb(
+				(
+					parachains_db::DatabaseKind::ParityDB,
 					paritydb_path.parent().ok_or(Error::DatabasePathRequired)?.into(),
-					parachains_db::CacheSizes::default(),
-				)?
+				)
 			} else {
-				parachains_db::open_creating_rocksdb(
-					rocksdb_path.clone(),
-					parachains_db::CacheSizes::default(),
-				)?
+				(parachains_db::DatabaseKind::RocksDB, rocksdb_path.clone())
 			}
 		},
 		DatabaseSource::Custom { .. } => {
 			unimplemented!("No polkadot subsystem db for custom source.");
 		},
 	};
-	Ok(parachains_db)
+	Ok(root)
 }
 
 /// Initialize the `Jeager` collector. The destination must listen
@@ -452,6 +554,7 @@ fn new_partial<RuntimeApi, ExecutorDispatch, ChainSelection>(
 		ExecutorDispatch,
 	>,
 	select_chain: ChainSelection,
+	overseer_handle: Option<Handle>,
 ) -> Result<
 	service::PartialComponents<
 		FullClient<RuntimeApi, ExecutorDispatch>,
@@ -597,6 +700,7 @@ where
 					beefy_best_block_stream: beefy_rpc_links.from_voter_best_beefy_stream.clone(),
 					subscription_executor,
 				},
+				overseer_handle: overseer_handle.clone(),
 			};
 
 			polkadot_rpc::create_full(deps, backend.clone()).map_err(Into::into)
@@ -674,6 +778,8 @@ impl IsCollator {
 pub const AVAILABILITY_CONFIG: AvailabilityConfig = AvailabilityConfig {
 	col_data: parachains_db::REAL_COLUMNS.col_availability_data,
 	col_meta: parachains_db::REAL_COLUMNS.col_availability_meta,
+	max_disk_usage: None,
+	min_keep_finalized_for: None,
 };
 
 /// Create a new full node of arbitrary runtime and executor.
@@ -698,6 +804,13 @@ pub fn new_full<RuntimeApi, ExecutorDispatch, OverseerGenerator>(
 	overseer_message_channel_capacity_override: Option<usize>,
 	_malus_finality_delay: Option<u32>,
 	hwbench: Option<sc_sysinfo::HwBench>,
+	pvf_sandbox: bool,
+	av_store_max_disk_usage: Option<u64>,
+	av_store_min_keep_finalized_for: Option<Duration>,
+	notification_compression: bool,
+	enable_v2_assignments: bool,
+	max_approval_coalesce_count: u32,
+	gossip_topology_degree: Option<usize>,
 ) -> Result<NewFull<Arc<FullClient<RuntimeApi, ExecutorDispatch>>>, Error>
 where
 	RuntimeApi: ConstructRuntimeApi<Block, FullClient<RuntimeApi, ExecutorDispatch>>
@@ -709,7 +822,10 @@ where
 	ExecutorDispatch: NativeExecutionDispatch + 'static,
 	OverseerGenerator: OverseerGen,
 {
-	use polkadot_node_network_protocol::request_response::IncomingRequest;
+	use polkadot_node_network_protocol::{
+		gossip_topology::{GossipTopology, GridTopology, RandomRegularTopology},
+		request_response::IncomingRequest,
+	};
 	use sc_network_common::sync::warp::WarpSyncParams;
 
 	let is_offchain_indexing_enabled = config.offchain_worker.indexing_enabled;
@@ -787,6 +903,7 @@ where
 		&mut config,
 		basics,
 		select_chain,
+		(auth_or_collator || overseer_enable_anyways).then(|| overseer_handle.clone()),
 	)?;
 
 	let shared_voter_state = rpc_setup;
@@ -823,7 +940,8 @@ where
 	}
 
 	let peerset_protocol_names =
-		PeerSetProtocolNames::new(genesis_hash, config.chain_spec.fork_id());
+		PeerSetProtocolNames::new(genesis_hash, config.chain_spec.fork_id())
+			.with_notification_compression(notification_compression);
 
 	{
 		use polkadot_network_bridge::{peer_sets_info, IsAuthority};
@@ -838,7 +956,9 @@ where
 
 	let (pov_req_receiver, cfg) = IncomingRequest::get_config_receiver(&req_protocol_names);
 	config.network.request_response_protocols.push(cfg);
-	let (chunk_req_receiver, cfg) = IncomingRequest::get_config_receiver(&req_protocol_names);
+	let (chunk_req_v1_receiver, cfg) = IncomingRequest::get_config_receiver(&req_protocol_names);
+	config.network.request_response_protocols.push(cfg);
+	let (chunk_req_v2_receiver, cfg) = IncomingRequest::get_config_receiver(&req_protocol_names);
 	config.network.request_response_protocols.push(cfg);
 	let (collation_req_receiver, cfg) = IncomingRequest::get_config_receiver(&req_protocol_names);
 	config.network.request_response_protocols.push(cfg);
@@ -899,6 +1019,8 @@ where
 		col_approval_data: parachains_db::REAL_COLUMNS.col_approval_data,
 		col_session_data: parachains_db::REAL_COLUMNS.col_session_window_data,
 		slot_duration_millis: slot_duration.as_millis() as u64,
+		enable_v2_assignments,
+		max_approval_coalesce_count,
 	};
 
 	let candidate_validation_config = CandidateValidationConfig {
@@ -911,6 +1033,7 @@ where
 			None => std::env::current_exe()?,
 			Some(p) => p,
 		},
+		pvf_sandbox,
 	};
 
 	let chain_selection_config = ChainSelectionConfig {
@@ -924,6 +1047,11 @@ where
 		col_session_data: parachains_db::REAL_COLUMNS.col_session_window_data,
 	};
 
+	let gossip_topology: Arc<dyn GossipTopology> = match gossip_topology_degree {
+		Some(degree) => Arc::new(RandomRegularTopology::new(degree)),
+		None => Arc::new(GridTopology),
+	};
+
 	let rpc_handlers = service::spawn_tasks(service::SpawnTasksParams {
 		config,
 		backend: backend.clone(),
@@ -1016,7 +1144,8 @@ where
 					sync_service: sync_service.clone(),
 					authority_discovery_service,
 					pov_req_receiver,
-					chunk_req_receiver,
+					chunk_req_v1_receiver,
+					chunk_req_v2_receiver,
 					collation_req_receiver,
 					available_data_req_receiver,
 					statement_req_receiver,
@@ -1025,7 +1154,12 @@ where
 					spawner,
 					is_collator,
 					approval_voting_config,
-					availability_config: AVAILABILITY_CONFIG,
+					availability_config: AvailabilityConfig {
+						max_disk_usage: av_store_max_disk_usage,
+						min_keep_finalized_for: av_store_min_keep_finalized_for
+							.or_else(|| dispute_window(&*overseer_client, babe_link.config())),
+						..AVAILABILITY_CONFIG
+					},
 					candidate_validation_config,
 					chain_selection_config,
 					dispute_coordinator_config,
@@ -1033,6 +1167,7 @@ where
 					overseer_message_channel_capacity_override,
 					req_protocol_names,
 					peerset_protocol_names,
+					gossip_topology,
 				},
 			)
 			.map_err(|e| {
@@ -1285,6 +1420,7 @@ macro_rules! chain_ops {
 				&mut config,
 				basics,
 				chain_selection,
+				None,
 			)?;
 		Ok((Arc::new(Client::$variant(client)), backend, import_queue, task_manager))
 	}};
@@ -1359,6 +1495,13 @@ pub fn build_full(
 	overseer_message_channel_override: Option<usize>,
 	malus_finality_delay: Option<u32>,
 	hwbench: Option<sc_sysinfo::HwBench>,
+	pvf_sandbox: bool,
+	av_store_max_disk_usage: Option<u64>,
+	av_store_min_keep_finalized_for: Option<Duration>,
+	notification_compression: bool,
+	enable_v2_assignments: bool,
+	max_approval_coalesce_count: u32,
+	gossip_topology_degree: Option<usize>,
 ) -> Result<NewFull<Client>, Error> {
 	#[cfg(feature = "rococo-native")]
 	if config.chain_spec.is_rococo() ||
@@ -1378,6 +1521,13 @@ pub fn build_full(
 			overseer_message_channel_override,
 			malus_finality_delay,
 			hwbench,
+			pvf_sandbox,
+			av_store_max_disk_usage,
+			av_store_min_keep_finalized_for,
+			notification_compression,
+			enable_v2_assignments,
+			max_approval_coalesce_count,
+			gossip_topology_degree,
 		)
 		.map(|full| full.with_client(Client::Rococo))
 	}
@@ -1397,6 +1547,13 @@ pub fn build_full(
 			overseer_message_channel_override,
 			malus_finality_delay,
 			hwbench,
+			pvf_sandbox,
+			av_store_max_disk_usage,
+			av_store_min_keep_finalized_for,
+			notification_compression,
+			enable_v2_assignments,
+			max_approval_coalesce_count,
+			gossip_topology_degree,
 		)
 		.map(|full| full.with_client(Client::Kusama))
 	}
@@ -1416,6 +1573,13 @@ pub fn build_full(
 			overseer_message_channel_override,
 			malus_finality_delay,
 			hwbench,
+			pvf_sandbox,
+			av_store_max_disk_usage,
+			av_store_min_keep_finalized_for,
+			notification_compression,
+			enable_v2_assignments,
+			max_approval_coalesce_count,
+			gossip_topology_degree,
 		)
 		.map(|full| full.with_client(Client::Westend))
 	}
@@ -1438,6 +1602,13 @@ pub fn build_full(
 			}),
 			malus_finality_delay,
 			hwbench,
+			pvf_sandbox,
+			av_store_max_disk_usage,
+			av_store_min_keep_finalized_for,
+			notification_compression,
+			enable_v2_assignments,
+			max_approval_coalesce_count,
+			gossip_topology_degree,
 		)
 		.map(|full| full.with_client(Client::Polkadot))
 	}
@@ -1519,6 +1690,8 @@ fn revert_approval_voting(db: Arc<dyn Database>, hash: Hash) -> sp_blockchain::R
 		col_approval_data: parachains_db::REAL_COLUMNS.col_approval_data,
 		col_session_data: parachains_db::REAL_COLUMNS.col_session_window_data,
 		slot_duration_millis: Default::default(),
+		enable_v2_assignments: false,
+		max_approval_coalesce_count: 1,
 	};
 
 	let approval_voting = approval_voting_subsystem::ApprovalVotingSubsystem::with_config(
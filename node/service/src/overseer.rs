@@ -25,8 +25,11 @@ use polkadot_node_core_candidate_validation::Config as CandidateValidationConfig
 use polkadot_node_core_chain_selection::Config as ChainSelectionConfig;
 use polkadot_node_core_dispute_coordinator::Config as DisputeCoordinatorConfig;
 use polkadot_node_network_protocol::{
+	gossip_topology::GossipTopology,
 	peer_set::PeerSetProtocolNames,
-	request_response::{v1 as request_v1, IncomingRequestReceiver, ReqProtocolNames},
+	request_response::{
+		v1 as request_v1, v2 as request_v2, IncomingRequestReceiver, ReqProtocolNames,
+	},
 };
 #[cfg(any(feature = "malus", test))]
 pub use polkadot_overseer::{
@@ -57,7 +60,7 @@ pub use polkadot_dispute_distribution::DisputeDistributionSubsystem;
 pub use polkadot_gossip_support::GossipSupport as GossipSupportSubsystem;
 pub use polkadot_network_bridge::{
 	Metrics as NetworkBridgeMetrics, NetworkBridgeRx as NetworkBridgeRxSubsystem,
-	NetworkBridgeTx as NetworkBridgeTxSubsystem,
+	NetworkBridgeTx as NetworkBridgeTxSubsystem, Shared as NetworkBridgeShared,
 };
 pub use polkadot_node_collation_generation::CollationGenerationSubsystem;
 pub use polkadot_node_core_approval_voting::ApprovalVotingSubsystem;
@@ -95,7 +98,8 @@ where
 	pub authority_discovery_service: AuthorityDiscoveryService,
 	/// POV request receiver
 	pub pov_req_receiver: IncomingRequestReceiver<request_v1::PoVFetchingRequest>,
-	pub chunk_req_receiver: IncomingRequestReceiver<request_v1::ChunkFetchingRequest>,
+	pub chunk_req_v1_receiver: IncomingRequestReceiver<request_v1::ChunkFetchingRequest>,
+	pub chunk_req_v2_receiver: IncomingRequestReceiver<request_v2::ChunkFetchingRequest>,
 	pub collation_req_receiver: IncomingRequestReceiver<request_v1::CollationFetchingRequest>,
 	pub available_data_req_receiver:
 		IncomingRequestReceiver<request_v1::AvailableDataFetchingRequest>,
@@ -125,6 +129,8 @@ where
 	pub req_protocol_names: ReqProtocolNames,
 	/// [`PeerSet`] protocol names to protocols mapping.
 	pub peerset_protocol_names: PeerSetProtocolNames,
+	/// Topology for gossiping approvals, bitfields and statements.
+	pub gossip_topology: Arc<dyn GossipTopology>,
 }
 
 /// Obtain a prepared `OverseerBuilder`, that is initialized
@@ -138,7 +144,8 @@ pub fn prepared_overseer_builder<Spawner, RuntimeClient>(
 		sync_service,
 		authority_discovery_service,
 		pov_req_receiver,
-		chunk_req_receiver,
+		chunk_req_v1_receiver,
+		chunk_req_v2_receiver,
 		collation_req_receiver,
 		available_data_req_receiver,
 		statement_req_receiver,
@@ -155,6 +162,7 @@ pub fn prepared_overseer_builder<Spawner, RuntimeClient>(
 		overseer_message_channel_capacity_override,
 		req_protocol_names,
 		peerset_protocol_names,
+		gossip_topology,
 	}: OverseerGenArgs<Spawner, RuntimeClient>,
 ) -> Result<
 	InitializedOverseerBuilder<
@@ -203,6 +211,7 @@ where
 	let spawner = SpawnGlue(spawner);
 
 	let network_bridge_metrics: NetworkBridgeMetrics = Metrics::register(registry)?;
+	let network_bridge_shared = NetworkBridgeShared::default();
 
 	let builder = Overseer::builder()
 		.network_bridge_tx(NetworkBridgeTxSubsystem::new(
@@ -211,6 +220,7 @@ where
 			network_bridge_metrics.clone(),
 			req_protocol_names,
 			peerset_protocol_names.clone(),
+			network_bridge_shared.clone(),
 		))
 		.network_bridge_rx(NetworkBridgeRxSubsystem::new(
 			network_service.clone(),
@@ -218,10 +228,15 @@ where
 			Box::new(sync_service.clone()),
 			network_bridge_metrics,
 			peerset_protocol_names,
+			network_bridge_shared,
 		))
 		.availability_distribution(AvailabilityDistributionSubsystem::new(
 			keystore.clone(),
-			IncomingRequestReceivers { pov_req_receiver, chunk_req_receiver },
+			IncomingRequestReceivers {
+				pov_req_receiver,
+				chunk_req_v1_receiver,
+				chunk_req_v2_receiver,
+			},
 			Metrics::register(registry)?,
 		))
 		.availability_recovery(AvailabilityRecoverySubsystem::with_chunks_if_pov_large(
@@ -234,7 +249,10 @@ where
 			Box::new(sync_service.clone()),
 			Metrics::register(registry)?,
 		))
-		.bitfield_distribution(BitfieldDistributionSubsystem::new(Metrics::register(registry)?))
+		.bitfield_distribution(
+			BitfieldDistributionSubsystem::new(Metrics::register(registry)?)
+				.with_gossip_topology(gossip_topology.clone()),
+		)
 		.bitfield_signing(BitfieldSigningSubsystem::new(
 			keystore.clone(),
 			Metrics::register(registry)?,
@@ -277,13 +295,19 @@ where
 			Metrics::register(registry)?,
 			spawner.clone(),
 		))
-		.statement_distribution(StatementDistributionSubsystem::new(
-			keystore.clone(),
-			statement_req_receiver,
-			Metrics::register(registry)?,
-			rand::rngs::StdRng::from_entropy(),
-		))
-		.approval_distribution(ApprovalDistributionSubsystem::new(Metrics::register(registry)?))
+		.statement_distribution(
+			StatementDistributionSubsystem::new(
+				keystore.clone(),
+				statement_req_receiver,
+				Metrics::register(registry)?,
+				rand::rngs::StdRng::from_entropy(),
+			)
+			.with_gossip_topology(gossip_topology.clone()),
+		)
+		.approval_distribution(
+			ApprovalDistributionSubsystem::new(Metrics::register(registry)?)
+				.with_gossip_topology(gossip_topology),
+		)
 		.approval_voting(ApprovalVotingSubsystem::with_config(
 			approval_voting_config,
 			parachains_db.clone(),
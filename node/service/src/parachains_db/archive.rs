@@ -0,0 +1,543 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+//! Export and import of the parachain's DB.
+//!
+//! The DB is exported to an archive which does not depend on the database backend, so that it can
+//! be imported on another machine or into another backend. The archive consists of a SCALE-encoded
+//! [`Header`] followed by SCALE-encoded [`Record`]s, the last of which is always [`Record::End`].
+//!
+//! The DB is opened in read-only mode for export, it must be at the current version already.
+//! ParityDB does not keep the keys of the columns which are not ordered, so they are recovered
+//! from the subsystems storing data in those columns. The export of a ParityDB parachain's DB
+//! fails if any entry of those columns is not known to the subsystems. The header lists the
+//! columns contained in the archive.
+
+#![cfg(feature = "full-node")]
+
+use super::{columns, other_io_error, upgrade, DatabaseKind, LOG_TARGET};
+use kvdb::KeyValueDB;
+use parity_scale_codec::{Decode, Encode, IoReader};
+use polkadot_node_core_approval_voting::inspect as approval_voting_inspect;
+use polkadot_node_core_av_store::{self as av_store, Config as AvailabilityConfig};
+use polkadot_node_subsystem_util::{
+	database::{paritydb_impl, Database},
+	rolling_session_window,
+};
+use std::{
+	fs::{self, File},
+	io::{self, BufReader, BufWriter, Read, Write},
+	path::Path,
+};
+
+/// Identifies a parachain's DB archive.
+const MAGIC: [u8; 8] = *b"pdbarchv";
+
+/// Current version of the archive format.
+const ARCHIVE_VERSION: u32 = 1;
+
+/// The number of entries written to the database in a single transaction during import.
+const IMPORT_BATCH_SIZE: usize = 1024;
+
+/// The header of an archive.
+#[derive(Encode, Decode)]
+struct Header {
+	magic: [u8; 8],
+	archive_version: u32,
+	/// The version of the DB the archive was exported from, see `upgrade.rs`.
+	db_version: u32,
+	/// The columns contained in the archive.
+	columns: Vec<u32>,
+}
+
+/// A record of an archive.
+#[derive(Encode, Decode)]
+enum Record {
+	/// An entry of one of the columns.
+	Entry { column: u32, key: Vec<u8>, value: Vec<u8> },
+	/// The end of the archive, with the number of entries it contains.
+	End { entries: u64 },
+}
+
+/// Export the RocksDB parachain's DB at `path` to a new archive at `output`.
+///
+/// Returns the number of exported entries.
+pub(crate) fn export_rocksdb(path: &Path, output: &Path) -> io::Result<u64> {
+	prepare_export(path, DatabaseKind::RocksDB)?;
+	let (db, _secondary_dir) = open_rocksdb_secondary(path)?;
+
+	let all_columns = (0..columns::v2::NUM_COLUMNS).collect();
+	let mut writer = ArchiveWriter::create(output, all_columns)?;
+	for column in 0..columns::v2::NUM_COLUMNS {
+		for entry in db.iter(column) {
+			let (key, value) = entry?;
+			writer.write_entry(column, key.to_vec(), value)?;
+		}
+	}
+
+	writer.finish()
+}
+
+/// Export the ParityDB parachain's DB at `path` to a new archive at `output`.
+///
+/// The keys of the unordered columns, which are not kept by ParityDB, are recovered from the
+/// subsystems storing data in them. Fails if any entry of those columns is not found that way.
+/// Returns the number of exported entries.
+pub(crate) fn export_paritydb(path: &Path, output: &Path) -> io::Result<u64> {
+	prepare_export(path, DatabaseKind::ParityDB)?;
+	let db = parity_db::Db::open_read_only(&upgrade::paritydb_version_2_config(path))
+		.map_err(|err| other_io_error(format!("Failed to open ParityDB: {:?}", err)))?;
+
+	// Only values can be iterated in the unordered columns, which is enough to count them.
+	let mut unordered_columns = Vec::new();
+	for column in
+		(0..columns::v2::NUM_COLUMNS).filter(|column| !columns::v2::ORDERED_COL.contains(column))
+	{
+		let mut entries = 0u64;
+		db.iter_column_while(column as u8, |_| {
+			entries += 1;
+			true
+		})
+		.map_err(|err| other_io_error(format!("Failed to iterate ParityDB: {:?}", err)))?;
+		unordered_columns.push((column, entries));
+	}
+	let db = paritydb_impl::DbAdapter::new(db, columns::v2::ORDERED_COL);
+
+	let all_columns = (0..columns::v2::NUM_COLUMNS).collect();
+	let mut writer = ArchiveWriter::create(output, all_columns)?;
+	match write_paritydb_entries(&db, &mut writer, unordered_columns) {
+		Ok(()) => writer.finish(),
+		Err(err) => {
+			// Don't leave an incomplete archive behind.
+			drop(writer);
+			let _ = fs::remove_file(output);
+			Err(err)
+		},
+	}
+}
+
+/// Write the entries of all the columns of a ParityDB parachain's DB, given the number of entries
+/// of each unordered column.
+fn write_paritydb_entries(
+	db: &dyn Database,
+	writer: &mut ArchiveWriter,
+	unordered_columns: Vec<(u32, u64)>,
+) -> io::Result<()> {
+	for column in columns::v2::ORDERED_COL {
+		for entry in db.iter(*column) {
+			let (key, value) = entry?;
+			writer.write_entry(*column, key.to_vec(), value)?;
+		}
+	}
+
+	for (column, entries) in unordered_columns {
+		let mut exported = 0u64;
+		for key in unordered_column_keys(db, column)? {
+			if let Some(value) = db.get(column, &key)? {
+				writer.write_entry(column, key, value)?;
+				exported += 1;
+			}
+		}
+		if exported != entries {
+			return Err(other_io_error(format!(
+				"Column {} of the ParityDB parachain's DB can't be exported, since the keys of {} of its {} entries are unknown",
+				column,
+				entries.saturating_sub(exported),
+				entries,
+			)))
+		}
+	}
+
+	Ok(())
+}
+
+/// The keys which entries of the given unordered column may be stored at, according to the
+/// subsystems storing data in it.
+fn unordered_column_keys(db: &dyn Database, column: u32) -> io::Result<Vec<Vec<u8>>> {
+	match column {
+		columns::v2::COL_AVAILABILITY_DATA => {
+			let config = AvailabilityConfig {
+				col_data: columns::v2::COL_AVAILABILITY_DATA,
+				col_meta: columns::v2::COL_AVAILABILITY_META,
+				max_disk_usage: None,
+				min_keep_finalized_for: None,
+			};
+			av_store::stored_data_keys(db, &config).map_err(|err| {
+				other_io_error(format!("Failed to read the availability store: {:?}", err))
+			})
+		},
+		columns::v2::COL_APPROVAL_DATA =>
+			approval_voting_inspect::stored_keys(db, column).map_err(|err| {
+				other_io_error(format!("Failed to read the approval voting DB: {:?}", err))
+			}),
+		columns::v2::COL_SESSION_WINDOW_DATA =>
+			Ok(vec![rolling_session_window::STORED_ROLLING_SESSION_WINDOW.to_vec()]),
+		_ => Ok(Vec::new()),
+	}
+}
+
+/// Import the archive at `input` into a new RocksDB parachain's DB at `path`.
+///
+/// Returns the number of imported entries.
+pub(crate) fn import_rocksdb(path: &Path, input: &Path) -> io::Result<u64> {
+	let mut reader = ArchiveReader::open(input)?;
+	prepare_import(path, DatabaseKind::RocksDB)?;
+	let db = open_rocksdb(path)?;
+
+	reader.read_entries(|entries| {
+		let mut transaction = db.transaction();
+		for (column, key, value) in entries {
+			transaction.put_vec(column, &key, value);
+		}
+		db.write(transaction)
+	})
+}
+
+/// Import the archive at `input` into a new ParityDB parachain's DB at `path`.
+///
+/// Returns the number of imported entries.
+pub(crate) fn import_paritydb(path: &Path, input: &Path) -> io::Result<u64> {
+	let mut reader = ArchiveReader::open(input)?;
+	prepare_import(path, DatabaseKind::ParityDB)?;
+	let db = parity_db::Db::open_or_create(&upgrade::paritydb_version_2_config(path))
+		.map_err(|err| other_io_error(format!("Failed to open ParityDB: {:?}", err)))?;
+
+	reader.read_entries(|entries| {
+		db.commit(entries.into_iter().map(|(column, key, value)| (column as u8, key, Some(value))))
+			.map_err(|err| other_io_error(format!("Failed to write to ParityDB: {:?}", err)))
+	})
+}
+
+/// Open the RocksDB parachain's DB at `path` as a secondary instance, which can only read the DB.
+///
+/// The secondary instance keeps its own info logs in the returned temporary directory, away from
+/// the node's files. The directory must be kept until the DB is dropped.
+pub(super) fn open_rocksdb_secondary(
+	path: &Path,
+) -> io::Result<(kvdb_rocksdb::Database, tempfile::TempDir)> {
+	use kvdb_rocksdb::{Database, DatabaseConfig};
+
+	let path_str = path
+		.to_str()
+		.ok_or_else(|| other_io_error(format!("Bad database path: {:?}", path)))?;
+	let secondary_dir = tempfile::Builder::new().prefix("polkadot-parachains-db-").tempdir()?;
+
+	let mut db_config = DatabaseConfig::with_columns(columns::v2::NUM_COLUMNS);
+	db_config.secondary = Some(secondary_dir.path().to_owned());
+	let db = Database::open(&db_config, path_str)?;
+
+	Ok((db, secondary_dir))
+}
+
+/// Make sure there is a DB at `path` and that it is at the current version, without upgrading it.
+fn prepare_export(path: &Path, db_kind: DatabaseKind) -> io::Result<()> {
+	if is_empty(path) {
+		return Err(other_io_error(format!("No parachain's DB found at {:?}", path)))
+	}
+	upgrade::ensure_current_version(path, db_kind)?;
+	Ok(())
+}
+
+/// Make sure there is no DB at `path` yet, and set up the version of the new DB.
+fn prepare_import(path: &Path, db_kind: DatabaseKind) -> io::Result<()> {
+	if !is_empty(path) {
+		return Err(other_io_error(format!(
+			"Refusing to import into the existing parachain's DB at {:?}",
+			path
+		)))
+	}
+	// Only writes the version file, since there is no DB to upgrade.
+	upgrade::try_upgrade_db(path, db_kind)?;
+	Ok(())
+}
+
+fn is_empty(path: &Path) -> bool {
+	path.read_dir().map_or(true, |mut dir| dir.next().is_none())
+}
+
+fn open_rocksdb(path: &Path) -> io::Result<kvdb_rocksdb::Database> {
+	use kvdb_rocksdb::{Database, DatabaseConfig};
+
+	let path_str = path
+		.to_str()
+		.ok_or_else(|| other_io_error(format!("Bad database path: {:?}", path)))?;
+	let db_config = DatabaseConfig::with_columns(columns::v2::NUM_COLUMNS);
+	Database::open(&db_config, path_str)
+}
+
+/// Writes the records of an archive.
+struct ArchiveWriter {
+	output: BufWriter<File>,
+	entries: u64,
+}
+
+impl ArchiveWriter {
+	/// Create a new archive containing the given columns. Fails if the file exists already.
+	fn create(path: &Path, columns: Vec<u32>) -> io::Result<Self> {
+		let file = File::options().write(true).create_new(true).open(path)?;
+		let mut output = BufWriter::new(file);
+		let header = Header {
+			magic: MAGIC,
+			archive_version: ARCHIVE_VERSION,
+			db_version: upgrade::CURRENT_VERSION,
+			columns,
+		};
+		output.write_all(&header.encode())?;
+
+		Ok(Self { output, entries: 0 })
+	}
+
+	fn write_entry(&mut self, column: u32, key: Vec<u8>, value: Vec<u8>) -> io::Result<()> {
+		self.output.write_all(&Record::Entry { column, key, value }.encode())?;
+		self.entries += 1;
+		Ok(())
+	}
+
+	/// Terminate the archive and return the number of entries written.
+	fn finish(mut self) -> io::Result<u64> {
+		self.output.write_all(&Record::End { entries: self.entries }.encode())?;
+		self.output.into_inner().map_err(|err| err.into_error())?.sync_all()?;
+		Ok(self.entries)
+	}
+}
+
+/// Reads the records of an archive.
+struct ArchiveReader {
+	input: BufReader<File>,
+	columns: Vec<u32>,
+}
+
+impl ArchiveReader {
+	/// Open an archive and check that it can be imported.
+	fn open(path: &Path) -> io::Result<Self> {
+		let mut input = BufReader::new(File::open(path)?);
+		let header: Header = decode(&mut input)?;
+
+		if header.magic != MAGIC {
+			return Err(other_io_error(format!("{:?} is not a parachain's DB archive", path)))
+		}
+		if header.archive_version != ARCHIVE_VERSION {
+			return Err(other_io_error(format!(
+				"Unsupported archive version (expected {}, found {})",
+				ARCHIVE_VERSION, header.archive_version,
+			)))
+		}
+		if header.db_version != upgrade::CURRENT_VERSION {
+			return Err(other_io_error(format!(
+				"The archive was exported from another DB version (expected {}, found {})",
+				upgrade::CURRENT_VERSION,
+				header.db_version,
+			)))
+		}
+		if let Some(column) =
+			header.columns.iter().find(|column| **column >= columns::v2::NUM_COLUMNS)
+		{
+			return Err(other_io_error(format!("Unknown column {} in the archive", column)))
+		}
+
+		gum::info!(target: LOG_TARGET, columns = ?header.columns, "Importing parachain's DB archive");
+
+		Ok(Self { input, columns: header.columns })
+	}
+
+	/// Read all of the entries and pass them to `write` in batches. Returns the number of entries
+	/// read.
+	fn read_entries(
+		&mut self,
+		mut write: impl FnMut(Vec<(u32, Vec<u8>, Vec<u8>)>) -> io::Result<()>,
+	) -> io::Result<u64> {
+		let mut batch = Vec::with_capacity(IMPORT_BATCH_SIZE);
+		let mut read = 0;
+		loop {
+			match decode(&mut self.input)? {
+				Record::Entry { column, key, value } => {
+					if !self.columns.contains(&column) {
+						return Err(other_io_error(format!(
+							"Entry of column {} which is not listed in the archive",
+							column
+						)))
+					}
+					batch.push((column, key, value));
+					read += 1;
+					if batch.len() == IMPORT_BATCH_SIZE {
+						write(std::mem::replace(
+							&mut batch,
+							Vec::with_capacity(IMPORT_BATCH_SIZE),
+						))?;
+					}
+				},
+				Record::End { entries } => {
+					if entries != read {
+						return Err(other_io_error(format!(
+							"The archive is corrupted (expected {} entries, found {})",
+							entries, read,
+						)))
+					}
+					write(batch)?;
+					return Ok(read)
+				},
+			}
+		}
+	}
+}
+
+fn decode<T: Decode>(input: &mut impl Read) -> io::Result<T> {
+	T::decode(&mut IoReader(input))
+		.map_err(|err| other_io_error(format!("Failed to decode the archive: {}", err)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{columns::v2::*, *};
+
+	#[test]
+	fn rocksdb_archive_roundtrip_into_paritydb() {
+		let dir = tempfile::tempdir().unwrap();
+		let rocksdb_path = dir.path().join("rocksdb");
+		let paritydb_path = dir.path().join("paritydb");
+		let archive = dir.path().join("archive");
+
+		{
+			fs::create_dir_all(&rocksdb_path).unwrap();
+			let db = open_rocksdb(&rocksdb_path).unwrap();
+			let mut transaction = db.transaction();
+			transaction.put(COL_DISPUTE_COORDINATOR_DATA, b"dispute", b"votes");
+			transaction.put(COL_CHAIN_SELECTION_DATA, b"block", b"entry");
+			transaction.put(COL_APPROVAL_DATA, b"approval", b"state");
+			db.write(transaction).unwrap();
+		}
+
+		assert_eq!(export_rocksdb(&rocksdb_path, &archive).unwrap(), 3);
+		assert_eq!(import_paritydb(&paritydb_path, &archive).unwrap(), 3);
+
+		let db = parity_db::Db::open(&upgrade::paritydb_version_2_config(&paritydb_path)).unwrap();
+		assert_eq!(
+			db.get(COL_DISPUTE_COORDINATOR_DATA as u8, b"dispute").unwrap(),
+			Some(b"votes".to_vec())
+		);
+		assert_eq!(
+			db.get(COL_CHAIN_SELECTION_DATA as u8, b"block").unwrap(),
+			Some(b"entry".to_vec())
+		);
+		assert_eq!(db.get(COL_APPROVAL_DATA as u8, b"approval").unwrap(), Some(b"state".to_vec()));
+	}
+
+	/// Entries of every column, following the layouts of the subsystems for the unordered ones.
+	fn entries_of_every_column() -> Vec<(u32, Vec<u8>, Vec<u8>)> {
+		use parity_scale_codec::Compact;
+
+		let candidate_hash = [1u8; 32];
+		let block_number = 10u32;
+		// `CandidateMeta` of the availability store: unavailable since the epoch, with the
+		// available data and the second of two chunks stored.
+		let candidate_meta = (0u8, [0u8; 8], true, Compact(2u32), 0b10u8).encode();
+
+		vec![
+			(COL_AVAILABILITY_DATA, (b"available", candidate_hash).encode(), b"data".to_vec()),
+			(COL_AVAILABILITY_DATA, (b"chunk", candidate_hash, 1u32).encode(), b"chunk".to_vec()),
+			(COL_AVAILABILITY_META, (b"meta", candidate_hash).encode(), candidate_meta),
+			(
+				COL_APPROVAL_DATA,
+				b"Approvals_StoredBlocks".to_vec(),
+				(block_number, block_number + 1).encode(),
+			),
+			(
+				COL_APPROVAL_DATA,
+				[&b"Approvals_at"[..], &block_number.encode()].concat(),
+				Vec::<[u8; 32]>::new().encode(),
+			),
+			(COL_CHAIN_SELECTION_DATA, b"block".to_vec(), b"entry".to_vec()),
+			(COL_DISPUTE_COORDINATOR_DATA, b"dispute".to_vec(), b"votes".to_vec()),
+			(
+				COL_SESSION_WINDOW_DATA,
+				rolling_session_window::STORED_ROLLING_SESSION_WINDOW.to_vec(),
+				b"sessions".to_vec(),
+			),
+		]
+	}
+
+	fn create_paritydb(path: &Path, entries: &[(u32, Vec<u8>, Vec<u8>)]) {
+		fs::create_dir_all(path).unwrap();
+		upgrade::try_upgrade_db(path, DatabaseKind::ParityDB).unwrap();
+		let db = parity_db::Db::open_or_create(&upgrade::paritydb_version_2_config(path)).unwrap();
+		db.commit(
+			entries
+				.iter()
+				.map(|(column, key, value)| (*column as u8, key.clone(), Some(value.clone()))),
+		)
+		.unwrap();
+	}
+
+	#[test]
+	fn paritydb_archive_roundtrip_into_rocksdb() {
+		let dir = tempfile::tempdir().unwrap();
+		let paritydb_path = dir.path().join("paritydb");
+		let rocksdb_path = dir.path().join("rocksdb");
+		let archive = dir.path().join("archive");
+
+		let entries = entries_of_every_column();
+		create_paritydb(&paritydb_path, &entries);
+
+		assert_eq!(export_paritydb(&paritydb_path, &archive).unwrap(), entries.len() as u64);
+		assert_eq!(import_rocksdb(&rocksdb_path, &archive).unwrap(), entries.len() as u64);
+
+		let db = open_rocksdb(&rocksdb_path).unwrap();
+		for (column, key, value) in entries {
+			assert_eq!(db.get(column, &key).unwrap(), Some(value));
+		}
+	}
+
+	#[test]
+	fn paritydb_export_fails_with_unknown_unordered_entries() {
+		let dir = tempfile::tempdir().unwrap();
+		let paritydb_path = dir.path().join("paritydb");
+		let archive = dir.path().join("archive");
+
+		let mut entries = entries_of_every_column();
+		entries.push((COL_APPROVAL_DATA, b"approval".to_vec(), b"state".to_vec()));
+		create_paritydb(&paritydb_path, &entries);
+
+		assert!(export_paritydb(&paritydb_path, &archive).is_err());
+		assert!(!archive.exists());
+	}
+
+	#[test]
+	fn export_does_not_upgrade_db() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("db");
+		let archive = dir.path().join("archive");
+
+		fs::create_dir_all(&path).unwrap();
+		open_rocksdb(&path).unwrap();
+		fs::write(path.join("parachain_db_version"), "1").unwrap();
+
+		assert!(export_rocksdb(&path, &archive).is_err());
+		assert_eq!(fs::read_to_string(path.join("parachain_db_version")).unwrap(), "1");
+	}
+
+	#[test]
+	fn import_refuses_existing_db() {
+		let dir = tempfile::tempdir().unwrap();
+		let source_path = dir.path().join("source");
+		let target_path = dir.path().join("target");
+		let archive = dir.path().join("archive");
+
+		fs::create_dir_all(&source_path).unwrap();
+		open_rocksdb(&source_path).unwrap();
+		export_rocksdb(&source_path, &archive).unwrap();
+
+		fs::create_dir_all(&target_path).unwrap();
+		fs::write(target_path.join("something"), b"").unwrap();
+		assert!(import_rocksdb(&target_path, &archive).is_err());
+	}
+}
@@ -15,9 +15,14 @@
 
 #[cfg(feature = "full-node")]
 use {
-	polkadot_node_subsystem_util::database::Database, std::io, std::path::PathBuf, std::sync::Arc,
+	polkadot_node_subsystem_util::database::Database,
+	std::io,
+	std::path::{Path, PathBuf},
+	std::sync::Arc,
 };
 
+#[cfg(feature = "full-node")]
+mod archive;
 #[cfg(feature = "full-node")]
 mod upgrade;
 
@@ -175,3 +180,108 @@ pub fn open_creating_paritydb(
 	);
 	Ok(Arc::new(db))
 }
+
+/// A parachain's DB opened in read-only mode, see [`open_read_only`].
+#[cfg(feature = "full-node")]
+pub(crate) struct ReadOnlyDatabase {
+	db: Arc<dyn Database>,
+	/// The directory of the RocksDB secondary instance, removed once the DB is dropped.
+	_secondary_dir: Option<tempfile::TempDir>,
+}
+
+#[cfg(feature = "full-node")]
+impl ReadOnlyDatabase {
+	/// The opened DB, which must not outlive `self`.
+	pub(crate) fn db(&self) -> Arc<dyn Database> {
+		self.db.clone()
+	}
+}
+
+/// Open the existing parachain's DB stored in `root` by the given backend, in read-only mode.
+///
+/// The DB is not upgraded, hence it must be at the current version already. RocksDB is opened as a
+/// secondary instance, so that the DB can be read while the node is running.
+#[cfg(feature = "full-node")]
+pub(crate) fn open_read_only(db_kind: DatabaseKind, root: &Path) -> io::Result<ReadOnlyDatabase> {
+	match db_kind {
+		DatabaseKind::RocksDB => {
+			let path = root.join("parachains").join("db");
+			upgrade::ensure_current_version(&path, DatabaseKind::RocksDB)?;
+
+			let (db, secondary_dir) = archive::open_rocksdb_secondary(&path)?;
+			let db = polkadot_node_subsystem_util::database::kvdb_impl::DbAdapter::new(
+				db,
+				columns::v2::ORDERED_COL,
+			);
+			Ok(ReadOnlyDatabase { db: Arc::new(db), _secondary_dir: Some(secondary_dir) })
+		},
+		DatabaseKind::ParityDB => {
+			let path = root.join("parachains");
+			upgrade::ensure_current_version(&path, DatabaseKind::ParityDB)?;
+
+			let db = parity_db::Db::open_read_only(&upgrade::paritydb_version_2_config(&path))
+				.map_err(|err| other_io_error(format!("Failed to open ParityDB: {:?}", err)))?;
+			let db = polkadot_node_subsystem_util::database::paritydb_impl::DbAdapter::new(
+				db,
+				columns::v2::ORDERED_COL,
+			);
+			Ok(ReadOnlyDatabase { db: Arc::new(db), _secondary_dir: None })
+		},
+	}
+}
+
+/// Migrate the RocksDB parachain's DB stored in `rocksdb_root` to a ParityDB parachain's DB stored
+/// in `paritydb_root`.
+///
+/// Does nothing if there is no RocksDB parachain's DB or if there is a ParityDB one already. The
+/// RocksDB parachain's DB is kept and can be removed once the migration is complete.
+#[cfg(feature = "full-node")]
+pub fn migrate_rocksdb_to_paritydb(rocksdb_root: &Path, paritydb_root: &Path) -> io::Result<()> {
+	let rocksdb_path = rocksdb_root.join("parachains").join("db");
+	let paritydb_path = paritydb_root.join("parachains");
+	let is_empty = |path: &Path| path.read_dir().map_or(true, |mut d| d.next().is_none());
+	if is_empty(&rocksdb_path) || !is_empty(&paritydb_path) {
+		return Ok(())
+	}
+
+	gum::info!(
+		target: LOG_TARGET,
+		?rocksdb_path,
+		?paritydb_path,
+		"Migrating parachains db from RocksDB to ParityDB ...",
+	);
+	let records = upgrade::migrate_rocksdb_to_paritydb(&rocksdb_path, &paritydb_path)?;
+	gum::info!(
+		target: LOG_TARGET,
+		records,
+		"Migration complete! The RocksDB parachains db at {:?} is not used anymore and can be removed.",
+		rocksdb_path,
+	);
+
+	Ok(())
+}
+
+/// Export the parachain's DB stored in `root` by the given backend to a new archive at `output`.
+///
+/// Returns the number of exported entries. The DB is opened in read-only mode, see
+/// [`archive::export_paritydb`] for the limits of the export from ParityDB.
+#[cfg(feature = "full-node")]
+pub(crate) fn export(db_kind: DatabaseKind, root: &Path, output: &Path) -> io::Result<u64> {
+	match db_kind {
+		DatabaseKind::RocksDB =>
+			archive::export_rocksdb(&root.join("parachains").join("db"), output),
+		DatabaseKind::ParityDB => archive::export_paritydb(&root.join("parachains"), output),
+	}
+}
+
+/// Import the archive at `input` into a new parachain's DB stored in `root` by the given backend.
+///
+/// Returns the number of imported entries. Fails if there is a parachain's DB in `root` already.
+#[cfg(feature = "full-node")]
+pub(crate) fn import(db_kind: DatabaseKind, root: &Path, input: &Path) -> io::Result<u64> {
+	match db_kind {
+		DatabaseKind::RocksDB =>
+			archive::import_rocksdb(&root.join("parachains").join("db"), input),
+		DatabaseKind::ParityDB => archive::import_paritydb(&root.join("parachains"), input),
+	}
+}
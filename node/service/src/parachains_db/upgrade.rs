@@ -28,7 +28,10 @@ type Version = u32;
 const VERSION_FILE_NAME: &'static str = "parachain_db_version";
 
 /// Current db version.
-const CURRENT_VERSION: Version = 2;
+pub(crate) const CURRENT_VERSION: Version = 2;
+
+/// The number of records written to ParityDB at once when migrating from RocksDB.
+const MIGRATION_BATCH_SIZE: usize = 1024;
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -38,6 +41,10 @@ pub enum Error {
 	CorruptedVersionFile,
 	#[error("Parachains DB has a future version (expected {current:?}, found {got:?})")]
 	FutureVersion { current: Version, got: Version },
+	#[error("Parachains DB is not at the current version (expected {current:?}, found {got:?}), start the node to upgrade it")]
+	OutdatedVersion { current: Version, got: Option<Version> },
+	#[error("Migrated column {column} has {got} matching records, expected {expected}")]
+	MigrationMismatch { column: u32, expected: u64, got: u64 },
 }
 
 impl From<Error> for io::Error {
@@ -75,6 +82,19 @@ pub(crate) fn try_upgrade_db(db_path: &Path, db_kind: DatabaseKind) -> Result<()
 	update_version(db_path)
 }
 
+/// Check that the parachain's database at the given path is at the current version, without
+/// upgrading it.
+pub(crate) fn ensure_current_version(db_path: &Path, db_kind: DatabaseKind) -> Result<(), Error> {
+	match get_db_version(db_path)? {
+		Some(CURRENT_VERSION) => Ok(()),
+		Some(v) if v > CURRENT_VERSION =>
+			Err(Error::FutureVersion { current: CURRENT_VERSION, got: v }),
+		// No version file. For `RocksDB` there is nothing to upgrade, see `try_upgrade_db`.
+		None if db_kind == DatabaseKind::RocksDB => Ok(()),
+		got => Err(Error::OutdatedVersion { current: CURRENT_VERSION, got }),
+	}
+}
+
 /// Reads current database version from the file at given path.
 /// If the file does not exist returns `None`, otherwise the version stored in the file.
 fn get_db_version(path: &Path) -> Result<Option<Version>, Error> {
@@ -217,6 +237,128 @@ fn paritydb_fix_columns(
 	Ok(())
 }
 
+/// Copy the RocksDB parachain's DB at `rocksdb_path` into a new ParityDB parachain's DB at
+/// `paritydb_path`, using the ParityDB column options of the current version.
+///
+/// The records are copied to a temporary directory which is moved to `paritydb_path` only once all
+/// of them have been verified. The RocksDB is left untouched, so that an interrupted or failed
+/// migration can simply be started over. Returns the number of migrated records.
+pub(crate) fn migrate_rocksdb_to_paritydb(
+	rocksdb_path: &Path,
+	paritydb_path: &Path,
+) -> Result<u64, Error> {
+	let migration_path = paritydb_path.with_extension("migration");
+	if migration_path.exists() {
+		gum::info!(target: LOG_TARGET, "Removing the leftovers of an interrupted migration ...");
+		fs::remove_dir_all(&migration_path)?;
+	}
+
+	// The columns of the RocksDB need to match the ones of the current version.
+	try_upgrade_db(rocksdb_path, DatabaseKind::RocksDB)?;
+
+	match copy_rocksdb_to_paritydb(rocksdb_path, &migration_path) {
+		Ok(records) => {
+			update_version(&migration_path)?;
+			fs::rename(&migration_path, paritydb_path)?;
+			Ok(records)
+		},
+		Err(err) => {
+			if let Err(err) = fs::remove_dir_all(&migration_path) {
+				gum::warn!(target: LOG_TARGET, ?err, "Failed to clean up after the failed migration");
+			}
+			Err(err)
+		},
+	}
+}
+
+fn copy_rocksdb_to_paritydb(rocksdb_path: &Path, paritydb_path: &Path) -> Result<u64, Error> {
+	use kvdb::KeyValueDB;
+	use kvdb_rocksdb::{Database, DatabaseConfig};
+
+	let db_path = rocksdb_path
+		.to_str()
+		.ok_or_else(|| super::other_io_error("Invalid database path".into()))?;
+	let db_cfg = DatabaseConfig::with_columns(columns::v2::NUM_COLUMNS);
+	let rocksdb = Database::open(&db_cfg, db_path)?;
+
+	fs::create_dir_all(paritydb_path)?;
+	let paritydb = parity_db::Db::open_or_create(&paritydb_version_2_config(paritydb_path))
+		.map_err(|e| other_io_error(format!("Error opening ParityDB {:?}", e)))?;
+
+	let mut records = 0;
+	for column in 0..columns::v2::NUM_COLUMNS {
+		let mut batch = Vec::with_capacity(MIGRATION_BATCH_SIZE);
+		let mut copied = 0;
+		for entry in rocksdb.iter(column) {
+			let (key, value) = entry?;
+			batch.push((column as u8, key.to_vec(), Some(value)));
+			copied += 1;
+			if batch.len() == MIGRATION_BATCH_SIZE {
+				paritydb
+					.commit(std::mem::take(&mut batch))
+					.map_err(|e| other_io_error(format!("Error writing to ParityDB {:?}", e)))?;
+			}
+		}
+		paritydb
+			.commit(batch)
+			.map_err(|e| other_io_error(format!("Error writing to ParityDB {:?}", e)))?;
+
+		verify_migrated_column(&rocksdb, &paritydb, column, copied)?;
+		gum::debug!(target: LOG_TARGET, column, records = copied, "Migrated column");
+		records += copied;
+	}
+
+	Ok(records)
+}
+
+/// Check that the column of the ParityDB holds exactly the `expected` records of the RocksDB column.
+///
+/// The records of the columns without a btree index can only be looked up by key, the others are
+/// counted as well.
+fn verify_migrated_column(
+	rocksdb: &kvdb_rocksdb::Database,
+	paritydb: &parity_db::Db,
+	column: u32,
+	expected: u64,
+) -> Result<(), Error> {
+	use kvdb::KeyValueDB;
+
+	let mut matching = 0;
+	for entry in rocksdb.iter(column) {
+		let (key, value) = entry?;
+		let migrated = paritydb
+			.get(column as u8, &key)
+			.map_err(|e| other_io_error(format!("Error reading from ParityDB {:?}", e)))?;
+		if migrated == Some(value) {
+			matching += 1;
+		}
+	}
+	if matching != expected {
+		return Err(Error::MigrationMismatch { column, expected, got: matching })
+	}
+
+	if columns::v2::ORDERED_COL.contains(&column) {
+		let mut stored = 0;
+		let mut iter = paritydb
+			.iter(column as u8)
+			.map_err(|e| other_io_error(format!("Error reading from ParityDB {:?}", e)))?;
+		iter.seek_to_first()
+			.map_err(|e| other_io_error(format!("Error reading from ParityDB {:?}", e)))?;
+		while iter
+			.next()
+			.map_err(|e| other_io_error(format!("Error reading from ParityDB {:?}", e)))?
+			.is_some()
+		{
+			stored += 1;
+		}
+		if stored != expected {
+			return Err(Error::MigrationMismatch { column, expected, got: stored })
+		}
+	}
+
+	Ok(())
+}
+
 /// Database configuration for version 1.
 pub(crate) fn paritydb_version_1_config(path: &Path) -> parity_db::Options {
 	let mut options =
@@ -416,4 +558,64 @@ mod tests {
 			Some("0xdeadb00b".as_bytes().to_vec())
 		);
 	}
+
+	#[test]
+	fn test_rocksdb_migrate_to_paritydb() {
+		use kvdb::KeyValueDB;
+		use kvdb_rocksdb::{Database, DatabaseConfig};
+
+		let db_dir = tempfile::tempdir().unwrap();
+		let rocksdb_path = db_dir.path().join("rocksdb");
+		let paritydb_path = db_dir.path().join("paritydb");
+		{
+			let db_cfg = DatabaseConfig::with_columns(columns::v2::NUM_COLUMNS);
+			let db = Database::open(&db_cfg, rocksdb_path.to_str().unwrap()).unwrap();
+			let mut transaction = db.transaction();
+			for column in 0..columns::v2::NUM_COLUMNS {
+				for i in 0..3u8 {
+					transaction.put(column, &[i], &[column as u8, i]);
+				}
+			}
+			db.write(transaction).unwrap();
+		}
+		fs::write(version_file_path(&rocksdb_path), "2").expect("Failed to write DB version");
+
+		// Leftovers of an interrupted migration are discarded.
+		fs::create_dir_all(paritydb_path.with_extension("migration")).unwrap();
+		fs::write(paritydb_path.with_extension("migration").join("garbage"), b"").unwrap();
+
+		let records = migrate_rocksdb_to_paritydb(&rocksdb_path, &paritydb_path).unwrap();
+		assert_eq!(records, 3 * columns::v2::NUM_COLUMNS as u64);
+		assert!(!paritydb_path.with_extension("migration").exists());
+		assert_eq!(get_db_version(&paritydb_path).unwrap(), Some(CURRENT_VERSION));
+
+		let db = parity_db::Db::open(&paritydb_version_2_config(&paritydb_path)).unwrap();
+		for column in 0..columns::v2::NUM_COLUMNS {
+			for i in 0..3u8 {
+				assert_eq!(db.get(column as u8, &[i]).unwrap(), Some(vec![column as u8, i]));
+			}
+		}
+	}
+
+	#[test]
+	fn ensure_current_version_accepts_rocksdb_without_version_file() {
+		let db_dir = tempfile::tempdir().unwrap();
+		let path = db_dir.path();
+
+		ensure_current_version(path, DatabaseKind::RocksDB).unwrap();
+		assert!(matches!(
+			ensure_current_version(path, DatabaseKind::ParityDB),
+			Err(Error::OutdatedVersion { got: None, .. })
+		));
+
+		fs::write(version_file_path(path), "1").unwrap();
+		assert!(matches!(
+			ensure_current_version(path, DatabaseKind::RocksDB),
+			Err(Error::OutdatedVersion { got: Some(1), .. })
+		));
+
+		fs::write(version_file_path(path), CURRENT_VERSION.to_string()).unwrap();
+		ensure_current_version(path, DatabaseKind::RocksDB).unwrap();
+		ensure_current_version(path, DatabaseKind::ParityDB).unwrap();
+	}
 }
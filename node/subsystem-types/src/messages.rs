@@ -22,7 +22,7 @@
 //!
 //! Subsystems' APIs are defined separately from their implementation, leading to easier mocking.
 
-use futures::channel::oneshot;
+use futures::channel::{mpsc, oneshot};
 use sc_network::Multiaddr;
 use thiserror::Error;
 
@@ -33,13 +33,17 @@ use polkadot_node_network_protocol::{
 	UnifiedReputationChange,
 };
 use polkadot_node_primitives::{
-	approval::{BlockApprovalMeta, IndirectAssignmentCert, IndirectSignedApprovalVote},
+	approval::{
+		BlockApprovalMeta, CandidateBitfield, IndirectAssignmentCertV2,
+		IndirectSignedApprovalVoteV2,
+	},
 	AvailableData, BabeEpoch, BlockWeight, CandidateVotes, CollationGenerationConfig,
-	CollationSecondedSignal, DisputeMessage, DisputeStatus, ErasureChunk, PoV,
-	SignedDisputeStatement, SignedFullStatement, ValidationResult,
+	CollationSecondedSignal, DisputeMessage, DisputeParticipation, DisputeStatus, DisputeUpdate,
+	ErasureChunk, PoV, SignedDisputeStatement, SignedFullStatement, StoredCandidateInfo,
+	ValidationResult,
 };
 use polkadot_primitives::{
-	AuthorityDiscoveryId, BackedCandidate, BlockNumber, CandidateEvent, CandidateHash,
+	vstaging, AuthorityDiscoveryId, BackedCandidate, BlockNumber, CandidateEvent, CandidateHash,
 	CandidateIndex, CandidateReceipt, CollatorId, CommittedCandidateReceipt, CoreState,
 	DisputeState, ExecutorParams, GroupIndex, GroupRotationInfo, Hash, Header as BlockHeader,
 	Id as ParaId, InboundDownwardMessage, InboundHrmpMessage, MultiDisputeStatementSet,
@@ -97,6 +101,16 @@ pub enum PreCheckOutcome {
 	Failed,
 }
 
+/// The priority of a PVF execution requested from the candidate validation subsystem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PvfExecPriority {
+	/// Executions which can wait for the critical ones, e.g. approval checking.
+	Normal,
+	/// Executions on the critical path, e.g. backing and dispute participation. These may use the
+	/// execution workers reserved for critical jobs.
+	Critical,
+}
+
 /// Messages received by the Validation subsystem.
 ///
 /// ## Validation Requests
@@ -121,6 +135,8 @@ pub enum CandidateValidationMessage {
 		Arc<PoV>,
 		/// Execution timeout
 		PvfExecTimeoutKind,
+		/// Execution priority
+		PvfExecPriority,
 		oneshot::Sender<Result<ValidationResult, ValidationFailed>>,
 	),
 	/// Validate a candidate with provided, exhaustive parameters for validation.
@@ -139,6 +155,8 @@ pub enum CandidateValidationMessage {
 		Arc<PoV>,
 		/// Execution timeout
 		PvfExecTimeoutKind,
+		/// Execution priority
+		PvfExecPriority,
 		oneshot::Sender<Result<ValidationResult, ValidationFailed>>,
 	),
 	/// Try to compile the given validation code and send back
@@ -247,6 +265,15 @@ pub enum DisputeCoordinatorMessage {
 		Vec<(SessionIndex, CandidateHash)>,
 		oneshot::Sender<Vec<(SessionIndex, CandidateHash, CandidateVotes)>>,
 	),
+	/// Get the state of the local participation in disputes about the given candidates.
+	QueryParticipation(
+		Vec<(SessionIndex, CandidateHash)>,
+		oneshot::Sender<Vec<(SessionIndex, CandidateHash, DisputeParticipation)>>,
+	),
+	/// Subscribe to the changes of disputes: a [`DisputeUpdate`] is sent whenever the votes or the
+	/// status of a dispute change. The subscription ends when the receiver is dropped, or when it
+	/// falls behind and the channel is full.
+	SubscribeDisputeUpdates(mpsc::Sender<DisputeUpdate>),
 	/// Sign and issue local dispute votes. A value of `true` indicates validity, and `false` invalidity.
 	IssueLocalStatement(SessionIndex, CandidateHash, CandidateReceipt, bool),
 	/// Determine the highest undisputed block within the given chain, based on where candidates
@@ -426,7 +453,7 @@ pub enum AvailabilityStoreMessage {
 	/// megabytes of data to get a single bit of information.
 	QueryDataAvailability(CandidateHash, oneshot::Sender<bool>),
 
-	/// Query an `ErasureChunk` from the AV store by the candidate hash and validator index.
+	/// Query an `ErasureChunk` from the AV store by the candidate hash and chunk index.
 	QueryChunk(CandidateHash, ValidatorIndex, oneshot::Sender<Option<ErasureChunk>>),
 
 	/// Get the size of an `ErasureChunk` from the AV store by the candidate hash.
@@ -439,9 +466,26 @@ pub enum AvailabilityStoreMessage {
 	///
 	/// This is useful in cases like bitfield signing, when existence
 	/// matters, but we don't want to necessarily pass around large
-	/// quantities of data to get a single bit of information.
+	/// quantities of data to get a single bit of information. The chunk is identified by its
+	/// chunk index, see `polkadot_node_primitives::AvailabilityChunkMapping`.
 	QueryChunkAvailability(CandidateHash, ValidatorIndex, oneshot::Sender<bool>),
 
+	/// Query what is stored about the given candidate, for inspecting the store.
+	QueryStoredCandidate(CandidateHash, oneshot::Sender<Option<StoredCandidateInfo>>),
+
+	/// List what is stored about all candidates, for inspecting the store.
+	///
+	/// Candidates are ordered by their hash. At most `limit` candidates with a hash greater than
+	/// `start_after` are returned, so the whole store can be listed in pages.
+	ListStoredCandidates {
+		/// Only list candidates with a greater hash than this one.
+		start_after: Option<CandidateHash>,
+		/// The maximum number of candidates to list.
+		limit: usize,
+		/// Sending side of the channel to send result to.
+		tx: oneshot::Sender<Vec<StoredCandidateInfo>>,
+	},
+
 	/// Store an `ErasureChunk` in the AV store.
 	///
 	/// Return `Ok(())` if the store operation succeeded, `Err(())` if it failed.
@@ -606,6 +650,27 @@ pub enum RuntimeApiRequest {
 	),
 	/// Returns all on-chain disputes at given block number. Available in `v3`.
 	Disputes(RuntimeApiSender<Vec<(SessionIndex, CandidateHash, DisputeState<BlockNumber>)>>),
+	/// Returns a list of validators that lost a past session dispute and need to be slashed.
+	/// `VStaging`
+	UnappliedSlashes(
+		RuntimeApiSender<Vec<(SessionIndex, CandidateHash, vstaging::slashing::PendingSlashes)>>,
+	),
+	/// Returns a merkle proof of a validator session key.
+	/// `VStaging`
+	KeyOwnershipProof(
+		ValidatorId,
+		RuntimeApiSender<Option<vstaging::slashing::OpaqueKeyOwnershipProof>>,
+	),
+	/// Submits an unsigned extrinsic to slash validator who lost a past session dispute.
+	/// `VStaging`
+	SubmitReportDisputeLost(
+		vstaging::slashing::DisputeProof,
+		vstaging::slashing::OpaqueKeyOwnershipProof,
+		RuntimeApiSender<Option<()>>,
+	),
+	/// Get the node features enabled in the current session.
+	/// `VStaging`
+	NodeFeatures(RuntimeApiSender<vstaging::NodeFeatures>),
 }
 
 impl RuntimeApiRequest {
@@ -616,6 +681,12 @@ impl RuntimeApiRequest {
 
 	/// `ExecutorParams`
 	pub const EXECUTOR_PARAMS_RUNTIME_REQUIREMENT: u32 = 4;
+
+	/// `UnappliedSlashes`, `KeyOwnershipProof` and `SubmitReportDisputeLost`
+	pub const SLASHING_RUNTIME_REQUIREMENT: u32 = 5;
+
+	/// `NodeFeatures`
+	pub const NODE_FEATURES_RUNTIME_REQUIREMENT: u32 = 6;
 }
 
 /// A message to the Runtime API subsystem.
@@ -708,6 +779,8 @@ pub enum AssignmentCheckError {
 	InvalidCandidateIndex(CandidateIndex),
 	#[error("Invalid candidate {0}: {1:?}")]
 	InvalidCandidate(CandidateIndex, CandidateHash),
+	#[error("Assignment claims no candidates")]
+	NoClaimedCandidates,
 	#[error("Invalid cert: {0:?}, reason: {1}")]
 	InvalidCert(ValidatorIndex, String),
 	#[error("Internal state mismatch: {0:?}, {1:?}")]
@@ -775,17 +848,19 @@ pub struct HighestApprovedAncestorBlock {
 #[derive(Debug)]
 pub enum ApprovalVotingMessage {
 	/// Check if the assignment is valid and can be accepted by our view of the protocol.
+	/// The assignment claims the candidates of the bitfield.
 	/// Should not be sent unless the block hash is known.
 	CheckAndImportAssignment(
-		IndirectAssignmentCert,
-		CandidateIndex,
+		IndirectAssignmentCertV2,
+		CandidateBitfield,
 		oneshot::Sender<AssignmentCheckResult>,
 	),
 	/// Check if the approval vote is valid and can be accepted by our view of the
 	/// protocol.
 	///
-	/// Should not be sent unless the block hash within the indirect vote is known.
-	CheckAndImportApproval(IndirectSignedApprovalVote, oneshot::Sender<ApprovalCheckResult>),
+	/// Should not be sent unless the block hash within the indirect vote is known. The vote
+	/// may approve several candidates of the block at once.
+	CheckAndImportApproval(IndirectSignedApprovalVoteV2, oneshot::Sender<ApprovalCheckResult>),
 	/// Returns the highest possible ancestor hash of the provided block hash which is
 	/// acceptable to vote on finality for.
 	/// The `BlockNumber` provided is the number of the block's ancestor which is the
@@ -799,9 +874,11 @@ pub enum ApprovalVotingMessage {
 	///
 	/// This message involves a linear search for candidates on each relay chain fork and also
 	/// requires calling into `approval-distribution`: Calls should be infrequent and bounded.
+	///
+	/// Each signature comes with the hashes of all the candidates it covers.
 	GetApprovalSignaturesForCandidate(
 		CandidateHash,
-		oneshot::Sender<HashMap<ValidatorIndex, ValidatorSignature>>,
+		oneshot::Sender<HashMap<ValidatorIndex, (Vec<CandidateHash>, ValidatorSignature)>>,
 	),
 }
 
@@ -812,20 +889,24 @@ pub enum ApprovalDistributionMessage {
 	/// and the candidates contained within them.
 	NewBlocks(Vec<BlockApprovalMeta>),
 	/// Distribute an assignment cert from the local validator. The cert is assumed
-	/// to be valid, relevant, and for the given relay-parent and validator index.
-	DistributeAssignment(IndirectAssignmentCert, CandidateIndex),
+	/// to be valid, relevant, and for the given relay-parent and validator index. The cert
+	/// claims the candidates of the bitfield.
+	DistributeAssignment(IndirectAssignmentCertV2, CandidateBitfield),
 	/// Distribute an approval vote for the local validator. The approval vote is assumed to be
 	/// valid, relevant, and the corresponding approval already issued.
 	/// If not, the subsystem is free to drop the message.
-	DistributeApproval(IndirectSignedApprovalVote),
+	DistributeApproval(IndirectSignedApprovalVoteV2),
 	/// An update from the network bridge.
 	#[from]
 	NetworkBridgeUpdate(NetworkBridgeEvent<net_protocol::ApprovalDistributionMessage>),
 
 	/// Get all approval signatures for all chains a candidate appeared in.
+	///
+	/// Each signature comes with the block it was issued under and the indices of all the
+	/// candidates of that block it covers.
 	GetApprovalSignatures(
 		HashSet<(Hash, CandidateIndex)>,
-		oneshot::Sender<HashMap<ValidatorIndex, ValidatorSignature>>,
+		oneshot::Sender<HashMap<ValidatorIndex, (Hash, Vec<CandidateIndex>, ValidatorSignature)>>,
 	),
 	/// Approval checking lag update measured in blocks.
 	ApprovalCheckingLagUpdate(BlockNumber),
@@ -42,8 +42,8 @@ use futures::channel::{mpsc, oneshot};
 use parity_scale_codec::Encode;
 
 use polkadot_primitives::{
-	AuthorityDiscoveryId, CandidateEvent, CommittedCandidateReceipt, CoreState, EncodeAs,
-	GroupIndex, GroupRotationInfo, Hash, Id as ParaId, OccupiedCoreAssumption,
+	vstaging, AuthorityDiscoveryId, CandidateEvent, CandidateHash, CommittedCandidateReceipt,
+	CoreState, EncodeAs, GroupIndex, GroupRotationInfo, Hash, Id as ParaId, OccupiedCoreAssumption,
 	PersistedValidationData, ScrapedOnChainVotes, SessionIndex, SessionInfo, Signed,
 	SigningContext, ValidationCode, ValidationCodeHash, ValidatorId, ValidatorIndex,
 	ValidatorSignature,
@@ -214,6 +214,10 @@ specialize_requests! {
 		-> Option<ValidationCodeHash>; ValidationCodeHash;
 	fn request_on_chain_votes() -> Option<ScrapedOnChainVotes>; FetchOnChainVotes;
 	fn request_session_executor_params(session_index: SessionIndex) -> Option<ExecutorParams>; SessionExecutorParams;
+	fn request_unapplied_slashes() -> Vec<(SessionIndex, CandidateHash, vstaging::slashing::PendingSlashes)>; UnappliedSlashes;
+	fn request_key_ownership_proof(validator_id: ValidatorId) -> Option<vstaging::slashing::OpaqueKeyOwnershipProof>; KeyOwnershipProof;
+	fn request_submit_report_dispute_lost(dp: vstaging::slashing::DisputeProof, okop: vstaging::slashing::OpaqueKeyOwnershipProof) -> Option<()>; SubmitReportDisputeLost;
+	fn request_node_features() -> vstaging::NodeFeatures; NodeFeatures;
 }
 
 /// Requests executor parameters from the runtime effective at given relay-parent. First obtains
@@ -264,6 +268,21 @@ pub async fn executor_params_at_relay_parent(
 	}
 }
 
+/// Requests the node features enabled in the session of the given relay-parent.
+/// Returns an error if failed to communicate to the runtime.
+/// Returns no enabled features if the runtime doesn't yet support the `NodeFeatures` API call.
+pub async fn node_features_at_relay_parent(
+	relay_parent: Hash,
+	sender: &mut impl overseer::SubsystemSender<RuntimeApiMessage>,
+) -> Result<vstaging::NodeFeatures, Error> {
+	match request_node_features(relay_parent, sender).await.await {
+		Err(err) => Err(Error::Oneshot(err)),
+		Ok(Err(RuntimeApiError::NotSupported { .. })) => Ok(vstaging::NodeFeatures::new()),
+		Ok(Err(err)) => Err(Error::RuntimeApi(err)),
+		Ok(Ok(node_features)) => Ok(node_features),
+	}
+}
+
 /// From the given set of validators, find the first key we can sign with, if any.
 pub fn signing_key(validators: &[ValidatorId], keystore: &KeystorePtr) -> Option<ValidatorId> {
 	signing_key_and_index(validators, keystore).map(|(k, _)| k)
@@ -38,7 +38,9 @@ use polkadot_node_subsystem::{
 // have been obsoleted.
 const SESSION_WINDOW_SIZE: SessionWindowSize = new_session_window_size!(6);
 const LOG_TARGET: &str = "parachain::rolling-session-window";
-const STORED_ROLLING_SESSION_WINDOW: &[u8] = b"Rolling_session_window";
+
+/// The key the rolling session window is stored at, the only one of its DB column.
+pub const STORED_ROLLING_SESSION_WINDOW: &[u8] = b"Rolling_session_window";
 
 /// Sessions unavailable in state to cache.
 #[derive(Debug, Clone, thiserror::Error)]
@@ -260,6 +262,20 @@ impl RollingSessionWindow {
 		}
 	}
 
+	/// Load the session window persisted in the database, without consulting chain state.
+	///
+	/// The returned window is never written back to the database, which makes it suitable to
+	/// inspect the database of a node. Returns `None` if there is no session window stored.
+	pub fn load_from_db(db_params: DatabaseParams) -> Option<Self> {
+		let stored_window = Self::db_load(db_params)?;
+		Some(RollingSessionWindow {
+			earliest_session: stored_window.earliest_session,
+			session_info: stored_window.session_info,
+			window_size: SESSION_WINDOW_SIZE,
+			db_params: None,
+		})
+	}
+
 	/// Initialize a new session info cache with the given window size and
 	/// initial data.
 	/// This is only used in `approval voting` tests.
@@ -942,6 +958,27 @@ mod tests {
 		cache_session_info_test(0, 3, Some(window), actual_window_size, None);
 	}
 
+	#[test]
+	fn load_from_db_works() {
+		let db_params = dummy_db_params();
+		assert!(RollingSessionWindow::load_from_db(db_params.clone()).is_none());
+
+		let session_info = vec![dummy_session_info(5), dummy_session_info(6)];
+		let mut window = RollingSessionWindow {
+			earliest_session: 5,
+			session_info: session_info.clone(),
+			window_size: SESSION_WINDOW_SIZE,
+			db_params: Some(db_params.clone()),
+		};
+		window.db_save(StoredWindow { earliest_session: 5, session_info: session_info.clone() });
+
+		let loaded = RollingSessionWindow::load_from_db(db_params).unwrap();
+		assert_eq!(loaded.earliest_session(), 5);
+		assert_eq!(loaded.latest_session(), 6);
+		assert_eq!(loaded.session_info(6), Some(&session_info[1]));
+		assert!(loaded.db_params.is_none());
+	}
+
 	#[test]
 	fn db_load_works() {
 		// Session index of the tip of our fake test chain.
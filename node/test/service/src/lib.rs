@@ -103,6 +103,13 @@ pub fn new_full(
 		None,
 		None,
 		None,
+		false,
+		None,
+		None,
+		false,
+		false,
+		1,
+		None,
 	)
 }
 
@@ -68,6 +68,13 @@ fn main() -> Result<()> {
 					None,
 					None,
 					None,
+					false,
+					None,
+					None,
+					false,
+					false,
+					1,
+					None,
 				)
 				.map_err(|e| e.to_string())?;
 				let mut overseer_handle = full_node
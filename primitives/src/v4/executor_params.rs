@@ -131,6 +131,16 @@ impl ExecutorParams {
 		}
 		None
 	}
+
+	/// Returns the max. amount of memory the preparation worker is allowed to use, if any
+	pub fn prechecking_max_memory(&self) -> Option<u64> {
+		for param in &self.0 {
+			if let ExecutorParam::PrecheckingMaxMemory(max_memory) = param {
+				return Some(*max_memory)
+			}
+		}
+		None
+	}
 }
 
 impl Deref for ExecutorParams {
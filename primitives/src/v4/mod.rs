@@ -1040,6 +1040,24 @@ impl ApprovalVote {
 	}
 }
 
+/// A vote of approval for multiple candidates.
+#[derive(Clone, RuntimeDebug)]
+pub struct ApprovalVoteMultipleCandidates<'a>(pub &'a [CandidateHash]);
+
+impl<'a> ApprovalVoteMultipleCandidates<'a> {
+	/// Yields the signing payload for this approval vote.
+	pub fn signing_payload(&self, session_index: SessionIndex) -> Vec<u8> {
+		const MAGIC: [u8; 4] = *b"APPR";
+		// A vote for a single candidate has the same payload as an `ApprovalVote`, so
+		// the signatures are interchangeable.
+		if self.0.len() == 1 {
+			ApprovalVote(self.0[0]).signing_payload(session_index)
+		} else {
+			(MAGIC, &self.0, session_index).encode()
+		}
+	}
+}
+
 /// Custom validity errors used in Polkadot while validating transactions.
 #[repr(u8)]
 pub enum ValidityError {
@@ -1230,6 +1248,9 @@ impl DisputeStatement {
 				}),
 			DisputeStatement::Valid(ValidDisputeStatementKind::ApprovalChecking) =>
 				ApprovalVote(candidate_hash).signing_payload(session),
+			DisputeStatement::Valid(
+				ValidDisputeStatementKind::ApprovalCheckingMultipleCandidates(ref candidate_hashes),
+			) => ApprovalVoteMultipleCandidates(candidate_hashes).signing_payload(session),
 			DisputeStatement::Invalid(InvalidDisputeStatementKind::Explicit) =>
 				ExplicitDisputeStatement { valid: false, candidate_hash, session }.signing_payload(),
 		}
@@ -1243,6 +1264,16 @@ impl DisputeStatement {
 		session: SessionIndex,
 		validator_signature: &ValidatorSignature,
 	) -> Result<(), ()> {
+		// A vote on multiple candidates is only a statement about the candidates it names.
+		if let DisputeStatement::Valid(
+			ValidDisputeStatementKind::ApprovalCheckingMultipleCandidates(ref candidate_hashes),
+		) = *self
+		{
+			if !candidate_hashes.contains(&candidate_hash) {
+				return Err(())
+			}
+		}
+
 		let payload = self.payload_data(candidate_hash, session);
 
 		if validator_signature.verify(&payload[..], &validator_public) {
@@ -1275,13 +1306,14 @@ impl DisputeStatement {
 			Self::Valid(ValidDisputeStatementKind::BackingValid(_)) => true,
 			Self::Valid(ValidDisputeStatementKind::Explicit) |
 			Self::Valid(ValidDisputeStatementKind::ApprovalChecking) |
+			Self::Valid(ValidDisputeStatementKind::ApprovalCheckingMultipleCandidates(_)) |
 			Self::Invalid(_) => false,
 		}
 	}
 }
 
 /// Different kinds of statements of validity on  a candidate.
-#[derive(Encode, Decode, Copy, Clone, PartialEq, RuntimeDebug, TypeInfo)]
+#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug, TypeInfo)]
 pub enum ValidDisputeStatementKind {
 	/// An explicit statement issued as part of a dispute.
 	#[codec(index = 0)]
@@ -1295,6 +1327,10 @@ pub enum ValidDisputeStatementKind {
 	/// An approval vote from the approval checking phase.
 	#[codec(index = 3)]
 	ApprovalChecking,
+	/// An approval vote from the approval checking phase, signed over several candidates at
+	/// once. The hashes are all the candidates covered by the signature.
+	#[codec(index = 4)]
+	ApprovalCheckingMultipleCandidates(Vec<CandidateHash>),
 }
 
 /// Different kinds of statements of invalidity on a candidate.
@@ -1815,4 +1851,19 @@ mod tests {
 
 		assert!(zero_b.leading_zeros() >= zero_u.leading_zeros());
 	}
+
+	#[test]
+	fn approval_vote_for_single_candidate_matches_approval_vote() {
+		let candidate_hash = CandidateHash(Hash::repeat_byte(1));
+		let other_hash = CandidateHash(Hash::repeat_byte(2));
+
+		assert_eq!(
+			ApprovalVoteMultipleCandidates(&[candidate_hash]).signing_payload(5),
+			ApprovalVote(candidate_hash).signing_payload(5),
+		);
+		assert_ne!(
+			ApprovalVoteMultipleCandidates(&[candidate_hash, other_hash]).signing_payload(5),
+			ApprovalVote(candidate_hash).signing_payload(5),
+		);
+	}
 }
@@ -20,10 +20,13 @@
 pub use crate::v4::*;
 use sp_std::prelude::*;
 
+use bitvec::vec::BitVec;
 use parity_scale_codec::{Decode, Encode};
 use primitives::RuntimeDebug;
 use scale_info::TypeInfo;
 
+pub mod slashing;
+
 /// Candidate's acceptance limitations for asynchronous backing per relay parent.
 #[derive(RuntimeDebug, Copy, Clone, PartialEq, Encode, Decode, TypeInfo)]
 #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
@@ -40,3 +43,28 @@ pub struct AsyncBackingParams {
 	/// When async backing is disabled, the only valid value is 0.
 	pub allowed_ancestry_len: u32,
 }
+
+/// Bit field of features the node side may enable, as configured on-chain. A feature is enabled
+/// if the bit at its [`node_features::FeatureIndex`] is set.
+pub type NodeFeatures = BitVec<u8, bitvec::order::Lsb0>;
+
+/// Features which can be enabled through [`NodeFeatures`].
+pub mod node_features {
+	/// Index of a feature's bit in [`NodeFeatures`](super::NodeFeatures).
+	#[repr(u8)]
+	#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+	pub enum FeatureIndex {
+		/// Assign availability chunks to validators in a shuffled order which is different for
+		/// every relay block and para, instead of validator `i` holding chunk `i`.
+		AvailabilityChunkShuffling = 0,
+		/// Accept approval votes signed over several candidates at once in disputes, and let
+		/// validators coalesce their approvals. Only to be enabled once all validators run a node
+		/// version supporting them.
+		ApprovalCoalescing = 1,
+	}
+
+	/// Whether the given feature is enabled in `features`.
+	pub fn is_enabled(features: &super::NodeFeatures, feature: FeatureIndex) -> bool {
+		features.get(feature as usize).map_or(false, |bit| *bit)
+	}
+}
@@ -0,0 +1,206 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC methods to inspect the candidates kept in the availability store.
+
+use futures::channel::oneshot;
+use jsonrpsee::{
+	core::{async_trait, Error as JsonRpseeError, RpcResult},
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use polkadot_node_primitives::{StoredCandidateInfo, StoredCandidateState};
+use polkadot_node_subsystem_types::messages::AvailabilityStoreMessage;
+use polkadot_overseer::Handle;
+use polkadot_primitives::{BlockNumber, CandidateHash, Hash};
+use sc_rpc::DenyUnsafe;
+use serde::Serialize;
+
+/// The error code returned if the availability store could not answer a request.
+const AVAILABILITY_STORE_UNAVAILABLE: i32 = 7002;
+
+/// The maximum number of candidates returned by a single `parachain_storedCandidates` call.
+const MAX_LISTED_CANDIDATES: u32 = 1000;
+
+/// Availability store RPC methods.
+#[rpc(server)]
+pub trait AvailabilityApi {
+	/// Returns what the availability store keeps about the given candidate, if anything.
+	#[method(name = "parachain_storedCandidate")]
+	async fn stored_candidate(&self, candidate_hash: Hash) -> RpcResult<Option<StoredCandidate>>;
+
+	/// Returns what the availability store keeps about the candidates, ordered by candidate hash.
+	///
+	/// At most `limit` candidates (capped at 1000) with a greater hash than `start_after` are
+	/// returned. Pass the hash of the last returned candidate to get the next page.
+	#[method(name = "parachain_storedCandidates")]
+	async fn stored_candidates(
+		&self,
+		start_after: Option<Hash>,
+		limit: Option<u32>,
+	) -> RpcResult<Vec<StoredCandidate>>;
+}
+
+/// A candidate kept in the availability store.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredCandidate {
+	/// The hash of the candidate.
+	pub candidate_hash: Hash,
+	/// The state of the candidate.
+	pub state: State,
+	/// Whether the full available data of the candidate is stored.
+	pub data_available: bool,
+	/// The stored chunks as a string of `0`s and `1`s, starting with chunk index 0.
+	pub chunks_stored: String,
+	/// The number of chunks stored.
+	pub chunks_stored_count: u32,
+	/// The number of bytes used for the available data and chunks.
+	pub stored_bytes: u64,
+	/// The UNIX timestamp the candidate is going to be pruned at. `None` while the candidate is
+	/// included in unfinalized blocks.
+	pub prune_at: Option<u64>,
+}
+
+/// The state of a candidate in the availability store, see [`StoredCandidateState`].
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum State {
+	/// The candidate is not included in any known block.
+	Unavailable {
+		/// The UNIX timestamp the candidate was first observed at.
+		since: u64,
+	},
+	/// The candidate is included in unfinalized blocks.
+	Unfinalized {
+		/// The UNIX timestamp the candidate was first observed at.
+		since: u64,
+		/// The blocks the candidate is included in.
+		blocks: Vec<BlockRef>,
+	},
+	/// The candidate is included in a finalized block.
+	Finalized {
+		/// The UNIX timestamp the block got finalized at.
+		since: u64,
+	},
+}
+
+/// A block by number and hash.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockRef {
+	/// The number of the block.
+	pub number: BlockNumber,
+	/// The hash of the block.
+	pub hash: Hash,
+}
+
+impl From<StoredCandidateState> for State {
+	fn from(state: StoredCandidateState) -> Self {
+		match state {
+			StoredCandidateState::Unavailable { since } => State::Unavailable { since },
+			StoredCandidateState::Unfinalized { since, blocks } => State::Unfinalized {
+				since,
+				blocks: blocks
+					.into_iter()
+					.map(|(number, hash)| BlockRef { number, hash })
+					.collect(),
+			},
+			StoredCandidateState::Finalized { since } => State::Finalized { since },
+		}
+	}
+}
+
+impl From<StoredCandidateInfo> for StoredCandidate {
+	fn from(info: StoredCandidateInfo) -> Self {
+		StoredCandidate {
+			candidate_hash: info.candidate_hash.0,
+			state: info.state.into(),
+			data_available: info.data_available,
+			chunks_stored: info
+				.chunks_stored
+				.iter()
+				.map(|stored| if *stored { '1' } else { '0' })
+				.collect(),
+			chunks_stored_count: info.chunks_stored.iter().filter(|stored| **stored).count() as u32,
+			stored_bytes: info.stored_bytes,
+			prune_at: info.prune_at,
+		}
+	}
+}
+
+/// Implements the [`AvailabilityApiServer`] by querying the availability store.
+pub struct Availability {
+	overseer_handle: Handle,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl Availability {
+	/// Create a new instance of the availability store RPC handler.
+	pub fn new(overseer_handle: Handle, deny_unsafe: DenyUnsafe) -> Self {
+		Self { overseer_handle, deny_unsafe }
+	}
+
+	/// Sends a request to the availability store and waits for the response.
+	async fn request<T>(
+		&self,
+		make_request: impl FnOnce(oneshot::Sender<T>) -> AvailabilityStoreMessage,
+	) -> RpcResult<T> {
+		let (tx, rx) = oneshot::channel();
+		self.overseer_handle.clone().send_msg(make_request(tx), "AvailabilityRpc").await;
+		rx.await.map_err(|_| {
+			JsonRpseeError::Call(CallError::Custom(ErrorObject::owned(
+				AVAILABILITY_STORE_UNAVAILABLE,
+				"The availability store did not respond",
+				None::<()>,
+			)))
+		})
+	}
+}
+
+#[async_trait]
+impl AvailabilityApiServer for Availability {
+	async fn stored_candidate(&self, candidate_hash: Hash) -> RpcResult<Option<StoredCandidate>> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let info = self
+			.request(|tx| {
+				AvailabilityStoreMessage::QueryStoredCandidate(CandidateHash(candidate_hash), tx)
+			})
+			.await?;
+
+		Ok(info.map(Into::into))
+	}
+
+	async fn stored_candidates(
+		&self,
+		start_after: Option<Hash>,
+		limit: Option<u32>,
+	) -> RpcResult<Vec<StoredCandidate>> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let limit = limit.unwrap_or(MAX_LISTED_CANDIDATES).min(MAX_LISTED_CANDIDATES);
+		let candidates = self
+			.request(|tx| AvailabilityStoreMessage::ListStoredCandidates {
+				start_after: start_after.map(CandidateHash),
+				limit: limit as usize,
+				tx,
+			})
+			.await?;
+
+		Ok(candidates.into_iter().map(Into::into).collect())
+	}
+}
@@ -0,0 +1,475 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC methods to inspect the disputes tracked by the dispute coordinator.
+
+use std::collections::HashMap;
+
+use futures::{
+	channel::{mpsc, oneshot},
+	FutureExt, StreamExt,
+};
+use jsonrpsee::{
+	core::{async_trait, Error as JsonRpseeError, RpcResult, SubscriptionResult},
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+	SubscriptionSink,
+};
+use polkadot_node_primitives::{
+	CandidateVotes, DisputeParticipation, DisputeStatus, Timestamp as DisputeTimestamp,
+};
+use polkadot_node_subsystem_types::messages::DisputeCoordinatorMessage;
+use polkadot_overseer::Handle;
+use polkadot_primitives::{
+	CandidateHash, Hash, InvalidDisputeStatementKind, SessionIndex, ValidDisputeStatementKind,
+	ValidatorIndex,
+};
+use sc_rpc::{DenyUnsafe, SubscriptionTaskExecutor};
+use serde::{Deserialize, Serialize};
+
+/// The error code returned if the dispute coordinator could not answer a request.
+const DISPUTE_COORDINATOR_UNAVAILABLE: i32 = 7001;
+
+/// The number of dispute updates buffered for a subscriber before it is dropped by the dispute
+/// coordinator.
+const DISPUTE_UPDATES_BUFFER_SIZE: usize = 1024;
+
+/// Disputes RPC methods.
+#[rpc(server)]
+pub trait DisputesApi {
+	/// Returns the disputes of recent sessions, including concluded ones. Only the active disputes
+	/// are returned if `active_only` is set.
+	#[method(name = "parachain_disputes")]
+	async fn disputes(&self, active_only: Option<bool>) -> RpcResult<Vec<DisputeInfo>>;
+
+	/// Returns the votes cast in the dispute about the given candidate, if the dispute
+	/// coordinator knows about any.
+	#[method(name = "parachain_disputeVotes")]
+	async fn dispute_votes(
+		&self,
+		session: SessionIndex,
+		candidate_hash: Hash,
+	) -> RpcResult<Option<DisputeVotes>>;
+
+	/// Subscribes to the changes of the disputes tracked by the dispute coordinator. A
+	/// notification is sent whenever new votes are imported or the status of a dispute changes.
+	#[subscription(
+		name = "parachain_subscribeDisputes" => "parachain_dispute",
+		unsubscribe = "parachain_unsubscribeDisputes",
+		item = DisputeUpdate,
+	)]
+	fn subscribe_disputes(&self);
+}
+
+/// A dispute as tracked by the dispute coordinator.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisputeInfo {
+	/// The session the disputed candidate appeared in.
+	pub session: SessionIndex,
+	/// The hash of the disputed candidate.
+	pub candidate_hash: Hash,
+	/// The status of the dispute.
+	pub status: Status,
+	/// The number of votes for the validity of the candidate.
+	pub valid_votes: u32,
+	/// The number of votes against the validity of the candidate.
+	pub invalid_votes: u32,
+	/// The state of the participation of this node in the dispute.
+	pub participation: Participation,
+}
+
+/// A change of a dispute, see [`polkadot_node_primitives::DisputeUpdate`].
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisputeUpdate {
+	/// The session the disputed candidate appeared in.
+	pub session: SessionIndex,
+	/// The hash of the disputed candidate.
+	pub candidate_hash: Hash,
+	/// The status of the dispute after the change.
+	pub status: Status,
+	/// The number of votes for the validity of the candidate.
+	pub valid_votes: u32,
+	/// The number of votes against the validity of the candidate.
+	pub invalid_votes: u32,
+}
+
+impl From<polkadot_node_primitives::DisputeUpdate> for DisputeUpdate {
+	fn from(update: polkadot_node_primitives::DisputeUpdate) -> Self {
+		DisputeUpdate {
+			session: update.session,
+			candidate_hash: update.candidate_hash.0,
+			status: update.status.into(),
+			valid_votes: update.valid_votes,
+			invalid_votes: update.invalid_votes,
+		}
+	}
+}
+
+/// The status of a dispute, see [`DisputeStatus`].
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Status {
+	/// The dispute is active and unconcluded.
+	Active,
+	/// The dispute has been confirmed, i.e. more than a byzantine threshold of validators voted.
+	Confirmed,
+	/// The dispute concluded in favor of the candidate at the given UNIX timestamp.
+	ConcludedFor(DisputeTimestamp),
+	/// The dispute concluded against the candidate at the given UNIX timestamp.
+	ConcludedAgainst(DisputeTimestamp),
+}
+
+impl From<DisputeStatus> for Status {
+	fn from(status: DisputeStatus) -> Self {
+		match status {
+			DisputeStatus::Active => Status::Active,
+			DisputeStatus::Confirmed => Status::Confirmed,
+			DisputeStatus::ConcludedFor(since) => Status::ConcludedFor(since),
+			DisputeStatus::ConcludedAgainst(since) => Status::ConcludedAgainst(since),
+		}
+	}
+}
+
+/// The participation of this node in a dispute, see [`DisputeParticipation`].
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Participation {
+	/// The candidate is currently being validated.
+	Running,
+	/// Participation is queued, in the priority queue if `priority` is set.
+	Queued {
+		/// Whether the participation is in the priority queue.
+		priority: bool,
+	},
+	/// Participation is neither running nor queued.
+	Idle,
+}
+
+impl From<DisputeParticipation> for Participation {
+	fn from(participation: DisputeParticipation) -> Self {
+		match participation {
+			DisputeParticipation::Running => Participation::Running,
+			DisputeParticipation::Queued { priority } => Participation::Queued { priority },
+			DisputeParticipation::Idle => Participation::Idle,
+		}
+	}
+}
+
+/// The votes cast in a dispute.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisputeVotes {
+	/// The votes for the validity of the candidate.
+	pub valid: Vec<Vote>,
+	/// The votes against the validity of the candidate.
+	pub invalid: Vec<Vote>,
+}
+
+/// A single vote in a dispute.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Vote {
+	/// The index of the voting validator in the session.
+	pub validator_index: u32,
+	/// How the vote was cast.
+	pub kind: VoteKind,
+}
+
+/// How a vote in a dispute was cast.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum VoteKind {
+	/// An explicit statement issued as part of the dispute.
+	Explicit,
+	/// A seconded statement from the backing phase.
+	BackingSeconded,
+	/// A valid statement from the backing phase.
+	BackingValid,
+	/// An approval vote from the approval checking phase.
+	ApprovalChecking,
+}
+
+impl From<&ValidDisputeStatementKind> for VoteKind {
+	fn from(kind: &ValidDisputeStatementKind) -> Self {
+		match kind {
+			ValidDisputeStatementKind::Explicit => VoteKind::Explicit,
+			ValidDisputeStatementKind::BackingSeconded(_) => VoteKind::BackingSeconded,
+			ValidDisputeStatementKind::BackingValid(_) => VoteKind::BackingValid,
+			ValidDisputeStatementKind::ApprovalChecking |
+			ValidDisputeStatementKind::ApprovalCheckingMultipleCandidates(_) => VoteKind::ApprovalChecking,
+		}
+	}
+}
+
+impl From<&InvalidDisputeStatementKind> for VoteKind {
+	fn from(kind: &InvalidDisputeStatementKind) -> Self {
+		match kind {
+			InvalidDisputeStatementKind::Explicit => VoteKind::Explicit,
+		}
+	}
+}
+
+impl From<CandidateVotes> for DisputeVotes {
+	fn from(votes: CandidateVotes) -> Self {
+		let vote = |index: &ValidatorIndex, kind: VoteKind| Vote { validator_index: index.0, kind };
+		DisputeVotes {
+			valid: votes
+				.valid
+				.raw()
+				.iter()
+				.map(|(index, (kind, _))| vote(index, kind.into()))
+				.collect(),
+			invalid: votes
+				.invalid
+				.iter()
+				.map(|(index, (kind, _))| vote(index, kind.into()))
+				.collect(),
+		}
+	}
+}
+
+/// Implements the [`DisputesApiServer`] by querying the dispute coordinator.
+pub struct Disputes {
+	overseer_handle: Handle,
+	executor: SubscriptionTaskExecutor,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl Disputes {
+	/// Create a new instance of the disputes RPC handler.
+	pub fn new(
+		overseer_handle: Handle,
+		executor: SubscriptionTaskExecutor,
+		deny_unsafe: DenyUnsafe,
+	) -> Self {
+		Self { overseer_handle, executor, deny_unsafe }
+	}
+
+	/// Sends a request to the dispute coordinator and waits for the response.
+	async fn request<T>(
+		&self,
+		make_request: impl FnOnce(oneshot::Sender<T>) -> DisputeCoordinatorMessage,
+	) -> RpcResult<T> {
+		let (tx, rx) = oneshot::channel();
+		self.overseer_handle.clone().send_msg(make_request(tx), "DisputesRpc").await;
+		rx.await.map_err(|_| {
+			JsonRpseeError::Call(CallError::Custom(ErrorObject::owned(
+				DISPUTE_COORDINATOR_UNAVAILABLE,
+				"The dispute coordinator did not respond",
+				None::<()>,
+			)))
+		})
+	}
+}
+
+#[async_trait]
+impl DisputesApiServer for Disputes {
+	async fn disputes(&self, active_only: Option<bool>) -> RpcResult<Vec<DisputeInfo>> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let disputes = if active_only.unwrap_or(false) {
+			self.request(DisputeCoordinatorMessage::ActiveDisputes).await?
+		} else {
+			self.request(DisputeCoordinatorMessage::RecentDisputes).await?
+		};
+		let query: Vec<_> = disputes
+			.iter()
+			.map(|(session, candidate_hash, _)| (*session, *candidate_hash))
+			.collect();
+
+		let votes: HashMap<_, _> = self
+			.request(|tx| DisputeCoordinatorMessage::QueryCandidateVotes(query.clone(), tx))
+			.await?
+			.into_iter()
+			.map(|(session, candidate_hash, votes)| ((session, candidate_hash), votes))
+			.collect();
+		let participation: HashMap<_, _> = self
+			.request(|tx| DisputeCoordinatorMessage::QueryParticipation(query, tx))
+			.await?
+			.into_iter()
+			.map(|(session, candidate_hash, state)| ((session, candidate_hash), state))
+			.collect();
+
+		Ok(disputes
+			.into_iter()
+			.map(|(session, candidate_hash, status)| {
+				let key = (session, candidate_hash);
+				let (valid_votes, invalid_votes) = votes.get(&key).map_or((0, 0), |votes| {
+					(votes.valid.raw().len() as u32, votes.invalid.len() as u32)
+				});
+				DisputeInfo {
+					session,
+					candidate_hash: candidate_hash.0,
+					status: status.into(),
+					valid_votes,
+					invalid_votes,
+					participation: participation
+						.get(&key)
+						.copied()
+						.unwrap_or(DisputeParticipation::Idle)
+						.into(),
+				}
+			})
+			.collect())
+	}
+
+	async fn dispute_votes(
+		&self,
+		session: SessionIndex,
+		candidate_hash: Hash,
+	) -> RpcResult<Option<DisputeVotes>> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let query = vec![(session, CandidateHash(candidate_hash))];
+		let votes = self
+			.request(|tx| DisputeCoordinatorMessage::QueryCandidateVotes(query, tx))
+			.await?;
+
+		Ok(votes.into_iter().next().map(|(_, _, votes)| votes.into()))
+	}
+
+	fn subscribe_disputes(&self, mut sink: SubscriptionSink) -> SubscriptionResult {
+		if let Err(err) = self.deny_unsafe.check_if_safe() {
+			let _ = sink.reject(JsonRpseeError::from(err));
+			return Ok(())
+		}
+
+		let mut overseer_handle = self.overseer_handle.clone();
+		let fut = async move {
+			let (tx, rx) = mpsc::channel(DISPUTE_UPDATES_BUFFER_SIZE);
+			overseer_handle
+				.send_msg(DisputeCoordinatorMessage::SubscribeDisputeUpdates(tx), "DisputesRpc")
+				.await;
+			sink.pipe_from_stream(rx.map(DisputeUpdate::from)).await;
+		};
+		self.executor
+			.spawn("polkadot-rpc-disputes-subscription", Some("rpc"), fut.boxed());
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use jsonrpsee::{types::EmptyServerParams as EmptyParams, RpcModule};
+	use polkadot_overseer::{gen::metered, AllMessages, Event};
+	use std::sync::Arc;
+
+	const SESSION: SessionIndex = 1;
+
+	fn candidate_hash() -> CandidateHash {
+		CandidateHash(Hash::repeat_byte(1))
+	}
+
+	/// Creates the RPC module along with a mock dispute coordinator answering its requests.
+	fn test_module(deny_unsafe: DenyUnsafe) -> RpcModule<Disputes> {
+		let (overseer_tx, mut overseer_rx) = metered::channel(64);
+
+		tokio::spawn(async move {
+			let mut subscribers = Vec::new();
+			while let Some(event) = overseer_rx.next().await {
+				let msg = match event {
+					Event::MsgToSubsystem { msg: AllMessages::DisputeCoordinator(msg), .. } => msg,
+					_ => continue,
+				};
+				match msg {
+					DisputeCoordinatorMessage::RecentDisputes(tx) |
+					DisputeCoordinatorMessage::ActiveDisputes(tx) => {
+						let _ = tx.send(vec![(SESSION, candidate_hash(), DisputeStatus::Active)]);
+					},
+					DisputeCoordinatorMessage::QueryCandidateVotes(_, tx) => {
+						let _ = tx.send(Vec::new());
+					},
+					DisputeCoordinatorMessage::QueryParticipation(query, tx) => {
+						let _ = tx.send(
+							query
+								.into_iter()
+								.map(|(session, candidate_hash)| {
+									(session, candidate_hash, DisputeParticipation::Running)
+								})
+								.collect(),
+						);
+					},
+					DisputeCoordinatorMessage::SubscribeDisputeUpdates(mut tx) => {
+						tx.try_send(polkadot_node_primitives::DisputeUpdate {
+							session: SESSION,
+							candidate_hash: candidate_hash(),
+							status: DisputeStatus::Confirmed,
+							valid_votes: 2,
+							invalid_votes: 3,
+						})
+						.unwrap();
+						// Keep the subscription open.
+						subscribers.push(tx);
+					},
+					msg => panic!("Unexpected message: {:?}", msg),
+				}
+			}
+		});
+
+		Disputes::new(
+			Handle::new(overseer_tx),
+			Arc::new(sp_core::testing::TaskExecutor::new()),
+			deny_unsafe,
+		)
+		.into_rpc()
+	}
+
+	#[tokio::test]
+	async fn disputes_are_listed_with_their_participation() {
+		let module = test_module(DenyUnsafe::No);
+
+		let disputes: Vec<DisputeInfo> = module.call("parachain_disputes", [true]).await.unwrap();
+
+		assert_eq!(disputes.len(), 1);
+		assert_eq!(disputes[0].session, SESSION);
+		assert_eq!(disputes[0].candidate_hash, candidate_hash().0);
+		assert!(matches!(disputes[0].status, Status::Active));
+		assert_eq!((disputes[0].valid_votes, disputes[0].invalid_votes), (0, 0));
+		assert!(matches!(disputes[0].participation, Participation::Running));
+	}
+
+	#[tokio::test]
+	async fn subscribers_receive_dispute_updates() {
+		let module = test_module(DenyUnsafe::No);
+
+		let mut sub = module
+			.subscribe("parachain_subscribeDisputes", EmptyParams::new())
+			.await
+			.unwrap();
+		let (update, _) = sub.next::<DisputeUpdate>().await.unwrap().unwrap();
+
+		assert_eq!(update.session, SESSION);
+		assert_eq!(update.candidate_hash, candidate_hash().0);
+		assert!(matches!(update.status, Status::Confirmed));
+		assert_eq!((update.valid_votes, update.invalid_votes), (2, 3));
+	}
+
+	#[tokio::test]
+	async fn subscription_is_unsafe() {
+		let module = test_module(DenyUnsafe::Yes);
+
+		assert!(module
+			.subscribe("parachain_subscribeDisputes", EmptyParams::new())
+			.await
+			.is_err());
+		assert!(module.call::<_, Vec<DisputeInfo>>("parachain_disputes", [true]).await.is_err());
+	}
+}
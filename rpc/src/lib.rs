@@ -21,6 +21,7 @@
 use std::sync::Arc;
 
 use jsonrpsee::RpcModule;
+use polkadot_overseer::Handle;
 use polkadot_primitives::{AccountId, Balance, Block, BlockNumber, Hash, Nonce};
 use sc_client_api::AuxStore;
 use sc_consensus_beefy::communication::notification::{
@@ -36,6 +37,12 @@ use sp_consensus_babe::BabeApi;
 use sp_keystore::KeystorePtr;
 use txpool_api::TransactionPool;
 
+mod availability;
+mod disputes;
+
+pub use availability::{Availability, AvailabilityApiServer};
+pub use disputes::{Disputes, DisputesApiServer};
+
 /// A type representing all RPC extensions.
 pub type RpcExtension = RpcModule<()>;
 
@@ -89,6 +96,8 @@ pub struct FullDeps<C, P, SC, B> {
 	pub grandpa: GrandpaDeps<B>,
 	/// BEEFY specific dependencies.
 	pub beefy: BeefyDeps,
+	/// A handle to the overseer, to query the subsystems. `None` if the node runs no overseer.
+	pub overseer_handle: Option<Handle>,
 }
 
 /// Instantiate all RPC extensions.
@@ -124,8 +133,17 @@ where
 	use substrate_state_trie_migration_rpc::{StateMigration, StateMigrationApiServer};
 
 	let mut io = RpcModule::new(());
-	let FullDeps { client, pool, select_chain, chain_spec, deny_unsafe, babe, grandpa, beefy } =
-		deps;
+	let FullDeps {
+		client,
+		pool,
+		select_chain,
+		chain_spec,
+		deny_unsafe,
+		babe,
+		grandpa,
+		beefy,
+		overseer_handle,
+	} = deps;
 	let BabeDeps { babe_worker_handle, keystore } = babe;
 	let GrandpaDeps {
 		shared_voter_state,
@@ -145,7 +163,7 @@ where
 	)?;
 	io.merge(
 		Grandpa::new(
-			subscription_executor,
+			subscription_executor.clone(),
 			shared_authority_set.clone(),
 			shared_voter_state,
 			justification_stream,
@@ -166,5 +184,10 @@ where
 		.into_rpc(),
 	)?;
 
+	if let Some(overseer_handle) = overseer_handle {
+		io.merge(Availability::new(overseer_handle.clone(), deny_unsafe).into_rpc())?;
+		io.merge(Disputes::new(overseer_handle, subscription_executor, deny_unsafe).into_rpc())?;
+	}
+
 	Ok(io)
 }
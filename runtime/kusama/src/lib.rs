@@ -1484,7 +1484,7 @@ pub mod migrations {
 	);
 
 	/// Unreleased migrations. Add new ones here:
-	pub type Unreleased = ();
+	pub type Unreleased = (parachains_configuration::migration::v6::MigrateToV6<Runtime>,);
 }
 
 /// Unchecked extrinsic type as expected by this runtime.
@@ -49,6 +49,8 @@ benchmarks! {
 		ExecutorParam::PvfExecTimeout(PvfExecTimeoutKind::Approval, 12_000),
 	][..]))
 
+	set_node_feature {}: set_node_feature(RawOrigin::Root, u8::MAX, true)
+
 	impl_benchmark_test_suite!(
 		Pallet,
 		crate::mock::new_test_ext(Default::default()),
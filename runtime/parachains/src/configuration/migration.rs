@@ -31,7 +31,8 @@ use sp_std::vec::Vec;
 /// v4-v5: <https://github.com/paritytech/polkadot/pull/6937>
 ///        + <https://github.com/paritytech/polkadot/pull/6961>
 ///        + <https://github.com/paritytech/polkadot/pull/6934>
-pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(5);
+/// v5-v6: `node_features` were added.
+pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(6);
 
 pub mod v5 {
 	use super::*;
@@ -155,7 +156,7 @@ pub mod v5 {
 				let weight_consumed = migrate_to_v5::<T>();
 
 				log::info!(target: configuration::LOG_TARGET, "MigrateToV5 executed successfully");
-				STORAGE_VERSION.put::<Pallet<T>>();
+				StorageVersion::new(5).put::<Pallet<T>>();
 
 				weight_consumed
 			} else {
@@ -168,7 +169,7 @@ pub mod v5 {
 		fn post_upgrade(_state: Vec<u8>) -> Result<(), &'static str> {
 			log::trace!(target: crate::configuration::LOG_TARGET, "Running post_upgrade()");
 			ensure!(
-				StorageVersion::get::<Pallet<T>>() == STORAGE_VERSION,
+				StorageVersion::get::<Pallet<T>>() == 5,
 				"Storage version should be 5 after the migration"
 			);
 
@@ -177,6 +178,177 @@ pub mod v5 {
 	}
 }
 
+pub mod v6 {
+	use super::*;
+	use frame_support::{traits::OnRuntimeUpgrade, weights::constants::WEIGHT_REF_TIME_PER_MILLIS};
+	use primitives::{Balance, ExecutorParams, SessionIndex};
+	#[cfg(feature = "try-runtime")]
+	use sp_std::prelude::*;
+
+	// Copied over from configuration.rs before `node_features` was added and removed all the
+	// comments.
+	#[derive(parity_scale_codec::Encode, parity_scale_codec::Decode, Debug, Clone)]
+	pub struct V5HostConfiguration<BlockNumber> {
+		pub max_code_size: u32,
+		pub max_head_data_size: u32,
+		pub max_upward_queue_count: u32,
+		pub max_upward_queue_size: u32,
+		pub max_upward_message_size: u32,
+		pub max_upward_message_num_per_candidate: u32,
+		pub hrmp_max_message_num_per_candidate: u32,
+		pub validation_upgrade_cooldown: BlockNumber,
+		pub validation_upgrade_delay: BlockNumber,
+		pub async_backing_params: AsyncBackingParams,
+		pub max_pov_size: u32,
+		pub max_downward_message_size: u32,
+		pub ump_service_total_weight: Weight,
+		pub hrmp_max_parachain_outbound_channels: u32,
+		pub hrmp_max_parathread_outbound_channels: u32,
+		pub hrmp_sender_deposit: Balance,
+		pub hrmp_recipient_deposit: Balance,
+		pub hrmp_channel_max_capacity: u32,
+		pub hrmp_channel_max_total_size: u32,
+		pub hrmp_max_parachain_inbound_channels: u32,
+		pub hrmp_max_parathread_inbound_channels: u32,
+		pub hrmp_channel_max_message_size: u32,
+		pub executor_params: ExecutorParams,
+		pub code_retention_period: BlockNumber,
+		pub parathread_cores: u32,
+		pub parathread_retries: u32,
+		pub group_rotation_frequency: BlockNumber,
+		pub chain_availability_period: BlockNumber,
+		pub thread_availability_period: BlockNumber,
+		pub scheduling_lookahead: u32,
+		pub max_validators_per_core: Option<u32>,
+		pub max_validators: Option<u32>,
+		pub dispute_period: SessionIndex,
+		pub dispute_post_conclusion_acceptance_period: BlockNumber,
+		pub no_show_slots: u32,
+		pub n_delay_tranches: u32,
+		pub zeroth_delay_tranche_width: u32,
+		pub needed_approvals: u32,
+		pub relay_vrf_modulo_samples: u32,
+		pub ump_max_individual_weight: Weight,
+		pub pvf_checking_enabled: bool,
+		pub pvf_voting_ttl: SessionIndex,
+		pub minimum_validation_upgrade_delay: BlockNumber,
+	}
+
+	impl<BlockNumber: Default + From<u32>> Default for V5HostConfiguration<BlockNumber> {
+		fn default() -> Self {
+			Self {
+				async_backing_params: AsyncBackingParams {
+					max_candidate_depth: 0,
+					allowed_ancestry_len: 0,
+				},
+				group_rotation_frequency: 1u32.into(),
+				chain_availability_period: 1u32.into(),
+				thread_availability_period: 1u32.into(),
+				no_show_slots: 1u32.into(),
+				validation_upgrade_cooldown: Default::default(),
+				validation_upgrade_delay: 2u32.into(),
+				code_retention_period: Default::default(),
+				max_code_size: Default::default(),
+				max_pov_size: Default::default(),
+				max_head_data_size: Default::default(),
+				parathread_cores: Default::default(),
+				parathread_retries: Default::default(),
+				scheduling_lookahead: Default::default(),
+				max_validators_per_core: Default::default(),
+				max_validators: None,
+				dispute_period: 6,
+				dispute_post_conclusion_acceptance_period: 100.into(),
+				n_delay_tranches: Default::default(),
+				zeroth_delay_tranche_width: Default::default(),
+				needed_approvals: Default::default(),
+				relay_vrf_modulo_samples: Default::default(),
+				max_upward_queue_count: Default::default(),
+				max_upward_queue_size: Default::default(),
+				max_downward_message_size: Default::default(),
+				ump_service_total_weight: Default::default(),
+				max_upward_message_size: Default::default(),
+				max_upward_message_num_per_candidate: Default::default(),
+				hrmp_sender_deposit: Default::default(),
+				hrmp_recipient_deposit: Default::default(),
+				hrmp_channel_max_capacity: Default::default(),
+				hrmp_channel_max_total_size: Default::default(),
+				hrmp_max_parachain_inbound_channels: Default::default(),
+				hrmp_max_parathread_inbound_channels: Default::default(),
+				hrmp_channel_max_message_size: Default::default(),
+				hrmp_max_parachain_outbound_channels: Default::default(),
+				hrmp_max_parathread_outbound_channels: Default::default(),
+				hrmp_max_message_num_per_candidate: Default::default(),
+				ump_max_individual_weight: Weight::from_parts(
+					20u64 * WEIGHT_REF_TIME_PER_MILLIS,
+					MAX_POV_SIZE as u64,
+				),
+				pvf_checking_enabled: false,
+				pvf_voting_ttl: 2u32.into(),
+				minimum_validation_upgrade_delay: 2.into(),
+				executor_params: Default::default(),
+			}
+		}
+	}
+
+	pub struct MigrateToV6<T>(sp_std::marker::PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV6<T> {
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			log::trace!(target: crate::configuration::LOG_TARGET, "Running pre_upgrade()");
+
+			ensure!(StorageVersion::get::<Pallet<T>>() == 5, "The migration requires version 5");
+
+			// Remember how many pending configurations there are, to check they all survived.
+			let pending_configs = frame_support::storage::unhashed::get::<
+				Vec<(SessionIndex, V5HostConfiguration<BlockNumberFor<T>>)>,
+			>(&PendingConfigs::<T>::hashed_key())
+			.unwrap_or_default();
+			Ok((pending_configs.len() as u32).encode())
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() == 5 {
+				let weight_consumed = migrate_to_v6::<T>();
+
+				log::info!(target: configuration::LOG_TARGET, "MigrateToV6 executed successfully");
+				STORAGE_VERSION.put::<Pallet<T>>();
+
+				weight_consumed
+			} else {
+				log::warn!(target: configuration::LOG_TARGET, "MigrateToV6 should be removed.");
+				T::DbWeight::get().reads(1)
+			}
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+			log::trace!(target: crate::configuration::LOG_TARGET, "Running post_upgrade()");
+			ensure!(
+				StorageVersion::get::<Pallet<T>>() == STORAGE_VERSION,
+				"Storage version should be 6 after the migration"
+			);
+
+			let active_config = ActiveConfig::<T>::try_get()
+				.map_err(|_| "The active configuration should be decodable after the migration")?;
+			// A pending configuration which failed to decode makes the whole list empty.
+			let pending_configs = PendingConfigs::<T>::get();
+			let n_pending_configs =
+				u32::decode(&mut &state[..]).map_err(|_| "Invalid pre-upgrade state")?;
+			ensure!(
+				pending_configs.len() as u32 == n_pending_configs,
+				"All pending configurations should be kept by the migration"
+			);
+			ensure!(
+				active_config.node_features.is_empty() &&
+					pending_configs.iter().all(|(_, config)| config.node_features.is_empty()),
+				"No node features should be enabled by the migration"
+			);
+
+			Ok(())
+		}
+	}
+}
+
 fn migrate_to_v5<T: Config>() -> Weight {
 	// Unusual formatting is justified:
 	// - make it easier to verify that fields assign what they supposed to assign.
@@ -185,9 +357,9 @@ fn migrate_to_v5<T: Config>() -> Weight {
 	#[rustfmt::skip]
 	let translate =
 		|pre: v5::OldHostConfiguration<BlockNumberFor<T>>| ->
-configuration::HostConfiguration<BlockNumberFor<T>>
+v6::V5HostConfiguration<BlockNumberFor<T>>
 	{
-		super::HostConfiguration {
+		v6::V5HostConfiguration {
 max_code_size                            : pre.max_code_size,
 max_head_data_size                       : pre.max_head_data_size,
 max_upward_queue_count                   : pre.max_upward_queue_count,
@@ -238,6 +410,109 @@ executor_params                          : Default::default(),
 		}
 	};
 
+	// The storage items are typed with the latest layout, so the v5 layout is written through
+	// untyped storage access.
+	let active_config_key = ActiveConfig::<T>::hashed_key();
+	match frame_support::storage::unhashed::get::<v5::OldHostConfiguration<BlockNumberFor<T>>>(
+		&active_config_key,
+	) {
+		Some(pre) => frame_support::storage::unhashed::put(&active_config_key, &translate(pre)),
+		None if !frame_support::storage::unhashed::exists(&active_config_key) => {},
+		// `None` is returned when the pre-migration type cannot be deserialized. This
+		// cannot happen if the migration runs correctly, i.e. against the expected version.
+		//
+		// This happening almost surely will lead to a panic somewhere else. Corruption seems
+		// to be unlikely to be caused by this. So we just log. Maybe it'll work out still?
+		None => log::error!(
+			target: configuration::LOG_TARGET,
+			"unexpected error when performing translation of the active configuration during storage upgrade to v5."
+		),
+	}
+
+	let pending_configs_key = PendingConfigs::<T>::hashed_key();
+	let mut num_configs = 1;
+	match frame_support::storage::unhashed::get::<
+		Vec<(primitives::SessionIndex, v5::OldHostConfiguration<BlockNumberFor<T>>)>,
+	>(&pending_configs_key)
+	{
+		Some(pre) => {
+			num_configs += pre.len() as u64;
+			let post = pre
+				.into_iter()
+				.map(|(session, config)| (session, translate(config)))
+				.collect::<Vec<_>>();
+			frame_support::storage::unhashed::put(&pending_configs_key, &post);
+		},
+		None if !frame_support::storage::unhashed::exists(&pending_configs_key) => {},
+		None => log::error!(
+			target: configuration::LOG_TARGET,
+			"unexpected error when performing translation of the pending configuration during storage upgrade to v5."
+		),
+	}
+
+	T::DbWeight::get().reads_writes(num_configs, num_configs)
+}
+
+fn migrate_to_v6<T: Config>() -> Weight {
+	// Unusual formatting is justified:
+	// - make it easier to verify that fields assign what they supposed to assign.
+	// - this code is transient and will be removed after all migrations are done.
+	// - this code is important enough to optimize for legibility sacrificing consistency.
+	#[rustfmt::skip]
+	let translate =
+		|pre: v6::V5HostConfiguration<BlockNumberFor<T>>| ->
+configuration::HostConfiguration<BlockNumberFor<T>>
+	{
+		super::HostConfiguration {
+max_code_size                            : pre.max_code_size,
+max_head_data_size                       : pre.max_head_data_size,
+max_upward_queue_count                   : pre.max_upward_queue_count,
+max_upward_queue_size                    : pre.max_upward_queue_size,
+max_upward_message_size                  : pre.max_upward_message_size,
+max_upward_message_num_per_candidate     : pre.max_upward_message_num_per_candidate,
+hrmp_max_message_num_per_candidate       : pre.hrmp_max_message_num_per_candidate,
+validation_upgrade_cooldown              : pre.validation_upgrade_cooldown,
+validation_upgrade_delay                 : pre.validation_upgrade_delay,
+async_backing_params                     : pre.async_backing_params,
+max_pov_size                             : pre.max_pov_size,
+max_downward_message_size                : pre.max_downward_message_size,
+ump_service_total_weight                 : pre.ump_service_total_weight,
+hrmp_max_parachain_outbound_channels     : pre.hrmp_max_parachain_outbound_channels,
+hrmp_max_parathread_outbound_channels    : pre.hrmp_max_parathread_outbound_channels,
+hrmp_sender_deposit                      : pre.hrmp_sender_deposit,
+hrmp_recipient_deposit                   : pre.hrmp_recipient_deposit,
+hrmp_channel_max_capacity                : pre.hrmp_channel_max_capacity,
+hrmp_channel_max_total_size              : pre.hrmp_channel_max_total_size,
+hrmp_max_parachain_inbound_channels      : pre.hrmp_max_parachain_inbound_channels,
+hrmp_max_parathread_inbound_channels     : pre.hrmp_max_parathread_inbound_channels,
+hrmp_channel_max_message_size            : pre.hrmp_channel_max_message_size,
+executor_params                          : pre.executor_params,
+code_retention_period                    : pre.code_retention_period,
+parathread_cores                         : pre.parathread_cores,
+parathread_retries                       : pre.parathread_retries,
+group_rotation_frequency                 : pre.group_rotation_frequency,
+chain_availability_period                : pre.chain_availability_period,
+thread_availability_period               : pre.thread_availability_period,
+scheduling_lookahead                     : pre.scheduling_lookahead,
+max_validators_per_core                  : pre.max_validators_per_core,
+max_validators                           : pre.max_validators,
+dispute_period                           : pre.dispute_period,
+dispute_post_conclusion_acceptance_period: pre.dispute_post_conclusion_acceptance_period,
+no_show_slots                            : pre.no_show_slots,
+n_delay_tranches                         : pre.n_delay_tranches,
+zeroth_delay_tranche_width               : pre.zeroth_delay_tranche_width,
+needed_approvals                         : pre.needed_approvals,
+relay_vrf_modulo_samples                 : pre.relay_vrf_modulo_samples,
+ump_max_individual_weight                : pre.ump_max_individual_weight,
+pvf_checking_enabled                     : pre.pvf_checking_enabled,
+pvf_voting_ttl                           : pre.pvf_voting_ttl,
+minimum_validation_upgrade_delay         : pre.minimum_validation_upgrade_delay,
+
+// No node features are enabled by default.
+node_features                            : Default::default(),
+		}
+	};
+
 	if let Err(_) = ActiveConfig::<T>::translate(|pre| pre.map(translate)) {
 		// `Err` is returned when the pre-migration type cannot be deserialized. This
 		// cannot happen if the migration runs correctly, i.e. against the expected version.
@@ -246,22 +521,20 @@ executor_params                          : Default::default(),
 		// to be unlikely to be caused by this. So we just log. Maybe it'll work out still?
 		log::error!(
 			target: configuration::LOG_TARGET,
-			"unexpected error when performing translation of the active configuration during storage upgrade to v5."
+			"unexpected error when performing translation of the active configuration during storage upgrade to v6."
 		);
 	}
 
 	if let Err(_) = PendingConfigs::<T>::translate(|pre| {
-		pre.map(
-			|v: Vec<(primitives::SessionIndex, v5::OldHostConfiguration<BlockNumberFor<T>>)>| {
-				v.into_iter()
-					.map(|(session, config)| (session, translate(config)))
-					.collect::<Vec<_>>()
-			},
-		)
+		pre.map(|v: Vec<(primitives::SessionIndex, v6::V5HostConfiguration<BlockNumberFor<T>>)>| {
+			v.into_iter()
+				.map(|(session, config)| (session, translate(config)))
+				.collect::<Vec<_>>()
+		})
 	}) {
 		log::error!(
 			target: configuration::LOG_TARGET,
-			"unexpected error when performing translation of the pending configuration during storage upgrade to v5."
+			"unexpected error when performing translation of the pending configuration during storage upgrade to v6."
 		);
 	}
 
@@ -345,8 +618,14 @@ mod tests {
 
 			migrate_to_v5::<Test>();
 
-			let v5 = configuration::ActiveConfig::<Test>::get();
-			let mut configs_to_check = configuration::PendingConfigs::<Test>::get();
+			let v5 = frame_support::storage::unhashed::get::<
+				v6::V5HostConfiguration<primitives::BlockNumber>,
+			>(&configuration::ActiveConfig::<Test>::hashed_key())
+			.unwrap();
+			let mut configs_to_check = frame_support::storage::unhashed::get::<
+				Vec<(primitives::SessionIndex, v6::V5HostConfiguration<primitives::BlockNumber>)>,
+			>(&configuration::PendingConfigs::<Test>::hashed_key())
+			.unwrap();
 			configs_to_check.push((0, v5.clone()));
 
 			for (_, v4) in configs_to_check {
@@ -401,4 +680,132 @@ mod tests {
 			}
 		});
 	}
+
+	#[test]
+	fn test_migrate_to_v6() {
+		// Only a field at the end is added, so we check that all the previous fields are carried
+		// over verbatim and that no node features are enabled.
+		let v5 = v6::V5HostConfiguration::<primitives::BlockNumber> {
+			ump_max_individual_weight: Weight::from_parts(0x71616e6f6e0au64, 0x71616e6f6e0au64),
+			needed_approvals: 69,
+			thread_availability_period: 55,
+			hrmp_recipient_deposit: 1337,
+			max_pov_size: 1111,
+			chain_availability_period: 33,
+			minimum_validation_upgrade_delay: 20,
+			async_backing_params: AsyncBackingParams {
+				max_candidate_depth: 3,
+				allowed_ancestry_len: 2,
+			},
+			..Default::default()
+		};
+
+		let mut pending_configs = Vec::new();
+		pending_configs.push((100, v5.clone()));
+		pending_configs.push((300, v5.clone()));
+
+		new_test_ext(Default::default()).execute_with(|| {
+			// Implant the v5 version in the state.
+			frame_support::storage::unhashed::put_raw(
+				&configuration::ActiveConfig::<Test>::hashed_key(),
+				&v5.encode(),
+			);
+			frame_support::storage::unhashed::put_raw(
+				&configuration::PendingConfigs::<Test>::hashed_key(),
+				&pending_configs.encode(),
+			);
+
+			migrate_to_v6::<Test>();
+
+			let v6 = configuration::ActiveConfig::<Test>::get();
+			let mut configs_to_check = configuration::PendingConfigs::<Test>::get();
+			configs_to_check.push((0, v6.clone()));
+
+			for (_, v6) in configs_to_check {
+				#[rustfmt::skip]
+				{
+					assert_eq!(v5.max_code_size                            , v6.max_code_size);
+					assert_eq!(v5.max_head_data_size                       , v6.max_head_data_size);
+					assert_eq!(v5.max_upward_queue_count                   , v6.max_upward_queue_count);
+					assert_eq!(v5.max_upward_queue_size                    , v6.max_upward_queue_size);
+					assert_eq!(v5.max_upward_message_size                  , v6.max_upward_message_size);
+					assert_eq!(v5.max_upward_message_num_per_candidate     , v6.max_upward_message_num_per_candidate);
+					assert_eq!(v5.hrmp_max_message_num_per_candidate       , v6.hrmp_max_message_num_per_candidate);
+					assert_eq!(v5.validation_upgrade_cooldown              , v6.validation_upgrade_cooldown);
+					assert_eq!(v5.validation_upgrade_delay                 , v6.validation_upgrade_delay);
+					assert_eq!(v5.async_backing_params                     , v6.async_backing_params);
+					assert_eq!(v5.max_pov_size                             , v6.max_pov_size);
+					assert_eq!(v5.max_downward_message_size                , v6.max_downward_message_size);
+					assert_eq!(v5.ump_service_total_weight                 , v6.ump_service_total_weight);
+					assert_eq!(v5.hrmp_max_parachain_outbound_channels     , v6.hrmp_max_parachain_outbound_channels);
+					assert_eq!(v5.hrmp_max_parathread_outbound_channels    , v6.hrmp_max_parathread_outbound_channels);
+					assert_eq!(v5.hrmp_sender_deposit                      , v6.hrmp_sender_deposit);
+					assert_eq!(v5.hrmp_recipient_deposit                   , v6.hrmp_recipient_deposit);
+					assert_eq!(v5.hrmp_channel_max_capacity                , v6.hrmp_channel_max_capacity);
+					assert_eq!(v5.hrmp_channel_max_total_size              , v6.hrmp_channel_max_total_size);
+					assert_eq!(v5.hrmp_max_parachain_inbound_channels      , v6.hrmp_max_parachain_inbound_channels);
+					assert_eq!(v5.hrmp_max_parathread_inbound_channels     , v6.hrmp_max_parathread_inbound_channels);
+					assert_eq!(v5.hrmp_channel_max_message_size            , v6.hrmp_channel_max_message_size);
+					assert_eq!(v5.executor_params                          , v6.executor_params);
+					assert_eq!(v5.code_retention_period                    , v6.code_retention_period);
+					assert_eq!(v5.parathread_cores                         , v6.parathread_cores);
+					assert_eq!(v5.parathread_retries                       , v6.parathread_retries);
+					assert_eq!(v5.group_rotation_frequency                 , v6.group_rotation_frequency);
+					assert_eq!(v5.chain_availability_period                , v6.chain_availability_period);
+					assert_eq!(v5.thread_availability_period               , v6.thread_availability_period);
+					assert_eq!(v5.scheduling_lookahead                     , v6.scheduling_lookahead);
+					assert_eq!(v5.max_validators_per_core                  , v6.max_validators_per_core);
+					assert_eq!(v5.max_validators                           , v6.max_validators);
+					assert_eq!(v5.dispute_period                           , v6.dispute_period);
+					assert_eq!(v5.dispute_post_conclusion_acceptance_period, v6.dispute_post_conclusion_acceptance_period);
+					assert_eq!(v5.no_show_slots                            , v6.no_show_slots);
+					assert_eq!(v5.n_delay_tranches                         , v6.n_delay_tranches);
+					assert_eq!(v5.zeroth_delay_tranche_width               , v6.zeroth_delay_tranche_width);
+					assert_eq!(v5.needed_approvals                         , v6.needed_approvals);
+					assert_eq!(v5.relay_vrf_modulo_samples                 , v6.relay_vrf_modulo_samples);
+					assert_eq!(v5.ump_max_individual_weight                , v6.ump_max_individual_weight);
+					assert_eq!(v5.pvf_checking_enabled                     , v6.pvf_checking_enabled);
+					assert_eq!(v5.pvf_voting_ttl                           , v6.pvf_voting_ttl);
+					assert_eq!(v5.minimum_validation_upgrade_delay         , v6.minimum_validation_upgrade_delay);
+				}; // ; makes this a statement. `rustfmt::skip` cannot be put on an expression.
+
+				assert!(v6.node_features.is_empty());
+			}
+		});
+	}
+
+	#[cfg(feature = "try-runtime")]
+	#[test]
+	fn migrate_to_v6_passes_try_runtime_checks() {
+		use frame_support::traits::OnRuntimeUpgrade;
+
+		let v5 = v6::V5HostConfiguration::<primitives::BlockNumber> {
+			needed_approvals: 69,
+			max_pov_size: 1111,
+			..Default::default()
+		};
+		let pending_configs = vec![(100, v5.clone()), (300, v5.clone())];
+
+		new_test_ext(Default::default()).execute_with(|| {
+			frame_support::storage::unhashed::put_raw(
+				&configuration::ActiveConfig::<Test>::hashed_key(),
+				&v5.encode(),
+			);
+			frame_support::storage::unhashed::put_raw(
+				&configuration::PendingConfigs::<Test>::hashed_key(),
+				&pending_configs.encode(),
+			);
+			StorageVersion::new(5).put::<Pallet<Test>>();
+
+			let state = v6::MigrateToV6::<Test>::pre_upgrade().unwrap();
+			v6::MigrateToV6::<Test>::on_runtime_upgrade();
+			v6::MigrateToV6::<Test>::post_upgrade(state).unwrap();
+
+			assert_eq!(configuration::ActiveConfig::<Test>::get().needed_approvals, 69);
+			assert_eq!(configuration::PendingConfigs::<Test>::get().len(), 2);
+
+			// The migration requires version 5, so it must not run twice.
+			assert!(v6::MigrateToV6::<Test>::pre_upgrade().is_err());
+		});
+	}
 }
@@ -327,6 +327,7 @@ fn setting_pending_config_members() {
 			pvf_voting_ttl: 3,
 			minimum_validation_upgrade_delay: 20,
 			executor_params: Default::default(),
+			node_features: bitvec::bitvec![u8, bitvec::order::Lsb0; 1, 0, 1],
 		};
 
 		Configuration::set_validation_upgrade_cooldown(
@@ -500,6 +501,8 @@ fn setting_pending_config_members() {
 		.unwrap();
 		Configuration::set_pvf_voting_ttl(RuntimeOrigin::root(), new_config.pvf_voting_ttl)
 			.unwrap();
+		Configuration::set_node_feature(RuntimeOrigin::root(), 2, true).unwrap();
+		Configuration::set_node_feature(RuntimeOrigin::root(), 0, true).unwrap();
 
 		assert_eq!(PendingConfigs::<Test>::get(), vec![(shared::SESSION_DELAY, new_config)],);
 	})
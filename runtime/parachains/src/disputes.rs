@@ -25,11 +25,12 @@ use frame_system::pallet_prelude::*;
 use parity_scale_codec::{Decode, Encode};
 use polkadot_runtime_metrics::get_current_time;
 use primitives::{
-	byzantine_threshold, supermajority_threshold, ApprovalVote, CandidateHash,
-	CheckedDisputeStatementSet, CheckedMultiDisputeStatementSet, CompactStatement, ConsensusLog,
-	DisputeState, DisputeStatement, DisputeStatementSet, ExplicitDisputeStatement,
-	InvalidDisputeStatementKind, MultiDisputeStatementSet, SessionIndex, SigningContext,
-	ValidDisputeStatementKind, ValidatorId, ValidatorIndex, ValidatorSignature,
+	byzantine_threshold, supermajority_threshold, vstaging::node_features, ApprovalVote,
+	ApprovalVoteMultipleCandidates, CandidateHash, CheckedDisputeStatementSet,
+	CheckedMultiDisputeStatementSet, CompactStatement, ConsensusLog, DisputeState,
+	DisputeStatement, DisputeStatementSet, ExplicitDisputeStatement, InvalidDisputeStatementKind,
+	MultiDisputeStatementSet, SessionIndex, SigningContext, ValidDisputeStatementKind, ValidatorId,
+	ValidatorIndex, ValidatorSignature,
 };
 use scale_info::TypeInfo;
 use sp_runtime::{
@@ -1034,6 +1035,11 @@ impl<T: Config> Pallet<T> {
 		let backers =
 			<BackersOnDisputes<T>>::get(&set.session, &set.candidate_hash).unwrap_or_default();
 
+		let approval_coalescing_enabled = node_features::is_enabled(
+			&<configuration::Pallet<T>>::config().node_features,
+			node_features::FeatureIndex::ApprovalCoalescing,
+		);
+
 		// Check and import all votes.
 		let summary = {
 			let mut importer = DisputeStateImporter::new(dispute_state, backers, now);
@@ -1048,6 +1054,19 @@ impl<T: Config> Pallet<T> {
 					Some(v) => v,
 				};
 
+				// Votes of coalesced approvals are only accepted once enabled, as validators
+				// not supporting them would fail to decode them.
+				if !approval_coalescing_enabled &&
+					matches!(
+						statement,
+						DisputeStatement::Valid(
+							ValidDisputeStatementKind::ApprovalCheckingMultipleCandidates(_)
+						)
+					) {
+					filter.remove_index(i);
+					continue
+				}
+
 				let kind = VoteKind::from(statement);
 
 				let undo = match importer.import(*validator_index, kind) {
@@ -1335,6 +1354,14 @@ fn check_signature(
 			}),
 		DisputeStatement::Valid(ValidDisputeStatementKind::ApprovalChecking) =>
 			ApprovalVote(candidate_hash).signing_payload(session),
+		DisputeStatement::Valid(ValidDisputeStatementKind::ApprovalCheckingMultipleCandidates(
+			ref candidates,
+		)) =>
+			if candidates.contains(&candidate_hash) {
+				ApprovalVoteMultipleCandidates(candidates).signing_payload(session)
+			} else {
+				return Err(())
+			},
 		DisputeStatement::Invalid(InvalidDisputeStatementKind::Explicit) =>
 			ExplicitDisputeStatement { valid: false, candidate_hash, session }.signing_payload(),
 	};
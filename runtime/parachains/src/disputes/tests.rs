@@ -1780,6 +1780,65 @@ fn test_check_signature() {
 		&signed_5
 	)
 	.is_err());
+
+	let statement_6 =
+		DisputeStatement::Valid(ValidDisputeStatementKind::ApprovalCheckingMultipleCandidates(
+			vec![candidate_hash, wrong_candidate_hash],
+		));
+	let signed_6 = validator_id.sign(
+		&ApprovalVoteMultipleCandidates(&[candidate_hash, wrong_candidate_hash])
+			.signing_payload(session),
+	);
+	let other_candidate_hash = CandidateHash(sp_core::H256::repeat_byte(5));
+
+	assert!(check_signature(
+		&validator_id.public(),
+		candidate_hash,
+		session,
+		&statement_6,
+		&signed_6
+	)
+	.is_ok());
+	assert!(check_signature(
+		&validator_id.public(),
+		wrong_candidate_hash,
+		session,
+		&statement_6,
+		&signed_6
+	)
+	.is_ok());
+	assert!(check_signature(
+		&validator_id.public(),
+		other_candidate_hash,
+		session,
+		&statement_6,
+		&signed_6
+	)
+	.is_err());
+	assert!(check_signature(
+		&wrong_validator_id.public(),
+		candidate_hash,
+		session,
+		&statement_6,
+		&signed_6
+	)
+	.is_err());
+	assert!(check_signature(
+		&validator_id.public(),
+		candidate_hash,
+		wrong_session,
+		&statement_6,
+		&signed_6
+	)
+	.is_err());
+	assert!(check_signature(
+		&validator_id.public(),
+		candidate_hash,
+		session,
+		&statement_4,
+		&signed_6
+	)
+	.is_err());
 }
 
 #[test]
@@ -2094,6 +2153,83 @@ fn filter_removes_session_out_of_bounds() {
 	})
 }
 
+#[test]
+fn filter_removes_coalesced_approvals_unless_enabled() {
+	let mut enabled_features = primitives::vstaging::NodeFeatures::new();
+	enabled_features.resize(node_features::FeatureIndex::ApprovalCoalescing as usize + 1, false);
+	enabled_features.set(node_features::FeatureIndex::ApprovalCoalescing as usize, true);
+
+	for (node_features, expect_kept) in [(Default::default(), false), (enabled_features, true)] {
+		let mock_genesis_config = MockGenesisConfig {
+			configuration: crate::configuration::GenesisConfig {
+				config: HostConfiguration { node_features, ..Default::default() },
+				..Default::default()
+			},
+			..Default::default()
+		};
+
+		new_test_ext(mock_genesis_config).execute_with(|| {
+			let v0 = <ValidatorId as CryptoType>::Pair::generate().0;
+			let v1 = <ValidatorId as CryptoType>::Pair::generate().0;
+
+			run_to_block(3, |b| {
+				// a new session at each block
+				Some((
+					true,
+					b,
+					vec![(&0, v0.public()), (&1, v1.public())],
+					Some(vec![(&0, v0.public()), (&1, v1.public())]),
+				))
+			});
+
+			let candidate_hash_a = CandidateHash(sp_core::H256::repeat_byte(1));
+			let candidate_hash_b = CandidateHash(sp_core::H256::repeat_byte(2));
+			let candidate_hashes = vec![candidate_hash_a, candidate_hash_b];
+
+			let payload_against = ExplicitDisputeStatement {
+				valid: false,
+				candidate_hash: candidate_hash_a,
+				session: 1,
+			}
+			.signing_payload();
+			let payload_coalesced =
+				ApprovalVoteMultipleCandidates(&candidate_hashes).signing_payload(1);
+
+			let set = DisputeStatementSet {
+				candidate_hash: candidate_hash_a,
+				session: 1,
+				statements: vec![
+					(
+						DisputeStatement::Invalid(InvalidDisputeStatementKind::Explicit),
+						ValidatorIndex(0),
+						v0.sign(&payload_against),
+					),
+					(
+						DisputeStatement::Valid(
+							ValidDisputeStatementKind::ApprovalCheckingMultipleCandidates(
+								candidate_hashes.clone(),
+							),
+						),
+						ValidatorIndex(1),
+						v1.sign(&payload_coalesced),
+					),
+				],
+			};
+
+			// Without the coalesced approval, the dispute lacks a vote for the candidate.
+			let statements = apply_filter_all::<Test, _>(vec![set.clone()]);
+			if expect_kept {
+				assert_eq!(
+					statements,
+					vec![CheckedDisputeStatementSet::unchecked_from_unchecked(set)]
+				);
+			} else {
+				assert!(statements.is_empty());
+			}
+		})
+	}
+}
+
 #[test]
 fn filter_removes_concluded_ancient() {
 	let dispute_post_conclusion_acceptance_period = 2;
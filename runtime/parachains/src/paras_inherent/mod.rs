@@ -1098,6 +1098,10 @@ pub(crate) fn assure_sanity_bitfields<T: crate::inclusion::Config>(
 /// `candidate_has_concluded_invalid_dispute` must return `true` if the candidate
 /// is disputed, false otherwise. The passed `usize` is the candidate index.
 ///
+/// A para may be scheduled on more than one core, but inclusion only supports a
+/// single pending candidate per para. Only the first candidate of each para is
+/// kept and assigned to the lowest of its cores.
+///
 /// The returned `Vec` is sorted according to the occupied core index.
 fn sanitize_backed_candidates<
 	T: crate::inclusion::Config,
@@ -1114,33 +1118,54 @@ fn sanitize_backed_candidates<
 		!candidate_has_concluded_invalid_dispute_or_is_invalid(candidate_idx, backed_candidate)
 	});
 
-	let scheduled_paras_to_core_idx = scheduled
-		.into_iter()
-		.map(|core_assignment| (core_assignment.para_id, core_assignment.core))
-		.collect::<BTreeMap<ParaId, CoreIndex>>();
+	// Check the candidate references the correct relay parent.
+	backed_candidates
+		.retain(|backed_candidate| backed_candidate.descriptor().relay_parent == relay_parent);
 
-	// Assure the backed candidate's `ParaId`'s core is free.
+	// Assure the backed candidate's `ParaId` has a free core for it.
 	// This holds under the assumption that `Scheduler::schedule` is called _before_.
-	// Also checks the candidate references the correct relay parent.
-
-	backed_candidates.retain(|backed_candidate| {
-		let desc = backed_candidate.descriptor();
-		desc.relay_parent == relay_parent &&
-			scheduled_paras_to_core_idx.get(&desc.para_id).is_some()
-	});
+	let cores = map_candidates_to_cores(&backed_candidates, scheduled);
+	let mut backed_candidates = backed_candidates
+		.into_iter()
+		.zip(cores)
+		.filter_map(|(backed_candidate, core)| core.map(|core| (core, backed_candidate)))
+		.collect::<Vec<_>>();
 
 	// Sort the `Vec` last, once there is a guarantee that these
 	// `BackedCandidates` references the expected relay chain parent,
 	// but more importantly are scheduled for a free core.
-	// This both avoids extra work for obviously invalid candidates,
-	// but also allows this to be done in place.
-	backed_candidates.sort_by(|x, y| {
-		// Never panics, since we filtered all panic arguments out in the previous `fn retain`.
-		scheduled_paras_to_core_idx[&x.descriptor().para_id]
-			.cmp(&scheduled_paras_to_core_idx[&y.descriptor().para_id])
-	});
+	// This avoids extra work for obviously invalid candidates.
+	backed_candidates.sort_by_key(|(core, _)| *core);
 
 	backed_candidates
+		.into_iter()
+		.map(|(_, backed_candidate)| backed_candidate)
+		.collect()
+}
+
+/// Assign each of the backed candidates to the scheduled core of its para.
+///
+/// Inclusion only supports a single pending candidate per para, so only the first
+/// candidate of a para is assigned, to the lowest of the para's cores. `None` is
+/// returned for the other candidates of the para and those of unscheduled paras.
+fn map_candidates_to_cores<H>(
+	backed_candidates: &[BackedCandidate<H>],
+	scheduled: &[CoreAssignment],
+) -> Vec<Option<CoreIndex>> {
+	let mut scheduled_paras_to_core_idx = BTreeMap::<ParaId, CoreIndex>::new();
+	for core_assignment in scheduled {
+		scheduled_paras_to_core_idx
+			.entry(core_assignment.para_id)
+			.and_modify(|core| *core = (*core).min(core_assignment.core))
+			.or_insert(core_assignment.core);
+	}
+
+	backed_candidates
+		.iter()
+		.map(|backed_candidate| {
+			scheduled_paras_to_core_idx.remove(&backed_candidate.descriptor().para_id)
+		})
+		.collect()
 }
 
 /// Assumes sorted candidates.
@@ -1166,18 +1191,18 @@ pub(crate) fn assure_sanity_backed_candidates<
 		if desc.relay_parent != relay_parent {
 			return Err(Error::<T>::UnexpectedRelayParent)
 		}
+		if !scheduled.iter().any(|core_assignment| core_assignment.para_id == desc.para_id) {
+			return Err(Error::<T>::UnscheduledCandidate)
+		}
 	}
 
-	let scheduled_paras_to_core_idx = scheduled
+	// All paras are scheduled, so a missing core means a second candidate of the same para.
+	let cores = map_candidates_to_cores(backed_candidates, scheduled)
 		.into_iter()
-		.map(|core_assignment| (core_assignment.para_id, core_assignment.core))
-		.collect::<BTreeMap<ParaId, CoreIndex>>();
-
-	if !IsSortedBy::is_sorted_by(backed_candidates, |x, y| {
-		// Never panics, since we would have early returned on those in the above loop.
-		scheduled_paras_to_core_idx[&x.descriptor().para_id]
-			.cmp(&scheduled_paras_to_core_idx[&y.descriptor().para_id])
-	}) {
+		.collect::<Option<Vec<CoreIndex>>>()
+		.ok_or(Error::<T>::UnsortedOrDuplicateBackedCandidates)?;
+
+	if !IsSortedBy::is_sorted_by(cores.as_slice(), |x, y| x.cmp(y)) {
 		return Err(Error::<T>::UnsortedOrDuplicateBackedCandidates)
 	}
 	Ok(())
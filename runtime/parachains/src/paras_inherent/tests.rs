@@ -871,6 +871,7 @@ fn default_header() -> primitives::Header {
 
 mod sanitizers {
 	use super::*;
+	use assert_matches::assert_matches;
 
 	use crate::inclusion::tests::{
 		back_candidate, collator_sign_candidate, BackingKind, TestCandidateBuilder,
@@ -1273,5 +1274,85 @@ mod sanitizers {
 				backed_candidates.len() / 2
 			);
 		}
+
+		// a para scheduled on several cores keeps only its first candidate, on its lowest core
+		{
+			let scheduled = vec![
+				CoreAssignment {
+					kind: scheduler::AssignmentKind::Parachain,
+					group_idx: GroupIndex::from(0),
+					para_id: ParaId::from(1_u32),
+					core: CoreIndex::from(2),
+				},
+				CoreAssignment {
+					kind: scheduler::AssignmentKind::Parachain,
+					group_idx: GroupIndex::from(1),
+					para_id: ParaId::from(2_u32),
+					core: CoreIndex::from(1),
+				},
+				CoreAssignment {
+					kind: scheduler::AssignmentKind::Parachain,
+					group_idx: GroupIndex::from(0),
+					para_id: ParaId::from(1_u32),
+					core: CoreIndex::from(0),
+				},
+			];
+
+			let make_candidate = |para_id: u32, pov_byte: u8| {
+				let mut candidate = TestCandidateBuilder {
+					para_id: ParaId::from(para_id),
+					relay_parent,
+					pov_hash: Hash::repeat_byte(pov_byte),
+					persisted_validation_data_hash: [42u8; 32].into(),
+					hrmp_watermark: RELAY_PARENT_NUM,
+					..Default::default()
+				}
+				.build();
+
+				collator_sign_candidate(Sr25519Keyring::One, &mut candidate);
+
+				back_candidate(
+					candidate,
+					&validators,
+					group_validators(GroupIndex::from(para_id - 1)).unwrap().as_ref(),
+					&keystore,
+					&signing_context,
+					BackingKind::Threshold,
+				)
+			};
+
+			let para_1_a = make_candidate(1, 0x1A);
+			let para_1_b = make_candidate(1, 0x1B);
+			let para_1_c = make_candidate(1, 0x1C);
+			let para_2 = make_candidate(2, 0x2A);
+
+			let sanitized = sanitize_backed_candidates::<Test, _>(
+				relay_parent,
+				vec![para_1_a.clone(), para_2.clone(), para_1_b.clone(), para_1_c],
+				has_concluded_invalid,
+				&scheduled[..],
+			);
+
+			// only a single candidate of para 1 can be pending availability
+			assert_eq!(sanitized, vec![para_1_a.clone(), para_2.clone()]);
+			assert!(assure_sanity_backed_candidates::<Test, _>(
+				relay_parent,
+				&sanitized,
+				has_concluded_invalid,
+				&scheduled[..],
+			)
+			.is_ok());
+
+			// a second candidate of the same para is rejected
+			assert_matches!(
+				assure_sanity_backed_candidates::<Test, _>(
+					relay_parent,
+					&[para_1_a.clone(), para_2, para_1_b],
+					has_concluded_invalid,
+					&scheduled[..],
+				),
+				Err(inclusion::Error::<Test>::UnsortedOrDuplicateBackedCandidates)
+			);
+		}
 	}
 }
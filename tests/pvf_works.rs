@@ -0,0 +1,60 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use assert_cmd::cargo::cargo_bin;
+use parity_scale_codec::Encode;
+use polkadot_node_primitives::{BlockData, PoV};
+use polkadot_primitives::{HeadData, PersistedValidationData};
+use std::process::Command;
+use tempfile::tempdir;
+use test_parachain_adder::{hash_state, BlockData as AdderBlockData, HeadData as AdderHeadData};
+
+#[test]
+#[cfg(unix)]
+fn pvf_subcommand_validates_a_candidate() {
+	let tmpdir = tempdir().expect("could not create temp dir");
+	let code = tmpdir.path().join("code.wasm");
+	let pvd = tmpdir.path().join("pvd.scale");
+	let pov = tmpdir.path().join("pov.scale");
+
+	let parent_head = AdderHeadData { number: 0, parent_hash: [0; 32], post_state: hash_state(0) };
+	let block_data = AdderBlockData { state: 0, add: 512 };
+
+	let persisted_validation_data: PersistedValidationData = PersistedValidationData {
+		parent_head: HeadData(parent_head.encode()),
+		relay_parent_number: 1,
+		relay_parent_storage_root: Default::default(),
+		max_pov_size: 5 * 1024 * 1024,
+	};
+
+	std::fs::write(&code, test_parachain_adder::wasm_binary_unwrap()).unwrap();
+	std::fs::write(&pvd, persisted_validation_data.encode()).unwrap();
+	std::fs::write(&pov, PoV { block_data: BlockData(block_data.encode()) }.encode()).unwrap();
+
+	let output = Command::new(cargo_bin("polkadot"))
+		.args(["pvf", "--code"])
+		.arg(&code)
+		.arg("--pvd")
+		.arg(&pvd)
+		.arg("--pov")
+		.arg(&pov)
+		.output()
+		.unwrap();
+
+	let logs = String::from_utf8_lossy(&output.stderr);
+	assert!(output.status.success(), "the pvf subcommand failed: {}", logs);
+	assert!(logs.contains("The candidate is valid"), "unexpected outcome: {}", logs);
+}